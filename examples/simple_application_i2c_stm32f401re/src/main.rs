@@ -2,13 +2,13 @@
 #![no_main]
 
 use vl53l5cx::{
-    consts::VL53L5CX_DEFAULT_I2C_ADDRESS, ResultsData, Vl53l5cx
+    app::write_results, config::Resolution, consts::VL53L5CX_DEFAULT_I2C_ADDRESS, Vl53l5cx
 };
 
 use panic_halt as _; 
 use cortex_m_rt::entry;
 
-use core::{fmt::Write, cell::RefCell};
+use core::cell::RefCell;
 
 use embedded_hal::i2c::SevenBitAddress;
 
@@ -33,64 +33,8 @@ use stm32f4xx_hal::{
     i2c::{I2c as StmI2c, I2c1, Mode}};
 use embedded_hal_bus::i2c::RefCellDevice;
 
-fn write_results(tx: &mut Tx<USART2>, results: &ResultsData, width: usize) {
-
-    writeln!(tx, "\x1B[2H").unwrap();
-
-    writeln!(tx, "VL53L5A1 Simple Ranging demo application\n").unwrap();
-    writeln!(tx, "Cell Format :\n").unwrap();
-    writeln!(
-        tx, 
-        "\x1b[96m{dis:>20}\x1b[0m \x1b[92m{sta:<20}\x1b[0m", 
-        dis="Distance [mm]", 
-        sta="Status"
-    ).unwrap();
-    writeln!(
-        tx, 
-        "\x1b[93m{sig:>20}\x1b[0m \x1b[91m{amb:<20}\x1b[0m", 
-        sig="Signal [kcps/spad]", 
-        amb="Ambient [kcps/spad]"
-    ).unwrap();
-
-    for j in 0..width {
-        for _ in 0..width { write!(tx, "+----------").unwrap(); } writeln!(tx, "+").unwrap();
-        
-        #[cfg(not(any(feature="VL53L5CX_DISABLE_DISTANCE_MM", feature="VL53L5CX_DISABLE_TARGET_STATUS")))]
-        {
-            for i in 0..width {
-                write!(
-                    tx, 
-                    "|\x1b[96m{dis:>5}\x1b[0m \x1b[92m{sta:<4}\x1b[0m", 
-                dis=results.distance_mm[width*j+i], 
-                sta=results.target_status[width*j+i]
-                ).unwrap();
-            } write!(tx, "|\n").unwrap();
-        }
-
-        #[cfg(not(any(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD")))]
-        {
-            for i in 0..width {
-                let mut sig: u32 = results.signal_per_spad[width*j+i];
-                if sig > 9999 { sig = 9999; }
-                write!(
-                    tx, 
-                    "|\x1b[93m{sig:>5}\x1b[0m \x1b[91m{amb:<4}\x1b[0m", 
-                    sig=sig, 
-                    amb=results.ambient_per_spad[width*j+i]
-                ).unwrap();
-            } write!(tx, "|\n").unwrap();
-        }
-    }
-    for _ in 0..width { write!(tx, "+----------").unwrap(); } writeln!(tx, "+").unwrap();
-
-}
-
-const WIDTH: usize = 4;
-
 #[entry]
 fn main() -> ! {
-    let mut results: ResultsData = ResultsData::new();
-    
     let dp: Peripherals = Peripherals::take().unwrap();
     let cp: CorePeripherals = CorePeripherals::take().unwrap();
     let rcc: Rcc = dp.RCC.constrain();
@@ -115,8 +59,7 @@ fn main() -> ! {
         .parity_none(),
         &clocks).unwrap();
     
-    let resolution: u8 = (WIDTH * WIDTH) as u8;
-
+    let resolution: Resolution = Resolution::Res4x4;
     
     let scl: Pin<'B', 8> = gpiob.pb8;
     let sda: Pin<'B', 9> = gpiob.pb9;
@@ -139,16 +82,11 @@ fn main() -> ! {
 
     sensor_top.init_sensor(address).unwrap(); 
     
-    sensor_top.set_resolution(resolution).unwrap();
-    sensor_top.set_frequency_hz(30).unwrap();
-    sensor_top.start_ranging().unwrap();
+    // Wait for data to be ready, get and parse the result data, and print the result to the output
+    sensor_top.run_simple_loop(resolution, 30, |results| {
+        write_results(&mut tx, results, resolution).unwrap();
+        true
+    }).unwrap();
 
-    write_results(&mut tx, &results, WIDTH);
-    
-    loop {
-        while !sensor_top.check_data_ready().unwrap() {} // Wait for data to be ready
-        results = sensor_top.get_ranging_data().unwrap(); // Get and parse the result data
-        write_results(&mut tx, &results, WIDTH); // Print the result to the output
-    }
-
-} 
+    loop {}
+}
@@ -0,0 +1,18 @@
+//! Feeds arbitrary frames to the frame parser.
+//! The first two bytes give the frame size, the remaining bytes the frame itself.
+//!
+//! Run with `cargo +nightly fuzz run parse_ranging_data` from the crate root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vl53l5cx::parser::parse_ranging_data;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 2 {
+        return;
+    }
+    let data_read_size = u16::from_le_bytes([data[0], data[1]]) as usize;
+    let mut buffer = data[2..].to_vec();
+    let _ = parse_ranging_data::<()>(&mut buffer, data_read_size);
+});
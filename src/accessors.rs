@@ -1,7 +1,7 @@
 use consts::*;
 use utils::*;
 
-use crate::{consts, utils, BusOperation, Vl53l5cx, Error, OutputPin, DelayNs};
+use crate::{consts, utils, BusArbiter, BusOperation, DriverState, FrameLayout, FrameWatchdog, FwVersion, InitStage, InitStrategy, InvalidDistance, ParseError, PowerMode, PowerTimings, SensorVariant, RangingMode, Resolution, LastErrorSnapshot, Stats, TargetOrder, TemperatureCompensation, VirtualArray, Vl53l5cx, Error, OutputPin, DelayNs};
 
 
 impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
@@ -10,20 +10,35 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// # Return
     /// 
-    /// `resolution` : Value of this pointer will be equal to 16 for 4x4 mode, and 64 for 8x8 mode.
-    pub fn get_resolution(&mut self) -> Result<u8, Error<B::Error>> {
+    /// `resolution` : Current resolution.
+    pub fn resolution(&mut self) -> Result<Resolution, Error<B::Error>> {
         self.dci_read_data(VL53L5CX_DCI_ZONE_CONFIG, 8)?;
         let resolution: u8 = self.temp_buffer[0x00] * self.temp_buffer[0x01];
 
-        Ok(resolution)
+        Resolution::try_from(resolution).map_err(|value| Error::Parse(ParseError::UnexpectedValue(value)))
     }
 
-    /// This function sets a new resolution (4x4 or 8x8).
+    /// This function gets the current resolution (4x4 or 8x8).
+    /// 
+    /// # Return
+    /// 
+    /// `resolution` : Value of this pointer will be equal to 16 for 4x4 mode, and 64 for 8x8 mode.
+    #[deprecated(note = "use resolution() instead")]
+    pub fn get_resolution(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(self.resolution()?.into())
+    }
+
+    /// This function sets a new resolution (4x4 or 8x8). The motion indicator map 
+    /// is updated to the new resolution, unless the motion indicator output is disabled.
     /// 
     /// # Arguments
     /// 
-    /// * `resolution` : Use macro VL53L5CX_RESOLUTION_4X4 or VL53L5CX_RESOLUTION_8X8 to set the resolution.
-    pub fn set_resolution(&mut self, resolution: u8) -> Result<(), Error<B::Error>> {
+    /// * `resolution` : Use Resolution, or macro VL53L5CX_RESOLUTION_4X4 or VL53L5CX_RESOLUTION_8X8 to set the resolution.
+    pub fn set_resolution(&mut self, resolution: impl Into<u8>) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        let resolution: u8 = resolution.into();
 
         if resolution == VL53L5CX_RESOLUTION_4X4 {
             self.dci_read_data(VL53L5CX_DCI_DSS_CONFIG, 16)?;
@@ -55,6 +70,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         self.send_offset_data(resolution)?;
         self.send_xtalk_data(resolution)?;
 
+        #[cfg(not(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+        self.motion_indicator_update_resolution(resolution)?;
+
         Ok(())
     }
 
@@ -62,44 +80,54 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// # Return
     /// 
-    /// `power_mode` : Current power mode. The value of this is equal to 0 if the sensor is in low power, (VL53L5CX_POWER_MODE_SLEEP), or 1 if sensor is in standard mode (VL53L5CX_POWER_MODE_WAKEUP).
-    pub fn get_power_mode(&mut self) -> Result<u8, Error<B::Error>> {
-        let power_mode: u8;
-        self.write_to_register(0x7fff, 0x00)?;
-        self.read_from_register(0x009, 1)?;    
+    /// `power_mode` : Current power mode, PowerMode::Sleep if the sensor is in low power, or PowerMode::Wakeup if sensor is in standard mode.
+    pub fn power_mode(&mut self) -> Result<PowerMode, Error<B::Error>> {
+        let power_mode: PowerMode;
+        self.with_bank(0x00, |s| s.read_from_register(0x009, 1))?;
         if self.temp_buffer[0] == 0x4 {
-            power_mode = VL53L5CX_POWER_MODE_WAKEUP;
+            power_mode = PowerMode::Wakeup;
         } else if self.temp_buffer[0] == 0x2 {
-            power_mode = VL53L5CX_POWER_MODE_SLEEP;
+            power_mode = PowerMode::Sleep;
         } else {
-            return Err(Error::Other);
+            return Err(Error::Parse(ParseError::UnexpectedValue(self.temp_buffer[0])));
         }
-        self.write_to_register(0x7fff, 0x02)?;
         
         Ok(power_mode)
     }
 
+    /// This function is used to get the current sensor power mode.
+    /// 
+    /// # Return
+    /// 
+    /// `power_mode` : Current power mode. The value of this is equal to 0 if the sensor is in low power, (VL53L5CX_POWER_MODE_SLEEP), or 1 if sensor is in standard mode (VL53L5CX_POWER_MODE_WAKEUP).
+    #[deprecated(note = "use power_mode() instead")]
+    pub fn get_power_mode(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(self.power_mode()?.into())
+    }
+
     /// This function is used to set the sensor in Low Power mode, for example if the sensor is not used during a long time. The macro VL53L5CX_POWER_MODE_SLEEP can be used to enable the low power mode. When user want to restart the sensor, he can use macro VL53L5CX_POWER_MODE_WAKEUP. Please ensure that the device is not streaming before calling the function.
     /// 
     /// # Arguments
     /// 
-    /// * `power_mode` : Selected power mode (VL53L5CX_POWER_MODE_SLEEP or VL53L5CX_POWER_MODE_WAKEUP)
-    pub fn set_power_mode(&mut self, power_mode: u8) -> Result<(), Error<B::Error>> {
-        let current_power_mode: u8 = self.get_power_mode()?;
+    /// * `power_mode` : Selected power mode (PowerMode, or VL53L5CX_POWER_MODE_SLEEP or VL53L5CX_POWER_MODE_WAKEUP)
+    pub fn set_power_mode(&mut self, power_mode: impl Into<u8>) -> Result<(), Error<B::Error>> {
+        let power_mode: u8 = power_mode.into();
+        let current_power_mode: u8 = self.power_mode()?.into();
         if power_mode != current_power_mode {
             if power_mode == VL53L5CX_POWER_MODE_WAKEUP {
-                self.write_to_register(0x7fff, 0x00)?;
-                self.write_to_register(0x09, 0x04)?;
-                self.poll_for_answer(1, 0, 0x06, 0x01, 1)?;
+                self.with_bank(0x00, |s| {
+                    s.write_to_register(0x09, 0x04)?;
+                    s.poll_for_answer(1, 0, 0x06, 0x01, 1)
+                })?;
             } else if power_mode == VL53L5CX_POWER_MODE_SLEEP {
-                self.write_to_register(0x7fff, 0x00)?;
-                self.write_to_register(0x09, 0x02)?;
-                self.poll_for_answer(1, 0, 0x06, 0x01, 0)?;
+                self.with_bank(0x00, |s| {
+                    s.write_to_register(0x09, 0x02)?;
+                    s.poll_for_answer(1, 0, 0x06, 0x01, 0)
+                })?;
             } else {
-                return Err(Error::Other);
+                return Err(Error::InvalidParam);
             }
         }
-        self.write_to_register(0x7fff, 0x02)?;
         
         Ok(())
     }
@@ -109,20 +137,33 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// # Return
     /// 
     /// `target_order` : Contains the target order.
-    pub fn get_target_order(&mut self) -> Result<u8, Error<B::Error>> {
-        let target_order: u8;
+    pub fn target_order(&mut self) -> Result<TargetOrder, Error<B::Error>> {
         self.dci_read_data(VL53L5CX_DCI_TARGET_ORDER, 4)?;
-        target_order = self.temp_buffer[0];
+        let target_order: u8 = self.temp_buffer[0];
+
+        TargetOrder::try_from(target_order).map_err(|value| Error::Parse(ParseError::UnexpectedValue(value)))
+    }
 
-        Ok(target_order)
+    /// This function gets the current target order (closest or strongest).
+    ///
+    /// # Return
+    /// 
+    /// `target_order` : Contains the target order.
+    #[deprecated(note = "use target_order() instead")]
+    pub fn get_target_order(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(self.target_order()?.into())
     }
 
     /// This function sets a new target order. Please use macros VL53L5CX_TARGET_ORDER_STRONGEST and VL53L5CX_TARGET_ORDER_CLOSEST to define the new output order. By default, the sensor is configured with the strongest output.
     /// 
     /// # Arguments
     /// 
-    /// * `target_order` : Required target order.\
-    pub fn set_target_order(&mut self, target_order: u8) -> Result<(), Error<B::Error>> {
+    /// * `target_order` : Required target order.
+    pub fn set_target_order(&mut self, target_order: impl Into<u8>) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        let target_order: u8 = target_order.into();
         if target_order == VL53L5CX_TARGET_ORDER_CLOSEST || target_order == VL53L5CX_TARGET_ORDER_STRONGEST {
             self.dci_replace_data(VL53L5CX_DCI_TARGET_ORDER, 4, &[target_order], 1, 0x0)?;
         } else {
@@ -131,6 +172,20 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         Ok(())
     }
 
+    /// This function gets the version of the firmware running on the sensor, e.g. to 
+    /// log it. The sensor must be initialized. FwVersion::is_expected() checks it 
+    /// against the firmware blob uploaded by the driver.
+    ///
+    /// # Return
+    /// 
+    /// `fw_version` : Major, minor and build numbers of the firmware.
+    pub fn get_fw_version(&mut self) -> Result<FwVersion, Error<B::Error>> {
+        self.dci_read_data(VL53L5CX_DCI_FW_VERSION, 4)?;
+        let raw: u32 = u32::from_le_bytes([self.temp_buffer[0], self.temp_buffer[1], self.temp_buffer[2], self.temp_buffer[3]]);
+
+        Ok(FwVersion::from_raw(raw))
+    }
+
     /// This function gets the current sharpener in percent. Sharpener can be changed to blur more or less zones depending of the application.
     ///
     /// # Return
@@ -150,6 +205,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `sharpener_percent` : Value between 0 (disabled) and 99%.
     pub fn set_sharpener_percent(&mut self, sharpener_percent: u32) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let sharpener: u32;
         if sharpener_percent >= 100 {
             return Err(Error::InvalidParam);
@@ -181,6 +239,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `time_ms` : Contains the integration time in ms. For all resolutions and frequency, the minimum value is 2ms, and the maximum is 1000ms.
     pub fn set_integration_time(&mut self, integration_time_ms: u32) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let mut integration: u32 = integration_time_ms;
 
         // Integration time must be between 2ms and 1000ms 
@@ -203,23 +264,31 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// # Return
     /// 
     /// `ranging_mode` : current ranging mode
-    pub fn get_ranging_mode(&mut self) -> Result<u8, Error<B::Error>> {
-        let ranging_mode: u8;
+    pub fn ranging_mode(&mut self) -> Result<RangingMode, Error<B::Error>> {
         self.dci_read_data(VL53L5CX_DCI_RANGING_MODE, 8)?;
-        if self.temp_buffer[1] == 1 {
-            ranging_mode = VL53L5CX_RANGING_MODE_CONTINUOUS;
-        } else {
-            ranging_mode = VL53L5CX_RANGING_MODE_AUTONOMOUS;
-        }
-        Ok(ranging_mode)
+        Ok(if self.temp_buffer[1] == 1 { RangingMode::Continuous } else { RangingMode::Autonomous })
+    }
+
+    /// This function is used to get the ranging mode. Two modes are available using ULD : Continuous and autonomous. The default mode is Autonomous.
+    ///
+    /// # Return
+    /// 
+    /// `ranging_mode` : current ranging mode
+    #[deprecated(note = "use ranging_mode() instead")]
+    pub fn get_ranging_mode(&mut self) -> Result<u8, Error<B::Error>> {
+        Ok(self.ranging_mode()?.into())
     }
 
     /// This function is used to set the ranging mode. Two modes are available using ULD : Continuous and autonomous. The default mode is Autonomous.
     ///
     /// # Arguments
     /// 
-    /// * `ranging_mode` : Use macros VL53L5CX_RANGING_MODE_CONTINUOUS, VL53L5CX_RANGING_MODE_AUTONOMOUS.
-    pub fn set_ranging_mode(&mut self, ranging_mode: u8) -> Result<(), Error<B::Error>> {
+    /// * `ranging_mode` : Use RangingMode, or macros VL53L5CX_RANGING_MODE_CONTINUOUS, VL53L5CX_RANGING_MODE_AUTONOMOUS.
+    pub fn set_ranging_mode(&mut self, ranging_mode: impl Into<u8>) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        let ranging_mode: u8 = ranging_mode.into();
         let mut single_range: [u32; 1] = [0];
         self.dci_read_data(VL53L5CX_DCI_RANGING_MODE, 8)?;
 
@@ -232,7 +301,7 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
             self.temp_buffer[3] = 2;
             single_range[0] = 1;
         } else {
-            return Err(Error::Other);
+            return Err(Error::InvalidParam);
         }
 
         self.dci_write_data(VL53L5CX_DCI_RANGING_MODE, 8)?;
@@ -245,6 +314,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
 
     /// This function is used to enable the VCSEL charge pump
     pub fn enable_internal_cp(&mut self) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let vcsel_bootup_fsm: [u8;1] = [1];
     	let analog_dynamic_pad_0: [u8;1] = [0];
 
@@ -259,6 +331,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// This optimizes the power consumption of the device
     /// To be used only if AVDD = 3.3V
     pub fn disable_internal_cp(&mut self) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let vcsel_bootup_fsm: [u8;1] = [0];
     	let analog_dynamic_pad_0: [u8;1] = [1];
 
@@ -289,6 +364,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `repeat_count` : Number of frames between temperature compensation. Set to 0 to disable the feature (default configuration).
         pub fn set_vhv_repeat_count(&mut self, repeat_count: u32) -> Result<(), Error<B::Error>> {
+            if self.config_locked {
+                return Err(Error::ConfigLocked);
+            }
         let mut tmp: [u8;4] = [0;4];
         from_u32_to_u8(&[repeat_count], &mut tmp);
         self.dci_replace_data(VL53L5CX_DCI_VHV_CONFIG, 16, &tmp, 4, 0x4)?;
@@ -315,10 +393,449 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// * `frequency_hz` : Contains the ranging frequency in Hz.
     ///  - For 4x4, min and max allowed values are : 1 to 60
     ///  - For 8x8, min and max allowed values are : 1 to 15
+    /// 
+    /// In autonomous mode, the frequency is also limited by the integration time (see timing::check_timing()).
     pub fn set_frequency_hz(&mut self, frequency_hz: u8) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let tmp: [u8; 1] = [frequency_hz];
         self.dci_replace_data(VL53L5CX_DCI_FREQ_HZ, 4, &tmp, 1, 0x01)?;
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// This function gets the layout of the headers and metadata used to parse the frames.
+    /// 
+    /// # Return
+    /// 
+    /// `frame_layout` : Current frame layout.
+    pub fn get_frame_layout(&self) -> FrameLayout {
+        self.frame_layout
+    }
+
+    /// This function sets the layout of the headers and metadata used to parse the frames. 
+    /// The default layout matches the firmware embedded in this driver, so it only needs 
+    /// to be changed for firmware revisions with a different frame layout.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `frame_layout` : New frame layout.
+    pub fn set_frame_layout(&mut self, frame_layout: FrameLayout) {
+        self.frame_layout = frame_layout;
+    }
+
+    /// This function gets the delay inserted between two chunks of a multi-bytes write.
+    /// 
+    /// # Return
+    /// 
+    /// `chunk_delay_us` : Delay in us.
+    pub fn get_chunk_delay_us(&self) -> u32 {
+        self.chunk_delay_us
+    }
+
+    /// This function sets a delay inserted between two chunks of a multi-bytes write, 
+    /// such as the firmware upload. Some USB to I2C bridges (FT260, CH341) need it 
+    /// to handle long write bursts. The default value is 0 (no delay).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `chunk_delay_us` : Delay in us.
+    pub fn set_chunk_delay_us(&mut self, chunk_delay_us: u32) {
+        self.chunk_delay_us = chunk_delay_us;
+    }
+
+    /// This function enables or disables the read-back verification of the firmware upload.
+    /// When enabled, init() reads back the uploaded firmware pages and returns 
+    /// Error::FirmwareMismatch with the offsets of the differences, which helps diagnosing 
+    /// unreliable buses. Please note that it roughly doubles the duration of init().
+    /// 
+    /// # Arguments
+    /// 
+    /// * `verify` : true to enable the verification, false to disable it (default).
+    pub fn set_verify_firmware_upload(&mut self, verify: bool) {
+        self.verify_firmware_upload = verify;
+    }
+
+    /// This function sets how init() handles the firmware. With InitStrategy::IfNeeded, 
+    /// init() first reads the version of the running firmware (for at most 10ms), and 
+    /// skips the reboot and the firmware upload if it is the expected version of the 
+    /// sensor variant (VL53L5CX_EXPECTED_FW_VERSION by default), which saves most of the duration of init() on warm restarts. The sensor must not 
+    /// be ranging, e.g. stopped with stop_ranging() before the host resets.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `strategy` : InitStrategy::Always (default) or InitStrategy::IfNeeded.
+    pub fn set_init_strategy(&mut self, strategy: InitStrategy) {
+        self.init_strategy = strategy;
+    }
+
+    /// This function sets the part driven by the driver. With SensorVariant::Vl53l7cx 
+    /// or SensorVariant::Vl53l8cx, init() uploads the firmware of the variant, and 
+    /// writes its default configuration and Xtalk data, which are also used by the 
+    /// Xtalk calibration. It must be set before init().
+    /// 
+    /// # Arguments
+    /// 
+    /// * `variant` : SensorVariant::Vl53l5cx (default), SensorVariant::Vl53l7cx or SensorVariant::Vl53l8cx.
+    pub fn set_sensor_variant(&mut self, variant: SensorVariant) {
+        self.sensor_variant = variant;
+    }
+
+    /// This function gets the part driven by the driver (see set_sensor_variant()).
+    pub fn get_sensor_variant(&self) -> SensorVariant {
+        self.sensor_variant
+    }
+
+    /// This function locks the configuration of the sensor, e.g. once it is set after 
+    /// init(), so that it can not change at runtime. Until unlock_configuration(), the 
+    /// functions changing the configuration (set_resolution(), set_frequency_hz(), the 
+    /// thresholds, the motion indicator configuration and output, the Xtalk, the debug 
+    /// outputs, the auto-trim mode, apply_const_config()...) and init() check the lock first, 
+    /// and return Error::ConfigLocked without accessing the sensor. The ranging sessions, 
+    /// the power mode and the other settings of the driver alone are not affected. 
+    /// This is a guard of the driver: the sensor itself is not write protected.
+    pub fn lock_configuration(&mut self) {
+        self.config_locked = true;
+    }
+
+    /// This function unlocks the configuration locked by lock_configuration().
+    pub fn unlock_configuration(&mut self) {
+        self.config_locked = false;
+    }
+
+    /// This function checks whether the configuration is locked (see lock_configuration()).
+    pub fn is_configuration_locked(&self) -> bool {
+        self.config_locked
+    }
+
+    /// This function sets a function switching the power rail of the sensor (e.g. a load 
+    /// switch on AVDD), called with true by on() before LPn is set high, and with false 
+    /// by off() after LPn is set low, so that init_sensor() fully power-cycles the module.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `power_rail` : Function switching the rail on (true) or off (false), None to disable it (default).
+    pub fn set_power_rail(&mut self, power_rail: Option<fn(bool)>) {
+        self.power_rail = power_rail;
+    }
+
+    /// This function sets the waits of the power sequence of on() and off(), e.g. longer 
+    /// waits for a power rail with a large capacitance, or shorter ones when the boot 
+    /// time was measured on the board (see PowerTimings for the defaults).
+    /// 
+    /// # Arguments
+    /// 
+    /// * `timings` : Waits of the power sequence.
+    pub fn set_power_timings(&mut self, timings: PowerTimings) {
+        self.power_timings = timings;
+    }
+
+    /// This function sets a function called at each change of the driver state (initialization, 
+    /// ranging, error, recovery), e.g. to tie a debug LED or a status register to the driver 
+    /// state without application code.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indicator` : Function called with the new state, None to disable it (default).
+    pub fn set_status_indicator(&mut self, indicator: Option<fn(DriverState)>) {
+        self.status_indicator = indicator;
+    }
+
+    /// This function gets the current driver state (see set_status_indicator()).
+    pub fn driver_state(&self) -> DriverState {
+        self.driver_state
+    }
+
+    /// This function sets a function called by init() at the start of each stage, 
+    /// e.g. to log the progress of the initialization and find where it hangs.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `observer` : Function called with each stage, None to disable it (default).
+    pub fn set_init_observer(&mut self, observer: Option<fn(InitStage)>) {
+        self.init_observer = observer;
+    }
+
+    /// This function sets a temperature compensation model, applied by get_ranging_data() 
+    /// to the distances of the valid targets with the silicon temperature of the frame 
+    /// (see the temperature module for the typical drift). The distances are corrected 
+    /// before the conversion, so the floating point view (feature float-results) matches them.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `compensation` : Model giving the corrected distance of a target, None to disable it (default).
+    pub fn set_temperature_compensation(&mut self, compensation: Option<TemperatureCompensation>) {
+        self.temperature_compensation = compensation;
+    }
+
+    /// This function sets a virtual array, applied by get_ranging_data() : the zones of
+    /// its sub-grid are moved to the start of the results (see VirtualArray::crop()).
+    /// The sensor still ranges all the zones, the virtual array must match the resolution.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `array` : Sub-grid of the zones kept in the results, None to keep the whole grid (default).
+    pub fn set_virtual_array(&mut self, array: Option<VirtualArray>) {
+        self.virtual_array = array;
+    }
+
+    /// This function sets the hooks called around each transaction burst, e.g. to share 
+    /// the I2C bus with other peripherals: the acquire hook can wait for the bus, or 
+    /// return false to defer the burst, in which case Error::BusDeferred is returned 
+    /// and the driver state is unchanged, so that the call can be retried.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `arbiter` : Acquire and release hooks, None to disable them (default).
+    pub fn set_bus_arbiter(&mut self, arbiter: Option<BusArbiter>) {
+        self.bus_arbiter = arbiter;
+    }
+
+    /// This function adds debug output blocks of the firmware to the frames, for advanced 
+    /// debugging, where the firmware supports them. The blocks are not parsed into ResultsData: 
+    /// they are given as raw bytes to the visitor of get_ranging_data_with(), as blocks 
+    /// whose output() is 0. This API is unstable: the debug blocks are not documented by ST, 
+    /// and may change with the firmware. It is applied by the next start_ranging().
+    /// 
+    /// # Arguments
+    /// 
+    /// * `block_headers` : Block headers (index, type and size) of the debug outputs, 
+    ///   at most VL53L5CX_MAX_DEBUG_OUTPUTS. An empty slice removes the debug outputs (default).
+    /// 
+    /// # Return
+    /// 
+    /// Error::InvalidParam if there are too many block headers or one is 0, or 
+    /// Error::ConfigLocked if the configuration is locked (see lock_configuration()).
+    pub fn set_debug_outputs(&mut self, block_headers: &[u32]) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        if block_headers.len() > VL53L5CX_MAX_DEBUG_OUTPUTS || block_headers.contains(&0) {
+            return Err(Error::InvalidParam);
+        }
+        self.debug_outputs[..block_headers.len()].copy_from_slice(block_headers);
+        self.nb_debug_outputs = block_headers.len();
+
+        Ok(())
+    }
+
+    /// This function enables or disables the motion indicator output between two ranging 
+    /// sessions, without rebuilding with the feature VL53L5CX_DISABLE_MOTION_INDICATOR. 
+    /// The output enables and the frame size are updated by the next start_ranging(). 
+    /// When the output is disabled, results.motion_indicator is left empty.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enable` : true to enable the motion indicator output (default), false to disable it.
+    /// 
+    /// # Return
+    /// 
+    /// Error::InvalidState if the sensor is ranging, Error::InvalidParam to enable it 
+    /// when the feature VL53L5CX_DISABLE_MOTION_INDICATOR is enabled, or Error::ConfigLocked 
+    /// if the configuration is locked (see lock_configuration()).
+    pub fn set_motion_indicator_output(&mut self, enable: bool) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        if self.is_ranging {
+            return Err(Error::InvalidState);
+        }
+        if enable && cfg!(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR") {
+            return Err(Error::InvalidParam);
+        }
+        self.motion_indicator_output = enable;
+
+        Ok(())
+    }
+
+    /// This function enables or disables the auto-trim mode. When enabled, start_ranging() 
+    /// drops optional outputs, in the order of VL53L5CX_TRIM_ORDER (reflectance, ambient, 
+    /// sigma), until the frames fit in the I2C bus budget at the current resolution and 
+    /// frequency (see bandwidth::trim_outputs()). The debug outputs are not counted.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bus_clock_hz` : I2C bus clock in Hz, or None to disable the auto-trim mode (default).
+    /// 
+    /// # Return
+    /// 
+    /// Error::ConfigLocked if the configuration is locked (see lock_configuration()).
+    pub fn set_auto_trim(&mut self, bus_clock_hz: Option<u32>) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        self.auto_trim_bus_clock_hz = bus_clock_hz;
+
+        Ok(())
+    }
+
+    /// This function gets the outputs dropped by the auto-trim mode at the last start_ranging().
+    /// 
+    /// # Return
+    /// 
+    /// `trimmed_outputs` : Dropped outputs, as a combination of macros VL53L5CX_OUTPUT_*.
+    pub fn trimmed_outputs(&self) -> u32 {
+        self.trimmed_outputs
+    }
+
+    /// This function enables or disables the overrun check of the frame reads. When enabled, 
+    /// the stream count is read again every 256 bytes of the frame, and the read is aborted 
+    /// with Error::CorruptedFrame as soon as the sensor starts writing the next frame, 
+    /// instead of reading the whole frame before detecting it. It is useful when the host 
+    /// can be late to read the frames, at the cost of a 1 byte read every 256 bytes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enable` : true to enable the overrun check, false to disable it (default).
+    pub fn set_overrun_check(&mut self, enable: bool) {
+        self.overrun_check = enable;
+    }
+
+    /// This function enables or disables the fast data ready check. When enabled, 
+    /// check_data_ready() only reads the stream count (1 byte instead of 4), which 
+    /// reduces the bus traffic of the poll loops at high ranging frequencies. 
+    /// The frame status is then only checked by get_ranging_data(), which returns 
+    /// Error::NotReady for an incomplete frame, and GO2 errors are not reported.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `fast` : true to read only the stream count, false to read the full status (default).
+    pub fn set_fast_data_ready(&mut self, fast: bool) {
+        self.fast_data_ready = fast;
+    }
+
+    /// This function sets the number of frames discarded after each start_ranging(), 
+    /// as the first frames of a session are often unreliable. A discarded frame is 
+    /// consumed by check_data_ready(), which returns false, or by get_ranging_data() 
+    /// called on the INT pin, which returns Error::NotReady.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `nb_frames` : Number of frames discarded, 0 to keep all the frames (default).
+    pub fn discard_first_frames(&mut self, nb_frames: u8) {
+        self.discarded_first_frames = nb_frames;
+    }
+
+    /// This function sets the expected frame period of the watchdog checked by 
+    /// check_stalled(), e.g. the ranging period, to detect a sensor which silently 
+    /// stopped streaming. The watchdog restarts at each start_ranging().
+    /// 
+    /// # Arguments
+    /// 
+    /// * `period_ms` : Expected time between two frames in ms, 0 to disable the watchdog (default).
+    pub fn expect_frame_every(&mut self, period_ms: u32) {
+        self.frame_watchdog = if period_ms == 0 { None } else { Some(FrameWatchdog::new(period_ms)) };
+    }
+
+    /// This function checks if a ranging session was started with start_ranging(), 
+    /// and not stopped yet. start_ranging() and stop_ranging() return 
+    /// an InvalidState error when called out of order.
+    /// 
+    /// # Return
+    /// 
+    /// `is_ranging` : true if the sensor is ranging.
+    pub fn is_ranging(&self) -> bool {
+        self.is_ranging
+    }
+
+    /// This function gets the number of failed and retried transactions since 
+    /// the creation of the driver, or since the last reset_stats().
+    /// 
+    /// # Return
+    /// 
+    /// `stats` : Transactions counters.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// This function resets the transactions counters returned by stats().
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    /// This function gets the status registers saved when the last GO2 error 
+    /// was reported by check_data_ready() or get_ranging_data(), to diagnose field failures from logs. 
+    /// 
+    /// # Return
+    /// 
+    /// `snapshot` : Status registers, None if no GO2 error occurred since 
+    /// the creation of the driver, or since the last clear_last_error().
+    pub fn last_error(&self) -> Option<LastErrorSnapshot> {
+        self.last_error
+    }
+
+    /// This function clears the snapshot returned by last_error().
+    pub fn clear_last_error(&mut self) {
+        self.last_error = None;
+    }
+
+    /// This function gets the value given to the distances without a valid measurement.
+    /// 
+    /// # Return
+    /// 
+    /// `invalid_distance` : Value of the invalid distances.
+    pub fn get_invalid_distance(&self) -> InvalidDistance {
+        self.invalid_distance
+    }
+
+    /// This function sets the value given by get_ranging_data() to the distances 
+    /// without a valid measurement. By default, negative distances are clamped to 0, 
+    /// which cannot be told apart from a genuine 0mm distance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `invalid_distance` : Value of the invalid distances.
+    pub fn set_invalid_distance(&mut self, invalid_distance: InvalidDistance) {
+        self.invalid_distance = invalid_distance;
+    }
+
+    /// This function gets the interval between two polls of the firmware status.
+    /// 
+    /// # Return
+    /// 
+    /// `poll_interval_us` : Interval in us.
+    pub fn get_poll_interval_us(&self) -> u32 {
+        self.poll_interval_us
+    }
+
+    /// This function sets the interval between two polls of the firmware status, used when 
+    /// waiting for the end of a command. A shorter interval lowers the commands latency, 
+    /// but increases the I2C traffic. The polling timeout is not modified. Default is 1000us.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `poll_interval_us` : Interval in us. Min value is 1us, and max is 100000us.
+    pub fn set_poll_interval_us(&mut self, poll_interval_us: u32) -> Result<(), Error<B::Error>> {
+        if !(1..=100_000).contains(&poll_interval_us) {
+            return Err(Error::InvalidParam);
+        }
+        self.poll_interval_us = poll_interval_us;
+
+        Ok(())
+    }
+
+    /// This function gets the interval between two polls of the MCU boot status.
+    /// 
+    /// # Return
+    /// 
+    /// `boot_poll_interval_us` : Interval in us.
+    pub fn get_boot_poll_interval_us(&self) -> u32 {
+        self.boot_poll_interval_us
+    }
+
+    /// This function sets the interval between two polls of the MCU boot status, used by init(). 
+    /// The boot timeout is not modified. Default is 100us.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `boot_poll_interval_us` : Interval in us. Min value is 1us, and max is 100000us.
+    pub fn set_boot_poll_interval_us(&mut self, boot_poll_interval_us: u32) -> Result<(), Error<B::Error>> {
+        if !(1..=100_000).contains(&boot_poll_interval_us) {
+            return Err(Error::InvalidParam);
+        }
+        self.boot_poll_interval_us = boot_poll_interval_us;
+
+        Ok(())
+    }
+}
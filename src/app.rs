@@ -0,0 +1,105 @@
+use core::fmt::{Result as FmtResult, Write};
+
+use consts::*;
+
+use crate::{consts, BusOperation, Error, Resolution, ResultsData, Vl53l5cx, OutputPin, DelayNs};
+
+/// This function writes the results as a table of zones, with ANSI colors, 
+/// as printed by the example applications on a serial terminal. 
+/// The cursor is moved to the top of the terminal first, so that the table 
+/// is redrawn in place at each frame.
+/// 
+/// # Arguments
+/// 
+/// * `out` : Output, e.g. a serial port implementing core::fmt::Write.
+/// * `results` : Results of the frame.
+/// * `resolution` : Resolution used for the frame.
+#[cfg_attr(all(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS"), any(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD")), allow(unused_variables))]
+pub fn write_results<W: Write>(out: &mut W, results: &ResultsData, resolution: Resolution) -> FmtResult {
+    let width: usize = if resolution == Resolution::Res8x8 { 8 } else { 4 };
+    let nb_targets: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+    writeln!(out, "\x1B[2H")?;
+
+    writeln!(out, "VL53L5CX ranging results\n")?;
+    writeln!(out, "Cell Format :\n")?;
+    writeln!(
+        out, 
+        "\x1b[96m{dis:>20}\x1b[0m \x1b[92m{sta:<20}\x1b[0m", 
+        dis="Distance [mm]", 
+        sta="Status"
+    )?;
+    writeln!(
+        out, 
+        "\x1b[93m{sig:>20}\x1b[0m \x1b[91m{amb:<20}\x1b[0m", 
+        sig="Signal [kcps/spad]", 
+        amb="Ambient [kcps/spad]"
+    )?;
+
+    for j in 0..width {
+        for _ in 0..width { write!(out, "+----------")?; } writeln!(out, "+")?;
+
+        #[cfg(not(any(feature="VL53L5CX_DISABLE_DISTANCE_MM", feature="VL53L5CX_DISABLE_TARGET_STATUS")))]
+        {
+            for i in 0..width {
+                write!(
+                    out, 
+                    "|\x1b[96m{dis:>5}\x1b[0m \x1b[92m{sta:<4}\x1b[0m", 
+                    dis=results.distance_mm[nb_targets*(width*j+i)], 
+                    sta=results.target_status[nb_targets*(width*j+i)]
+                )?;
+            } writeln!(out, "|")?;
+        }
+
+        #[cfg(not(any(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD")))]
+        {
+            for i in 0..width {
+                write!(
+                    out, 
+                    "|\x1b[93m{sig:>5}\x1b[0m \x1b[91m{amb:<4}\x1b[0m", 
+                    sig=results.signal_per_spad[nb_targets*(width*j+i)].min(9999), 
+                    amb=results.ambient_per_spad[width*j+i]
+                )?;
+            } writeln!(out, "|")?;
+        }
+    }
+    for _ in 0..width { write!(out, "+----------")?; } writeln!(out, "+")?;
+
+    Ok(())
+}
+
+impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
+    /// This function runs the loop of the simple example application: it configures 
+    /// the resolution and the ranging frequency, starts the ranging, and calls `sink` 
+    /// with each new frame. The sensor must be initialized (see init_sensor()). 
+    /// The ranging is stopped when `sink` returns false, or on error.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `resolution` : Resolution of the frames.
+    /// * `frequency_hz` : Ranging frequency in Hz (see set_frequency_hz()).
+    /// * `sink` : Function called with each frame, returning false to stop the loop.
+    pub fn run_simple_loop(&mut self, resolution: Resolution, frequency_hz: u8, mut sink: impl FnMut(&ResultsData) -> bool) -> Result<(), Error<B::Error>> {
+        self.set_resolution(resolution)?;
+        self.set_frequency_hz(frequency_hz)?;
+        self.start_ranging()?;
+
+        loop {
+            let results: Result<Option<ResultsData>, Error<B::Error>> = self.try_get_ranging_data();
+            match results {
+                Ok(Some(results)) => {
+                    if !sink(&results) {
+                        break;
+                    }
+                }
+                Ok(None) => self.delay_us(self.poll_interval_us),
+                Err(e) => {
+                    self.stop_ranging().ok();
+                    return Err(e);
+                }
+            }
+        }
+
+        self.stop_ranging()
+    }
+}
@@ -0,0 +1,672 @@
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+use core::task::Poll;
+
+use embedded_hal::i2c::SevenBitAddress;
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+use buffers::*;
+use consts::*;
+use dci::*;
+use utils::*;
+
+use crate::{
+    buffers, consts, dci, utils, build_output_list, is_frame_complete, is_go2_error, output_enables,
+    parse_ranging_data_with_options, prepare_offset_data, prepare_xtalk_data, BuiltinFirmware,
+    CommandStatus, Error, FirmwareSource, FrameLayout, InvalidDistance, ParseError, Resolution,
+    ResultsData, NB_STANDARD_OUTPUTS, VL53L5CX_FIRMWARE_PAGES
+};
+
+/// Structure Vl53l5cxAsync is the async variant of the driver, on the embedded-hal-async
+/// I2c and DelayNs traits: the firmware upload, the polls of the firmware answers and
+/// the frame reads yield to the executor instead of blocking it. The frames are parsed
+/// as the blocking driver does (see parser::parse_ranging_data_with_options()).
+///
+/// It covers the ranging session: init, resolution, frequency, start, stop and frames.
+/// The other settings are only available in the blocking driver, which can be used on
+/// the same sensor before the async driver (init() must not be called again then).
+pub struct Vl53l5cxAsync<I2C, T> {
+    temp_buffer: [u8; VL53L5CX_TEMPORARY_BUFFER_SIZE],
+    offset_data: [u8; VL53L5CX_OFFSET_BUFFER_SIZE],
+    xtalk_data: [u8; VL53L5CX_XTALK_BUFFER_SIZE],
+    streamcount: u8,
+    data_pending: bool,
+    data_read_size: u32,
+    is_ranging: bool,
+    frame_layout: FrameLayout,
+    invalid_distance: InvalidDistance,
+    poll_interval_us: u32,
+    address: SevenBitAddress,
+    i2c: I2C,
+    tim: T
+}
+
+impl<I2C: I2c, T: DelayNs> Vl53l5cxAsync<I2C, T> {
+    /// This function creates an async driver, with the default settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c` : I2C bus of the sensor, at VL53L5CX_DEFAULT_I2C_ADDRESS.
+    /// * `tim` : Async delay.
+    pub fn new(i2c: I2C, tim: T) -> Self {
+        Vl53l5cxAsync {
+            temp_buffer: [0; VL53L5CX_TEMPORARY_BUFFER_SIZE],
+            offset_data: [0; VL53L5CX_OFFSET_BUFFER_SIZE],
+            xtalk_data: [0; VL53L5CX_XTALK_BUFFER_SIZE],
+            streamcount: 0,
+            data_pending: false,
+            data_read_size: 0,
+            is_ranging: false,
+            frame_layout: FrameLayout::new(),
+            invalid_distance: InvalidDistance::Zero,
+            poll_interval_us: VL53L5CX_POLL_INTERVAL_US,
+            address: VL53L5CX_DEFAULT_I2C_ADDRESS,
+            i2c,
+            tim
+        }
+    }
+
+    /// This function releases the I2C bus and the delay.
+    pub fn release(self) -> (I2C, T) {
+        (self.i2c, self.tim)
+    }
+
+    /// This function changes the I2C address of the sensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `i2c_address` : New I2C address.
+    pub async fn set_i2c_address(&mut self, i2c_address: SevenBitAddress) -> Result<(), Error<I2C::Error>> {
+        self.write_to_register(VL53L5CX_BANK_SELECT, 0x00).await?;
+        let result: Result<(), Error<I2C::Error>> = self.write_to_register(0x4, i2c_address).await;
+        if result.is_ok() {
+            self.address = i2c_address;
+        }
+        self.write_to_register(VL53L5CX_BANK_SELECT, VL53L5CX_DEFAULT_BANK).await?;
+
+        result
+    }
+
+    /// This function checks that the device and revision ids of the sensor are the
+    /// ones of a VL53L5CX, as Vl53l5cx::probe_identity() does for SensorVariant::Vl53l5cx.
+    pub async fn probe_identity(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write_to_register(VL53L5CX_BANK_SELECT, 0x00).await?;
+        let result: Result<(), Error<I2C::Error>> = self.read_from_register(0, 2).await;
+        self.write_to_register(VL53L5CX_BANK_SELECT, VL53L5CX_DEFAULT_BANK).await?;
+        result?;
+        let device_id: u8 = self.temp_buffer[0];
+        let revision_id: u8 = self.temp_buffer[1];
+        if (device_id != 0xF0) || (revision_id != 0x02) {
+            return Err(Error::IdMismatch { device_id, revision_id });
+        }
+
+        Ok(())
+    }
+
+    /// This function initializes the sensor, as Vl53l5cx::init() does. The
+    /// firmware is always uploaded.
+    pub async fn init(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.init_with_firmware(&mut BuiltinFirmware).await
+    }
+
+    /// This function initializes the sensor with the firmware read from a
+    /// FirmwareSource, as Vl53l5cx::init_with_firmware() does.
+    ///
+    /// # Arguments
+    ///
+    /// * `firmware` : Source of the firmware to upload.
+    pub async fn init_with_firmware<F: FirmwareSource>(&mut self, firmware: &mut F) -> Result<(), Error<I2C::Error>> {
+        let pipe_ctrl: [u8; 4] = [VL53L5CX_NB_TARGET_PER_ZONE as u8, 0x00, 0x01, 0x00];
+        let single_range: [u32; 1] = [0x01];
+
+        self.is_ranging = false;
+
+        // SW reboot sequence
+        self.write_to_register(0x7fff, 0x00).await?;
+        self.write_to_register(0x0009, 0x04).await?;
+        self.write_to_register(0x000F, 0x40).await?;
+        self.write_to_register(0x000A, 0x03).await?;
+        self.read_from_register(0x7FFF, 1).await?;
+        self.write_to_register(0x000C, 0x01).await?;
+
+        self.write_to_register(0x0101, 0x00).await?;
+        self.write_to_register(0x0102, 0x00).await?;
+        self.write_to_register(0x010A, 0x01).await?;
+        self.write_to_register(0x4002, 0x01).await?;
+        self.write_to_register(0x4002, 0x00).await?;
+        self.write_to_register(0x010A, 0x03).await?;
+        self.write_to_register(0x0103, 0x01).await?;
+        self.write_to_register(0x000C, 0x00).await?;
+        self.write_to_register(0x000F, 0x43).await?;
+        self.tim.delay_ms(1).await;
+
+        self.write_to_register(0x000F, 0x40).await?;
+        self.write_to_register(0x000A, 0x01).await?;
+        self.tim.delay_ms(100).await;
+
+        // Wait for sensor booted (several ms required to get sensor ready )
+        self.write_to_register(0x7fff, 0x00).await?;
+        self.poll_for_answer(1, 0, 0x06, 0xff, 1).await?;
+
+        self.write_to_register(0x000E, 0x01).await?;
+        self.write_to_register(0x7fff, 0x02).await?;
+
+        // Enable FW access
+        self.write_to_register(0x03, 0x0D).await?;
+        self.write_to_register(0x7fff, 0x01).await?;
+        self.poll_for_answer(1, 0, 0x21, 0x10, 0x10).await?;
+        self.write_to_register(0x7fff, 0x00).await?;
+
+        // Enable host access to GO1
+        self.read_from_register(0x7fff, 1).await?;
+        self.write_to_register(0x0C, 0x01).await?;
+
+        // Power ON status
+        self.write_to_register(0x7fff, 0x00).await?;
+        self.write_to_register(0x101, 0x00).await?;
+        self.write_to_register(0x102, 0x00).await?;
+        self.write_to_register(0x010A, 0x01).await?;
+        self.write_to_register(0x4002, 0x01).await?;
+        self.write_to_register(0x4002, 0x00).await?;
+        self.write_to_register(0x010A, 0x03).await?;
+        self.write_to_register(0x103, 0x01).await?;
+        self.write_to_register(0x400F, 0x00).await?;
+        self.write_to_register(0x21A, 0x43).await?;
+        self.write_to_register(0x21A, 0x03).await?;
+        self.write_to_register(0x21A, 0x01).await?;
+        self.write_to_register(0x21A, 0x00).await?;
+        self.write_to_register(0x219, 0x00).await?;
+        self.write_to_register(0x21B, 0x00).await?;
+
+        // Wake up MCU
+        self.write_to_register(0x7fff, 0x00).await?;
+        self.read_from_register(0x7fff, 1).await?;
+        self.write_to_register(0x0C, 0x00).await?;
+        self.write_to_register(0x7fff, 0x01).await?;
+        self.write_to_register(0x20, 0x07).await?;
+        self.write_to_register(0x20, 0x06).await?;
+
+        // Download FW into VL53L5
+        for (page, start, end) in VL53L5CX_FIRMWARE_PAGES {
+            self.write_to_register(0x7fff, page).await?;
+            for offset in (start..end).step_by(VL53L5CX_TEMPORARY_BUFFER_SIZE) {
+                let write_size: usize = (end - offset).min(VL53L5CX_TEMPORARY_BUFFER_SIZE);
+                firmware.read(offset, &mut self.temp_buffer[..write_size]).map_err(|_| Error::FirmwareSource)?;
+                self.write_multi_to_register_temp_buffer((offset - start) as u16, write_size).await?;
+            }
+        }
+        self.write_to_register(0x7fff, 0x01).await?;
+
+        // Check if FW correctly downloaded
+        self.write_to_register(0x7fff, 0x02).await?;
+        self.write_to_register(0x03, 0x0D).await?;
+        self.write_to_register(0x7fff, 0x01).await?;
+        self.poll_for_answer(1, 0, 0x21, 0x10, 0x10).await?;
+
+        self.write_to_register(0x7fff, 0x00).await?;
+        self.read_from_register(0x7fff, 1).await?;
+        self.write_to_register(0x0C, 0x01).await?;
+
+        // Reset MCU and wait boot
+        self.write_to_register(0x7FFF, 0x00).await?;
+        self.write_to_register(0x114, 0x00).await?;
+        self.write_to_register(0x115, 0x00).await?;
+        self.write_to_register(0x116, 0x42).await?;
+        self.write_to_register(0x117, 0x00).await?;
+        self.write_to_register(0x0B, 0x00).await?;
+        self.read_from_register(0x7fff, 1).await?;
+        self.write_to_register(0x0C, 0x00).await?;
+        self.write_to_register(0x0B, 0x01).await?;
+        self.poll_for_mcu_boot().await?;
+
+        self.write_to_register(0x7fff, 0x02).await?;
+
+        // Get offset NVM data and store them into the offset buffer
+        self.write_multi_to_register(0x2fd8, &VL53L5CX_GET_NVM_CMD).await?;
+        self.wait_for_command_status(CommandStatus::NvmReady).await?;
+        self.read_from_register(VL53L5CX_UI_CMD_START, VL53L5CX_NVM_DATA_SIZE).await?;
+        self.offset_data.copy_from_slice(&self.temp_buffer[..VL53L5CX_OFFSET_BUFFER_SIZE]);
+        self.send_offset_data(VL53L5CX_RESOLUTION_4X4).await?;
+
+        // Set default Xtalk shape. Send Xtalk to sensor
+        self.xtalk_data.copy_from_slice(&VL53L5CX_DEFAULT_XTALK);
+        self.send_xtalk_data(VL53L5CX_RESOLUTION_4X4).await?;
+
+        // Send default configuration to VL53L5CX firmware
+        self.write_multi_to_register(0x2c34, &VL53L5CX_DEFAULT_CONFIGURATION).await?;
+        self.wait_for_command_status(CommandStatus::Done).await?;
+
+        self.temp_buffer[..4].copy_from_slice(&pipe_ctrl);
+        self.dci_write_data(VL53L5CX_DCI_PIPE_CONTROL, 4).await?;
+
+        if VL53L5CX_NB_TARGET_PER_ZONE != 1 {
+            self.dci_replace_data(VL53L5CX_DCI_FW_NB_TARGET, 16, &[VL53L5CX_NB_TARGET_PER_ZONE as u8], 0x0C).await?;
+        }
+
+        from_u32_to_u8(&single_range, &mut self.temp_buffer[..4]);
+        self.dci_write_data(VL53L5CX_DCI_SINGLE_RANGE, 4).await?;
+
+        self.dci_replace_data(VL53L5CX_GLARE_FILTER, 40, &[1], 0x26).await?;
+        self.dci_replace_data(VL53L5CX_GLARE_FILTER, 40, &[1], 0x25).await?;
+
+        Ok(())
+    }
+
+    /// This function gets the current resolution (4x4 or 8x8).
+    pub async fn resolution(&mut self) -> Result<Resolution, Error<I2C::Error>> {
+        self.dci_read_data(VL53L5CX_DCI_ZONE_CONFIG, 8).await?;
+        let resolution: u8 = self.temp_buffer[0x00] * self.temp_buffer[0x01];
+
+        Resolution::try_from(resolution).map_err(|value| Error::Parse(ParseError::UnexpectedValue(value)))
+    }
+
+    /// This function sets a new resolution (4x4 or 8x8). Unlike Vl53l5cx::set_resolution(),
+    /// the motion indicator map is not updated.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolution` : New resolution.
+    pub async fn set_resolution(&mut self, resolution: Resolution) -> Result<(), Error<I2C::Error>> {
+        let (dss_size, dss_step, zone_size, zone_step): (u8, u8, u8, u8) = match resolution {
+            Resolution::Res4x4 => (64, 4, 4, 8),
+            Resolution::Res8x8 => (16, 1, 8, 4)
+        };
+
+        self.dci_read_data(VL53L5CX_DCI_DSS_CONFIG, 16).await?;
+        self.temp_buffer[0x04] = dss_size;
+        self.temp_buffer[0x06] = dss_size;
+        self.temp_buffer[0x09] = dss_step;
+        self.dci_write_data(VL53L5CX_DCI_DSS_CONFIG, 16).await?;
+        self.dci_read_data(VL53L5CX_DCI_ZONE_CONFIG, 8).await?;
+        self.temp_buffer[0x00] = zone_size;
+        self.temp_buffer[0x01] = zone_size;
+        self.temp_buffer[0x04] = zone_step;
+        self.temp_buffer[0x05] = zone_step;
+        self.dci_write_data(VL53L5CX_DCI_ZONE_CONFIG, 8).await?;
+
+        self.send_offset_data(resolution.into()).await?;
+        self.send_xtalk_data(resolution.into()).await?;
+
+        Ok(())
+    }
+
+    /// This function gets the current ranging frequency in Hz.
+    pub async fn get_frequency_hz(&mut self) -> Result<u8, Error<I2C::Error>> {
+        self.dci_read_data(VL53L5CX_DCI_FREQ_HZ, 4).await?;
+
+        Ok(self.temp_buffer[0x01])
+    }
+
+    /// This function sets a new ranging frequency in Hz, within the limits of
+    /// Vl53l5cx::set_frequency_hz().
+    ///
+    /// # Arguments
+    ///
+    /// * `frequency_hz` : Ranging frequency in Hz.
+    pub async fn set_frequency_hz(&mut self, frequency_hz: u8) -> Result<(), Error<I2C::Error>> {
+        self.dci_replace_data(VL53L5CX_DCI_FREQ_HZ, 4, &[frequency_hz], 0x01).await
+    }
+
+    /// This function starts a ranging session, with the standard outputs which are
+    /// not removed by the VL53L5CX_DISABLE_* features.
+    /// An InvalidState error is returned if the sensor is already ranging.
+    pub async fn start_ranging(&mut self) -> Result<(), Error<I2C::Error>> {
+        let resolution: u8 = self.resolution().await?.into();
+        let mut output: [u32; NB_STANDARD_OUTPUTS + VL53L5CX_MAX_DEBUG_OUTPUTS] = [0; NB_STANDARD_OUTPUTS + VL53L5CX_MAX_DEBUG_OUTPUTS];
+        let mut output_bh_enable: [u32; 4] = output_enables(!cfg!(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"));
+        let mut tmp: [u16; 1] = [0];
+        let cmd: [u8; 4] = [0x00, 0x03, 0x00, 0x00];
+
+        if self.is_ranging {
+            return Err(Error::InvalidState);
+        }
+
+        self.streamcount = 255;
+        self.data_pending = false;
+        let (nb_outputs, data_read_size) = build_output_list(resolution, &mut output_bh_enable, &[], &mut output);
+        self.data_read_size = data_read_size;
+
+        from_u32_to_u8(&output[..nb_outputs], &mut self.temp_buffer[..4 * nb_outputs]);
+        self.dci_write_data(VL53L5CX_DCI_OUTPUT_LIST, 4 * nb_outputs).await?;
+
+        from_u32_to_u8(&[self.data_read_size, nb_outputs as u32 + 1], &mut self.temp_buffer[..8]);
+        self.dci_write_data(VL53L5CX_DCI_OUTPUT_CONFIG, 8).await?;
+
+        from_u32_to_u8(&output_bh_enable, &mut self.temp_buffer[..16]);
+        self.dci_write_data(VL53L5CX_DCI_OUTPUT_ENABLES, 16).await?;
+
+        // Start xshut bypass (interrupt mode)
+        self.write_to_register(0x7fff, 0x00).await?;
+        self.write_to_register(0x09, 0x05).await?;
+        self.write_to_register(0x7fff, 0x02).await?;
+
+        // Start ranging session
+        self.write_multi_to_register(VL53L5CX_UI_CMD_END - (4-1), &cmd).await?;
+        self.wait_for_command_status(CommandStatus::Done).await?;
+        self.is_ranging = true;
+
+        // Read ui range data content and compare if data size is the correct one
+        self.dci_read_data(0x5440, 12).await?;
+        from_u8_to_u16(&self.temp_buffer[0x8..0x8+2], &mut tmp);
+        if tmp[0] != self.data_read_size as u16 {
+            return Err(Error::SizeMismatch { expected: self.data_read_size as usize, got: tmp[0] as usize });
+        }
+
+        Ok(())
+    }
+
+    /// This function stops the ranging session.
+    /// An InvalidState error is returned if the sensor is not ranging.
+    pub async fn stop_ranging(&mut self) -> Result<(), Error<I2C::Error>> {
+        let mut auto_flag_stop: [u32; 1] = [0];
+
+        if !self.is_ranging {
+            return Err(Error::InvalidState);
+        }
+
+        self.read_from_register(0x2ffc, 4).await?;
+        from_u8_to_u32(&self.temp_buffer[..4], &mut auto_flag_stop);
+
+        if auto_flag_stop[0] != 0x4ff {
+            self.write_to_register(0x7fff, 0x00).await?;
+
+            // Provoke MCU stop
+            self.write_to_register(0x15, 0x16).await?;
+            self.write_to_register(0x14, 0x01).await?;
+
+            // Poll for G02 status 0 MCU stop
+            let mut timeout: u16 = 0;
+            while self.temp_buffer[0] & 0x80 >> 7 == 0x00 && timeout <= 500 {
+                self.read_from_register(0x6, 1).await?;
+                self.tim.delay_ms(10).await;
+                timeout += 1;
+            }
+        }
+
+        // Check GO2 status 1 if status is still OK
+        self.read_from_register(0x6, 1).await?;
+        if self.temp_buffer[0] & 0x80 != 0 {
+            self.read_from_register(0x7, 1).await?;
+            if self.temp_buffer[0] != 0x84 && self.temp_buffer[0] != 0x85 {
+                self.is_ranging = false;
+                return Ok(());
+            }
+        }
+
+        // Undo MCU stop
+        self.write_to_register(0x7fff, 0x00).await?;
+        self.write_to_register(0x14, 0x00).await?;
+        self.write_to_register(0x15, 0x00).await?;
+
+        // Stop xshut bypass
+        self.write_to_register(0x09, 0x04).await?;
+        self.write_to_register(0x7fff, 0x02).await?;
+        self.is_ranging = false;
+
+        Ok(())
+    }
+
+    /// This function checks if a new data is ready by polling I2C.
+    ///
+    /// # Return
+    ///
+    /// * `isReady` : Value is false if data is not ready, or true if a new data is ready.
+    pub async fn check_data_ready(&mut self) -> Result<bool, Error<I2C::Error>> {
+        self.read_from_register(0, 4).await?;
+        if self.temp_buffer[0] != self.streamcount && is_frame_complete(&self.temp_buffer[..4]) {
+            self.streamcount = self.temp_buffer[0];
+            self.data_pending = true;
+            return Ok(true);
+        }
+        if is_go2_error(&self.temp_buffer[..4]) {
+            return Err(Error::Go2);
+        }
+
+        Ok(false)
+    }
+
+    /// This function waits for a new frame, polling the data ready flag every
+    /// `period_us` microseconds, and yielding to the executor in between.
+    ///
+    /// # Arguments
+    ///
+    /// * `period_us` : Time between two polls, e.g. a tenth of the ranging period.
+    pub async fn wait_for_data(&mut self, period_us: u32) -> Result<(), Error<I2C::Error>> {
+        while !self.check_data_ready().await? {
+            self.tim.delay_us(period_us).await;
+        }
+
+        Ok(())
+    }
+
+    /// This function gets the ranging data. It must follow a data ready event
+    /// (check_data_ready(), wait_for_data() or INT pin),
+    /// Error::NotReady is returned if no new frame is pending.
+    ///
+    /// # Return
+    ///
+    /// * `results` : VL53L5 results structure.
+    pub async fn get_ranging_data(&mut self) -> Result<ResultsData, Error<I2C::Error>> {
+        // Without a frame reported by check_data_ready(), the UI status is checked 
+        // first, so that the full frame is only read when a new frame is pending 
+        if !self.data_pending {
+            self.read_from_register(0, 4).await?;
+            if is_go2_error(&self.temp_buffer[..4]) {
+                return Err(Error::Go2);
+            }
+            if self.temp_buffer[0] == self.streamcount || !is_frame_complete(&self.temp_buffer[..4]) {
+                return Err(Error::NotReady);
+            }
+        }
+        self.read_from_register(0, self.data_read_size as usize).await?;
+        if is_go2_error(&self.temp_buffer[..4]) {
+            self.data_pending = false;
+            return Err(Error::Go2);
+        }
+        let is_new: bool = self.data_pending || self.temp_buffer[0] != self.streamcount;
+        if !is_new || !is_frame_complete(&self.temp_buffer[..4]) {
+            return Err(Error::NotReady);
+        }
+        self.data_pending = false;
+        self.streamcount = self.temp_buffer[0];
+
+        parse_ranging_data_with_options(&mut self.temp_buffer, self.data_read_size as usize, &self.frame_layout, self.invalid_distance)
+    }
+
+    /// This function waits for the firmware command status to reach `status`,
+    /// as Vl53l5cx::wait_for_command_status() does.
+    pub async fn wait_for_command_status(&mut self, status: CommandStatus) -> Result<(), Error<I2C::Error>> {
+        let (pos, mask, expected_val) = status.decode();
+        self.poll_for_answer(4, pos, VL53L5CX_UI_CMD_STATUS, mask, expected_val).await
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function is used to wait for an answer from VL53L5CX sensor.
+    async fn poll_for_answer(&mut self, size: usize, pos: u8, reg: u16, mask: u8, expected_val: u8) -> Result<(), Error<I2C::Error>> {
+        let mut elapsed_us: u32 = 0;
+
+        while elapsed_us <= VL53L5CX_POLL_TIMEOUT_US {
+            self.read_from_register(reg, size).await?;
+            self.tim.delay_us(self.poll_interval_us).await;
+
+            if size >= 4 && self.temp_buffer[2] >= 0x7F {
+                return Err(Error::Mcu);
+            }
+            if self.temp_buffer[pos as usize] & mask == expected_val {
+                return Ok(());
+            }
+            elapsed_us += self.poll_interval_us;
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function is used to wait for the MCU to boot.
+    async fn poll_for_mcu_boot(&mut self) -> Result<(), Error<I2C::Error>> {
+        let mut elapsed_us: u32 = 0;
+
+        while elapsed_us <= VL53L5CX_BOOT_TIMEOUT_US {
+            self.read_from_register(0x06, 2).await?;
+            if self.temp_buffer[0] & 0x80 != 0 && self.temp_buffer[1] & 0x01 != 0 {
+                return Ok(());
+            }
+            self.tim.delay_us(VL53L5CX_BOOT_POLL_INTERVAL_US).await;
+            if self.temp_buffer[0] & 0x01 != 0 {
+                return Ok(());
+            }
+            elapsed_us += VL53L5CX_BOOT_POLL_INTERVAL_US;
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function sends the offset data, prepared by prepare_offset_data().
+    async fn send_offset_data(&mut self, resolution: u8) -> Result<(), Error<I2C::Error>> {
+        prepare_offset_data(&mut self.temp_buffer, &self.offset_data, resolution);
+
+        self.write_multi_to_register_temp_buffer(0x2E18, VL53L5CX_OFFSET_BUFFER_SIZE).await?;
+        self.wait_for_command_status(CommandStatus::Done).await
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function sends the Xtalk data, prepared by prepare_xtalk_data().
+    async fn send_xtalk_data(&mut self, resolution: u8) -> Result<(), Error<I2C::Error>> {
+        prepare_xtalk_data(&mut self.temp_buffer, &self.xtalk_data, resolution);
+
+        self.write_multi_to_register_temp_buffer(0x2CF8, VL53L5CX_XTALK_BUFFER_SIZE).await?;
+        self.wait_for_command_status(CommandStatus::Done).await
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function reads the DCI block `index` in the first `data_size` bytes of temp_buffer.
+    async fn dci_read_data(&mut self, index: u16, data_size: usize) -> Result<(), Error<I2C::Error>> {
+        let read_size: usize = data_size + DCI_HEADER_SIZE + DCI_FOOTER_SIZE;
+        if read_size > VL53L5CX_TEMPORARY_BUFFER_SIZE {
+            return Err(Error::BufferTooSmall);
+        }
+
+        // Request data reading from FW
+        self.write_multi_to_register(VL53L5CX_UI_CMD_END - 11, &read_command(index, data_size)).await?;
+        self.wait_for_command_status(CommandStatus::Done).await?;
+
+        // Read new data sent (4 bytes header + data_size + 8 bytes footer)
+        self.read_from_register(VL53L5CX_UI_CMD_START, read_size).await?;
+        unframe_read_answer(&mut self.temp_buffer, data_size)
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function writes the first `data_size` bytes of temp_buffer in the DCI block `index`.
+    async fn dci_write_data(&mut self, index: u16, data_size: usize) -> Result<(), Error<I2C::Error>> {
+        // Add headers and footer around the data, in FW format
+        let frame_size: usize = frame_write_command(&mut self.temp_buffer, index, data_size)?;
+        let address: u16 = VL53L5CX_UI_CMD_END - frame_size as u16 + 1;
+
+        // Send data to FW
+        let mut sent: Result<(), Error<I2C::Error>> = self.write_multi_to_register_temp_buffer(address, frame_size).await;
+        if sent.is_ok() {
+            sent = self.wait_for_command_status(CommandStatus::Done).await;
+        }
+        unframe_write_command(&mut self.temp_buffer, data_size);
+
+        sent
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function replaces `new_data` at `new_data_pos` in the DCI block `index`.
+    async fn dci_replace_data(&mut self, index: u16, data_size: usize, new_data: &[u8], new_data_pos: usize) -> Result<(), Error<I2C::Error>> {
+        self.dci_read_data(index, data_size).await?;
+        self.temp_buffer[new_data_pos..new_data_pos + new_data.len()].copy_from_slice(new_data);
+        self.dci_write_data(index, data_size).await
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function reads `size` bytes from `reg` into temp_buffer.
+    async fn read_from_register(&mut self, reg: u16, size: usize) -> Result<(), Error<I2C::Error>> {
+        if size > VL53L5CX_TEMPORARY_BUFFER_SIZE {
+            return Err(Error::BufferTooSmall);
+        }
+        for i in (0..size).step_by(I2C_CHUNK_SIZE) {
+            let read_size: usize = (size - i).min(I2C_CHUNK_SIZE);
+            let address: [u8; 2] = (reg + i as u16).to_be_bytes();
+            self.i2c.write_read(self.address, &address, &mut self.temp_buffer[i..i+read_size]).await.map_err(Error::Bus)?;
+        }
+
+        Ok(())
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function writes `val` in `reg`.
+    async fn write_to_register(&mut self, reg: u16, val: u8) -> Result<(), Error<I2C::Error>> {
+        let address: [u8; 2] = reg.to_be_bytes();
+        self.i2c.write(self.address, &[address[0], address[1], val]).await.map_err(Error::Bus)
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function writes `wbuf` in the registers starting from `reg`.
+    async fn write_multi_to_register(&mut self, reg: u16, wbuf: &[u8]) -> Result<(), Error<I2C::Error>> {
+        let mut tmp: [u8; I2C_CHUNK_SIZE] = [0; I2C_CHUNK_SIZE];
+        for i in (0..wbuf.len()).step_by(I2C_CHUNK_SIZE - 2) {
+            let write_size: usize = (wbuf.len() - i).min(I2C_CHUNK_SIZE - 2);
+            tmp[..2].copy_from_slice(&(reg + i as u16).to_be_bytes());
+            tmp[2..2+write_size].copy_from_slice(&wbuf[i..i+write_size]);
+            self.i2c.write(self.address, &tmp[..2+write_size]).await.map_err(Error::Bus)?;
+        }
+
+        Ok(())
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function writes the first `size` bytes of temp_buffer in the registers starting from `reg`.
+    async fn write_multi_to_register_temp_buffer(&mut self, reg: u16, size: usize) -> Result<(), Error<I2C::Error>> {
+        let mut tmp: [u8; I2C_CHUNK_SIZE] = [0; I2C_CHUNK_SIZE];
+        for i in (0..size).step_by(I2C_CHUNK_SIZE - 2) {
+            let write_size: usize = (size - i).min(I2C_CHUNK_SIZE - 2);
+            tmp[..2].copy_from_slice(&(reg + i as u16).to_be_bytes());
+            tmp[2..2+write_size].copy_from_slice(&self.temp_buffer[i..i+write_size]);
+            self.i2c.write(self.address, &tmp[..2+write_size]).await.map_err(Error::Bus)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// This function waits for a new frame on every sensor and reads it, as wait_for_data() 
+/// and get_ranging_data() do, with the sensors polled concurrently: a sensor waiting 
+/// for its frame does not delay the frames of the others.
+/// 
+/// # Arguments
+/// 
+/// * `sensors` : Sensors, ranging.
+/// * `period_us` : Time between two polls of a sensor, e.g. a tenth of the ranging period.
+/// 
+/// # Return
+/// 
+/// * `frames` : Frame of each sensor, or the error of its wait or read, 
+///   in the order of `sensors`.
+pub async fn collect_all<I2C: I2c, T: DelayNs, const N: usize>(sensors: &mut [Vl53l5cxAsync<I2C, T>; N], period_us: u32) -> [Result<ResultsData, Error<I2C::Error>>; N] {
+    let mut futures = pin!(sensors.each_mut().map(|sensor| async move {
+        sensor.wait_for_data(period_us).await?;
+        sensor.get_ranging_data().await
+    }));
+    let mut frames: [Option<Result<ResultsData, Error<I2C::Error>>>; N] = core::array::from_fn(|_| None);
+
+    poll_fn(|cx| {
+        let mut is_done: bool = true;
+        for (i, frame) in frames.iter_mut().enumerate() {
+            // A finished future is not polled again
+            if frame.is_some() {
+                continue;
+            }
+            // SAFETY: the futures are pinned in the array, and are never moved out of it
+            let future = unsafe { futures.as_mut().map_unchecked_mut(|futures| &mut futures[i]) };
+            match future.poll(cx) {
+                Poll::Ready(result) => *frame = Some(result),
+                Poll::Pending => is_done = false
+            }
+        }
+        if is_done { Poll::Ready(()) } else { Poll::Pending }
+    }).await;
+
+    // Every frame is set once poll_fn() is ready
+    frames.map(|frame| frame.unwrap_or(Err(Error::InvalidState)))
+}
@@ -0,0 +1,105 @@
+use consts::*;
+
+use crate::{consts, ResultsData, Resolution};
+
+/// Number of fractional bits of the background distances.
+const BACKGROUND_FRAC_BITS: u32 = 4;
+
+/// Additional adaptation shift used for the zones in the foreground, 
+/// so that static objects are slowly learnt as background (16 times slower).
+const FOREGROUND_EXTRA_SHIFT: u8 = 4;
+
+/// Structure BackgroundModel contains a per zone long-term average of the first 
+/// target distance, used to detect the targets in front of the static scene 
+/// (e.g. furniture). The average is an exponential moving average in fixed-point 
+/// (mm with 4 fractional bits), updated with a weight of 1/2^`adaptation_shift`. 
+/// Only the zones with a valid target (status 5 or 9) are used.
+#[derive(Copy, Clone, Debug)]
+pub struct BackgroundModel {
+    background: [i32; VL53L5CX_RESOLUTION_8X8 as usize],
+    learnt_mask: u64,
+    adaptation_shift: u8,
+    threshold_mm: u16,
+}
+
+impl BackgroundModel {
+    /// This function creates an empty background model.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `adaptation_shift` : Adaptation speed, the background moves by 1/2^`adaptation_shift` 
+    ///   of the difference at each frame (clamped to 0..=15). E.g. 6 adapts in about 64 frames.
+    /// * `threshold_mm` : Minimum distance in mm between a target and the background 
+    ///   for the zone to be in the foreground.
+    pub fn new(adaptation_shift: u8, threshold_mm: u16) -> Self {
+        BackgroundModel {
+            background: [0; VL53L5CX_RESOLUTION_8X8 as usize],
+            learnt_mask: 0,
+            adaptation_shift: adaptation_shift.min(15),
+            threshold_mm,
+        }
+    }
+
+    /// This function forgets the learnt background.
+    pub fn reset(&mut self) {
+        self.background = [0; VL53L5CX_RESOLUTION_8X8 as usize];
+        self.learnt_mask = 0;
+    }
+
+    /// This function gets the background distance of a zone.
+    /// 
+    /// # Return
+    /// 
+    /// * `distance_mm` : Background distance in mm, None if no valid target 
+    ///   was seen in this zone yet.
+    pub fn background_mm(&self, zone: usize) -> Option<i16> {
+        if zone >= VL53L5CX_RESOLUTION_8X8 as usize || self.learnt_mask & (1 << zone) == 0 {
+            return None;
+        }
+        Some((self.background[zone] >> BACKGROUND_FRAC_BITS) as i16)
+    }
+
+    /// This function updates the model with a new frame and computes the foreground mask. 
+    /// A zone is in the foreground when its target is closer than the background minus the 
+    /// threshold. The background of the foreground zones adapts 16 times slower, so that 
+    /// objects which stay still (e.g. a moved chair) become part of the background.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `results` : Results of the new frame.
+    /// * `resolution` : Resolution used for the frame.
+    /// 
+    /// # Return
+    /// 
+    /// * `foreground_mask` : Bit n is set if the zone n is in the foreground.
+    pub fn update(&mut self, results: &ResultsData, resolution: Resolution) -> u64 {
+        let mut foreground_mask: u64 = 0;
+
+        for zone in 0..resolution.nb_zones() {
+            let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+            let status: u8 = results.target_status[idx];
+            if status != 5 && status != 9 {
+                continue;
+            }
+            let distance: i32 = (results.distance_mm[idx] as i32) << BACKGROUND_FRAC_BITS;
+
+            if self.learnt_mask & (1 << zone) == 0 {
+                self.background[zone] = distance;
+                self.learnt_mask |= 1 << zone;
+                continue;
+            }
+
+            let mut shift: u8 = self.adaptation_shift;
+            if distance + ((self.threshold_mm as i32) << BACKGROUND_FRAC_BITS) < self.background[zone] {
+                foreground_mask |= 1 << zone;
+                shift += FOREGROUND_EXTRA_SHIFT;
+            }
+            let diff: i32 = distance - self.background[zone];
+            // Round towards the target, so that small differences are still learnt
+            let step: i32 = if diff >= 0 { (diff + (1 << shift) - 1) >> shift } else { -((-diff + (1 << shift) - 1) >> shift) };
+            self.background[zone] += step;
+        }
+
+        foreground_mask
+    }
+}
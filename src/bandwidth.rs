@@ -0,0 +1,137 @@
+use consts::*;
+
+use crate::{consts, Resolution};
+
+// Bus clocks of a register read transaction, besides the data bytes :
+// start, device address, 2 bytes register address, repeated start, device address, stop.
+const I2C_READ_OVERHEAD_CLOCKS: u32 = 1 + 9 + 18 + 1 + 9 + 1;
+
+// Bus clocks of a data byte, with its acknowledge bit.
+const I2C_BYTE_CLOCKS: u32 = 9;
+
+/// Structure BusBudget contains the I2C traffic needed to read the frames of
+/// a configuration, as computed by check_bus_budget().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BusBudget {
+  // Size of a frame in bytes, as programmed by start_ranging()
+    pub frame_size: usize,
+  // Bus clocks needed per second to poll and read the frames
+    pub required_hz: u32,
+  // Bus clock in Hz
+    pub available_hz: u32,
+}
+
+impl BusBudget {
+    /// This function gets the part of the bus clock used by the frames, in percent.
+    pub fn load_percent(&self) -> u32 {
+        if self.available_hz == 0 {
+            return u32::MAX;
+        }
+        (self.required_hz as u64 * 100 / self.available_hz as u64) as u32
+    }
+}
+
+/// This function computes the size of the frames read from the sensor, as done
+/// by start_ranging(). The start, metadata and common data blocks are always read.
+///
+/// # Arguments
+///
+/// * `resolution` : Resolution of the frames.
+/// * `nb_targets` : Number of targets per zone (1 to 4).
+/// * `outputs` : Enabled outputs, as a combination of macros VL53L5CX_OUTPUT_*.
+pub fn frame_size(resolution: Resolution, nb_targets: u8, outputs: u32) -> usize {
+    let nb_zones: usize = resolution.nb_zones();
+    let nb_per_target: usize = nb_zones * nb_targets as usize;
+    // Output, and size of its block data
+    let blocks: [(u32, usize); 9] = [
+        (VL53L5CX_OUTPUT_AMBIENT_PER_SPAD, 4 * nb_zones),
+        (VL53L5CX_OUTPUT_NB_SPADS_ENABLED, 4 * nb_zones),
+        (VL53L5CX_OUTPUT_NB_TARGET_DETECTED, nb_zones),
+        (VL53L5CX_OUTPUT_SIGNAL_PER_SPAD, 4 * nb_per_target),
+        (VL53L5CX_OUTPUT_RANGE_SIGMA_MM, 2 * nb_per_target),
+        (VL53L5CX_OUTPUT_DISTANCE_MM, 2 * nb_per_target),
+        (VL53L5CX_OUTPUT_REFLECTANCE_PERCENT, nb_per_target),
+        (VL53L5CX_OUTPUT_TARGET_STATUS, nb_per_target),
+        (VL53L5CX_OUTPUT_MOTION_INDICATOR, 140)
+    ];
+
+    // Start, metadata and common data blocks, and frame header and footer
+    let mut size: usize = 4 + (4 + 12) + (4 + 4) + 24;
+    for (output, block_size) in blocks {
+        if outputs & output != 0 {
+            size += 4 + block_size;
+        }
+    }
+
+    size
+}
+
+/// Optional outputs dropped by trim_outputs(), in this order : the reflectance, 
+/// the ambient rate and the range sigma are the least used by the applications.
+pub const VL53L5CX_TRIM_ORDER: [u32; 3] = [
+    VL53L5CX_OUTPUT_REFLECTANCE_PERCENT,
+    VL53L5CX_OUTPUT_AMBIENT_PER_SPAD,
+    VL53L5CX_OUTPUT_RANGE_SIGMA_MM
+];
+
+/// This function drops optional outputs, in the order of VL53L5CX_TRIM_ORDER, 
+/// until the frames fit in the bus budget (see check_bus_budget()).
+/// 
+/// # Arguments
+/// 
+/// * `bus_clock_hz` : I2C bus clock in Hz.
+/// * `resolution` : Resolution of the frames.
+/// * `nb_targets` : Number of targets per zone (1 to 4).
+/// * `frequency_hz` : Ranging frequency in Hz.
+/// * `outputs` : Wanted outputs, as a combination of macros VL53L5CX_OUTPUT_*.
+/// 
+/// # Return
+/// 
+/// * `outputs` : Outputs kept, or the budget of the trimmed outputs if they still do not fit.
+pub fn trim_outputs(bus_clock_hz: u32, resolution: Resolution, nb_targets: u8, frequency_hz: u8, outputs: u32) -> Result<u32, BusBudget> {
+    let mut kept: u32 = outputs;
+    let mut result: Result<BusBudget, BusBudget> = check_bus_budget(bus_clock_hz, resolution, nb_targets, frequency_hz, kept);
+    for output in VL53L5CX_TRIM_ORDER {
+        if result.is_ok() {
+            break;
+        }
+        kept &= !output;
+        result = check_bus_budget(bus_clock_hz, resolution, nb_targets, frequency_hz, kept);
+    }
+    result.map(|_| kept)
+}
+
+/// This function checks that the I2C bus can sustain the frames of a configuration.
+/// The bus clocks needed per frame are the ones of a data ready poll (4 bytes), and
+/// of the frame read, by chunks of 32 bytes. It is a lower bound, as the gaps between
+/// the transactions and the clock stretching are not counted.
+///
+/// # Arguments
+///
+/// * `bus_clock_hz` : I2C bus clock in Hz (e.g. 400000, or 1000000 in fast mode plus).
+/// * `resolution` : Resolution of the frames.
+/// * `nb_targets` : Number of targets per zone (1 to 4).
+/// * `frequency_hz` : Ranging frequency in Hz.
+/// * `outputs` : Enabled outputs, as a combination of macros VL53L5CX_OUTPUT_*.
+///
+/// # Return
+///
+/// * `budget` : Traffic of the configuration, as an error if it exceeds the bus clock.
+pub fn check_bus_budget(bus_clock_hz: u32, resolution: Resolution, nb_targets: u8, frequency_hz: u8, outputs: u32) -> Result<BusBudget, BusBudget> {
+    let frame_size: usize = frame_size(resolution, nb_targets, outputs);
+
+    let nb_chunks: u32 = frame_size.div_ceil(I2C_CHUNK_SIZE) as u32;
+    let frame_clocks: u32 = nb_chunks * I2C_READ_OVERHEAD_CLOCKS + frame_size as u32 * I2C_BYTE_CLOCKS;
+    let poll_clocks: u32 = I2C_READ_OVERHEAD_CLOCKS + 4 * I2C_BYTE_CLOCKS;
+
+    let budget: BusBudget = BusBudget {
+        frame_size,
+        required_hz: (frame_clocks + poll_clocks) * frequency_hz as u32,
+        available_hz: bus_clock_hz
+    };
+    if budget.required_hz > budget.available_hz {
+        return Err(budget);
+    }
+
+    Ok(budget)
+}
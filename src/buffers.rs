@@ -1,12 +1,16 @@
 use crate::VL53L5CX_XTALK_BUFFER_SIZE;
 use crate::VL53L5CX_CONFIGURATION_SIZE;
 use crate::VL53L5CX_NB_TARGET_PER_ZONE;
+use crate::VL53L5CX_FIRMWARE_SIZE;
 
 /// Inner internal number of targets.
 pub(crate) const VL53L5CX_FW_NBTAR_RANGING: u8 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 2 } else { VL53L5CX_NB_TARGET_PER_ZONE as u8 };
 
-/// This buffer contains the VL53L5CX firmware (MM1.8)
-pub(crate) const  VL53L5CX_FIRMWARE: [u8; 0x15000] = [
+/// This buffer contains the VL53L5CX firmware (MM1.8).
+/// It is a static, stored once in the binary, and placed in the link section
+/// .vl53l5cx_firmware with the feature firmware-link-section.
+#[cfg_attr(feature = "firmware-link-section", link_section = ".vl53l5cx_firmware")]
+pub(crate) static VL53L5CX_FIRMWARE: [u8; VL53L5CX_FIRMWARE_SIZE] = [
 	
 0xe0, 0x00, 0x03, 0x08,
 0xe0, 0x00, 0x0a, 0xc8,
@@ -21517,7 +21521,9 @@ pub(crate) const  VL53L5CX_FIRMWARE: [u8; 0x15000] = [
 ];
 
 /// This buffer contains the VL53L5CX default configuration.
-pub(crate) const VL53L5CX_DEFAULT_CONFIGURATION: [u8; VL53L5CX_CONFIGURATION_SIZE as usize] = [
+/// It is placed next to the firmware with the feature firmware-link-section.
+#[cfg_attr(feature = "firmware-link-section", link_section = ".vl53l5cx_firmware")]
+pub(crate) static VL53L5CX_DEFAULT_CONFIGURATION: [u8; VL53L5CX_CONFIGURATION_SIZE as usize] = [
 	0x54, 0x50, 0x00, 0x80,
 	0x00, 0x04, 0x04, 0x04,
 	0x00, 0x00, 0x08, 0x08,
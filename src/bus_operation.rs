@@ -1,5 +1,5 @@
 use consts::*;
-use crate::{consts, Vl53l5cx, Error, SevenBitAddress, I2c, OutputPin, DelayNs};
+use crate::{consts, Vl53l5cx, Error, SevenBitAddress, I2c, OutputPin, StatefulOutputPin, DelayNs, SpiDevice, SpiOperation};
 
 pub trait BusOperation {
     type Error;
@@ -44,6 +44,64 @@ impl<P: I2c> BusOperation for Vl53l5cxI2C<P> {
     }
 }
 
+/// Structure Vl53l5cxSpi is the SPI bus of the sensor, for the variants with a SPI
+/// interface and the level shifters bridging to SPI. The register address is sent
+/// first, MSB first, with VL53L5CX_SPI_WRITE_BIT set for the writes. The CS pin is
+/// driven by the SpiDevice, e.g. embedded-hal-bus ExclusiveDevice, which keeps it
+/// asserted for the whole transaction.
+pub struct Vl53l5cxSpi<S> {
+    spi: S,
+}
+
+impl<S: SpiDevice> Vl53l5cxSpi<S> {
+    pub(crate) fn new(spi: S) -> Self {
+        Vl53l5cxSpi { spi }
+    }
+}
+
+impl<S: SpiDevice> BusOperation for Vl53l5cxSpi<S> {
+    type Error = S::Error;
+
+    #[inline]
+    fn read(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.spi.read(rbuf)
+    }
+
+    #[inline]
+    fn write(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        let address: [u8; 2] = [wbuf[0] | VL53L5CX_SPI_WRITE_BIT, wbuf[1]];
+        self.spi.transaction(&mut [SpiOperation::Write(&address), SpiOperation::Write(&wbuf[2..])])
+    }
+
+    #[inline]
+    fn write_read(&mut self, wbuf: &[u8], rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        let address: [u8; 2] = [wbuf[0] & !VL53L5CX_SPI_WRITE_BIT, wbuf[1]];
+        self.spi.transaction(&mut [SpiOperation::Write(&address), SpiOperation::Read(rbuf)])
+    }
+}
+
+impl<S, LPN, RST, T> Vl53l5cx<Vl53l5cxSpi<S>, LPN, RST, T>
+    where
+    S: SpiDevice,
+    LPN: OutputPin,
+    RST: OutputPin,
+    T: DelayNs
+{
+    /// This function creates a driver on the SPI bus. The sensor has no address
+    /// on SPI, so it is initialized with off(), on() and init().
+    ///
+    /// # Arguments
+    ///
+    /// * `spi` : SPI device of the sensor, driving its CS pin.
+    /// * `lpn_pin` : LPn pin of the sensor.
+    /// * `i2c_rst_pin` : I2C reset pin of the sensor.
+    /// * `tim` : Delay provider.
+    pub fn new_spi(spi: S, lpn_pin: LPN, i2c_rst_pin: RST, tim: T) -> Result<Self, Error<S::Error>>
+    {
+        Ok(Vl53l5cx::with_bus(Vl53l5cxSpi::new(spi), lpn_pin, i2c_rst_pin, tim))
+    }
+}
+
 impl<P, LPN, RST, T> Vl53l5cx<Vl53l5cxI2C<P>, LPN, RST, T>
     where
     P: I2c,
@@ -53,28 +111,65 @@ impl<P, LPN, RST, T> Vl53l5cx<Vl53l5cxI2C<P>, LPN, RST, T>
 {
     pub fn new_i2c(i2c: P, lpn_pin: LPN, i2c_rst_pin: RST, tim: T) -> Result<Self, Error<P::Error>> 
     {
-        Ok(Vl53l5cx { 
-            temp_buffer: [0; VL53L5CX_TEMPORARY_BUFFER_SIZE],
-            offset_data: [0; VL53L5CX_OFFSET_BUFFER_SIZE],
-            xtalk_data: [0; VL53L5CX_XTALK_BUFFER_SIZE],
-            streamcount: 0,
-            data_read_size: 0,
-            is_auto_stop_enabled: false,
-            lpn_pin: lpn_pin,
-            i2c_rst_pin: i2c_rst_pin,
-            bus: Vl53l5cxI2C::new(i2c),
-            tim: tim,
-            chunk_size: I2C_CHUNK_SIZE
-        })
+        Ok(Vl53l5cx::with_bus(Vl53l5cxI2C::new(i2c), lpn_pin, i2c_rst_pin, tim))
     }
     
     pub fn set_i2c_address(&mut self, i2c_address: SevenBitAddress) -> Result<(), Error<P::Error>> {
-        self.write_to_register(0x7fff, 0x00)?;
-        self.write_to_register(0x4, i2c_address)?;
-        self.bus.address = i2c_address;
-        self.write_to_register(0x7fff, 0x02)?;
-        
-        Ok(())
+        self.with_bank(0x00, |s| {
+            s.write_to_register(0x4, i2c_address)?;
+            s.bus.address = i2c_address;
+            Ok(())
+        })
+    }
+    
+    /// This function changes the I2C address of the sensor, while holding the 
+    /// other sensors of the bus in LPn low, so that only this sensor answers 
+    /// at the current address during the change. The LPn pins which were high are 
+    /// set back to high afterwards, so the sensors powered down by the application 
+    /// stay powered down. There is no sensor array manager in this driver, 
+    /// so the LPn pins of the other sensors must be given by the application.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `i2c_address` : New I2C address.
+    /// * `other_lpn_pins` : LPn pins of the other sensors sharing the bus, 128 at most.
+    /// 
+    /// # Return
+    /// 
+    /// * `Error::InvalidParam` if more than 128 pins are given, and `Error::Pin` 
+    ///   if a pin could not be read or driven (the pins set low are set back to high).
+    pub fn set_i2c_address_exclusive<O: StatefulOutputPin>(&mut self, i2c_address: SevenBitAddress, other_lpn_pins: &mut [O]) -> Result<(), Error<P::Error>> {
+        let mut lowered: u128 = 0;
+        let mut result: Result<(), Error<P::Error>> = Ok(());
+
+        if other_lpn_pins.len() > 128 {
+            return Err(Error::InvalidParam);
+        }
+        for (i, pin) in other_lpn_pins.iter_mut().enumerate() {
+            match pin.is_set_high() {
+                Ok(true) if pin.set_low().is_ok() => lowered |= 1 << i,
+                Ok(false) => {}
+                _ => {
+                    result = Err(Error::Pin);
+                    break;
+                }
+            }
+        }
+        if result.is_ok() {
+            self.delay(10);
+            result = self.set_i2c_address(i2c_address);
+        }
+
+        for (i, pin) in other_lpn_pins.iter_mut().enumerate() {
+            if lowered & 1 << i != 0 && pin.set_high().is_err() && result.is_ok() {
+                result = Err(Error::Pin);
+            }
+        }
+        if lowered != 0 {
+            self.delay(10);
+        }
+
+        result
     }
     
     pub fn i2c_reset(&mut self) -> Result<(), Error<P::Error>> {
@@ -91,7 +186,7 @@ impl<P, LPN, RST, T> Vl53l5cx<Vl53l5cxI2C<P>, LPN, RST, T>
         if address != self.bus.address {
             self.set_i2c_address(address)?;
         }
-        self.is_alive()?;
+        self.probe_identity()?;
         self.init()?;
         Ok(())
     }
@@ -0,0 +1,125 @@
+use consts::*;
+
+use crate::{consts, ResultsData, Resolution};
+
+/// Number of frames used to find the height before a drop.
+const HEIGHT_HISTORY_SIZE: usize = 8;
+
+/// Structure FallEvent is returned by CeilingMount::update() when the highest 
+/// target dropped close to the floor, and stayed there for the dwell time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FallEvent {
+  // Highest target height before the drop, in mm above the floor
+    pub height_before_mm: i16,
+  // Highest target height at the end of the dwell time, in mm above the floor
+    pub height_after_mm: i16,
+}
+
+/// Structure CeilingMount processes the frames of a sensor mounted on the ceiling 
+/// and looking down, e.g. for fall detection prototypes. Distances are converted 
+/// to heights above the floor, assuming that the zones look straight down. 
+/// A fall is reported when the highest target drops by at least `drop_mm` within 
+/// a few frames, below `floor_height_mm`, and stays there for `dwell_frames` frames.
+#[derive(Copy, Clone, Debug)]
+pub struct CeilingMount {
+    mount_height_mm: i16,
+    drop_mm: i16,
+    floor_height_mm: i16,
+    dwell_frames: u16,
+    history: [i16; HEIGHT_HISTORY_SIZE],
+    nb_frames: usize,
+    drop_from: Option<i16>,
+    dwell: u16,
+}
+
+impl CeilingMount {
+    /// This function creates a ceiling mount processing.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mount_height_mm` : Height of the sensor above the floor in mm.
+    /// * `drop_mm` : Minimum height drop in mm to detect a fall.
+    /// * `floor_height_mm` : Maximum height in mm of the target after the fall.
+    /// * `dwell_frames` : Number of frames the target must stay below `floor_height_mm`.
+    pub fn new(mount_height_mm: i16, drop_mm: i16, floor_height_mm: i16, dwell_frames: u16) -> Self {
+        CeilingMount {
+            mount_height_mm,
+            drop_mm,
+            floor_height_mm,
+            dwell_frames,
+            history: [0; HEIGHT_HISTORY_SIZE],
+            nb_frames: 0,
+            drop_from: None,
+            dwell: 0,
+        }
+    }
+
+    /// This function converts the first target distance of each zone to a height 
+    /// above the floor. Zones without a valid target (status 5 or 9) are at height 0.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `results` : Results of the frame.
+    /// * `resolution` : Resolution used for the frame.
+    /// 
+    /// # Return
+    /// 
+    /// * `height_map` : Height of each zone in mm. Zones not present in the frame are left to 0.
+    pub fn height_map(&self, results: &ResultsData, resolution: Resolution) -> [i16; VL53L5CX_RESOLUTION_8X8 as usize] {
+        let mut height_map: [i16; VL53L5CX_RESOLUTION_8X8 as usize] = [0; VL53L5CX_RESOLUTION_8X8 as usize];
+
+        for (zone, height) in height_map.iter_mut().enumerate().take(resolution.nb_zones()) {
+            let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+            let status: u8 = results.target_status[idx];
+            if status == 5 || status == 9 {
+                *height = self.mount_height_mm.saturating_sub(results.distance_mm[idx]).max(0);
+            }
+        }
+
+        height_map
+    }
+
+    /// This function updates the fall detection with a new frame.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `results` : Results of the new frame.
+    /// * `resolution` : Resolution used for the frame.
+    /// 
+    /// # Return
+    /// 
+    /// * `event` : Some(FallEvent) at the end of the dwell time following a fall, None otherwise.
+    pub fn update(&mut self, results: &ResultsData, resolution: Resolution) -> Option<FallEvent> {
+        let height: i16 = self.height_map(results, resolution).iter().copied().max().unwrap_or(0);
+        let peak: i16 = self.history[..self.nb_frames.min(HEIGHT_HISTORY_SIZE)].iter().copied().max().unwrap_or(height);
+        self.history[self.nb_frames % HEIGHT_HISTORY_SIZE] = height;
+        self.nb_frames += 1;
+
+        match self.drop_from {
+            None => {
+                if height <= self.floor_height_mm && peak.saturating_sub(height) >= self.drop_mm {
+                    self.drop_from = Some(peak);
+                    self.dwell = 1;
+                }
+            }
+            Some(_) => {
+                if height > self.floor_height_mm {
+                    self.drop_from = None;
+                } else {
+                    self.dwell = self.dwell.saturating_add(1);
+                }
+            }
+        }
+
+        if let Some(height_before_mm) = self.drop_from {
+            if self.dwell >= self.dwell_frames {
+                // Forget the height before the fall, so that the event is reported once
+                self.drop_from = None;
+                self.history = [height; HEIGHT_HISTORY_SIZE];
+                return Some(FallEvent { height_before_mm, height_after_mm: height });
+            }
+        }
+
+        None
+    }
+}
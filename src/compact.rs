@@ -0,0 +1,98 @@
+use consts::*;
+
+use crate::{consts, Error, ResultsData, Resolution};
+
+/// Version of the compact encoding, stored in the high nibble of the first byte.
+const COMPACT_VERSION: u8 = 1;
+
+/// Maximum size in bytes of a compact frame (8x8).
+pub const COMPACT_FRAME_MAX_SIZE: usize = 2 + 2 * VL53L5CX_RESOLUTION_8X8 as usize;
+
+/// Structure CompactFrame contains the first target of each zone, as transmitted
+/// by encode_frame() and decode_frame(). The encoding is :
+/// - byte 0 : encoding version (high nibble), and resolution (low nibble, 0 for 4x4 and 1 for 8x8).
+/// - byte 1 : silicon temperature in degrees (i8).
+/// - 2 bytes per zone, little endian : distance in mm on 12 bits (clamped to 4095mm),
+///   and target status on the 4 high bits (15 when the status does not fit, e.g. 255 for no target).
+///
+/// A 4x4 frame uses 34 bytes, and a 8x8 frame 130 bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CompactFrame {
+  // Resolution of the frame
+    pub resolution: Resolution,
+  // Internal sensor silicon temperature
+    pub silicon_temp_degc: i8,
+  // Distance of the first target of each zone in mm
+    pub distance_mm: [u16; VL53L5CX_RESOLUTION_8X8 as usize],
+  // Status of the first target of each zone, 15 if the status does not fit in 4 bits
+    pub target_status: [u8; VL53L5CX_RESOLUTION_8X8 as usize]
+}
+
+/// This function encodes the first target of each zone of a frame into `out`.
+///
+/// # Arguments
+///
+/// * `results` : Results of the frame.
+/// * `resolution` : Resolution used for the frame.
+/// * `out` : Output buffer, of at least COMPACT_FRAME_MAX_SIZE bytes for 8x8.
+///
+/// # Return
+///
+/// * `size` : Number of bytes written in `out`.
+pub fn encode_frame<E>(results: &ResultsData, resolution: Resolution, out: &mut [u8]) -> Result<usize, Error<E>> {
+    let nb_zones: usize = resolution.nb_zones();
+    let size: usize = 2 + 2 * nb_zones;
+    if out.len() < size {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let resolution_code: u8 = if resolution == Resolution::Res8x8 { 1 } else { 0 };
+    out[0] = COMPACT_VERSION << 4 | resolution_code;
+    out[1] = results.silicon_temp_degc as u8;
+    for zone in 0..nb_zones {
+        let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        let distance_mm: u16 = results.distance_mm[idx].clamp(0, 0x0FFF) as u16;
+        let status: u16 = results.target_status[idx].min(15) as u16;
+        out[2 + 2*zone..4 + 2*zone].copy_from_slice(&(distance_mm | status << 12).to_le_bytes());
+    }
+
+    Ok(size)
+}
+
+/// This function decodes a frame encoded with encode_frame().
+///
+/// # Arguments
+///
+/// * `input` : Encoded frame.
+///
+/// # Return
+///
+/// * `frame` : Decoded frame. Zones not present in the frame are left to zero.
+pub fn decode_frame<E>(input: &[u8]) -> Result<CompactFrame, Error<E>> {
+    if input.len() < 2 || input[0] >> 4 != COMPACT_VERSION {
+        return Err(Error::CorruptedFrame);
+    }
+    let resolution: Resolution = match input[0] & 0x0F {
+        0 => Resolution::Res4x4,
+        1 => Resolution::Res8x8,
+        _ => return Err(Error::CorruptedFrame)
+    };
+    let nb_zones: usize = resolution.nb_zones();
+    if input.len() != 2 + 2 * nb_zones {
+        return Err(Error::CorruptedFrame);
+    }
+
+    let mut frame: CompactFrame = CompactFrame {
+        resolution,
+        silicon_temp_degc: input[1] as i8,
+        distance_mm: [0; VL53L5CX_RESOLUTION_8X8 as usize],
+        target_status: [0; VL53L5CX_RESOLUTION_8X8 as usize]
+    };
+    for zone in 0..nb_zones {
+        let value: u16 = u16::from_le_bytes([input[2 + 2*zone], input[3 + 2*zone]]);
+        frame.distance_mm[zone] = value & 0x0FFF;
+        frame.target_status[zone] = (value >> 12) as u8;
+    }
+
+    Ok(frame)
+}
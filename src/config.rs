@@ -0,0 +1,287 @@
+use consts::*;
+
+use crate::{consts, VL53L5CX_DEFAULT_CONFIGURATION, VL53L5CX_DEFAULT_XTALK};
+
+/// Enum Resolution contains the available resolutions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    Res4x4,
+    Res8x8
+}
+
+impl Resolution {
+    /// This function gets the number of zones of the resolution.
+    pub fn nb_zones(self) -> usize {
+        u8::from(self) as usize
+    }
+}
+
+impl From<Resolution> for u8 {
+    fn from(resolution: Resolution) -> u8 {
+        match resolution {
+            Resolution::Res4x4 => VL53L5CX_RESOLUTION_4X4,
+            Resolution::Res8x8 => VL53L5CX_RESOLUTION_8X8
+        }
+    }
+}
+
+impl TryFrom<u8> for Resolution {
+    type Error = u8;
+
+    fn try_from(resolution: u8) -> Result<Self, u8> {
+        match resolution {
+            VL53L5CX_RESOLUTION_4X4 => Ok(Resolution::Res4x4),
+            VL53L5CX_RESOLUTION_8X8 => Ok(Resolution::Res8x8),
+            _ => Err(resolution)
+        }
+    }
+}
+
+/// Enum RangingMode contains the available ranging modes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RangingMode {
+    Continuous,
+    Autonomous
+}
+
+impl From<RangingMode> for u8 {
+    fn from(ranging_mode: RangingMode) -> u8 {
+        match ranging_mode {
+            RangingMode::Continuous => VL53L5CX_RANGING_MODE_CONTINUOUS,
+            RangingMode::Autonomous => VL53L5CX_RANGING_MODE_AUTONOMOUS
+        }
+    }
+}
+
+impl TryFrom<u8> for RangingMode {
+    type Error = u8;
+
+    fn try_from(ranging_mode: u8) -> Result<Self, u8> {
+        match ranging_mode {
+            VL53L5CX_RANGING_MODE_CONTINUOUS => Ok(RangingMode::Continuous),
+            VL53L5CX_RANGING_MODE_AUTONOMOUS => Ok(RangingMode::Autonomous),
+            _ => Err(ranging_mode)
+        }
+    }
+}
+
+/// Enum TargetOrder contains the available target orders.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TargetOrder {
+    Closest,
+    Strongest
+}
+
+impl From<TargetOrder> for u8 {
+    fn from(target_order: TargetOrder) -> u8 {
+        match target_order {
+            TargetOrder::Closest => VL53L5CX_TARGET_ORDER_CLOSEST,
+            TargetOrder::Strongest => VL53L5CX_TARGET_ORDER_STRONGEST
+        }
+    }
+}
+
+impl TryFrom<u8> for TargetOrder {
+    type Error = u8;
+
+    fn try_from(target_order: u8) -> Result<Self, u8> {
+        match target_order {
+            VL53L5CX_TARGET_ORDER_CLOSEST => Ok(TargetOrder::Closest),
+            VL53L5CX_TARGET_ORDER_STRONGEST => Ok(TargetOrder::Strongest),
+            _ => Err(target_order)
+        }
+    }
+}
+
+/// Enum PowerMode contains the available power modes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PowerMode {
+    Sleep,
+    Wakeup
+}
+
+impl From<PowerMode> for u8 {
+    fn from(power_mode: PowerMode) -> u8 {
+        match power_mode {
+            PowerMode::Sleep => VL53L5CX_POWER_MODE_SLEEP,
+            PowerMode::Wakeup => VL53L5CX_POWER_MODE_WAKEUP
+        }
+    }
+}
+
+impl TryFrom<u8> for PowerMode {
+    type Error = u8;
+
+    fn try_from(power_mode: u8) -> Result<Self, u8> {
+        match power_mode {
+            VL53L5CX_POWER_MODE_SLEEP => Ok(PowerMode::Sleep),
+            VL53L5CX_POWER_MODE_WAKEUP => Ok(PowerMode::Wakeup),
+            _ => Err(power_mode)
+        }
+    }
+}
+
+/// Enum InitStrategy contains the ways init() handles the firmware, see set_init_strategy().
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum InitStrategy {
+    /// The sensor is rebooted and the firmware is uploaded at each init() (default).
+    #[default]
+    Always,
+    /// The firmware upload is skipped when the firmware of the driver is already running, 
+    /// e.g. after a reset of the host which kept the sensor powered. The configuration 
+    /// is still set back to the default one.
+    IfNeeded
+}
+
+/// Structure PowerTimings contains the waits of the power sequence of on() and off(),
+/// see set_power_timings(). The datasheet requires AVDD and IOVDD to be stable before
+/// LPn goes high, and the boot of the sensor to be over before the first I2C access.
+/// The defaults follow ST's reference platform code, which waits 100 ms after
+/// switching the supplies and LPn, with margin for the slow ramps and boot at
+/// temperature extremes. The deviations from it are:
+/// - LPn is set high after the supplies, instead of at the same time.
+/// - LPn is held low for `lpn_low_us` even when the supplies are not switched, so
+///   that a quick off() / on() still resets the sensor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PowerTimings {
+  // Wait between the supplies switched on and LPn set high, in us
+    pub supply_settle_us: u32,
+  // Wait between LPn set high and the first I2C access (boot of the sensor), in us
+    pub boot_us: u32,
+  // Minimum low pulse of LPn, before the supplies are switched off, in us
+    pub lpn_low_us: u32,
+  // Wait after the supplies are switched off, so that they discharge before the next on(), in us
+    pub discharge_us: u32
+}
+
+impl PowerTimings {
+    pub const fn new() -> Self {
+        PowerTimings {
+            supply_settle_us: VL53L5CX_SUPPLY_SETTLE_US,
+            boot_us: VL53L5CX_BOOT_US,
+            lpn_low_us: VL53L5CX_LPN_LOW_US,
+            discharge_us: VL53L5CX_DISCHARGE_US
+        }
+    }
+}
+
+impl Default for PowerTimings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Enum InvalidDistance contains the values which can be given to the distance 
+/// of the targets without a valid measurement (negative distance, or target 
+/// not detected in the zone), see set_invalid_distance().
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum InvalidDistance {
+    /// Negative distances are clamped to 0, as the ST driver does (default). 
+    /// Not detected targets keep the distance reported by the firmware.
+    #[default]
+    Zero,
+    /// Invalid distances are set to i16::MIN.
+    Min,
+    /// Invalid distances are set to i16::MAX, i.e. farther than any target.
+    Max
+}
+
+/// Firmware version expected by the driver, i.e. the version of the firmware 
+/// uploaded by init() from buffers.rs.
+pub const VL53L5CX_EXPECTED_FW_VERSION: FwVersion = FwVersion { major: 1, minor: 3, build: 11 };
+
+/// Structure FwVersion contains the version of the firmware running on the sensor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FwVersion {
+  // Major version
+    pub major: u8,
+  // Minor version
+    pub minor: u8,
+  // Build number
+    pub build: u16,
+}
+
+impl FwVersion {
+    /// This function decodes the firmware version word read from the sensor : 
+    /// major (bits 31..24), minor (bits 23..16) and build (bits 15..0).
+    pub fn from_raw(raw: u32) -> Self {
+        FwVersion {
+            major: (raw >> 24) as u8,
+            minor: (raw >> 16) as u8,
+            build: raw as u16
+        }
+    }
+
+    /// This function checks that the version is the one of the firmware blob of the 
+    /// driver, so that the host can warn when they mismatch.
+    pub fn is_expected(&self) -> bool {
+        *self == VL53L5CX_EXPECTED_FW_VERSION
+    }
+}
+
+/// Structure VariantBlobs contains the firmware and the default buffers of a sensor
+/// sharing the VL53L5CX register map and ULD flow, e.g. the VL53L7CX. They are
+/// distributed by ST with the ULD of the part, and are not embedded in the driver.
+#[derive(Copy, Clone, Debug)]
+pub struct VariantBlobs {
+  // Firmware uploaded by init(), VL53L5CX_FIRMWARE_SIZE bytes split in the same 3 pages
+    pub firmware: &'static [u8],
+  // Default configuration written by init() (e.g. the 90° FoV configuration of the VL53L7CX)
+    pub default_configuration: &'static [u8; VL53L5CX_CONFIGURATION_SIZE],
+  // Default Xtalk data sent by init()
+    pub default_xtalk: &'static [u8; VL53L5CX_XTALK_BUFFER_SIZE],
+  // Version reported by the firmware, checked by InitStrategy::IfNeeded
+    pub fw_version: FwVersion
+}
+
+/// Enum SensorVariant contains the parts driven by the driver, see set_sensor_variant().
+/// The init and ranging state machine is the same for all of them, only the firmware
+/// and the default configuration and Xtalk data change.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum SensorVariant {
+    /// VL53L5CX, with the firmware and buffers embedded in the driver (default).
+    #[default]
+    Vl53l5cx,
+    /// VL53L7CX (90° FoV), with the firmware and buffers of its ULD.
+    Vl53l7cx(VariantBlobs),
+    /// VL53L8CX, with the firmware and buffers of its ULD, driven with the VL53L5CX init 
+    /// flow: the firmware must be VL53L5CX_FIRMWARE_SIZE bytes in the same 3 pages, and the 
+    /// firmware paging and power modes specific to the VL53L8CX are not supported. It also 
+    /// has a SPI interface (see new_spi()).
+    Vl53l8cx(VariantBlobs)
+}
+
+impl SensorVariant {
+    /// This function gets the default configuration written by init().
+    pub fn default_configuration(&self) -> &'static [u8; VL53L5CX_CONFIGURATION_SIZE] {
+        match self {
+            SensorVariant::Vl53l5cx => &VL53L5CX_DEFAULT_CONFIGURATION,
+            SensorVariant::Vl53l7cx(blobs) | SensorVariant::Vl53l8cx(blobs) => blobs.default_configuration
+        }
+    }
+
+    /// This function gets the default Xtalk data sent by init().
+    pub fn default_xtalk(&self) -> &'static [u8; VL53L5CX_XTALK_BUFFER_SIZE] {
+        match self {
+            SensorVariant::Vl53l5cx => &VL53L5CX_DEFAULT_XTALK,
+            SensorVariant::Vl53l7cx(blobs) | SensorVariant::Vl53l8cx(blobs) => blobs.default_xtalk
+        }
+    }
+
+    /// This function gets the version of the firmware uploaded by init().
+    pub fn expected_fw_version(&self) -> FwVersion {
+        match self {
+            SensorVariant::Vl53l5cx => VL53L5CX_EXPECTED_FW_VERSION,
+            SensorVariant::Vl53l7cx(blobs) | SensorVariant::Vl53l8cx(blobs) => blobs.fw_version
+        }
+    }
+
+    /// This function gets the revision id checked by probe_identity(). The device id 
+    /// is 0xF0 for all the variants.
+    pub fn expected_revision_id(&self) -> u8 {
+        match self {
+            SensorVariant::Vl53l5cx | SensorVariant::Vl53l7cx(_) => 0x02,
+            SensorVariant::Vl53l8cx(_) => 0x0C
+        }
+    }
+}
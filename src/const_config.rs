@@ -0,0 +1,110 @@
+use core::marker::PhantomData;
+
+use consts::*;
+
+use crate::{consts, check_timing, VL53L5CX_MIN_INTEGRATION_TIME_MS, VL53L5CX_MAX_INTEGRATION_TIME_MS, BusOperation, Error, RangingMode, Resolution, TargetOrder, Vl53l5cx, OutputPin, DelayNs};
+
+/// Structure SensorConfig contains a full ranging configuration. It can be
+/// defined as a const in flash, and applied with apply_const_config(), see ConstConfig.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SensorConfig {
+  // Resolution of the frames
+    pub resolution: Resolution,
+  // Ranging mode
+    pub ranging_mode: RangingMode,
+  // Ranging frequency in Hz
+    pub frequency_hz: u8,
+  // Integration time in ms, only used in autonomous mode
+    pub integration_time_ms: u32,
+  // Sharpener in percent, between 0 (disabled) and 99
+    pub sharpener_percent: u8,
+  // Order of the targets in the zones
+    pub target_order: TargetOrder,
+}
+
+impl SensorConfig {
+    /// This function checks the configuration : the frequency must be reachable
+    /// with the resolution, ranging mode and integration time (see check_timing()),
+    /// the integration time must be between 2ms and 1000ms, even in continuous mode, 
+    /// and the sharpener must be lower than 100%. It can be evaluated at compile time.
+    pub const fn is_valid(&self) -> bool {
+        check_timing(self.resolution, self.ranging_mode, self.frequency_hz, self.integration_time_ms).feasible
+            && self.integration_time_ms >= VL53L5CX_MIN_INTEGRATION_TIME_MS
+            && self.integration_time_ms <= VL53L5CX_MAX_INTEGRATION_TIME_MS
+            && self.sharpener_percent < 100
+    }
+}
+
+/// Trait ConstConfig gives a SensorConfig known at compile time. The configuration
+/// is checked, and its DCI payloads are computed, when apply_const_config() is compiled,
+/// so an invalid configuration is a build error and no RAM is used for it.
+///
+/// ```ignore
+/// struct Tracking;
+/// impl ConstConfig for Tracking {
+///     const CONFIG: SensorConfig = SensorConfig { resolution: Resolution::Res8x8, ... };
+/// }
+/// sensor.apply_const_config::<Tracking>()?;
+/// ```
+pub trait ConstConfig {
+    const CONFIG: SensorConfig;
+}
+
+/// Structure ConstPayloads contains the DCI payloads of a ConstConfig, evaluated at compile time.
+struct ConstPayloads<C>(PhantomData<C>);
+
+impl<C: ConstConfig> ConstPayloads<C> {
+    const VALID: () = assert!(C::CONFIG.is_valid(), "invalid SensorConfig");
+
+    const RESOLUTION: u8 = match C::CONFIG.resolution {
+        Resolution::Res4x4 => VL53L5CX_RESOLUTION_4X4,
+        Resolution::Res8x8 => VL53L5CX_RESOLUTION_8X8
+    };
+
+    // Bytes 1 and 3 of the ranging mode, and single range flag
+    const RANGING_MODE: [u8; 2] = match C::CONFIG.ranging_mode {
+        RangingMode::Continuous => [1, 3],
+        RangingMode::Autonomous => [3, 2]
+    };
+    const SINGLE_RANGE: [u8; 4] = match C::CONFIG.ranging_mode {
+        RangingMode::Continuous => 0u32.to_le_bytes(),
+        RangingMode::Autonomous => 1u32.to_le_bytes()
+    };
+
+    const INTEGRATION_TIME: [u8; 4] = (C::CONFIG.integration_time_ms * 1000).to_le_bytes();
+
+    const SHARPENER: u8 = (C::CONFIG.sharpener_percent as u32 * 255 / 100) as u8;
+
+    const TARGET_ORDER: u8 = match C::CONFIG.target_order {
+        TargetOrder::Closest => VL53L5CX_TARGET_ORDER_CLOSEST,
+        TargetOrder::Strongest => VL53L5CX_TARGET_ORDER_STRONGEST
+    };
+}
+
+impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
+    /// This function applies a configuration known at compile time (see ConstConfig).
+    /// It does the same as the setters, with payloads computed at compile time. The
+    /// resolution is set first, as the frequency depends on it. The sensor must not be ranging.
+    pub fn apply_const_config<C: ConstConfig>(&mut self) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        let () = ConstPayloads::<C>::VALID;
+
+        self.set_resolution(ConstPayloads::<C>::RESOLUTION)?;
+
+        self.dci_read_data(VL53L5CX_DCI_RANGING_MODE, 8)?;
+        self.temp_buffer[1] = ConstPayloads::<C>::RANGING_MODE[0];
+        self.temp_buffer[3] = ConstPayloads::<C>::RANGING_MODE[1];
+        self.dci_write_data(VL53L5CX_DCI_RANGING_MODE, 8)?;
+        self.temp_buffer[..4].copy_from_slice(&ConstPayloads::<C>::SINGLE_RANGE);
+        self.dci_write_data(VL53L5CX_DCI_SINGLE_RANGE, 4)?;
+
+        self.dci_replace_data(VL53L5CX_DCI_FREQ_HZ, 4, &[C::CONFIG.frequency_hz], 1, 0x01)?;
+        self.dci_replace_data(VL53L5CX_DCI_INT_TIME, 20, &ConstPayloads::<C>::INTEGRATION_TIME, 4, 0x00)?;
+        self.dci_replace_data(VL53L5CX_DCI_SHARPENER, 16, &[ConstPayloads::<C>::SHARPENER], 1, 0xd)?;
+        self.dci_replace_data(VL53L5CX_DCI_TARGET_ORDER, 4, &[ConstPayloads::<C>::TARGET_ORDER], 1, 0x0)?;
+
+        Ok(())
+    }
+}
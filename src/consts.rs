@@ -4,6 +4,10 @@ use embedded_hal::i2c::SevenBitAddress;
 // Can be changed using function set_i2c_address() function is called.
 pub const VL53L5CX_DEFAULT_I2C_ADDRESS: SevenBitAddress = 0x52 >> 1;
 
+// Bit set in the MSB of the register address of the SPI writes,
+// cleared for the SPI reads.
+pub const VL53L5CX_SPI_WRITE_BIT: u8 = 0x80;
+
 // Macro VL53L5CX_RESOLUTION_4X4 or VL53L5CX_RESOLUTION_8X8 allows
 // setting sensor in 4x4 mode or 8x8 mode, 
 // using function set_resolution().
@@ -26,8 +30,8 @@ pub(crate) const VL53L5CX_START_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 {
 pub(crate) const VL53L5CX_METADATA_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0x54B400C0 } else { 0x54B400C0 };
 pub(crate) const VL53L5CX_COMMONDATA_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0x54C00040 } else { 0x54C00040 };
 pub(crate) const VL53L5CX_AMBIENT_RATE_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0x54D00104 } else { 0x54D00104 };
-pub(crate) const VL53L5CX_SPAD_COUNT_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0x55D00404 } else { 0x57D00401 };
-pub(crate) const VL53L5CX_NB_TARGET_DETECTED_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xDB840401 } else { 0x55D00404 };
+pub(crate) const VL53L5CX_SPAD_COUNT_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0x55D00404 } else { 0x55D00404 };
+pub(crate) const VL53L5CX_NB_TARGET_DETECTED_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xDB840401 } else { 0x57D00401 };
 pub(crate) const VL53L5CX_SIGNAL_RATE_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xDBC40404 } else { 0x58900404 };
 pub(crate) const VL53L5CX_RANGE_SIGMA_MM_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xDEC40402 } else { 0x64900402 };
 pub(crate) const VL53L5CX_DISTANCE_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xDF440402 } else { 0x66900402 };
@@ -35,7 +39,25 @@ pub(crate) const VL53L5CX_REFLECTANCE_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE =
 pub(crate) const VL53L5CX_TARGET_STATUS_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xE0840401 } else { 0x6B900401 };
 pub(crate) const VL53L5CX_MOTION_DETECT_BH: u32 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xD85808C0 } else { 0xCC5008C0 };
 
+// Macros VL53L5CX_OUTPUT_* are used to identify the outputs of a frame. 
+// They match the bits of the output enables programmed by start_ranging().
+pub const VL53L5CX_OUTPUT_METADATA: u32 = 1 << 1;
+pub const VL53L5CX_OUTPUT_COMMONDATA: u32 = 1 << 2;
+pub const VL53L5CX_OUTPUT_AMBIENT_PER_SPAD: u32 = 1 << 3;
+pub const VL53L5CX_OUTPUT_NB_SPADS_ENABLED: u32 = 1 << 4;
+pub const VL53L5CX_OUTPUT_NB_TARGET_DETECTED: u32 = 1 << 5;
+pub const VL53L5CX_OUTPUT_SIGNAL_PER_SPAD: u32 = 1 << 6;
+pub const VL53L5CX_OUTPUT_RANGE_SIGMA_MM: u32 = 1 << 7;
+pub const VL53L5CX_OUTPUT_DISTANCE_MM: u32 = 1 << 8;
+pub const VL53L5CX_OUTPUT_REFLECTANCE_PERCENT: u32 = 1 << 9;
+pub const VL53L5CX_OUTPUT_TARGET_STATUS: u32 = 1 << 10;
+pub const VL53L5CX_OUTPUT_MOTION_INDICATOR: u32 = 1 << 11;
+
+// Macro VL53L5CX_METADATA_SIZE is the size in bytes of the metadata block.
+pub const VL53L5CX_METADATA_SIZE: usize = 12;
+
 pub(crate) const VL53L5CX_METADATA_IDX: u16 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0x54B4 } else { 0x54B4 };
+pub(crate) const VL53L5CX_COMMONDATA_IDX: u16 = 0x54C0;
 pub(crate) const VL53L5CX_SPAD_COUNT_IDX: u16 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0x55D0 } else { 0x55D0 };
 pub(crate) const VL53L5CX_AMBIENT_RATE_IDX: u16 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0x54D0 } else { 0x54D0 };
 pub(crate) const VL53L5CX_NB_TARGET_DETECTED_IDX: u16 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xDB84 } else { 0x57D0 };
@@ -47,9 +69,9 @@ pub(crate) const VL53L5CX_TARGET_STATUS_IDX: u16 = if VL53L5CX_NB_TARGET_PER_ZON
 pub(crate) const VL53L5CX_MOTION_DETEC_IDX: u16 = if VL53L5CX_NB_TARGET_PER_ZONE == 1 { 0xD858 } else { 0xCC50 };
 
 pub(crate) const VL53L5CX_NVM_DATA_SIZE: usize = 492;
-pub(crate) const VL53L5CX_CONFIGURATION_SIZE: usize = 972;
+pub const VL53L5CX_CONFIGURATION_SIZE: usize = 972;
 pub(crate) const VL53L5CX_OFFSET_BUFFER_SIZE: usize = 488;
-pub(crate) const VL53L5CX_XTALK_BUFFER_SIZE: usize = 776;
+pub const VL53L5CX_XTALK_BUFFER_SIZE: usize = 776;
 
 // Macro VL53L5CX_TARGET_ORDER_STRONGEST or VL53L5CX_TARGET_ORDER_CLOSEST
 // are used to select the target order for data output.
@@ -70,10 +92,11 @@ pub const VL53L5CX_POWER_MODE_SLEEP: u8 = 0;
 pub const VL53L5CX_POWER_MODE_WAKEUP: u8 = 1;
 
 // Inner Macro for API. Not for user, only for development.
+pub(crate) const VL53L5CX_DCI_FW_VERSION: u16 = 0x5440;
 pub(crate) const VL53L5CX_DCI_ZONE_CONFIG: u16 = 0x5450;
 pub(crate) const VL53L5CX_DCI_FREQ_HZ: u16 = 0x5458;
 pub(crate) const VL53L5CX_DCI_INT_TIME: u16 = 0x545C;
-pub(crate) const VL53L5CX_DCI_FW_NB_TARGET: u16 = 0x547;
+pub(crate) const VL53L5CX_DCI_FW_NB_TARGET: u16 = 0x5478;
 pub(crate) const VL53L5CX_DCI_RANGING_MODE: u16 = 0xAD30;
 pub(crate) const VL53L5CX_DCI_DSS_CONFIG: u16 = 0xAD38;
 pub(crate) const VL53L5CX_DCI_VHV_CONFIG: u16 = 0xAD60;
@@ -127,12 +150,55 @@ if VL53L5CX_MAX_RESULTS_SIZE < 1024 {
 pub(crate) const VL53L5CX_USE_RAW_FORMAT: u8 = 0;
 
 // The macro below is used to define the number of target per zone sent through I2C. 
-// This value can be changed by user, in order to tune I2C transaction, and also the total memory size (a lower number of target per zone means a lower RAM). The value must be between 1 and 4.
-pub const VL53L5CX_NB_TARGET_PER_ZONE: u32 = 1;
+// This value can be changed by user with features nb_targets_2, nb_targets_3 or nb_targets_4, in order to tune I2C transaction, and also the total memory size (a lower number of target per zone means a lower RAM). The value must be between 1 and 4.
+pub const VL53L5CX_NB_TARGET_PER_ZONE: u32 = 
+if cfg!(feature = "nb_targets_4") {
+    4
+} else if cfg!(feature = "nb_targets_3") {
+    3
+} else if cfg!(feature = "nb_targets_2") {
+    2
+} else {
+    1
+};
+
+#[cfg(any(
+    all(feature = "nb_targets_2", feature = "nb_targets_3"),
+    all(feature = "nb_targets_2", feature = "nb_targets_4"),
+    all(feature = "nb_targets_3", feature = "nb_targets_4")
+))]
+compile_error!("Only one of the features nb_targets_2, nb_targets_3 and nb_targets_4 can be enabled.");
+
+const _: () = assert!(VL53L5CX_NB_TARGET_PER_ZONE >= 1 && VL53L5CX_NB_TARGET_PER_ZONE <= 4);
 
 // Define the max chunk size of the written/read data for I2C transmission
 pub(crate) const I2C_CHUNK_SIZE: usize = 32;
 
+// Register selecting the bank of the following accesses, and bank used by the firmware interface
+pub const VL53L5CX_BANK_SELECT: u16 = 0x7fff;
+pub const VL53L5CX_DEFAULT_BANK: u8 = 0x02;
+
+// Default interval between two polls, and maximum polling duration, in us
+pub(crate) const VL53L5CX_POLL_INTERVAL_US: u32 = 1000;
+pub(crate) const VL53L5CX_POLL_TIMEOUT_US: u32 = 2_000_000;
+pub(crate) const VL53L5CX_BOOT_POLL_INTERVAL_US: u32 = 100;
+pub(crate) const VL53L5CX_BOOT_TIMEOUT_US: u32 = 500_000;
+
+// Maximum duration in us of the check of the firmware already running (see InitStrategy::IfNeeded)
+pub(crate) const VL53L5CX_FW_CHECK_TIMEOUT_US: u32 = 10_000;
+
+// Default waits of the power sequence of on() and off(), in us (see PowerTimings)
+pub const VL53L5CX_SUPPLY_SETTLE_US: u32 = 1_000;
+pub const VL53L5CX_BOOT_US: u32 = 100_000;
+pub const VL53L5CX_LPN_LOW_US: u32 = 10_000;
+pub const VL53L5CX_DISCHARGE_US: u32 = 100_000;
+
+// Maximum number of debug output blocks added to the frames (see set_debug_outputs())
+pub const VL53L5CX_MAX_DEBUG_OUTPUTS: usize = 4;
+
+// Number of frame bytes read between two checks of the stream count, when the overrun check is enabled
+pub(crate) const VL53L5CX_OVERRUN_CHECK_INTERVAL: usize = 256;
+
 // Macro VL53L5CX_NB_THRESHOLDS indicates the number of checkers. This value cannot be changed.
 pub const VL53L5CX_NB_THRESHOLDS: usize = 64;
 
@@ -173,4 +239,7 @@ pub const VL53L5CX_NOT_EQUAL_MIN_CHECKER: u8 = 5;
 // Please note that the first checker MUST always be a OR operation.
 pub const VL53L5CX_OPERATION_NONE: u8 = 0;
 pub const VL53L5CX_OPERATION_OR: u8 = 0;
-pub const VL53L5CX_OPERATION_AND: u8 = 2;
\ No newline at end of file
+pub const VL53L5CX_OPERATION_AND: u8 = 2;
+
+// Default interval in us between two reads of the INT pin (see DataReadyInterrupt)
+pub const VL53L5CX_INT_POLL_INTERVAL_US: u32 = 10;
@@ -0,0 +1,107 @@
+//! Framing of the DCI (Device Configuration Interface) commands exchanged with the
+//! firmware. A DCI block is sent as a 4 bytes header (index and size), the data
+//! as big endian 32 bits words, and an 8 bytes footer (command and size). These
+//! functions build and strip the frames in place, in a buffer holding the data
+//! first, so that the driver does not need a second scratch buffer.
+
+use crate::{swap_buffer, Error};
+
+/// Size in bytes of the header of a DCI frame.
+pub const DCI_HEADER_SIZE: usize = 4;
+
+/// Size in bytes of the footer of a DCI frame.
+pub const DCI_FOOTER_SIZE: usize = 8;
+
+/// This function builds the header of a DCI frame : the index in big endian,
+/// followed by the data size in bytes, on 12 bits.
+fn header(index: u16, data_size: usize) -> [u8; DCI_HEADER_SIZE] {
+    let [index_hi, index_lo] = index.to_be_bytes();
+    [index_hi, index_lo, ((data_size & 0xff0) >> 4) as u8, ((data_size & 0xf) << 4) as u8]
+}
+
+/// This function builds the command requesting `data_size` bytes at `index`,
+/// written at VL53L5CX_UI_CMD_END - 11. The answer is read back with
+/// unframe_read_answer().
+///
+/// # Arguments
+///
+/// * `index` : Index of the DCI block.
+/// * `data_size` : Size of the block in bytes, a multiple of 4.
+///
+/// # Return
+///
+/// * `cmd` : Header followed by the read command footer.
+pub fn read_command(index: u16, data_size: usize) -> [u8; DCI_HEADER_SIZE + DCI_FOOTER_SIZE] {
+    let mut cmd: [u8; DCI_HEADER_SIZE + DCI_FOOTER_SIZE] = [
+        0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x0f,
+        0x00, 0x02, 0x00, 0x08
+    ];
+    cmd[..DCI_HEADER_SIZE].copy_from_slice(&header(index, data_size));
+    cmd
+}
+
+/// This function strips the answer to read_command(), read from VL53L5CX_UI_CMD_START :
+/// the words are converted to the host format and the header is removed, leaving
+/// the data in the first `data_size` bytes of `buffer`.
+///
+/// # Arguments
+///
+/// * `buffer` : Answer of the firmware, of at least `data_size` + 12 bytes.
+/// * `data_size` : Size of the block in bytes, a multiple of 4.
+///
+/// # Return
+///
+/// * `()` : Or Error::BufferTooSmall if `buffer` cannot hold the answer.
+pub fn unframe_read_answer<E>(buffer: &mut [u8], data_size: usize) -> Result<(), Error<E>> {
+    let frame_size: usize = data_size + DCI_HEADER_SIZE + DCI_FOOTER_SIZE;
+    if buffer.len() < frame_size {
+        return Err(Error::BufferTooSmall);
+    }
+    swap_buffer(buffer, frame_size);
+    buffer.copy_within(DCI_HEADER_SIZE..DCI_HEADER_SIZE + data_size, 0);
+
+    Ok(())
+}
+
+/// This function frames in place the first `data_size` bytes of `buffer`, to write
+/// them at `index` : the data is converted to the firmware format and moved after
+/// the header, and the footer is appended. The frame is written so that it ends at
+/// VL53L5CX_UI_CMD_END, and unframe_write_command() restores the data.
+///
+/// # Arguments
+///
+/// * `buffer` : Data to write, of at least `data_size` + 12 bytes.
+/// * `index` : Index of the DCI block.
+/// * `data_size` : Size of the block in bytes, a multiple of 4.
+///
+/// # Return
+///
+/// * `frame_size` : Size of the frame in bytes (`data_size` + 12), or Error::BufferTooSmall.
+pub fn frame_write_command<E>(buffer: &mut [u8], index: u16, data_size: usize) -> Result<usize, Error<E>> {
+    let frame_size: usize = data_size + DCI_HEADER_SIZE + DCI_FOOTER_SIZE;
+    if buffer.len() < frame_size {
+        return Err(Error::BufferTooSmall);
+    }
+    let [size_hi, size_lo] = ((data_size + DCI_FOOTER_SIZE) as u16).to_be_bytes();
+    let footer: [u8; DCI_FOOTER_SIZE] = [0x00, 0x00, 0x00, 0x0f, 0x05, 0x01, size_hi, size_lo];
+
+    swap_buffer(buffer, data_size);
+    buffer.copy_within(..data_size, DCI_HEADER_SIZE);
+    buffer[..DCI_HEADER_SIZE].copy_from_slice(&header(index, data_size));
+    buffer[DCI_HEADER_SIZE + data_size..frame_size].copy_from_slice(&footer);
+
+    Ok(frame_size)
+}
+
+/// This function reverts frame_write_command(), leaving the data in the host format
+/// in the first `data_size` bytes of `buffer`.
+///
+/// # Arguments
+///
+/// * `buffer` : Frame built by frame_write_command().
+/// * `data_size` : Size of the block in bytes, a multiple of 4.
+pub fn unframe_write_command(buffer: &mut [u8], data_size: usize) {
+    buffer.copy_within(DCI_HEADER_SIZE..DCI_HEADER_SIZE + data_size, 0);
+    swap_buffer(buffer, data_size);
+}
@@ -0,0 +1,78 @@
+use crate::DelayNs;
+
+/// Structure BusyWait is a delay provider for environments without a timer 
+/// (e.g. bootloaders). Waits are done by busy looping, using a number of loop 
+/// iterations per microsecond given by the user, usually measured once against 
+/// a reference clock. The waits are at least as long as requested if the 
+/// calibration is not overestimated.
+/// 
+/// With this provider, the polling timeouts of the driver are expressed in poll iterations : 
+/// the number of polls is the timeout divided by the interval set with set_poll_interval_us().
+pub struct BusyWait {
+    loops_per_us: u32
+}
+
+impl BusyWait {
+    /// This function creates a busy wait delay provider.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `loops_per_us` : Number of loop iterations per microsecond. Min value is 1.
+    pub fn new(loops_per_us: u32) -> Self {
+        BusyWait { loops_per_us: loops_per_us.max(1) }
+    }
+
+    /// This function gets the number of loop iterations per microsecond.
+    pub fn get_loops_per_us(&self) -> u32 {
+        self.loops_per_us
+    }
+}
+
+impl DelayNs for BusyWait {
+    fn delay_ns(&mut self, ns: u32) {
+        let loops: u64 = (ns as u64 * self.loops_per_us as u64).div_ceil(1000);
+        for _ in 0..loops {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Structure SleepUs is a delay provider calling a sleep function of the platform, 
+/// e.g. `k_usleep()` on Zephyr or `vTaskDelay()` on FreeRTOS, for RTOSes without 
+/// embedded-hal delay implementations. The function is given the wait in microseconds, 
+/// rounded up, and must not return before. The waits of the RTOSes are usually rounded 
+/// up to the tick period, so the poll intervals should be set to the tick period with 
+/// set_poll_interval_us() and set_boot_poll_interval_us(), for the polling timeouts to stay accurate.
+pub struct SleepUs<F: FnMut(u32)> {
+    sleep_us: F
+}
+
+impl<F: FnMut(u32)> SleepUs<F> {
+    /// This function creates a delay provider from a sleep function.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sleep_us` : Function or closure sleeping for the given number of microseconds.
+    pub fn new(sleep_us: F) -> Self {
+        SleepUs { sleep_us }
+    }
+}
+
+impl<F: FnMut(u32)> DelayNs for SleepUs<F> {
+    fn delay_ns(&mut self, ns: u32) {
+        (self.sleep_us)(ns.div_ceil(1000));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        (self.sleep_us)(us);
+    }
+
+    fn delay_ms(&mut self, mut ms: u32) {
+        // By steps of 1s, as the wait in microseconds overflows after 71 minutes
+        while ms > 1000 {
+            (self.sleep_us)(1_000_000);
+            ms -= 1000;
+        }
+        (self.sleep_us)(ms * 1000);
+    }
+}
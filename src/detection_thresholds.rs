@@ -2,7 +2,23 @@
 use consts::*;
 use utils::*;
 
-use crate::{consts, utils, BusOperation, Vl53l5cx, Error, OutputPin, DelayNs};
+use crate::{consts, utils, BusOperation, ResultsData, Vl53l5cx, Error, OutputPin, DelayNs};
+
+/// Enum ThresholdError contains the invalid arguments of the detection 
+/// thresholds helpers, given by Error::Threshold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ThresholdError {
+    /// The distance is outside of 1mm to 4000mm.
+    InvalidDistance,
+    /// The zones mask is empty, or contains zones outside of the resolution.
+    InvalidZonesMask
+}
+
+impl<E> From<ThresholdError> for Error<E> {
+    fn from(err: ThresholdError) -> Self {
+        Error::Threshold(err)
+    }
+}
 
 /// Structure DetectionThresholds contains a single threshold. This structure  is never used alone, it must be used as an array of 64 thresholds (defined by macro VL53L5CX_NB_THRESHOLDS).
 #[repr(C)]
@@ -27,6 +43,125 @@ impl DetectionThresholds {
     }
 }
 
+/// Structure ThresholdEvents contains the zones which reached their detection 
+/// thresholds for a frame, as returned by get_threshold_events().
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThresholdEvents {
+  // Bit n is set if zone n reached its thresholds 
+    pub zones_mask: u64,
+  // Value measured by the first checker of each zone, in the thresholds units
+    pub measurements: [i32; VL53L5CX_RESOLUTION_8X8 as usize]
+}
+
+impl Default for ThresholdEvents {
+    fn default() -> Self {
+        ThresholdEvents::new()
+    }
+}
+
+impl ThresholdEvents {
+    pub fn new() -> Self {
+        ThresholdEvents { zones_mask: 0, measurements: [0; VL53L5CX_RESOLUTION_8X8 as usize] }
+    }
+
+    /// This function checks if a zone reached its thresholds.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `zone` : Zone number, between 0 and 63.
+    pub fn is_zone_triggered(&self, zone: u8) -> bool {
+        zone < 64 && self.zones_mask & (1u64 << zone) != 0
+    }
+}
+
+/// Inner function, not available outside this file. 
+/// This function gets the value checked by a threshold in the results, 
+/// or None if the measurement is not available.
+#[cfg_attr(all(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM", feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS"), allow(unused_variables))]
+fn threshold_measurement(results: &ResultsData, measurement: u8, zone: usize) -> Option<i32> {
+    let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+    match measurement {
+        #[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+        VL53L5CX_DISTANCE_MM => Some(results.distance_mm[idx] as i32),
+        #[cfg(not(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+        VL53L5CX_SIGNAL_PER_SPAD_KCPS => Some(results.signal_per_spad[idx] as i32),
+        #[cfg(not(feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+        VL53L5CX_RANGE_SIGMA_MM => Some(results.range_sigma_mm[idx] as i32),
+        #[cfg(not(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+        VL53L5CX_AMBIENT_PER_SPAD_KCPS => Some(results.ambient_per_spad[zone] as i32),
+        #[cfg(not(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+        VL53L5CX_NB_TARGET_DETECTED => Some(results.nb_target_detected[zone] as i32),
+        #[cfg(not(feature = "VL53L5CX_DISABLE_TARGET_STATUS"))]
+        VL53L5CX_TARGET_STATUS => Some(results.target_status[idx] as i32),
+        #[cfg(not(feature = "VL53L5CX_DISABLE_NB_SPADS_ENABLED"))]
+        VL53L5CX_NB_SPADS_ENABLED => Some(results.nb_spads_enabled[zone] as i32),
+        #[cfg(not(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+        VL53L5CX_MOTION_INDICATOR => results.motion_indicator.motion.get(zone).map(|&m| m as i32),
+        _ => None
+    }
+}
+
+/// Inner function, not available outside this file. 
+/// This function checks a value against a single threshold.
+fn threshold_reached(threshold: &DetectionThresholds, value: i32) -> bool {
+    let low: i32 = threshold.param_low_thresh;
+    let high: i32 = threshold.param_high_thresh;
+    match threshold.th_type {
+        VL53L5CX_IN_WINDOW => value >= low && value <= high,
+        VL53L5CX_OUT_OF_WINDOW => value < low || value > high,
+        VL53L5CX_LESS_THAN_EQUAL_MIN_CHECKER => value <= low,
+        VL53L5CX_GREATER_THAN_MAX_CHECKER => value > high,
+        VL53L5CX_EQUAL_MIN_CHECKER => value == low,
+        VL53L5CX_NOT_EQUAL_MIN_CHECKER => value != low,
+        _ => false
+    }
+}
+
+/// This function evaluates detection thresholds on a frame, the same way as the sensor 
+/// does to raise the interrupt. Thresholds of a same zone are combined in order, using 
+/// their math_op (VL53L5CX_OPERATION_AND or VL53L5CX_OPERATION_OR). The list ends at the 
+/// first threshold with zone_num set to VL53L5CX_LAST_THRESHOLD.
+/// 
+/// # Arguments
+/// 
+/// * `results` : Frame to evaluate.
+/// * `thresholds` : Thresholds in the units given to set_detection_thresholds().
+/// 
+/// # Return
+/// 
+/// * `events` : Zones which reached their thresholds, with the measured values.
+pub fn evaluate_thresholds(results: &ResultsData, thresholds: &[DetectionThresholds]) -> ThresholdEvents {
+    let mut events: ThresholdEvents = ThresholdEvents::new();
+    let mut zone_state: [Option<bool>; VL53L5CX_RESOLUTION_8X8 as usize] = [None; VL53L5CX_RESOLUTION_8X8 as usize];
+
+    for threshold in thresholds {
+        let zone: usize = (threshold.zone_num & !VL53L5CX_LAST_THRESHOLD) as usize;
+        if zone < VL53L5CX_RESOLUTION_8X8 as usize {
+            if let Some(value) = threshold_measurement(results, threshold.measurement, zone) {
+                let reached: bool = threshold_reached(threshold, value);
+                zone_state[zone] = match zone_state[zone] {
+                    None => {
+                        events.measurements[zone] = value;
+                        Some(reached)
+                    }
+                    Some(previous) if threshold.math_op == VL53L5CX_OPERATION_AND => Some(previous && reached),
+                    Some(previous) => Some(previous || reached)
+                };
+            }
+        }
+        if threshold.zone_num & VL53L5CX_LAST_THRESHOLD != 0 {
+            break;
+        }
+    }
+
+    for (zone, state) in zone_state.iter().enumerate() {
+        if *state == Some(true) {
+            events.zones_mask |= 1u64 << zone;
+        }
+    }
+    events
+}
+
 fn from_u8_to_thresholds(src: &[u8], dst: &mut [DetectionThresholds]) {
     for i in 0..dst.len() {
         let j: usize = 12 * i;
@@ -74,6 +209,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `enabled` : Set to 1 to enable, or 0 to disable thresholds.
     pub fn set_detection_thresholds_enable(&mut self, enabled: u8) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let mut grp_global_config: [u8; 4] = [0x01, 0x00, 0x01, 0x00];
         let mut tmp: [u8; 1] = [0];
         if enabled == 1 {
@@ -135,6 +273,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `thresholds` :  Array of 64 thresholds.
     pub fn set_detection_thresholds(&mut self, thresholds: &mut [DetectionThresholds; VL53L5CX_NB_THRESHOLDS] ) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         for i in 0..VL53L5CX_NB_THRESHOLDS {
             if thresholds[i].measurement == VL53L5CX_DISTANCE_MM {
                 thresholds[i].param_low_thresh  *= 4;
@@ -185,9 +326,87 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `auto_stop` :  auto-stop feature, false if disabled (default)
     pub fn set_detection_thresholds_auto_stop(&mut self, auto_stop: bool) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let tmp: [u8; 1] = if auto_stop {[1]} else {[0]};
         self.dci_replace_data(VL53L5CX_DCI_PIPE_CONTROL, 4, &tmp, 1, 0x03)?;
         self.is_auto_stop_enabled = auto_stop;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// This function reads the new frame after a detection thresholds interrupt, and 
+    /// returns the zones which reached their thresholds. Reading the frame acknowledges 
+    /// it, so the INT pin is raised again at the next frame reaching the thresholds.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `thresholds` : Thresholds in the units given to set_detection_thresholds(). Please note that set_detection_thresholds() converts its argument in place, so a copy must be kept.
+    /// 
+    /// # Return
+    /// 
+    /// * `events` : Zones which reached their thresholds, with the measured values.
+    pub fn get_threshold_events(&mut self, thresholds: &[DetectionThresholds; VL53L5CX_NB_THRESHOLDS]) -> Result<ThresholdEvents, Error<B::Error>> {
+        let results: ResultsData = self.get_ranging_data()?;
+
+        Ok(evaluate_thresholds(&results, thresholds))
+    }
+
+    /// This function configures the sensor to raise an interrupt (INT pin) when a target 
+    /// gets closer than `distance_mm` in one of the selected zones, and starts ranging. 
+    /// The sensor is set in autonomous mode at 2Hz with an integration time of 10ms, 
+    /// so the host MCU can stay in its low power mode until the INT line wakes it up. 
+    /// The events can then be read with get_threshold_events(). 
+    /// Please ensure that the device is not ranging before calling the function.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `distance_mm` : Wake up distance in mm. Min value is 1mm, and max is 4000mm.
+    /// * `zones_mask` : Bit n set to monitor zone n. Zones must be lower than the current resolution.
+    /// 
+    /// # Return
+    /// 
+    /// * `thresholds` : Programmed thresholds, to give to get_threshold_events().
+    pub fn configure_wake_on_approach(&mut self, distance_mm: u16, zones_mask: u64) -> Result<[DetectionThresholds; VL53L5CX_NB_THRESHOLDS], Error<B::Error>> {
+        let mut thresholds: [DetectionThresholds; VL53L5CX_NB_THRESHOLDS] = [DetectionThresholds::new(); VL53L5CX_NB_THRESHOLDS];
+        let mut nb_thresholds: usize = 0;
+
+        // Check input arguments validity 
+        let resolution: u8 = self.resolution()?.into();
+        if !(1..=4000).contains(&distance_mm) {
+            return Err(Error::Threshold(ThresholdError::InvalidDistance));
+        }
+        if zones_mask == 0 || (resolution < 64 && zones_mask >> resolution != 0) {
+            return Err(Error::Threshold(ThresholdError::InvalidZonesMask));
+        }
+
+        // Wake up on any monitored zone closer than distance_mm 
+        for zone in 0..resolution {
+            if zones_mask & (1u64 << zone) != 0 {
+                thresholds[nb_thresholds] = DetectionThresholds {
+                    param_low_thresh: distance_mm as i32,
+                    param_high_thresh: 0,
+                    measurement: VL53L5CX_DISTANCE_MM,
+                    th_type: VL53L5CX_LESS_THAN_EQUAL_MIN_CHECKER,
+                    zone_num: zone,
+                    math_op: VL53L5CX_OPERATION_OR
+                };
+                nb_thresholds += 1;
+            }
+        }
+        thresholds[nb_thresholds - 1].zone_num |= VL53L5CX_LAST_THRESHOLD;
+
+        // Low power autonomous ranging 
+        self.set_power_mode(VL53L5CX_POWER_MODE_WAKEUP)?;
+        self.set_ranging_mode(VL53L5CX_RANGING_MODE_AUTONOMOUS)?;
+        self.set_frequency_hz(2)?;
+        self.set_integration_time(10)?;
+
+        let programmed: [DetectionThresholds; VL53L5CX_NB_THRESHOLDS] = thresholds;
+        self.set_detection_thresholds(&mut thresholds)?;
+        self.set_detection_thresholds_enable(1)?;
+        self.start_ranging()?;
+
+        Ok(programmed)
+    }
+}
@@ -0,0 +1,112 @@
+use consts::*;
+
+use crate::{consts, ResultsData};
+
+/// Enum DownsampleMode contains the aggregations of the 2x2 blocks of zones 
+/// available in downsample_8x8().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DownsampleMode {
+    /// The closest valid target of the block is kept, e.g. for obstacle avoidance.
+    Min,
+    /// The valid targets of the block are averaged, e.g. for smoother depth maps.
+    Mean
+}
+
+/// This function aggregates a 8x8 frame into a 4x4 frame, each 4x4 zone summarizing 
+/// the first target of a 2x2 block of zones, so that the processing written for 
+/// 4x4 frames can run on frames captured in 8x8. Only the targets with a valid status 
+/// (5 or 9) are aggregated. A block without valid target gives a zone without target 
+/// (status 255, distance 0). Ambient and number of SPADs are averaged over the block. 
+/// The motion indicator is not downsampled.
+/// 
+/// # Arguments
+/// 
+/// * `results` : Results of a 8x8 frame.
+/// * `mode` : Aggregation of the valid targets of each block.
+/// 
+/// # Return
+/// 
+/// * `downsampled` : Results of the 4x4 frame, with one target per zone.
+pub fn downsample_8x8(results: &ResultsData, mode: DownsampleMode) -> ResultsData {
+    let nb_targets: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+    let mut downsampled: ResultsData = ResultsData::new();
+    downsampled.silicon_temp_degc = results.silicon_temp_degc;
+    downsampled.frame_info = results.frame_info;
+    downsampled.target_status = [255; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)];
+
+    for zone in 0..VL53L5CX_RESOLUTION_4X4 as usize {
+        let (row, col) = (zone / 4, zone % 4);
+        let block: [usize; 4] = [
+            2 * row * 8 + 2 * col,
+            2 * row * 8 + 2 * col + 1,
+            (2 * row + 1) * 8 + 2 * col,
+            (2 * row + 1) * 8 + 2 * col + 1
+        ];
+        let out: usize = zone * nb_targets;
+
+        #[cfg(not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+        {
+            downsampled.ambient_per_spad[zone] = block.iter().map(|&z| results.ambient_per_spad[z]).sum::<u32>() / 4;
+        }
+        #[cfg(not(feature="VL53L5CX_DISABLE_NB_SPADS_ENABLED"))]
+        {
+            downsampled.nb_spads_enabled[zone] = block.iter().map(|&z| results.nb_spads_enabled[z]).sum::<u32>() / 4;
+        }
+
+        let mut valid: [usize; 4] = [0; 4];
+        let mut nb_valid: usize = 0;
+        for &z in &block {
+            let status: u8 = results.target_status[z * nb_targets];
+            if status == 5 || status == 9 {
+                valid[nb_valid] = z * nb_targets;
+                nb_valid += 1;
+            }
+        }
+        if nb_valid == 0 {
+            continue;
+        }
+        let valid: &[usize] = &valid[..nb_valid];
+
+        #[cfg(not(feature="VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+        {
+            downsampled.nb_target_detected[zone] = 1;
+        }
+        match mode {
+            DownsampleMode::Min => {
+                let closest: usize = *valid.iter().min_by_key(|&&t| results.distance_mm[t]).unwrap();
+                downsampled.distance_mm[out] = results.distance_mm[closest];
+                downsampled.target_status[out] = results.target_status[closest];
+                #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+                {
+                    downsampled.signal_per_spad[out] = results.signal_per_spad[closest];
+                }
+                #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+                {
+                    downsampled.range_sigma_mm[out] = results.range_sigma_mm[closest];
+                }
+                #[cfg(not(feature="VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
+                {
+                    downsampled.reflectance[out] = results.reflectance[closest];
+                }
+            }
+            DownsampleMode::Mean => {
+                downsampled.distance_mm[out] = (valid.iter().map(|&t| results.distance_mm[t] as i32).sum::<i32>() / nb_valid as i32) as i16;
+                downsampled.target_status[out] = results.target_status[valid[0]];
+                #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+                {
+                    downsampled.signal_per_spad[out] = valid.iter().map(|&t| results.signal_per_spad[t]).sum::<u32>() / nb_valid as u32;
+                }
+                #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+                {
+                    downsampled.range_sigma_mm[out] = (valid.iter().map(|&t| results.range_sigma_mm[t] as u32).sum::<u32>() / nb_valid as u32) as u16;
+                }
+                #[cfg(not(feature="VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
+                {
+                    downsampled.reflectance[out] = (valid.iter().map(|&t| results.reflectance[t] as u32).sum::<u32>() / nb_valid as u32) as u8;
+                }
+            }
+        }
+    }
+
+    downsampled
+}
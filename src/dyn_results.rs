@@ -0,0 +1,87 @@
+use alloc::vec::Vec;
+
+use consts::*;
+use motion_indicator::*;
+
+use crate::{consts, motion_indicator, FrameInfo, ResultsData, Resolution};
+
+/// Structure DynResultsData contains the ranging results in heap allocated arrays, 
+/// sized for the resolution and the number of targets per zone of the frame. 
+/// Per zone arrays contain `nb_zones` values, and per target arrays contain 
+/// `nb_zones * nb_targets_per_zone` values, ordered as in ResultsData.
+#[derive(Clone, Debug)]
+pub struct DynResultsData {
+  // Number of zones of the frame (16 or 64)
+    pub nb_zones: usize,
+  // Number of targets per zone
+    pub nb_targets_per_zone: usize,
+  // Internal sensor silicon temperature 
+    pub silicon_temp_degc: i8,
+    #[cfg(not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+  // Ambient noise in kcps/spads 
+    pub ambient_per_spad: Vec<u32>,
+    #[cfg(not(feature="VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+  // Number of valid target detected for 1 zone 
+    pub nb_target_detected: Vec<u8>,
+    #[cfg(not(feature="VL53L5CX_DISABLE_NB_SPADS_ENABLED"))]
+  // Number of spads enabled for this ranging 
+    pub nb_spads_enabled: Vec<u32>,
+    #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+  // Signal returned to the sensor in kcps/spads 
+    pub signal_per_spad: Vec<u32>,
+    #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+  // Sigma of the current distance in mm 
+    pub range_sigma_mm: Vec<u16>,
+    #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+  // Measured distance in mm 
+    pub distance_mm: Vec<i16>,
+    #[cfg(not(feature="VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
+  // Estimated reflectance in percent 
+    pub reflectance: Vec<u8>,
+    #[cfg(not(feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
+  // Status indicating the measurement validity (5 & 9 means ranging OK)
+    pub target_status: Vec<u8>,
+    #[cfg(not(feature="VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+  // Motion detector results 
+    pub motion_indicator: MotionIndicator,
+  // Information about the parsed frame, such as the outputs present in the frame
+    pub frame_info: FrameInfo
+}
+
+impl DynResultsData {
+    /// This function copies the results of a frame into heap allocated arrays.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `results` : Results of the frame.
+    /// * `resolution` : Resolution used for the frame.
+    #[cfg_attr(all(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM", feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_TARGET_STATUS"), allow(unused_variables))]
+    pub fn from_results(results: &ResultsData, resolution: Resolution) -> Self {
+        let nb_zones: usize = resolution.nb_zones();
+        let nb_targets: usize = nb_zones * VL53L5CX_NB_TARGET_PER_ZONE as usize;
+        DynResultsData {
+            nb_zones,
+            nb_targets_per_zone: VL53L5CX_NB_TARGET_PER_ZONE as usize,
+            silicon_temp_degc: results.silicon_temp_degc,
+            #[cfg(not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+            ambient_per_spad: results.ambient_per_spad[..nb_zones].to_vec(),
+            #[cfg(not(feature="VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+            nb_target_detected: results.nb_target_detected[..nb_zones].to_vec(),
+            #[cfg(not(feature="VL53L5CX_DISABLE_NB_SPADS_ENABLED"))]
+            nb_spads_enabled: results.nb_spads_enabled[..nb_zones].to_vec(),
+            #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+            signal_per_spad: results.signal_per_spad[..nb_targets].to_vec(),
+            #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+            range_sigma_mm: results.range_sigma_mm[..nb_targets].to_vec(),
+            #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+            distance_mm: results.distance_mm[..nb_targets].to_vec(),
+            #[cfg(not(feature="VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
+            reflectance: results.reflectance[..nb_targets].to_vec(),
+            #[cfg(not(feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
+            target_status: results.target_status[..nb_targets].to_vec(),
+            #[cfg(not(feature="VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+            motion_indicator: results.motion_indicator,
+            frame_info: results.frame_info
+        }
+    }
+}
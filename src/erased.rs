@@ -0,0 +1,57 @@
+use embedded_hal::digital::{self, ErrorKind, ErrorType};
+
+use crate::{DelayNs, Error, I2c, OutputPin, Vl53l5cx, Vl53l5cxI2C};
+
+/// Trait DynOutputPin is an object safe version of OutputPin, implemented 
+/// for all output pins. The pin errors are converted to digital::ErrorKind.
+pub trait DynOutputPin {
+    fn dyn_set_low(&mut self) -> Result<(), ErrorKind>;
+    fn dyn_set_high(&mut self) -> Result<(), ErrorKind>;
+}
+
+impl<P: OutputPin> DynOutputPin for P {
+    fn dyn_set_low(&mut self) -> Result<(), ErrorKind> {
+        self.set_low().map_err(|e| digital::Error::kind(&e))
+    }
+
+    fn dyn_set_high(&mut self) -> Result<(), ErrorKind> {
+        self.set_high().map_err(|e| digital::Error::kind(&e))
+    }
+}
+
+/// Structure DynPin wraps any output pin behind a trait object, so that sensors 
+/// using pins of different types share the same driver type. 
+pub struct DynPin<'a>(pub &'a mut dyn DynOutputPin);
+
+impl<'a> ErrorType for DynPin<'a> {
+    type Error = ErrorKind;
+}
+
+impl<'a> OutputPin for DynPin<'a> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.dyn_set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.dyn_set_high()
+    }
+}
+
+/// Type Vl53l5cxErased is the driver with type erased pins and delay. All the sensors 
+/// sharing the same I2C bus type are then instances of a single type, which avoids 
+/// duplicating the driver code in flash for each combination of pins.
+pub type Vl53l5cxErased<'a, P> = Vl53l5cx<Vl53l5cxI2C<P>, DynPin<'a>, DynPin<'a>, &'a mut dyn DelayNs>;
+
+impl<'a, P: I2c> Vl53l5cxErased<'a, P> {
+    /// This function creates a driver with type erased pins and delay.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `i2c` : I2C bus.
+    /// * `lpn_pin` : LPn pin of the sensor.
+    /// * `i2c_rst_pin` : I2C reset pin of the sensor.
+    /// * `tim` : Delay provider.
+    pub fn new_i2c_erased(i2c: P, lpn_pin: &'a mut dyn DynOutputPin, i2c_rst_pin: &'a mut dyn DynOutputPin, tim: &'a mut dyn DelayNs) -> Result<Self, Error<P::Error>> {
+        Vl53l5cx::new_i2c(i2c, DynPin(lpn_pin), DynPin(i2c_rst_pin), tim)
+    }
+}
@@ -0,0 +1,74 @@
+use consts::*;
+
+use crate::{consts, BusOperation, DetectionThresholds, Error, ResultsData, ThresholdEvents, Vl53l5cx, OutputPin, DelayNs, evaluate_thresholds};
+
+/// Type EventHandler is a function called by process() with the value of an event.
+pub type EventHandler<'a, T> = &'a mut dyn FnMut(&T);
+
+/// Structure EventHandlers contains the functions called by process() for each 
+/// kind of event. Handlers left to None are not called. 
+pub struct EventHandlers<'a, E> {
+  // Called for each new frame
+    pub frame_ready: Option<EventHandler<'a, ResultsData>>,
+  // Called when a new frame reaches the thresholds given in `thresholds`
+    pub threshold_crossed: Option<EventHandler<'a, ThresholdEvents>>,
+  // Called when checking or reading a frame fails
+    pub sensor_error: Option<EventHandler<'a, Error<E>>>,
+  // Thresholds programmed with set_detection_thresholds(), in the user units
+    pub thresholds: Option<&'a [DetectionThresholds; VL53L5CX_NB_THRESHOLDS]>
+}
+
+impl<'a, E> Default for EventHandlers<'a, E> {
+    fn default() -> Self {
+        EventHandlers::new()
+    }
+}
+
+impl<'a, E> EventHandlers<'a, E> {
+    pub fn new() -> Self {
+        EventHandlers { 
+            frame_ready: None, 
+            threshold_crossed: None, 
+            sensor_error: None, 
+            thresholds: None 
+        }
+    }
+}
+
+impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
+    /// This function checks if a new frame is ready and dispatches the corresponding events 
+    /// to `handlers`. It can be called from the main loop, or from the INT pin interrupt. 
+    /// The error, if any, is given to the sensor_error handler and then returned.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `handlers` : Functions called for each event.
+    /// 
+    /// # Return
+    /// 
+    /// * `processed` : True if a new frame has been processed, false if no new data was ready.
+    pub fn process(&mut self, handlers: &mut EventHandlers<'_, B::Error>) -> Result<bool, Error<B::Error>> {
+        let results: ResultsData = match self.try_get_ranging_data() {
+            Ok(Some(results)) => results,
+            Ok(None) => return Ok(false),
+            Err(e) => {
+                if let Some(handler) = handlers.sensor_error.as_mut() {
+                    handler(&e);
+                }
+                return Err(e);
+            }
+        };
+
+        if let (Some(thresholds), Some(handler)) = (handlers.thresholds, handlers.threshold_crossed.as_mut()) {
+            let events: ThresholdEvents = evaluate_thresholds(&results, thresholds);
+            if events.zones_mask != 0 {
+                handler(&events);
+            }
+        }
+        if let Some(handler) = handlers.frame_ready.as_mut() {
+            handler(&results);
+        }
+
+        Ok(true)
+    }
+}
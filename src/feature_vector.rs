@@ -0,0 +1,90 @@
+use consts::*;
+
+use crate::{consts, ResultsData, Resolution};
+
+/// Number of features returned by extract_features().
+pub const NB_FEATURES: usize = 7;
+
+// Index of each feature in the vector returned by extract_features(). The
+// ordering is stable : new features will only be appended.
+pub const FEATURE_MEAN_TOP_LEFT: usize = 0;
+pub const FEATURE_MEAN_TOP_RIGHT: usize = 1;
+pub const FEATURE_MEAN_BOTTOM_LEFT: usize = 2;
+pub const FEATURE_MEAN_BOTTOM_RIGHT: usize = 3;
+pub const FEATURE_MIN_DISTANCE: usize = 4;
+pub const FEATURE_MOTION_ENERGY: usize = 5;
+pub const FEATURE_VALID_COUNT: usize = 6;
+
+/// This function summarizes a frame in a small vector of features, as the input of
+/// a gesture or occupancy classifier running on the MCU. Only the first target of
+/// the zones with a valid status (5 or 9) is used. The features are, in this order :
+/// - FEATURE_MEAN_TOP_LEFT to FEATURE_MEAN_BOTTOM_RIGHT : mean distance in mm of the
+///   valid zones of each quadrant of the grid (rows 0 to N/2 - 1 are the top), 0 if none.
+/// - FEATURE_MIN_DISTANCE : minimum distance in mm of the valid zones, 0 if none.
+/// - FEATURE_MOTION_ENERGY : mean motion of the aggregates of the motion indicator,
+///   clamped to i16::MAX, 0 if the motion indicator is not configured or disabled.
+/// - FEATURE_VALID_COUNT : number of valid zones.
+///
+/// # Arguments
+///
+/// * `results` : Results of the frame.
+/// * `resolution` : Resolution used for the frame.
+///
+/// # Return
+///
+/// * `features` : Vector of NB_FEATURES features.
+pub fn extract_features(results: &ResultsData, resolution: Resolution) -> [i16; NB_FEATURES] {
+    let width: usize = match resolution {
+        Resolution::Res4x4 => 4,
+        Resolution::Res8x8 => 8
+    };
+    let mut sums: [i32; 4] = [0; 4];
+    let mut counts: [i32; 4] = [0; 4];
+    let mut min_distance: i16 = i16::MAX;
+
+    for zone in 0..resolution.nb_zones() {
+        let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        let status: u8 = results.target_status[idx];
+        if status != 5 && status != 9 {
+            continue;
+        }
+        let quadrant: usize = 2 * (zone / width >= width / 2) as usize + (zone % width >= width / 2) as usize;
+        sums[quadrant] += results.distance_mm[idx] as i32;
+        counts[quadrant] += 1;
+        min_distance = min_distance.min(results.distance_mm[idx]);
+    }
+
+    let mut features: [i16; NB_FEATURES] = [0; NB_FEATURES];
+    for quadrant in 0..4 {
+        if counts[quadrant] != 0 {
+            features[FEATURE_MEAN_TOP_LEFT + quadrant] = (sums[quadrant] / counts[quadrant]) as i16;
+        }
+    }
+    let valid_count: i32 = counts.iter().sum();
+    if valid_count != 0 {
+        features[FEATURE_MIN_DISTANCE] = min_distance;
+    }
+    features[FEATURE_MOTION_ENERGY] = motion_energy(results);
+    features[FEATURE_VALID_COUNT] = valid_count as i16;
+
+    features
+}
+
+/// This function computes the mean motion of the aggregates of the motion indicator.
+#[cfg(not(feature="VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+fn motion_energy(results: &ResultsData) -> i16 {
+    let motion = &results.motion_indicator;
+    let nb_aggregates: usize = (motion.nb_of_aggregates as usize).min(motion.motion.len());
+    if nb_aggregates == 0 {
+        return 0;
+    }
+    let sum: u64 = motion.motion[..nb_aggregates].iter().map(|&m| m as u64).sum();
+
+    (sum / nb_aggregates as u64).min(i16::MAX as u64) as i16
+}
+
+/// The motion indicator is not in the frames.
+#[cfg(feature="VL53L5CX_DISABLE_MOTION_INDICATOR")]
+fn motion_energy(_results: &ResultsData) -> i16 {
+    0
+}
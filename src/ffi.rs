@@ -0,0 +1,380 @@
+//! C ABI of the driver, enabled with the feature `ffi`, so that C firmware can link the
+//! Rust driver and migrate incrementally. The header is generated with cbindgen:
+//!
+//! ```sh
+//! cbindgen --crate vl53l5cx --lang c --output vl53l5cx_ffi.h
+//! ```
+//!
+//! The driver is an opaque Vl53l5cxHandle, created in a storage given by the C code, and
+//! the bus and delay are callbacks of Vl53l5cxPlatform. The functions are prefixed with
+//! `vl53l5cx_ffi_`, so they can be linked next to ST's C ULD, and return the status codes
+//! of the C ULD (0 if OK, 1 on timeout, 2 for a corrupted frame, 127 for an invalid
+//! parameter and 255 for the other errors).
+//!
+//! The size of the handle depends on the features of the crate (about 3.7 KB, 7.5 KB with
+//! `nb_targets_4`), so the storage is sized with vl53l5cx_ffi_handle_size() and
+//! vl53l5cx_ffi_handle_align(). A static storage must be at least as large and as aligned,
+//! vl53l5cx_ffi_create() returns null otherwise.
+//!
+//! ```c
+//! void *storage = aligned_alloc(vl53l5cx_ffi_handle_align(), vl53l5cx_ffi_handle_size());
+//! Vl53l5cxHandle *dev = vl53l5cx_ffi_create(storage, vl53l5cx_ffi_handle_size(), &platform);
+//! status = vl53l5cx_ffi_init(dev);
+//! status = vl53l5cx_ffi_start_ranging(dev);
+//! status = vl53l5cx_ffi_check_data_ready(dev, &is_ready);
+//! status = vl53l5cx_ffi_get_ranging_data(dev, (uint8_t *)&results, sizeof(results));
+//! ```
+
+use core::convert::Infallible;
+use core::ffi::c_void;
+use core::mem::{align_of, size_of};
+
+use embedded_hal::digital::ErrorType;
+
+use consts::*;
+
+use crate::{consts, BusOperation, Error, ResultsData, Vl53l5cx, OutputPin, DelayNs, VL53L5CX_ST_RESULTS_SIZE};
+
+/// Structure Vl53l5cxPlatform contains the callbacks of the C code accessing the sensor.
+/// The callbacks return 0 on success, and a non zero value on a bus error. They are
+/// nullable function pointers, and a platform with a null callback is rejected by
+/// vl53l5cx_ffi_create().
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Vl53l5cxPlatform {
+  // Pointer given to the callbacks, e.g. the I2C handle and address of the sensor
+    pub user: *mut c_void,
+  // Writes `len` bytes, starting with the register address (big endian)
+    pub write: Option<extern "C" fn(user: *mut c_void, data: *const u8, len: u32) -> u8>,
+  // Reads `len` bytes, from the register written before
+    pub read: Option<extern "C" fn(user: *mut c_void, data: *mut u8, len: u32) -> u8>,
+  // Writes `wlen` bytes, then reads `rlen` bytes with a repeated start
+    pub write_read: Option<extern "C" fn(user: *mut c_void, wdata: *const u8, wlen: u32, rdata: *mut u8, rlen: u32) -> u8>,
+  // Waits for `us` microseconds
+    pub delay_us: Option<extern "C" fn(user: *mut c_void, us: u32)>,
+}
+
+/// Structure FfiBus is the bus of the driver, calling the platform callbacks.
+/// The error is the non zero value returned by the callback.
+pub struct FfiBus {
+    user: *mut c_void,
+    write: extern "C" fn(user: *mut c_void, data: *const u8, len: u32) -> u8,
+    read: extern "C" fn(user: *mut c_void, data: *mut u8, len: u32) -> u8,
+    write_read: extern "C" fn(user: *mut c_void, wdata: *const u8, wlen: u32, rdata: *mut u8, rlen: u32) -> u8,
+}
+
+impl BusOperation for FfiBus {
+    type Error = u8;
+
+    fn read(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        match (self.read)(self.user, rbuf.as_mut_ptr(), rbuf.len() as u32) {
+            0 => Ok(()),
+            err => Err(err)
+        }
+    }
+
+    fn write(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        match (self.write)(self.user, wbuf.as_ptr(), wbuf.len() as u32) {
+            0 => Ok(()),
+            err => Err(err)
+        }
+    }
+
+    fn write_read(&mut self, wbuf: &[u8], rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        match (self.write_read)(self.user, wbuf.as_ptr(), wbuf.len() as u32, rbuf.as_mut_ptr(), rbuf.len() as u32) {
+            0 => Ok(()),
+            err => Err(err)
+        }
+    }
+}
+
+/// Structure FfiDelay is the delay of the driver, calling the platform callback.
+pub struct FfiDelay {
+    user: *mut c_void,
+    delay_us: extern "C" fn(user: *mut c_void, us: u32),
+}
+
+impl DelayNs for FfiDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        (self.delay_us)(self.user, ns.div_ceil(1000));
+    }
+}
+
+/// Structure FfiPin is a pin driven by the C code: the LPN and reset pins are
+/// not used by the driver through the C ABI.
+pub struct FfiPin;
+
+impl ErrorType for FfiPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for FfiPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Structure Vl53l5cxHandle is the driver given to the C code, as an opaque type.
+pub struct Vl53l5cxHandle {
+    sensor: Vl53l5cx<FfiBus, FfiPin, FfiPin, FfiDelay>,
+}
+
+/// This function converts a result to the status codes of the C ULD.
+fn status<R>(result: Result<R, Error<u8>>) -> u8 {
+    match result {
+        Ok(_) => VL53L5CX_STATUS_OK,
+        Err(Error::Timeout) => VL53L5CX_STATUS_TIMEOUT_ERROR,
+        Err(Error::CorruptedFrame) | Err(Error::Parse(_)) => VL53L5CX_STATUS_CORRUPTED_FRAME,
+        Err(Error::InvalidParam) | Err(Error::Calibration(_)) | Err(Error::Threshold(_)) => VL53L5CX_STATUS_INVALID_PARAM,
+        Err(_) => VL53L5CX_STATUS_ERROR
+    }
+}
+
+/// This function runs `f` on the driver of the handle, or returns the invalid
+/// parameter status if the handle is null.
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+unsafe fn with_sensor(handle: *mut Vl53l5cxHandle, f: impl FnOnce(&mut Vl53l5cx<FfiBus, FfiPin, FfiPin, FfiDelay>) -> Result<(), Error<u8>>) -> u8 {
+    match handle.as_mut() {
+        Some(handle) => status(f(&mut handle.sensor)),
+        None => VL53L5CX_STATUS_INVALID_PARAM
+    }
+}
+
+/// This function gets the size in bytes of the storage of a handle.
+#[no_mangle]
+pub extern "C" fn vl53l5cx_ffi_handle_size() -> usize {
+    size_of::<Vl53l5cxHandle>()
+}
+
+/// This function gets the alignment in bytes of the storage of a handle.
+#[no_mangle]
+pub extern "C" fn vl53l5cx_ffi_handle_align() -> usize {
+    align_of::<Vl53l5cxHandle>()
+}
+
+/// This function gets the size in bytes of the results written by vl53l5cx_ffi_get_ranging_data().
+#[no_mangle]
+pub extern "C" fn vl53l5cx_ffi_results_size() -> usize {
+    VL53L5CX_ST_RESULTS_SIZE
+}
+
+/// This function creates a driver in `storage`. The sensor is not accessed.
+///
+/// # Return
+///
+/// * `handle` : Driver, or null if a pointer or a callback is null, or if the storage is too small or not aligned.
+///
+/// # Safety
+///
+/// `storage` must be valid for writes of `size` bytes, and not used by the C code until
+/// vl53l5cx_ffi_destroy(). `platform` must point to a valid Vl53l5cxPlatform, which is copied.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_create(storage: *mut c_void, size: usize, platform: *const Vl53l5cxPlatform) -> *mut Vl53l5cxHandle {
+    let handle: *mut Vl53l5cxHandle = storage.cast();
+    if handle.is_null() || platform.is_null() || size < size_of::<Vl53l5cxHandle>() || !handle.is_aligned() {
+        return core::ptr::null_mut();
+    }
+    let platform: Vl53l5cxPlatform = *platform;
+    let (Some(write), Some(read), Some(write_read), Some(delay_us)) = (platform.write, platform.read, platform.write_read, platform.delay_us) else {
+        return core::ptr::null_mut();
+    };
+    let bus: FfiBus = FfiBus { user: platform.user, write, read, write_read };
+    let delay: FfiDelay = FfiDelay { user: platform.user, delay_us };
+    handle.write(Vl53l5cxHandle { sensor: Vl53l5cx::with_bus(bus, FfiPin, FfiPin, delay) });
+    handle
+}
+
+/// This function destroys a driver, after which its storage can be reused.
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_destroy(handle: *mut Vl53l5cxHandle) {
+    if !handle.is_null() {
+        handle.drop_in_place();
+    }
+}
+
+/// This function initializes the sensor, as vl53l5cx_init().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_init(handle: *mut Vl53l5cxHandle) -> u8 {
+    with_sensor(handle, |sensor| sensor.init())
+}
+
+/// This function starts a ranging session, as vl53l5cx_start_ranging().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_start_ranging(handle: *mut Vl53l5cxHandle) -> u8 {
+    with_sensor(handle, |sensor| sensor.start_ranging())
+}
+
+/// This function stops the ranging session, as vl53l5cx_stop_ranging().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_stop_ranging(handle: *mut Vl53l5cxHandle) -> u8 {
+    with_sensor(handle, |sensor| sensor.stop_ranging())
+}
+
+/// This function checks if a new frame is ready, as vl53l5cx_check_data_ready().
+/// `is_ready` is set to 1 if a new frame is ready, 0 otherwise.
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+/// `is_ready` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_check_data_ready(handle: *mut Vl53l5cxHandle, is_ready: *mut u8) -> u8 {
+    if is_ready.is_null() {
+        return VL53L5CX_STATUS_INVALID_PARAM;
+    }
+    with_sensor(handle, |sensor| {
+        is_ready.write(sensor.check_data_ready()? as u8);
+        Ok(())
+    })
+}
+
+/// This function reads the new frame, as vl53l5cx_get_ranging_data(). The results are
+/// written in the layout of ST's VL53L5CX_ResultsData (see ResultsData::as_st_c_layout()),
+/// so `results` can point to a VL53L5CX_ResultsData compiled with the same macros.
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+/// `results` must be null or valid for writes of `size` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_get_ranging_data(handle: *mut Vl53l5cxHandle, results: *mut u8, size: u32) -> u8 {
+    if results.is_null() {
+        return VL53L5CX_STATUS_INVALID_PARAM;
+    }
+    let out: &mut [u8] = core::slice::from_raw_parts_mut(results, size as usize);
+    with_sensor(handle, |sensor| {
+        if out.len() < VL53L5CX_ST_RESULTS_SIZE {
+            return Err(Error::InvalidParam);
+        }
+        let frame: ResultsData = sensor.get_ranging_data()?;
+        frame.as_st_c_layout(out)?;
+        Ok(())
+    })
+}
+
+/// This function gets the resolution, as vl53l5cx_get_resolution().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+/// `resolution` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_get_resolution(handle: *mut Vl53l5cxHandle, resolution: *mut u8) -> u8 {
+    if resolution.is_null() {
+        return VL53L5CX_STATUS_INVALID_PARAM;
+    }
+    with_sensor(handle, |sensor| {
+        resolution.write(sensor.resolution()?.into());
+        Ok(())
+    })
+}
+
+/// This function sets the resolution, as vl53l5cx_set_resolution().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_set_resolution(handle: *mut Vl53l5cxHandle, resolution: u8) -> u8 {
+    with_sensor(handle, |sensor| sensor.set_resolution(resolution))
+}
+
+/// This function gets the ranging frequency, as vl53l5cx_get_ranging_frequency_hz().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+/// `frequency_hz` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_get_ranging_frequency_hz(handle: *mut Vl53l5cxHandle, frequency_hz: *mut u8) -> u8 {
+    if frequency_hz.is_null() {
+        return VL53L5CX_STATUS_INVALID_PARAM;
+    }
+    with_sensor(handle, |sensor| {
+        frequency_hz.write(sensor.get_frequency_hz()?);
+        Ok(())
+    })
+}
+
+/// This function sets the ranging frequency, as vl53l5cx_set_ranging_frequency_hz().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_set_ranging_frequency_hz(handle: *mut Vl53l5cxHandle, frequency_hz: u8) -> u8 {
+    with_sensor(handle, |sensor| sensor.set_frequency_hz(frequency_hz))
+}
+
+/// This function sets the integration time, as vl53l5cx_set_integration_time_ms().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_set_integration_time_ms(handle: *mut Vl53l5cxHandle, integration_time_ms: u32) -> u8 {
+    with_sensor(handle, |sensor| sensor.set_integration_time(integration_time_ms))
+}
+
+/// This function sets the ranging mode, as vl53l5cx_set_ranging_mode().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_set_ranging_mode(handle: *mut Vl53l5cxHandle, ranging_mode: u8) -> u8 {
+    with_sensor(handle, |sensor| sensor.set_ranging_mode(ranging_mode))
+}
+
+/// This function sets the power mode, as vl53l5cx_set_power_mode().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_set_power_mode(handle: *mut Vl53l5cxHandle, power_mode: u8) -> u8 {
+    with_sensor(handle, |sensor| sensor.set_power_mode(power_mode))
+}
+
+/// This function sets the target order, as vl53l5cx_set_target_order().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_set_target_order(handle: *mut Vl53l5cxHandle, target_order: u8) -> u8 {
+    with_sensor(handle, |sensor| sensor.set_target_order(target_order))
+}
+
+/// This function sets the sharpener, as vl53l5cx_set_sharpener_percent().
+///
+/// # Safety
+///
+/// `handle` must be null, or created by vl53l5cx_ffi_create() and not destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn vl53l5cx_ffi_set_sharpener_percent(handle: *mut Vl53l5cxHandle, sharpener_percent: u8) -> u8 {
+    with_sensor(handle, |sensor| sensor.set_sharpener_percent(sharpener_percent as u32))
+}
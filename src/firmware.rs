@@ -0,0 +1,62 @@
+use crate::buffers::VL53L5CX_FIRMWARE;
+
+/// Size in bytes of the VL53L5CX firmware.
+pub const VL53L5CX_FIRMWARE_SIZE: usize = 0x15000;
+
+// Register page, start and end offsets of each part of the firmware
+pub(crate) const VL53L5CX_FIRMWARE_PAGES: [(u8, usize, usize); 3] = [
+    (0x09, 0, 0x8000),
+    (0x0a, 0x8000, 0x10000),
+    (0x0b, 0x10000, VL53L5CX_FIRMWARE_SIZE)
+];
+
+/// Structure FirmwareReadError is returned by a FirmwareSource which can not 
+/// provide the requested bytes (e.g. external flash error, or host stream closed).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FirmwareReadError;
+
+/// Trait FirmwareSource provides the firmware uploaded by init_with_firmware(), 
+/// so that it can come from the built-in array, an external flash or a host stream. 
+/// The firmware is read in increasing offsets, by chunks of at most 
+/// VL53L5CX_TEMPORARY_BUFFER_SIZE bytes.
+pub trait FirmwareSource {
+    /// This function fills `buf` with the firmware bytes starting at `offset`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `offset` : Offset in bytes from the start of the firmware.
+    /// * `buf` : Buffer to fill, offset + buf.len() is at most VL53L5CX_FIRMWARE_SIZE.
+    fn read(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), FirmwareReadError>;
+}
+
+/// Structure SliceFirmware is the FirmwareSource of a firmware array given by the
+/// application, e.g. the firmware of another sensor variant (see set_sensor_variant()).
+pub struct SliceFirmware<'a>(pub &'a [u8]);
+
+impl FirmwareSource for SliceFirmware<'_> {
+    fn read(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), FirmwareReadError> {
+        let end: usize = offset + buf.len();
+        if end > self.0.len() {
+            return Err(FirmwareReadError);
+        }
+        buf.copy_from_slice(&self.0[offset..end]);
+
+        Ok(())
+    }
+}
+
+/// Structure BuiltinFirmware is the FirmwareSource of the firmware array embedded 
+/// in the driver, used by init().
+pub struct BuiltinFirmware;
+
+impl FirmwareSource for BuiltinFirmware {
+    fn read(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), FirmwareReadError> {
+        let end: usize = offset + buf.len();
+        if end > VL53L5CX_FIRMWARE.len() {
+            return Err(FirmwareReadError);
+        }
+        buf.copy_from_slice(&VL53L5CX_FIRMWARE[offset..end]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use embedded_storage::nor_flash::NorFlash;
+
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use crate::{encode_frame, Resolution, ResultsData, COMPACT_FRAME_MAX_SIZE};
+
+/// Marker of a recorded slot ("VL5R").
+const RECORD_MAGIC: u32 = 0x5235_4C56;
+
+/// Size in bytes of the flash accesses, which must be a multiple of the
+/// read and write sizes of the flash.
+const CHUNK_SIZE: usize = 64;
+
+/// Enum RecorderError contains the errors of the FlashRecorder.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RecorderError<E> {
+    /// The flash returned an error.
+    Flash(E),
+    /// The region or the slot size does not fit the erase, write and read sizes of the flash.
+    InvalidGeometry,
+    /// The record does not fit in a slot.
+    RecordTooLarge,
+    /// The output buffer is smaller than the record.
+    BufferTooSmall
+}
+
+/// Structure FlashRecorder writes the last frames into a region of a NOR flash
+/// used as a ring, as a black box saving the seconds before a failure.
+///
+/// The region is split in erase sectors, and each sector in slots of `slot_size` bytes.
+/// A slot holds one record, behind a 16 bytes header (marker, sequence number and size)
+/// written after the record, so that a record interrupted by a reset is ignored. When
+/// the ring enters a sector, the sector is erased, dropping its oldest records.
+///
+/// After a reset, new() finds the last record in the headers and resumes after it,
+/// at the next sector.
+pub struct FlashRecorder<F: NorFlash> {
+    flash: F,
+    base: u32,
+    slot_size: u32,
+    header_size: u32,
+    slots_per_sector: u32,
+    nb_slots: u32,
+    next_slot: u32,
+    sequence: u32
+}
+
+impl<F: NorFlash> FlashRecorder<F> {
+    /// This function creates a recorder on a region of the flash, and resumes
+    /// after the records already in the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `flash` : Flash storing the records.
+    /// * `base` : Offset of the region in the flash, aligned on F::ERASE_SIZE.
+    /// * `size` : Size of the region in bytes, at least 2 erase sectors.
+    /// * `slot_size` : Size of a slot in bytes (header included), a multiple of F::WRITE_SIZE not above F::ERASE_SIZE.
+    ///
+    /// # Return
+    ///
+    /// * `recorder` : Recorder, or RecorderError::InvalidGeometry.
+    pub fn new(flash: F, base: u32, size: u32, slot_size: u32) -> Result<Self, RecorderError<F::Error>> {
+        let erase_size: u32 = F::ERASE_SIZE as u32;
+        let header_size: u32 = 16.max(F::WRITE_SIZE).max(F::READ_SIZE) as u32;
+        if !CHUNK_SIZE.is_multiple_of(F::WRITE_SIZE) || !CHUNK_SIZE.is_multiple_of(F::READ_SIZE)
+            || !header_size.is_multiple_of(F::WRITE_SIZE as u32) || !header_size.is_multiple_of(F::READ_SIZE as u32)
+            || !slot_size.is_multiple_of(F::WRITE_SIZE as u32) || !slot_size.is_multiple_of(F::READ_SIZE as u32)
+            || slot_size <= header_size || slot_size > erase_size
+            || !base.is_multiple_of(erase_size) || !size.is_multiple_of(erase_size) || size < 2 * erase_size
+            || base as usize + size as usize > flash.capacity() {
+            return Err(RecorderError::InvalidGeometry);
+        }
+        let slots_per_sector: u32 = erase_size / slot_size;
+
+        let mut recorder = FlashRecorder {
+            flash,
+            base,
+            slot_size,
+            header_size,
+            slots_per_sector,
+            nb_slots: size / erase_size * slots_per_sector,
+            next_slot: 0,
+            sequence: 0
+        };
+
+        let mut newest: Option<(u32, u32)> = None;
+        for slot in 0..recorder.nb_slots {
+            if let Some((sequence, _)) = recorder.read_header(slot)? {
+                if newest.is_none_or(|(newest_sequence, _)| sequence > newest_sequence) {
+                    newest = Some((sequence, slot));
+                }
+            }
+        }
+        if let Some((sequence, slot)) = newest {
+            // The rest of the sector may hold an interrupted record, start at the next one
+            recorder.next_slot = (slot / slots_per_sector + 1) * slots_per_sector % recorder.nb_slots;
+            recorder.sequence = sequence.wrapping_add(1);
+        }
+
+        Ok(recorder)
+    }
+
+    /// This function releases the flash.
+    pub fn release(self) -> F {
+        self.flash
+    }
+
+    /// This function gets the maximum size of a record.
+    pub fn max_record_size(&self) -> usize {
+        (self.slot_size - self.header_size) as usize
+    }
+
+    /// This function gets the number of slots of the ring.
+    pub fn nb_slots(&self) -> u32 {
+        self.nb_slots
+    }
+
+    /// This function writes a record in the next slot of the ring, e.g. a frame
+    /// serialized with as_st_c_layout(), or with encode_frame() (see record_compact()).
+    ///
+    /// # Arguments
+    ///
+    /// * `data` : Record, of at most max_record_size() bytes.
+    ///
+    /// # Return
+    ///
+    /// * `sequence` : Sequence number of the record.
+    pub fn record(&mut self, data: &[u8]) -> Result<u32, RecorderError<F::Error>> {
+        if data.len() > self.max_record_size() {
+            return Err(RecorderError::RecordTooLarge);
+        }
+        let slot: u32 = self.next_slot;
+        let address: u32 = self.slot_address(slot);
+        if slot.is_multiple_of(self.slots_per_sector) {
+            self.flash.erase(address, address + F::ERASE_SIZE as u32).map_err(RecorderError::Flash)?;
+        }
+
+        let mut chunk: [u8; CHUNK_SIZE] = [0xFF; CHUNK_SIZE];
+        for (i, part) in data.chunks(CHUNK_SIZE).enumerate() {
+            let write_size: usize = part.len().div_ceil(F::WRITE_SIZE) * F::WRITE_SIZE;
+            chunk[..part.len()].copy_from_slice(part);
+            chunk[part.len()..].fill(0xFF);
+            let offset: u32 = address + self.header_size + (i * CHUNK_SIZE) as u32;
+            self.flash.write(offset, &chunk[..write_size]).map_err(RecorderError::Flash)?;
+        }
+
+        // The header is written last, it validates the record
+        let sequence: u32 = self.sequence;
+        let size: u32 = data.len() as u32;
+        chunk.fill(0xFF);
+        for (i, word) in [RECORD_MAGIC, sequence, size, !(sequence ^ size)].iter().enumerate() {
+            chunk[4*i..4*i + 4].copy_from_slice(&word.to_le_bytes());
+        }
+        self.flash.write(address, &chunk[..self.header_size as usize]).map_err(RecorderError::Flash)?;
+
+        self.next_slot = (slot + 1) % self.nb_slots;
+        self.sequence = sequence.wrapping_add(1);
+
+        Ok(sequence)
+    }
+
+    /// This function writes the first target of each zone of a frame, encoded with encode_frame().
+    ///
+    /// # Arguments
+    ///
+    /// * `results` : Results of the frame.
+    /// * `resolution` : Resolution used for the frame.
+    ///
+    /// # Return
+    ///
+    /// * `sequence` : Sequence number of the record.
+    #[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+    pub fn record_compact(&mut self, results: &ResultsData, resolution: Resolution) -> Result<u32, RecorderError<F::Error>> {
+        let mut encoded: [u8; COMPACT_FRAME_MAX_SIZE] = [0; COMPACT_FRAME_MAX_SIZE];
+        let size: usize = encode_frame::<()>(results, resolution, &mut encoded).map_err(|_| RecorderError::BufferTooSmall)?;
+        self.record(&encoded[..size])
+    }
+
+    /// This function reads a record back, from the newest to the oldest.
+    ///
+    /// # Arguments
+    ///
+    /// * `age` : Age of the record, 0 for the newest.
+    /// * `out` : Output buffer, of at least the size of the record.
+    ///
+    /// # Return
+    ///
+    /// * `record` : Sequence number and size of the record, or None if the ring holds less than `age` + 1 records.
+    pub fn read(&mut self, age: u32, out: &mut [u8]) -> Result<Option<(u32, usize)>, RecorderError<F::Error>> {
+        let mut count: u32 = 0;
+        let mut previous: Option<u32> = None;
+
+        for i in 1..=self.nb_slots {
+            let slot: u32 = (self.next_slot + self.nb_slots - i) % self.nb_slots;
+            let Some((sequence, size)) = self.read_header(slot)? else {
+                continue;
+            };
+            if previous.is_some_and(|previous| sequence >= previous) {
+                // Went around the ring
+                break;
+            }
+            if count == age {
+                let size: usize = size as usize;
+                if out.len() < size {
+                    return Err(RecorderError::BufferTooSmall);
+                }
+                self.read_payload(slot, &mut out[..size])?;
+                return Ok(Some((sequence, size)));
+            }
+            count += 1;
+            previous = Some(sequence);
+        }
+
+        Ok(None)
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function computes the offset of a slot in the flash.
+    fn slot_address(&self, slot: u32) -> u32 {
+        self.base + slot / self.slots_per_sector * F::ERASE_SIZE as u32 + slot % self.slots_per_sector * self.slot_size
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function reads the header of a slot, and returns the sequence number
+    /// and the size of its record, or None if the slot holds no valid record.
+    fn read_header(&mut self, slot: u32) -> Result<Option<(u32, u32)>, RecorderError<F::Error>> {
+        let mut header: [u8; CHUNK_SIZE] = [0; CHUNK_SIZE];
+        let address: u32 = self.slot_address(slot);
+        self.flash.read(address, &mut header[..self.header_size as usize]).map_err(RecorderError::Flash)?;
+
+        let word = |i: usize| u32::from_le_bytes([header[4*i], header[4*i + 1], header[4*i + 2], header[4*i + 3]]);
+        let (magic, sequence, size, check) = (word(0), word(1), word(2), word(3));
+        if magic != RECORD_MAGIC || check != !(sequence ^ size) || size as usize > self.max_record_size() {
+            return Ok(None);
+        }
+
+        Ok(Some((sequence, size)))
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function reads the record of a slot into `out`.
+    fn read_payload(&mut self, slot: u32, out: &mut [u8]) -> Result<(), RecorderError<F::Error>> {
+        let mut chunk: [u8; CHUNK_SIZE] = [0; CHUNK_SIZE];
+        let address: u32 = self.slot_address(slot) + self.header_size;
+        for (i, part) in out.chunks_mut(CHUNK_SIZE).enumerate() {
+            let read_size: usize = part.len().div_ceil(F::READ_SIZE) * F::READ_SIZE;
+            self.flash.read(address + (i * CHUNK_SIZE) as u32, &mut chunk[..read_size]).map_err(RecorderError::Flash)?;
+            part.copy_from_slice(&chunk[..part.len()]);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use consts::*;
+
+use units::*;
+
+use crate::{consts, units, ResultsData};
+
+/// Structure FloatResults contains a floating point view of the ranging results.
+/// Values are converted from the firmware fixed-point format, so they keep 
+/// the fractional part which is truncated in ResultsData:
+/// - `ambient_per_spad` and `signal_per_spad` are in kcps/spads (firmware value / 2048).
+/// - `range_sigma_mm` is in mm (firmware value / 128).
+/// - `distance_mm` is in mm (firmware value / 4), negative distances are clamped to 0.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct FloatResults {
+    #[cfg(not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+  // Ambient noise in kcps/spads 
+    pub ambient_per_spad: [f32; VL53L5CX_RESOLUTION_8X8 as usize],
+    #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+  // Signal returned to the sensor in kcps/spads 
+    pub signal_per_spad: [f32; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)],
+    #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+  // Sigma of the current distance in mm 
+    pub range_sigma_mm: [f32; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)],
+    #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+  // Measured distance in mm 
+    pub distance_mm: [f32; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)]
+}
+
+impl Default for FloatResults {
+    fn default() -> Self {
+        FloatResults::new()
+    }
+}
+
+impl FloatResults {
+    pub fn new() -> Self {
+        FloatResults {
+            #[cfg(not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+            ambient_per_spad: [0.0; VL53L5CX_RESOLUTION_8X8 as usize],
+            #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+            signal_per_spad: [0.0; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)],
+            #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+            range_sigma_mm: [0.0; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)],
+            #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+            distance_mm: [0.0; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)]
+        }
+    }
+}
+
+/// Inner function, not available outside the crate. 
+/// This function fills the floating point view of `result`, 
+/// it must be called while `result` still holds the firmware format.
+#[cfg_attr(all(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM", feature = "VL53L5CX_DISABLE_DISTANCE_MM"), allow(unused_variables))]
+pub(crate) fn fill_float_results(result: &mut ResultsData) {
+    #[cfg(not(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))] {
+        for i in 0..VL53L5CX_RESOLUTION_8X8 as usize {
+            result.float_results.ambient_per_spad[i] = rate_kcps_per_spad_f32(result.ambient_per_spad[i]);
+        }
+    }
+    for i in 0..(VL53L5CX_RESOLUTION_8X8 as usize)*(VL53L5CX_NB_TARGET_PER_ZONE as usize) {
+        #[cfg(not(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))] {
+            result.float_results.signal_per_spad[i] = rate_kcps_per_spad_f32(result.signal_per_spad[i]);
+        }
+        #[cfg(not(feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM"))] {
+            result.float_results.range_sigma_mm[i] = range_sigma_mm_f32(result.range_sigma_mm[i]);
+        }
+        #[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))] {
+            result.float_results.distance_mm[i] = distance_mm_f32(result.distance_mm[i]);
+        }
+    }
+}
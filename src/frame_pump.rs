@@ -0,0 +1,53 @@
+use heapless::spsc::Producer;
+
+use crate::{BusOperation, Error, ResultsData, Vl53l5cx, OutputPin, DelayNs};
+
+/// Structure FramePump pushes the frames read from the sensor into a 
+/// heapless::spsc::Queue. The pump is used by the producer (usually the 
+/// data ready interrupt handler), while a consumer task drains the queue 
+/// with the matching heapless::spsc::Consumer. 
+pub struct FramePump<'a, const N: usize> {
+    producer: Producer<'a, ResultsData, N>,
+    dropped_frames: u32
+}
+
+impl<'a, const N: usize> FramePump<'a, N> {
+    /// This function creates a pump from the producer side of a queue.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `producer` : Producer obtained with heapless::spsc::Queue::split().
+    pub fn new(producer: Producer<'a, ResultsData, N>) -> Self {
+        FramePump { producer, dropped_frames: 0 }
+    }
+
+    /// This function reads a frame from the sensor and pushes it into the queue. 
+    /// It must be called when a new frame is ready (e.g. on the INT pin falling edge). 
+    /// If the queue is full, the frame is dropped and the dropped frames counter is incremented.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sensor` : Sensor in ranging state.
+    /// 
+    /// # Return
+    /// 
+    /// `queued` : True if the frame has been pushed into the queue, false if it has been dropped.
+    pub fn pump<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(&mut self, sensor: &mut Vl53l5cx<B, LPN, RST, T>) -> Result<bool, Error<B::Error>> {
+        let results: ResultsData = sensor.get_ranging_data()?;
+        if self.producer.enqueue(results).is_err() {
+            self.dropped_frames = self.dropped_frames.wrapping_add(1);
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    /// This function gets the number of frames dropped because the queue was full.
+    /// 
+    /// # Return
+    /// 
+    /// `dropped_frames` : Number of dropped frames since the creation of the pump.
+    pub fn get_dropped_frames(&self) -> u32 {
+        self.dropped_frames
+    }
+}
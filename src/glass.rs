@@ -0,0 +1,72 @@
+use consts::*;
+
+use crate::{consts, ResultsData};
+
+/// Minimum distance in mm between the glass and the target seen through it.
+pub const GLASS_MIN_SEPARATION_MM: i16 = 40;
+
+/// Maximum reflectance in percent of a glass surface.
+pub const GLASS_MAX_REFLECTANCE_PERCENT: u8 = 10;
+
+/// Structure GlassReport contains the zones where a glass surface is probable, 
+/// as found by detect_glass().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GlassReport {
+  // Bit n is set if a glass surface is probable in the zone n
+    pub zones_mask: u64,
+  // Number of zones where a glass surface is probable
+    pub nb_zones: u8,
+  // Distance of the glass surface in mm, 0 for the other zones
+    pub distance_mm: [i16; VL53L5CX_RESOLUTION_8X8 as usize],
+}
+
+impl GlassReport {
+    /// This function checks if a glass surface is probable in a zone.
+    pub fn is_glass(&self, zone: usize) -> bool {
+        zone < 64 && self.zones_mask & (1 << zone) != 0
+    }
+}
+
+/// This function flags the zones where the first target is probably a glass surface, 
+/// using the relationship between the first two targets of the zone: a transparent 
+/// surface gives a weak first target (reflectance below GLASS_MAX_REFLECTANCE_PERCENT), 
+/// and a second valid target seen through it, at least GLASS_MIN_SEPARATION_MM behind. 
+/// The sensor must report at least 2 targets per zone (feature `nb_targets_2` or more), 
+/// ordered by distance (TargetOrder::Closest), otherwise no zone is flagged.
+/// 
+/// # Arguments
+/// 
+/// * `results` : Results of the frame.
+/// 
+/// # Return
+/// 
+/// * `report` : Zones with a probable glass surface.
+pub fn detect_glass(results: &ResultsData) -> GlassReport {
+    let mut report: GlassReport = GlassReport { zones_mask: 0, nb_zones: 0, distance_mm: [0; VL53L5CX_RESOLUTION_8X8 as usize] };
+    let nb_targets: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+    if nb_targets < 2 {
+        return report;
+    }
+
+    for zone in 0..VL53L5CX_RESOLUTION_8X8 as usize {
+        let first: usize = nb_targets * zone;
+        let second: usize = first + 1;
+        if results.nb_target_detected[zone] < 2 {
+            continue;
+        }
+        let is_valid = |idx: usize| results.target_status[idx] == 5 || results.target_status[idx] == 9;
+        if !is_valid(first) || !is_valid(second) {
+            continue;
+        }
+
+        if results.reflectance[first] <= GLASS_MAX_REFLECTANCE_PERCENT 
+            && results.distance_mm[second].saturating_sub(results.distance_mm[first]) >= GLASS_MIN_SEPARATION_MM 
+        {
+            report.zones_mask |= 1 << zone;
+            report.nb_zones += 1;
+            report.distance_mm[zone] = results.distance_mm[first];
+        }
+    }
+
+    report
+}
@@ -0,0 +1,101 @@
+use consts::*;
+
+use crate::{consts, ResultsData};
+
+/// Value of the zones without a valid target in the images of to_depth_image_u8().
+pub const DEPTH_IMAGE_INVALID: u8 = 0;
+
+/// Structure Grid is only used to restrict the grid sizes to the sensor resolutions, 
+/// with the bound `Grid<N>: GridSize`.
+pub struct Grid<const N: usize>;
+
+/// Trait GridSize is implemented for the grid sizes of the sensor, 
+/// 4 for the 4x4 resolution and 8 for the 8x8 resolution.
+pub trait GridSize {}
+
+impl GridSize for Grid<4> {}
+impl GridSize for Grid<8> {}
+
+/// This function arranges per zone values in a N x N grid, indexed by (row, col), 
+/// where the zone number is row * N + col. Per target arrays are accepted, 
+/// `stride` values being skipped between two zones.
+/// 
+/// # Arguments
+/// 
+/// * `values` : Per zone or per target values.
+/// * `stride` : Number of values per zone (1, or VL53L5CX_NB_TARGET_PER_ZONE for the first target).
+/// 
+/// # Return
+/// 
+/// * `grid` : Values of the first N * N zones.
+pub fn to_grid<T: Copy + Default, const N: usize>(values: &[T], stride: usize) -> [[T; N]; N]
+    where Grid<N>: GridSize
+{
+    let mut grid: [[T; N]; N] = [[T::default(); N]; N];
+    for (row, line) in grid.iter_mut().enumerate() {
+        for (col, value) in line.iter_mut().enumerate() {
+            *value = values[(row * N + col) * stride];
+        }
+    }
+
+    grid
+}
+
+impl ResultsData {
+    /// This function gets the distance of the first target of each zone 
+    /// as a grid indexed by (row, col), e.g. `results.distance_grid::<8>()[row][col]`. 
+    /// The grid size must be the resolution of the frame (4 or 8).
+    #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+    pub fn distance_grid<const N: usize>(&self) -> [[i16; N]; N]
+        where Grid<N>: GridSize
+    {
+        to_grid(&self.distance_mm, VL53L5CX_NB_TARGET_PER_ZONE as usize)
+    }
+
+    /// This function gets the status of the first target of each zone 
+    /// as a grid indexed by (row, col). The grid size must be the resolution of the frame (4 or 8).
+    #[cfg(not(feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
+    pub fn target_status_grid<const N: usize>(&self) -> [[u8; N]; N]
+        where Grid<N>: GridSize
+    {
+        to_grid(&self.target_status, VL53L5CX_NB_TARGET_PER_ZONE as usize)
+    }
+
+    /// This function converts the distance of the first target of each zone to a
+    /// grid of bytes indexed by (row, col), e.g. for a display or as the input of an
+    /// inference model. Distances are scaled linearly from `min_mm` (1) to `max_mm`
+    /// (255) and clamped to this range. Zones without a valid target (status other
+    /// than 5 or 9) are set to DEPTH_IMAGE_INVALID (0). The grid size must be the
+    /// resolution of the frame (4 or 8).
+    ///
+    /// # Arguments
+    ///
+    /// * `min_mm` : Distance mapped to 1, closer targets are clamped to it.
+    /// * `max_mm` : Distance mapped to 255, farther targets are clamped to it.
+    ///
+    /// # Return
+    ///
+    /// * `image` : Depth image, e.g. `results.to_depth_image_u8::<8>(0, 4000)`.
+    #[cfg(not(any(feature="VL53L5CX_DISABLE_DISTANCE_MM", feature="VL53L5CX_DISABLE_TARGET_STATUS")))]
+    pub fn to_depth_image_u8<const N: usize>(&self, min_mm: i16, max_mm: i16) -> [[u8; N]; N]
+        where Grid<N>: GridSize
+    {
+        let distances: [[i16; N]; N] = self.distance_grid();
+        let statuses: [[u8; N]; N] = self.target_status_grid();
+        let mut image: [[u8; N]; N] = [[DEPTH_IMAGE_INVALID; N]; N];
+
+        for (row, line) in image.iter_mut().enumerate() {
+            for (col, pixel) in line.iter_mut().enumerate() {
+                let distance: i16 = distances[row][col];
+                *pixel = match statuses[row][col] {
+                    5 | 9 if distance <= min_mm => 1,
+                    5 | 9 if distance >= max_mm => 255,
+                    5 | 9 => (1 + (distance as i32 - min_mm as i32) * 254 / (max_mm as i32 - min_mm as i32)) as u8,
+                    _ => DEPTH_IMAGE_INVALID
+                };
+            }
+        }
+
+        image
+    }
+}
@@ -0,0 +1,97 @@
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::digital::InputPin;
+
+use consts::*;
+
+use crate::{consts, BusOperation, Error, Vl53l5cx, OutputPin, DelayNs};
+
+/// Structure DataReadyInterrupt waits for the frames on the INT pin (GPIO1) of the
+/// sensor, instead of polling check_data_ready() over I2C, which frees the bus for
+/// the other sensors. The INT pin is open drain and active low: the sensor pulls it
+/// low when a frame is ready. The driver is accessed through the wrapper, e.g. to
+/// read the frames with get_ranging_data(). The pin is sampled, not latched: a low 
+/// pulse shorter than the poll interval (see set_poll_interval_us()) can fall between 
+/// two reads, and its frame is then only seen with the next pulse.
+pub struct DataReadyInterrupt<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs, I: InputPin> {
+    sensor: &'a mut Vl53l5cx<B, LPN, RST, T>,
+    int_pin: I,
+    poll_interval_us: u32,
+  // Level of the INT pin at the last read, None before the first wait
+    was_low: Option<bool>
+}
+
+impl<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs, I: InputPin> DataReadyInterrupt<'a, B, LPN, RST, T, I> {
+    /// This function creates the wrapper on the driver and the INT pin.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor` : Driver of the sensor.
+    /// * `int_pin` : Input pin connected to the INT pin of the sensor, with a pull-up.
+    pub fn new(sensor: &'a mut Vl53l5cx<B, LPN, RST, T>, int_pin: I) -> Self {
+        DataReadyInterrupt { sensor, int_pin, poll_interval_us: VL53L5CX_INT_POLL_INTERVAL_US, was_low: None }
+    }
+
+    /// This function gives back the INT pin.
+    pub fn release(self) -> I {
+        self.int_pin
+    }
+
+    /// This function sets the interval between two reads of the INT pin. It must be
+    /// shorter than the low pulse of the INT pin, otherwise a pulse can be missed and 
+    /// wait_for_data_ready() keeps waiting for the next one, or times out.
+    ///
+    /// # Arguments
+    ///
+    /// * `poll_interval_us` : Interval in us, VL53L5CX_INT_POLL_INTERVAL_US by default.
+    pub fn set_poll_interval_us(&mut self, poll_interval_us: u32) {
+        self.poll_interval_us = poll_interval_us;
+    }
+
+    /// This function waits for a new frame. Only the INT pin is read while waiting:
+    /// the bus is accessed once the pin goes low, by check_data_ready(), which
+    /// confirms the frame (the warm-up frames are still discarded, see
+    /// discard_first_frames()). The frame is then read with get_ranging_data().
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout_ms` : Maximum wait in ms.
+    ///
+    /// # Return
+    ///
+    /// * `Error::Timeout` if no frame was ready within `timeout_ms`, or `Error::Pin` 
+    ///   if the INT pin can not be read.
+    pub fn wait_for_data_ready(&mut self, timeout_ms: u32) -> Result<(), Error<B::Error>> {
+        let timeout_us: u64 = timeout_ms as u64 * 1000;
+        let mut elapsed_us: u64 = 0;
+
+        loop {
+            let is_low: bool = self.int_pin.is_low().map_err(|_| Error::Pin)?;
+            // The bus is only polled on a falling edge, or at the first wait if the pin is already low
+            if is_low && self.was_low != Some(true) && self.sensor.check_data_ready()? {
+                self.was_low = Some(true);
+                return Ok(());
+            }
+            self.was_low = Some(is_low);
+            if elapsed_us >= timeout_us {
+                return Err(self.sensor.stats.record(Error::Timeout));
+            }
+            self.sensor.delay_us(self.poll_interval_us);
+            elapsed_us += self.poll_interval_us.max(1) as u64;
+        }
+    }
+}
+
+impl<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs, I: InputPin> Deref for DataReadyInterrupt<'a, B, LPN, RST, T, I> {
+    type Target = Vl53l5cx<B, LPN, RST, T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.sensor
+    }
+}
+
+impl<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs, I: InputPin> DerefMut for DataReadyInterrupt<'a, B, LPN, RST, T, I> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.sensor
+    }
+}
@@ -86,27 +86,164 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod accessors;
+pub mod app;
+#[cfg(feature = "async")]
+pub mod asynch;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod background;
+pub mod bandwidth;
 pub mod buffers;
 pub mod bus_operation;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod ceiling_mount;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod compact;
+pub mod config;
+pub mod const_config;
 pub mod consts;
+pub mod dci;
+pub mod delay;
 pub mod detection_thresholds;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod downsample;
+#[cfg(feature = "alloc")]
+pub mod dyn_results;
+pub mod erased;
+pub mod events;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod feature_vector;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod firmware;
+#[cfg(feature = "embedded-storage")]
+pub mod flash_recorder;
+#[cfg(feature = "float-results")]
+pub mod float_results;
+#[cfg(feature = "heapless")]
+pub mod frame_pump;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod glass;
+pub mod grid;
+pub mod interrupt;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod material;
 pub mod motion_indicator;
+pub mod multizone;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod noise_floor;
+#[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+pub mod offset_check;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub mod orientation;
+pub mod parser;
+pub mod prelude;
+pub mod processor;
+#[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+pub mod rangefinder;
+pub mod ranging_session;
+mod shared;
+#[cfg(feature = "soft-i2c")]
+pub mod soft_i2c;
+pub mod st_layout;
+#[cfg(not(feature = "VL53L5CX_DISABLE_TARGET_STATUS"))]
+pub mod status_histogram;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", all(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))))]
+pub mod targets;
+pub mod temperature;
+pub mod timing;
+#[cfg(feature = "uld-api")]
+pub mod uld;
+pub mod units;
+pub mod upsample;
 pub mod utils;
+pub mod virtual_array;
+#[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+pub mod vl53l1x_compat;
+pub mod watchdog;
 pub mod xtalk;
 
 use accessors::*;
+use app::*;
+#[cfg(feature = "async")]
+use asynch::*;
+use bandwidth::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use background::*;
 use buffers::*;
 use bus_operation::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use ceiling_mount::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use compact::*;
+use config::*;
+use const_config::*;
 use consts::*;
+use dci::*;
+use delay::*;
 use detection_thresholds::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use downsample::*;
+#[cfg(feature = "alloc")]
+use dyn_results::*;
+use erased::*;
+use events::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use feature_vector::*;
+#[cfg(feature = "ffi")]
+use ffi::*;
+use firmware::*;
+#[cfg(feature = "embedded-storage")]
+use flash_recorder::*;
+#[cfg(feature = "float-results")]
+use float_results::*;
+#[cfg(feature = "heapless")]
+use frame_pump::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use glass::*;
+use grid::*;
+use interrupt::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use material::*;
 use motion_indicator::*;
+use multizone::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use noise_floor::*;
+#[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+use offset_check::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+use orientation::*;
+use parser::*;
+use processor::*;
+use ranging_session::*;
+use shared::*;
+#[cfg(feature = "soft-i2c")]
+use soft_i2c::*;
+use st_layout::*;
+#[cfg(not(feature = "VL53L5CX_DISABLE_TARGET_STATUS"))]
+use status_histogram::*;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", all(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))))]
+use targets::*;
+use temperature::*;
+use timing::*;
+#[cfg(feature = "uld-api")]
+use uld::*;
+use units::*;
+use upsample::*;
 use utils::*;
+use virtual_array::*;
+#[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+use vl53l1x_compat::*;
+use watchdog::*;
 use xtalk::*;
 
 use embedded_hal::{
     i2c::{I2c, SevenBitAddress},
-    digital::OutputPin, 
+    spi::{SpiDevice, Operation as SpiOperation},
+    digital::{OutputPin, StatefulOutputPin}, 
     delay::DelayNs
 };
 
@@ -125,13 +262,44 @@ pub struct Vl53l5cx<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>
     pub(crate) offset_data: [u8;  VL53L5CX_OFFSET_BUFFER_SIZE],
     pub(crate) xtalk_data: [u8; VL53L5CX_XTALK_BUFFER_SIZE],
     pub(crate) streamcount: u8,
+    pub(crate) data_pending: bool,
+    pub(crate) discarded_first_frames: u8,
+    pub(crate) frames_to_discard: u8,
+    pub(crate) frame_watchdog: Option<FrameWatchdog>,
     pub(crate) data_read_size: u32,
     pub(crate) is_auto_stop_enabled: bool,
+    pub(crate) is_ranging: bool,
+    pub(crate) frame_layout: FrameLayout,
+    pub(crate) invalid_distance: InvalidDistance,
 
     pub(crate) lpn_pin: LPN,
     pub(crate) i2c_rst_pin: RST,
     
     pub(crate) chunk_size: usize,
+    pub(crate) chunk_delay_us: u32,
+    pub(crate) poll_interval_us: u32,
+    pub(crate) boot_poll_interval_us: u32,
+    pub(crate) verify_firmware_upload: bool,
+    pub(crate) init_strategy: InitStrategy,
+    pub(crate) fast_data_ready: bool,
+    pub(crate) init_observer: Option<fn(InitStage)>,
+    pub(crate) temperature_compensation: Option<TemperatureCompensation>,
+    pub(crate) virtual_array: Option<VirtualArray>,
+    pub(crate) bus_arbiter: Option<BusArbiter>,
+    pub(crate) overrun_check: bool,
+    pub(crate) power_rail: Option<fn(bool)>,
+    pub(crate) power_timings: PowerTimings,
+    pub(crate) sensor_variant: SensorVariant,
+    pub(crate) config_locked: bool,
+    pub(crate) status_indicator: Option<fn(DriverState)>,
+    pub(crate) driver_state: DriverState,
+    pub(crate) debug_outputs: [u32; VL53L5CX_MAX_DEBUG_OUTPUTS],
+    pub(crate) nb_debug_outputs: usize,
+    pub(crate) motion_indicator_output: bool,
+    pub(crate) auto_trim_bus_clock_hz: Option<u32>,
+    pub(crate) trimmed_outputs: u32,
+    pub(crate) stats: Stats,
+    pub(crate) last_error: Option<LastErrorSnapshot>,
     pub(crate) bus: B,
     pub(crate) tim: T
 }
@@ -139,13 +307,180 @@ pub struct Vl53l5cx<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>
 #[derive(Copy, Clone, Debug)]
 pub enum Error<B> {
     Bus(B),
-    Other,
     Timeout,
     Mcu,
     Go2,
     CorruptedFrame,
     InvalidParam,
-    CheckSumFail
+    CheckSumFail,
+    FirmwareMismatch(FirmwareMismatch),
+    BufferTooSmall,
+    NoValidTarget,
+    NotReady,
+    InvalidState,
+    FirmwareSource,
+    BusDeferred,
+    Parse(ParseError),
+    Calibration(CalibrationError),
+    Threshold(ThresholdError),
+    IdMismatch { device_id: u8, revision_id: u8 },
+    SizeMismatch { expected: usize, got: usize },
+    BusBudget(BusBudget),
+    ConfigLocked,
+    Pin
+}
+
+/// Structure Stats contains the number of failed and retried transactions 
+/// since the creation of the driver, or since the last reset_stats(). 
+/// Counters wrap around on overflow.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+  // Number of I2C transactions returning an error
+    pub bus_errors: u32,
+  // Number of status polls repeated because the expected value was not reached yet
+    pub retries: u32,
+  // Number of polls which reached their timeout
+    pub timeouts: u32,
+  // Number of errors reported by the sensor MCU
+    pub mcu_errors: u32,
+  // Number of GO2 errors reported when checking for new data
+    pub go2_errors: u32,
+  // Number of frames rejected by the parser
+    pub corrupted_frames: u32
+}
+
+impl Stats {
+    /// Inner function, not available outside this crate. 
+    /// This function counts `err` and returns it.
+    pub(crate) fn record<E>(&mut self, err: Error<E>) -> Error<E> {
+        let counter: Option<&mut u32> = match err {
+            Error::Bus(_) => Some(&mut self.bus_errors),
+            Error::Timeout => Some(&mut self.timeouts),
+            Error::Mcu => Some(&mut self.mcu_errors),
+            Error::Go2 => Some(&mut self.go2_errors),
+            Error::CorruptedFrame | Error::Parse(_) => Some(&mut self.corrupted_frames),
+            _ => None
+        };
+        if let Some(counter) = counter {
+            *counter = counter.wrapping_add(1);
+        }
+        err
+    }
+}
+
+/// Structure LastErrorSnapshot contains the status registers read when the 
+/// last GO2 error was reported by check_data_ready() (see last_error()).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LastErrorSnapshot {
+  // First 4 bytes of the UI frame (stream count, status bytes and GO2 flags)
+    pub ui_status: [u8; 4],
+  // GO2 error status reported by the firmware (byte 2 of the UI frame)
+    pub go2_status: u8,
+  // MCU status registers 0x06 and 0x07, None if they could not be read
+    pub mcu_status: Option<[u8; 2]>,
+  // Stream count of the last frame read before the error
+    pub streamcount: u8,
+  // Value of the GO2 errors counter after this error (see stats())
+    pub go2_errors: u32
+}
+
+/// Structure FirmwareMismatch reports the differences found when reading back 
+/// the firmware uploaded in the sensor (see set_verify_firmware_upload()). 
+/// Offsets are given in bytes from the start of the firmware buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FirmwareMismatch {
+    pub first_offset: usize,
+    pub last_offset: usize,
+    pub count: usize
+}
+
+/// Enum BusBurst contains the kinds of transaction bursts given to the bus arbiter, 
+/// so that it can prioritize or defer them relative to the other users of the bus.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BusBurst {
+    /// Read of the stream count and status bytes, a few bytes.
+    DataReadyCheck,
+    /// Read of a frame, up to VL53L5CX_MAX_RESULTS_SIZE bytes.
+    FrameRead,
+    /// Read or write of the firmware configuration (DCI command).
+    Command,
+    /// Upload of the firmware by init(), about 86kB.
+    FirmwareUpload
+}
+
+/// Structure BusArbiter contains the hooks called around each transaction burst 
+/// (see set_bus_arbiter()), for systems sharing the I2C bus with other peripherals.
+#[derive(Copy, Clone, Debug)]
+pub struct BusArbiter {
+  // Called before the burst, returns false to defer it (Error::BusDeferred is returned)
+    pub acquire: fn(BusBurst) -> bool,
+  // Called after the burst, only if the bus was acquired
+    pub release: fn(BusBurst),
+}
+
+/// Enum DriverState contains the states of the driver reported to the status indicator 
+/// (see set_status_indicator()), e.g. to drive a debug LED.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DriverState {
+    /// The sensor is not initialized, or is powered off.
+    Off,
+    /// The sensor is being initialized.
+    Init,
+    /// The sensor is initialized, and not ranging.
+    Idle,
+    /// The sensor is ranging.
+    Ranging,
+    /// The initialization failed, or the firmware reported a GO2 error. 
+    /// The state is kept until the next initialization.
+    Error,
+    /// The sensor is being initialized again after an error.
+    Recovering
+}
+
+/// Enum InitStage contains the stages of init(), given to the observer set with 
+/// set_init_observer() when each stage starts. When init() hangs or fails, 
+/// the last reported stage tells where it stopped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InitStage {
+    /// Check of the firmware already running, with InitStrategy::IfNeeded.
+    FwCheck,
+    /// Software reboot of the sensor.
+    Reboot,
+    /// Upload of the firmware.
+    FwUpload,
+    /// Reset of the MCU, and wait for its boot.
+    McuBoot,
+    /// Read of the offset data from the NVM.
+    NvmRead,
+    /// Upload of the default Xtalk data.
+    XtalkSent,
+    /// Upload of the default configuration.
+    ConfigSent,
+    /// The sensor is initialized.
+    Done
+}
+
+/// Enum CommandStatus contains the decoded values of the firmware 
+/// command status register (VL53L5CX_UI_CMD_STATUS). It is used with 
+/// wait_for_command_status() to wait for the end of a DCI command.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The firmware has processed the last command (byte 1 equals 0x03).
+    Done,
+    /// The NVM data requested by the host is available (byte 0 equals 0x02).
+    NvmReady,
+    /// Any other status, described by its byte position, mask and expected value.
+    Raw { pos: u8, mask: u8, expected_val: u8 }
+}
+
+impl CommandStatus {
+    pub(crate) fn decode(self) -> (u8, u8, u8) {
+        match self {
+            CommandStatus::Done => (1, 0xff, 0x03),
+            CommandStatus::NvmReady => (0, 0xff, 0x02),
+            CommandStatus::Raw { pos, mask, expected_val } => (pos, mask, expected_val)
+        }
+    }
 }
 
 /// Structure ResultsData contains the ranging results of
@@ -186,7 +521,12 @@ pub struct ResultsData {
     pub target_status: [u8; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)],
     #[cfg(not(feature="VL53L5CX_DISABLE_MOTION_INDICATOR"))]
   // Motion detector results 
-    pub motion_indicator: MotionIndicator
+    pub motion_indicator: MotionIndicator,
+  // Information about the parsed frame, such as the outputs present in the frame
+    pub frame_info: FrameInfo,
+    #[cfg(feature = "float-results")]
+  // Floating point view of the results 
+    pub float_results: FloatResults
 } 
 
 impl ResultsData {
@@ -210,102 +550,177 @@ impl ResultsData {
             #[cfg(not(feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
             target_status: [0; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)],
             #[cfg(not(feature="VL53L5CX_DISABLE_MOTION_INDICATOR"))]
-            motion_indicator: MotionIndicator::new()
+            motion_indicator: MotionIndicator::new(),
+            frame_info: FrameInfo::new(),
+            #[cfg(feature = "float-results")]
+            float_results: FloatResults::new()
         }
     }
 }
 
+/// Structure FrameInfo contains information about a parsed frame. 
+/// Outputs which are not present in the frame are left to zero in ResultsData, 
+/// so `present_outputs` must be used to distinguish them from real zeros.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameInfo {
+  // Bitmask of the outputs present in the frame (macros VL53L5CX_OUTPUT_*)
+    pub present_outputs: u32,
+  // Raw metadata block, in host byte order. Apart from the silicon temperature, 
+  // its fields are not documented by ST: no frame timestamp or age is known to be 
+  // available, so latency compensation must rely on the host time of the data ready event.
+    pub metadata: [u8; VL53L5CX_METADATA_SIZE]
+}
+
+impl Default for FrameInfo {
+    fn default() -> Self {
+        FrameInfo::new()
+    }
+}
+
+impl FrameInfo {
+    pub fn new() -> Self {
+        FrameInfo { present_outputs: 0, metadata: [0; VL53L5CX_METADATA_SIZE] }
+    }
+
+    /// This function checks if an output is present in the frame.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `output` : Output to check, using macros VL53L5CX_OUTPUT_*.
+    pub fn is_present(&self, output: u32) -> bool {
+        self.present_outputs & output == output
+    }
+}
+
 impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
+    /// Inner function, not available outside this crate. 
+    /// This function creates a driver on the given bus, with the default settings.
+    pub(crate) fn with_bus(bus: B, lpn_pin: LPN, i2c_rst_pin: RST, tim: T) -> Self {
+        Vl53l5cx { 
+            temp_buffer: [0; VL53L5CX_TEMPORARY_BUFFER_SIZE],
+            offset_data: [0; VL53L5CX_OFFSET_BUFFER_SIZE],
+            xtalk_data: [0; VL53L5CX_XTALK_BUFFER_SIZE],
+            streamcount: 0,
+            data_pending: false,
+            discarded_first_frames: 0,
+            frames_to_discard: 0,
+            frame_watchdog: None,
+            data_read_size: 0,
+            is_auto_stop_enabled: false,
+            is_ranging: false,
+            frame_layout: FrameLayout::new(),
+            invalid_distance: InvalidDistance::Zero,
+            lpn_pin,
+            i2c_rst_pin,
+            bus,
+            tim,
+            chunk_size: I2C_CHUNK_SIZE,
+            chunk_delay_us: 0,
+            poll_interval_us: VL53L5CX_POLL_INTERVAL_US,
+            boot_poll_interval_us: VL53L5CX_BOOT_POLL_INTERVAL_US,
+            verify_firmware_upload: false,
+            init_strategy: InitStrategy::Always,
+            fast_data_ready: false,
+            init_observer: None,
+            temperature_compensation: None,
+            virtual_array: None,
+            bus_arbiter: None,
+            overrun_check: false,
+            power_rail: None,
+            power_timings: PowerTimings::new(),
+            sensor_variant: SensorVariant::Vl53l5cx,
+            config_locked: false,
+            status_indicator: None,
+            driver_state: DriverState::Off,
+            debug_outputs: [0; VL53L5CX_MAX_DEBUG_OUTPUTS],
+            nb_debug_outputs: 0,
+            motion_indicator_output: !cfg!(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"),
+            auto_trim_bus_clock_hz: None,
+            trimmed_outputs: 0,
+            stats: Stats::default(),
+            last_error: None
+        }
+    }
+
     /// Inner function, not available outside this file. 
     /// This function is used to wait for an answer from VL53L5CX sensor.
     pub(crate) fn poll_for_answer(&mut self, size: usize, pos: u8, reg: u16, mask: u8, expected_val: u8) -> Result<(), Error<B::Error>> {
-        let mut timeout: u8 = 0;
+        let mut elapsed_us: u32 = 0;
 
-        while timeout <= 200 {
+        while elapsed_us <= VL53L5CX_POLL_TIMEOUT_US {
             self.read_from_register(reg, size)?;
-            self.delay(10);
+            self.delay_us(self.poll_interval_us);
             
             if size >= 4 && self.temp_buffer[2] >= 0x7F {
-                return Err(Error::Mcu);
+                return Err(self.stats.record(Error::Mcu));
             }
             if self.temp_buffer[pos as usize] & mask == expected_val {
                 return Ok(());
             }
-            timeout+=1;
+            self.stats.retries = self.stats.retries.wrapping_add(1);
+            elapsed_us += self.poll_interval_us;
+        }
+        Err(self.stats.record(Error::Timeout))
+    }
+
+    /// This function waits for the firmware command status to reach `status`.
+    /// It can be used after sending a custom DCI command to the firmware. 
+    /// An MCU error is returned if the firmware reports an error, 
+    /// and a timeout error if the status is not reached after 2 seconds.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `status` : Expected command status.
+    pub fn wait_for_command_status(&mut self, status: CommandStatus) -> Result<(), Error<B::Error>> {
+        let (pos, mask, expected_val) = status.decode();
+        self.poll_for_answer(4, pos, VL53L5CX_UI_CMD_STATUS, mask, expected_val)
+    }
+
+    /// Inner function, not available outside this crate. 
+    /// This function waits for a new frame, polling check_data_ready() at the interval 
+    /// of the firmware status polls, and returns a timeout error after 2 seconds.
+    pub(crate) fn wait_for_data_ready(&mut self) -> Result<(), Error<B::Error>> {
+        let mut elapsed_us: u32 = 0;
+
+        while elapsed_us <= VL53L5CX_POLL_TIMEOUT_US {
+            if self.check_data_ready()? {
+                return Ok(());
+            }
+            self.delay_us(self.poll_interval_us);
+            elapsed_us += self.poll_interval_us;
         }
-        Err(Error::Timeout)
+        Err(self.stats.record(Error::Timeout))
     }
 
     /// Inner function, not available outside this file. 
     /// This function is used to wait for the MCU to boot.
     pub(crate) fn poll_for_mcu_boot(&mut self) -> Result<(), Error<B::Error>> {
-        let mut timeout: u16 = 0;
+        let mut elapsed_us: u32 = 0;
 
-        while timeout <= 500 {
+        while elapsed_us <= VL53L5CX_BOOT_TIMEOUT_US {
             self.read_from_register(0x06, 2)?;
             if self.temp_buffer[0] & 0x80 != 0 {
                 if self.temp_buffer[1] & 0x01 != 0 {
                     return Ok(());
                 }
             }
-            self.delay(1);
+            self.delay_us(self.boot_poll_interval_us);
             if self.temp_buffer[0] & 0x01 != 0 {
                 return Ok(());
             }
-            timeout += 1;
+            self.stats.retries = self.stats.retries.wrapping_add(1);
+            elapsed_us += self.boot_poll_interval_us;
         }
-        Err(Error::Timeout)
+        Err(self.stats.record(Error::Timeout))
     }
 
     /// Inner function, not available outside this file. 
     /// This function is used to set the offset data gathered from NVM.
     pub(crate) fn send_offset_data(&mut self, resolution: u8) -> Result<(), Error<B::Error>> {
-        let mut signal_grid: [u32; 64] = [0; 64];
-        let mut range_grid: [i16; 64] = [0; 64];
-        let dss_4x4: [u8; 8] = [0x0F, 0x04, 0x04, 0x00, 0x08, 0x10, 0x10, 0x07];
-        let footer: [u8; 8] = [0x00, 0x00, 0x00, 0x0F, 0x03, 0x01, 0x01, 0xE4];
-
-        self.temp_buffer[..VL53L5CX_OFFSET_BUFFER_SIZE].copy_from_slice(&self.offset_data);
-
-        // Data extrapolation is required for 4X4 offset 
-        if resolution == VL53L5CX_RESOLUTION_4X4 {
-            self.temp_buffer[0x10..0x10+dss_4x4.len()].copy_from_slice(&dss_4x4);
-            swap_buffer(&mut self.temp_buffer, VL53L5CX_OFFSET_BUFFER_SIZE);
-            from_u8_to_u32(&mut self.temp_buffer[0x3c..0x3c+256], &mut signal_grid);
-            from_u8_to_i16(&mut self.temp_buffer[0x140..0x140+128], &mut range_grid);
-            
-            for j in 0..4 {
-                for i in 0..4 {
-                    signal_grid[i + (4 * j)] = ((
-                          signal_grid[(2 * i) + (16 * j) + 0] as u64 
-                        + signal_grid[(2 * i) + (16 * j) + 1] as u64 
-                        + signal_grid[(2 * i) + (16 * j) + 8] as u64 
-                        + signal_grid[(2 * i) + (16 * j) + 9] as u64 
-                    ) /4) as u32;
-                    range_grid[i + (4 * j)] = ((
-                          range_grid[(2 * i) + (16 * j) + 0] as i32 
-                        + range_grid[(2 * i) + (16 * j) + 1] as i32 
-                        + range_grid[(2 * i) + (16 * j) + 8] as i32 
-                        + range_grid[(2 * i) + (16 * j) + 9] as i32
-                    ) /4) as i16;
-                }
-            }
-            signal_grid[16..].copy_from_slice(&[0;48]);
-            range_grid[16..].copy_from_slice(&[0;48]);
-
-            from_u32_to_u8(&mut signal_grid, &mut self.temp_buffer[0x3c..0x3c+256]);
-            from_i16_to_u8(&mut range_grid, &mut self.temp_buffer[0x140..0x140+128]);
+        prepare_offset_data(&mut self.temp_buffer, &self.offset_data, resolution);
 
-            swap_buffer(&mut self.temp_buffer, VL53L5CX_OFFSET_BUFFER_SIZE);
-        }
-
-        for i in 0..VL53L5CX_OFFSET_BUFFER_SIZE-4 {
-            self.temp_buffer[i] = self.temp_buffer[i+8];
-        }
-
-        self.temp_buffer[0x1E0..0x1E0+footer.len()].copy_from_slice(&footer);
         self.write_multi_to_register_temp_buffer(0x2E18, VL53L5CX_OFFSET_BUFFER_SIZE)?;
-        self.poll_for_answer(4, 1, VL53L5CX_UI_CMD_STATUS, 0xFF, 0x03)?;
+        self.wait_for_command_status(CommandStatus::Done)?;
 
         Ok(())
     }   
@@ -313,41 +728,10 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// Inner function, not available outside this file. 
     /// This function is used to set the Xtalk data from generic configuration, or user's calibration.
     pub(crate) fn send_xtalk_data(&mut self, resolution: u8) -> Result<(), Error<B::Error>> {
-        let res4x4: [u8; 8] = [0x0F, 0x04, 0x04, 0x17, 0x08, 0x10, 0x10, 0x07];
-        let dss_4x4: [u8; 8] = [0x00, 0x78, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08];
-        let profile_4x4: [u8; 4] = [0xA0, 0xFC, 0x01, 0x00];
-        let mut signal_grid: [u32; 64] = [0; 64];
-
-        self.temp_buffer[..VL53L5CX_XTALK_BUFFER_SIZE].copy_from_slice(&self.xtalk_data);
-
-        // Data extrapolation is required for 4X4 Xtalk 
-        if resolution == VL53L5CX_RESOLUTION_4X4 {
-            self.temp_buffer[0x8..0x8 + res4x4.len()].copy_from_slice(&res4x4);
-            self.temp_buffer[0x020..0x020 + dss_4x4.len()].copy_from_slice(&dss_4x4);
-
-            swap_buffer(&mut self.temp_buffer, VL53L5CX_XTALK_BUFFER_SIZE);
-            from_u8_to_u32(&mut self.temp_buffer[0x34..0x34+256], &mut signal_grid);
-
-            for j in 0..4 {
-                for i in 0..4 {
-                    signal_grid[i + (4 * j)] = ((
-                        signal_grid[(2 * i) + (16 * j) + 0] as u64 
-                      + signal_grid[(2 * i) + (16 * j) + 1] as u64 
-                      + signal_grid[(2 * i) + (16 * j) + 8] as u64 
-                      + signal_grid[(2 * i) + (16 * j) + 9] as u64 
-                  ) /4) as u32;
-                }
-            }
-            signal_grid[16..].copy_from_slice(&[0;48]);
-            from_u32_to_u8(&mut signal_grid, &mut self.temp_buffer[0x34..0x34+256]);
-
-            swap_buffer(&mut self.temp_buffer, VL53L5CX_XTALK_BUFFER_SIZE);
-            self.temp_buffer[0x134..0x134+profile_4x4.len()].copy_from_slice(&profile_4x4);
-            self.temp_buffer[0x078..0x078+4].copy_from_slice(&[0; 4]);
-        }
+        prepare_xtalk_data(&mut self.temp_buffer, &self.xtalk_data, resolution);
 
         self.write_multi_to_register_temp_buffer(0x2CF8, VL53L5CX_XTALK_BUFFER_SIZE)?;
-        self.poll_for_answer(4, 1, VL53L5CX_UI_CMD_STATUS, 0xFF, 0x03)?;
+        self.wait_for_command_status(CommandStatus::Done)?;
 
         Ok(())
     }  
@@ -362,15 +746,87 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// * `size` : number of bytes to be read.
     pub(crate) fn read_from_register(&mut self, reg: u16, size: usize) -> Result<(), Error<B::Error>> {
             let mut read_size: usize;
+            if size > VL53L5CX_TEMPORARY_BUFFER_SIZE {
+                return Err(Error::BufferTooSmall);
+            }
             for i in (0..size).step_by(self.chunk_size) {
                 read_size = if size - i > self.chunk_size { self.chunk_size } else { size - i };
-                let a: u8 = (reg + i as u16 >> 8) as u8;
-                let b: u8 = (reg + i as u16 & 0xFF) as u8; 
-                self.bus.write_read(&[a, b], &mut self.temp_buffer[i..i+read_size]).map_err(Error::Bus)?;
+                let address: [u8; 2] = (reg + i as u16).to_be_bytes();
+                self.bus.write_read(&address, &mut self.temp_buffer[i..i+read_size]).map_err(|e| self.stats.record(Error::Bus(e)))?;
             }
         Ok(())
     }
 
+    /// This function runs `f` with the register bank `bank` selected, and selects 
+    /// the default bank (VL53L5CX_DEFAULT_BANK) afterwards, even if `f` fails. 
+    /// It is part of the expert API, with read_register() and write_register(), 
+    /// to access registers outside of the firmware interface.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bank` : Register bank selected during `f`.
+    /// * `f` : Function accessing the registers of the bank.
+    /// 
+    /// # Return
+    /// 
+    /// * `result` : Result of `f`, or the bus error of the bank selection.
+    pub fn with_bank<R>(&mut self, bank: u8, f: impl FnOnce(&mut Self) -> Result<R, Error<B::Error>>) -> Result<R, Error<B::Error>> {
+        self.write_to_register(VL53L5CX_BANK_SELECT, bank)?;
+        let result: Result<R, Error<B::Error>> = f(self);
+        let restore: Result<(), Error<B::Error>> = self.write_to_register(VL53L5CX_BANK_SELECT, VL53L5CX_DEFAULT_BANK);
+
+        let value: R = result?;
+        restore?;
+        Ok(value)
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function runs a burst of transactions between the acquire and release 
+    /// hooks of the bus arbiter (see set_bus_arbiter()). The bus is always released, 
+    /// even when the burst fails.
+    /// 
+    /// # Return
+    /// 
+    /// * `Error::BusDeferred` if the arbiter does not grant the bus, the burst being skipped.
+    fn with_bus_burst<R>(&mut self, burst: BusBurst, f: impl FnOnce(&mut Self) -> Result<R, Error<B::Error>>) -> Result<R, Error<B::Error>> {
+        let arbiter: BusArbiter = match self.bus_arbiter {
+            Some(arbiter) => arbiter,
+            None => return f(self)
+        };
+        if !(arbiter.acquire)(burst) {
+            return Err(Error::BusDeferred);
+        }
+        let result: Result<R, Error<B::Error>> = f(self);
+        (arbiter.release)(burst);
+
+        result
+    }
+
+    /// This function reads registers of the current bank (see with_bank()). 
+    /// It is part of the expert API: the driver state is not updated.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reg` : First register to read.
+    /// * `rbuf` : Buffer receiving the values, of at most VL53L5CX_TEMPORARY_BUFFER_SIZE bytes.
+    pub fn read_register(&mut self, reg: u16, rbuf: &mut [u8]) -> Result<(), Error<B::Error>> {
+        self.read_from_register(reg, rbuf.len())?;
+        rbuf.copy_from_slice(&self.temp_buffer[..rbuf.len()]);
+
+        Ok(())
+    }
+
+    /// This function writes a register of the current bank (see with_bank()). 
+    /// It is part of the expert API: the driver state is not updated.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reg` : Register to write.
+    /// * `val` : Value to write.
+    pub fn write_register(&mut self, reg: u16, val: u8) -> Result<(), Error<B::Error>> {
+        self.write_to_register(reg, val)
+    }
+
     /// Utility function to write data.
     /// * `val` is written in `reg`.
     /// 
@@ -379,9 +835,8 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// * `reg` : specifies internal address register to be overwritten.
     /// * `val` : value to be written.
     pub(crate) fn write_to_register(&mut self, reg: u16, val: u8) -> Result<(), Error<B::Error>> {
-        let a: u8 = (reg >> 8) as u8;
-        let b: u8 = (reg & 0xFF) as u8; 
-        self.bus.write(&[a, b, val]).map_err(Error::Bus)?;
+        let address: [u8; 2] = reg.to_be_bytes();
+        self.bus.write(&[address[0], address[1], val]).map_err(|e| self.stats.record(Error::Bus(e)))?;
        
         Ok(())
     }
@@ -399,10 +854,12 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         let mut tmp: [u8; 32] = [0; 32];
         for i in (0..size).step_by(self.chunk_size-2) {
             write_size = if size - i > self.chunk_size-2 { self.chunk_size-2 } else { size - i };
-            tmp[0] = (reg + i as u16 >> 8) as u8;
-            tmp[1] = (reg + i as u16 & 0xFF) as u8;
+            tmp[..2].copy_from_slice(&(reg + i as u16).to_be_bytes());
             tmp[2..2+write_size].copy_from_slice(&wbuf[i..i+write_size]);
-            self.bus.write(&tmp[..2+write_size]).map_err(Error::Bus)?;    
+            self.bus.write(&tmp[..2+write_size]).map_err(|e| self.stats.record(Error::Bus(e)))?;    
+            if self.chunk_delay_us != 0 {
+                self.tim.delay_us(self.chunk_delay_us);
+            }
         }   
         Ok(())
     }
@@ -421,10 +878,12 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         
         for i in (0..size).step_by(self.chunk_size-2) {
             write_size = if size - i > self.chunk_size-2 { self.chunk_size-2 } else { size - i };
-            tmp[0] = (reg + i as u16 >> 8) as u8;
-            tmp[1] = (reg + i as u16 & 0xFF) as u8;
+            tmp[..2].copy_from_slice(&(reg + i as u16).to_be_bytes());
             tmp[2..2+write_size].copy_from_slice(&self.temp_buffer[i..i+write_size]);
-            self.bus.write(&tmp[..2+write_size]).map_err(Error::Bus)?;   
+            self.bus.write(&tmp[..2+write_size]).map_err(|e| self.stats.record(Error::Bus(e)))?;   
+            if self.chunk_delay_us != 0 {
+                self.tim.delay_us(self.chunk_delay_us);
+            }
         }
         Ok(())
     }
@@ -438,33 +897,89 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         self.tim.delay_ms(ms);
     }
 
-    /// PowerOn the sensor
+    /// Utility function to wait.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `us` : microseconds to wait.
+    pub(crate) fn delay_us(&mut self, us: u32) {
+        self.tim.delay_us(us);
+    }
+
+    /// PowerOn the sensor. The power rail is switched on first (see set_power_rail()), 
+    /// then LPn is set high, with the waits of the power timings (see set_power_timings()). 
+    /// I2C_RST is held low, as required for the I2C interface to answer. 
+    /// Error::Pin is returned if a pin can not be set.
     pub fn on(&mut self) -> Result<(), Error<B::Error>>{
-        self.lpn_pin.set_high().unwrap();
-        self.delay(10);
+        self.i2c_rst_pin.set_low().map_err(|_| Error::Pin)?;
+        if let Some(power_rail) = self.power_rail {
+            power_rail(true);
+            self.delay_us(self.power_timings.supply_settle_us);
+        }
+        self.lpn_pin.set_high().map_err(|_| Error::Pin)?;
+        self.delay_us(self.power_timings.boot_us);
         Ok(())
     }
 
-    /// PowerOff the sensor
+    /// PowerOff the sensor. LPn is set low, and the power rail is switched off last 
+    /// (see set_power_rail()), with the waits of the power timings (see set_power_timings()). 
+    /// Error::Pin is returned if LPn can not be set.
     pub fn off(&mut self) -> Result<(), Error<B::Error>>{
-        self.lpn_pin.set_low().unwrap();
-        self.delay(10);
+        self.lpn_pin.set_low().map_err(|_| Error::Pin)?;
+        self.is_ranging = false;
+        // An error is kept until the next init(), which reports the recovery
+        if self.driver_state != DriverState::Error {
+            self.indicate(DriverState::Off);
+        }
+        self.delay_us(self.power_timings.lpn_low_us);
+        if let Some(power_rail) = self.power_rail {
+            power_rail(false);
+            self.delay_us(self.power_timings.discharge_us);
+        }
         Ok(())
     }
     
-    /// Check if the VL53L5CX sensor is alive (responding to communication).
-    pub fn is_alive(&mut self) -> Result<(), Error<B::Error>> {
-        self.write_to_register(0x7fff, 0x00)?;
-        self.read_from_register(0, 2)?;
-        self.write_to_register(0x7fff, 0x02)?;
+    /// This function checks that the device and revision ids of the sensor are the 
+    /// ones of the sensor variant (see set_sensor_variant()), which ensures that the sensor 
+    /// is alive (responding to communication). Error::IdMismatch is returned with the ids read otherwise.
+    pub fn probe_identity(&mut self) -> Result<(), Error<B::Error>> {
+        self.with_bank(0x00, |s| s.read_from_register(0, 2))?;
         let device_id: u8 = self.temp_buffer[0];
         let revision_id: u8 = self.temp_buffer[1];
-        if (device_id != 0xF0) || (revision_id != 0x02) {
-            return Err(Error::Other);
+        if (device_id != 0xF0) || (revision_id != self.sensor_variant.expected_revision_id()) {
+            return Err(Error::IdMismatch { device_id, revision_id });
+        }
+
+        Ok(())
+    }
+
+    /// This function checks that the sensor is still responding and has not reported 
+    /// a GO2 error, e.g. from a watchdog task after a long idle period. It only reads 
+    /// the identity registers and the status bytes of the UI frame, so it does not 
+    /// disturb the ranging, and does not consume a pending frame.
+    /// 
+    /// # Return
+    /// 
+    /// * `Error::Go2` if the firmware reports a GO2 error (see last_error()), and the 
+    ///   errors of probe_identity() if the sensor does not answer as the sensor variant.
+    pub fn ping(&mut self) -> Result<(), Error<B::Error>> {
+        self.probe_identity()?;
+        self.read_from_register(0, 4)?;
+        if is_go2_error(&self.temp_buffer[..4]) {
+            let err: Error<B::Error> = self.stats.record(Error::Go2);
+            self.capture_go2_snapshot();
+            self.indicate(DriverState::Error);
+            return Err(err);
         }
 
         Ok(())
     }
+
+    /// Check if the VL53L5CX sensor is alive (responding to communication).
+    #[deprecated(note = "use probe_identity() instead")]
+    pub fn is_alive(&mut self) -> Result<(), Error<B::Error>> {
+        self.probe_identity()
+    }
     
     /// This function can be used to read 'extra data' from DCI. 
     /// Using a known `index`, the function fills the first `data_size` bytes 
@@ -477,34 +992,24 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// * `index` : Index of required value.
     /// * `data_size` : This field must be the structure or array size
     pub(crate) fn dci_read_data(&mut self, index: u16, data_size: usize) -> Result<(), Error<B::Error>> {
-        let read_size: usize = data_size + 12; 
-        let mut cmd: [u8; 12] = [
-            0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x0f,
-            0x00, 0x02, 0x00, 0x08
-        ];
+        self.with_bus_burst(BusBurst::Command, |s| s.dci_read(index, data_size))
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function is the body of dci_read_data(), called once the bus is acquired.
+    fn dci_read(&mut self, index: u16, data_size: usize) -> Result<(), Error<B::Error>> {
+        let read_size: usize = data_size + DCI_HEADER_SIZE + DCI_FOOTER_SIZE;
         if read_size > VL53L5CX_TEMPORARY_BUFFER_SIZE {
-            return Err(Error::Other);
+            return Err(Error::BufferTooSmall);
         } 
-        cmd[0] = (index >> 8) as u8;
-        cmd[1] = (index & 0xff) as u8;
-        cmd[2] = ((data_size & 0xff0) >> 4) as u8;
-        cmd[3] = ((data_size & 0xf) << 4) as u8;
         
         // Request data reading from FW 
-        self.write_multi_to_register(VL53L5CX_UI_CMD_END - 11, &cmd)?;
-        self.poll_for_answer(4, 1, VL53L5CX_UI_CMD_STATUS, 0xFF, 0x03)?;
+        self.write_multi_to_register(VL53L5CX_UI_CMD_END - 11, &read_command(index, data_size))?;
+        self.wait_for_command_status(CommandStatus::Done)?;
         
         // Read new data sent (4 bytes header + data_size + 8 bytes footer) 
         self.read_from_register(VL53L5CX_UI_CMD_START, read_size)?;
-        swap_buffer(&mut self.temp_buffer, read_size);
-        
-        // Copy data from FW into input structure (-4 bytes to remove header) 
-        for i in 0..data_size {
-            self.temp_buffer[i] = self.temp_buffer[i+4];
-        }
-        
-        Ok(())
+        unframe_read_answer(&mut self.temp_buffer, data_size)
     }   
     
     /// This function can be used to write 'extra data' from DCI. 
@@ -517,42 +1022,31 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `index` : Index of required value.
     /// * `data_size` : This field must be the structure or array size
+    /// 
+    /// # Return
+    /// 
+    /// * `Error::ConfigLocked` if the configuration is locked (see lock_configuration()), 
+    ///   unless `index` is one of the outputs written by start_ranging().
     pub(crate) fn dci_write_data(&mut self, index: u16, data_size: usize) -> Result<(), Error<B::Error>> {
-        let mut headers: [u8; 4] = [0x00, 0x00, 0x00, 0x00];
-        let footer: [u8; 8] = [0x00, 0x00, 0x00, 0x0f, 0x05, 0x01,
-            ((data_size + 8) >> 8) as u8,
-            ((data_size + 8) & 0xFF) as u8
-        ];
-        
-        let address: u16 = VL53L5CX_UI_CMD_END - (data_size as u16 + 12) + 1;
-
-        // Check if cmd buffer is large enough 
-        if (data_size + 12) > VL53L5CX_TEMPORARY_BUFFER_SIZE {
-            return Err(Error::Other);
-        } else {
-            headers[0] = (index >> 8) as u8;
-            headers[1] = (index & 0xff) as u8;
-            headers[2] = ((data_size & 0xff0) >> 4) as u8;
-            headers[3] = ((data_size & 0xf) << 4) as u8;
-
-            // Copy data from structure to FW format (+4 bytes to add header) 
-            swap_buffer(&mut self.temp_buffer, data_size);
-            for i in 0..data_size {
-                self.temp_buffer[data_size-1 - i+4] = self.temp_buffer[data_size-1 - i];
-            }
-
-            // Add headers and footer 
-            self.temp_buffer[..headers.len()].copy_from_slice(&headers);
-            self.temp_buffer[data_size+4..data_size+4+footer.len()].copy_from_slice(&footer);
-
-            // Send data to FW 
-            self.write_multi_to_register_temp_buffer(address, data_size + 12)?;
-            self.poll_for_answer(4, 1, VL53L5CX_UI_CMD_STATUS, 0xff, 0x03)?;
-
-            swap_buffer(&mut self.temp_buffer, data_size);
+        if self.config_locked && !matches!(index, VL53L5CX_DCI_OUTPUT_LIST | VL53L5CX_DCI_OUTPUT_CONFIG | VL53L5CX_DCI_OUTPUT_ENABLES) {
+            return Err(Error::ConfigLocked);
         }
+        self.with_bus_burst(BusBurst::Command, |s| s.dci_write(index, data_size))
+    }
 
-        Ok(())
+    /// Inner function, not available outside this file. 
+    /// This function is the body of dci_write_data(), called once the bus is acquired.
+    fn dci_write(&mut self, index: u16, data_size: usize) -> Result<(), Error<B::Error>> {
+        // Add headers and footer around the data, in FW format 
+        let frame_size: usize = frame_write_command(&mut self.temp_buffer, index, data_size)?;
+        let address: u16 = VL53L5CX_UI_CMD_END - frame_size as u16 + 1;
+
+        // Send data to FW 
+        let sent = self.write_multi_to_register_temp_buffer(address, frame_size)
+            .and_then(|()| self.wait_for_command_status(CommandStatus::Done));
+        unframe_write_command(&mut self.temp_buffer, data_size);
+
+        sent
     }   
     
     /// This function can be used to replace 'extra data' from DCI. 
@@ -576,15 +1070,144 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         Ok(())
     }   
 
+    /// Inner function, not available outside this file. 
+    /// This function reads back the firmware pages uploaded in the sensor, 
+    /// and compares them with the firmware source.
+    pub(crate) fn check_firmware_upload<F: FirmwareSource>(&mut self, firmware: &mut F) -> Result<(), Error<B::Error>> {
+        let mut expected: [u8; 64] = [0; 64];
+        let mut mismatch: Option<FirmwareMismatch> = None;
+
+        for (page, start, end) in VL53L5CX_FIRMWARE_PAGES {
+            self.write_to_register(0x7fff, page)?;
+            for offset in (start..end).step_by(VL53L5CX_TEMPORARY_BUFFER_SIZE) {
+                let read_size: usize = if end - offset > VL53L5CX_TEMPORARY_BUFFER_SIZE { VL53L5CX_TEMPORARY_BUFFER_SIZE } else { end - offset };
+                self.read_from_register((offset - start) as u16, read_size)?;
+                for chunk in (0..read_size).step_by(expected.len()) {
+                    let chunk_size: usize = if read_size - chunk > expected.len() { expected.len() } else { read_size - chunk };
+                    firmware.read(offset + chunk, &mut expected[..chunk_size]).map_err(|_| Error::FirmwareSource)?;
+                    for i in chunk..chunk + chunk_size {
+                        if self.temp_buffer[i] == expected[i - chunk] {
+                            continue;
+                        }
+                        match mismatch.as_mut() {
+                            Some(m) => {
+                                m.last_offset = offset + i;
+                                m.count += 1;
+                            }
+                            None => {
+                                mismatch = Some(FirmwareMismatch { first_offset: offset + i, last_offset: offset + i, count: 1 });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        self.write_to_register(0x7fff, 0x01)?;
+
+        match mismatch {
+            Some(m) => Err(Error::FirmwareMismatch(m)),
+            None => Ok(())
+        }
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function uploads the firmware pages from the firmware source.
+    pub(crate) fn upload_firmware<F: FirmwareSource>(&mut self, firmware: &mut F) -> Result<(), Error<B::Error>> {
+        self.with_bus_burst(BusBurst::FirmwareUpload, |s| s.upload_firmware_pages(firmware))
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function is the body of upload_firmware(), called once the bus is acquired.
+    fn upload_firmware_pages<F: FirmwareSource>(&mut self, firmware: &mut F) -> Result<(), Error<B::Error>> {
+        for (page, start, end) in VL53L5CX_FIRMWARE_PAGES {
+            self.write_to_register(0x7fff, page)?;
+            for offset in (start..end).step_by(VL53L5CX_TEMPORARY_BUFFER_SIZE) {
+                let write_size: usize = if end - offset > VL53L5CX_TEMPORARY_BUFFER_SIZE { VL53L5CX_TEMPORARY_BUFFER_SIZE } else { end - offset };
+                firmware.read(offset, &mut self.temp_buffer[..write_size]).map_err(|_| Error::FirmwareSource)?;
+                self.write_multi_to_register_temp_buffer((offset - start) as u16, write_size)?;
+            }
+        }
+        self.write_to_register(0x7fff, 0x01)?;
+
+        Ok(())
+    }
+
     /// Mandatory function used to initialize the sensor. 
     /// This function must be called after a power on, 
     /// to load the firmware into the VL53L5CX. 
-    /// It takes a few hundred milliseconds.
+    /// It takes a few hundred milliseconds, unless the upload is skipped (see set_init_strategy()).
+    /// The firmware is the one of the sensor variant (see set_sensor_variant()), and a
+    /// SizeMismatch error is returned if it is not VL53L5CX_FIRMWARE_SIZE bytes.
     pub fn init(&mut self) -> Result<(), Error<B::Error>> {
-        let pipe_ctrl: [u8; 4] = [VL53L5CX_NB_TARGET_PER_ZONE as u8, 0x00, 0x01, 0x00];
-        let single_range: [u32; 1] = [0x01];
+        match self.sensor_variant {
+            SensorVariant::Vl53l5cx => self.init_with_firmware(&mut BuiltinFirmware),
+            SensorVariant::Vl53l7cx(blobs) | SensorVariant::Vl53l8cx(blobs) => {
+                if blobs.firmware.len() != VL53L5CX_FIRMWARE_SIZE {
+                    return Err(Error::SizeMismatch { expected: VL53L5CX_FIRMWARE_SIZE, got: blobs.firmware.len() });
+                }
+                self.init_with_firmware(&mut SliceFirmware(blobs.firmware))
+            }
+        }
+    }
 
+    /// This function initializes the sensor as init() does, with the firmware read 
+    /// from a FirmwareSource instead of the array embedded in the driver, e.g. 
+    /// to store the firmware in an external flash, or to update it in the field.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `firmware` : Source of the firmware to upload.
+    /// 
+    /// # Return
+    /// 
+    /// * `Error::FirmwareSource` if the firmware source fails, and `Error::ConfigLocked` 
+    ///   if the configuration is locked, as init() resets it (see lock_configuration()).
+    pub fn init_with_firmware<F: FirmwareSource>(&mut self, firmware: &mut F) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        let state: DriverState = if self.driver_state == DriverState::Error { DriverState::Recovering } else { DriverState::Init };
+        self.indicate(state);
+        let result: Result<(), Error<B::Error>> = self.init_sequence(firmware);
+        self.indicate(if result.is_ok() { DriverState::Idle } else { DriverState::Error });
+
+        result
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function checks if the firmware of the driver is already running, e.g. after 
+    /// a reset of the host which kept the sensor powered. Its version is read with a short 
+    /// timeout, as a sensor without firmware does not answer the DCI commands. The answer 
+    /// is cleared first, so that an answer left in RAM is not taken for the firmware one.
+    fn firmware_is_expected(&mut self) -> Result<bool, Error<B::Error>> {
+        let (pos, mask, expected_val) = CommandStatus::Done.decode();
+
+        self.init_stage(InitStage::FwCheck);
+        self.write_to_register(0x7fff, 0x02)?;
+        self.write_multi_to_register(VL53L5CX_UI_CMD_START, &[0; 16])?;
+        self.write_multi_to_register(VL53L5CX_UI_CMD_END - 11, &read_command(VL53L5CX_DCI_FW_VERSION, 4))?;
+
+        let mut elapsed_us: u32 = 0;
+        while elapsed_us <= VL53L5CX_FW_CHECK_TIMEOUT_US {
+            self.read_from_register(VL53L5CX_UI_CMD_STATUS, 4)?;
+            if self.temp_buffer[2] < 0x7F && self.temp_buffer[pos as usize] & mask == expected_val {
+                self.read_from_register(VL53L5CX_UI_CMD_START, 16)?;
+                unframe_read_answer(&mut self.temp_buffer, 4)?;
+                let raw: u32 = u32::from_le_bytes([self.temp_buffer[0], self.temp_buffer[1], self.temp_buffer[2], self.temp_buffer[3]]);
+                return Ok(FwVersion::from_raw(raw) == self.sensor_variant.expected_fw_version());
+            }
+            self.delay_us(self.poll_interval_us);
+            elapsed_us += self.poll_interval_us;
+        }
+
+        Ok(false)
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function reboots the sensor, uploads the firmware and waits for the MCU boot.
+    fn boot_firmware<F: FirmwareSource>(&mut self, firmware: &mut F) -> Result<(), Error<B::Error>> {
         // SW reboot sequence 
+        self.init_stage(InitStage::Reboot);
         self.write_to_register(0x7fff, 0x00)?;
 	self.write_to_register(0x0009, 0x04)?;
 	self.write_to_register(0x000F, 0x40)?;
@@ -650,13 +1273,12 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
 	self.write_to_register(0x20, 0x06)?;
 
 	/* Download FW into VL53L5 */
-	self.write_to_register(0x7fff, 0x09)?;
-	self.write_multi_to_register(0, &VL53L5CX_FIRMWARE[..0x8000])?;
-	self.write_to_register(0x7fff, 0x0a)?;
-	self.write_multi_to_register(0, &VL53L5CX_FIRMWARE[0x8000..0x10000])?;
-	self.write_to_register(0x7fff, 0x0b)?;
-	self.write_multi_to_register(0, &VL53L5CX_FIRMWARE[0x10000..])?;
-	self.write_to_register(0x7fff, 0x01)?;
+	self.init_stage(InitStage::FwUpload);
+	self.upload_firmware(firmware)?;
+
+	if self.verify_firmware_upload {
+	    self.check_firmware_upload(firmware)?;
+	}
 
 	/* Check if FW correctly downloaded */
 	self.write_to_register(0x7fff, 0x02)?;
@@ -669,6 +1291,7 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
 	self.write_to_register(0x0C, 0x01)?;
 
 	/* Reset MCU and wait boot */
+	self.init_stage(InitStage::McuBoot);
 	self.write_to_register(0x7FFF, 0x00)?;
 	self.write_to_register(0x114, 0x00)?;
 	self.write_to_register(0x115, 0x00)?;
@@ -680,25 +1303,41 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
 	self.write_to_register(0x0B, 0x01)?;
 	self.poll_for_mcu_boot()?;
 
+        Ok(())
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function is the initialization sequence of init_with_firmware().
+    fn init_sequence<F: FirmwareSource>(&mut self, firmware: &mut F) -> Result<(), Error<B::Error>> {
+        let pipe_ctrl: [u8; 4] = [VL53L5CX_NB_TARGET_PER_ZONE as u8, 0x00, 0x01, 0x00];
+        let single_range: [u32; 1] = [0x01];
+
+        self.is_ranging = false;
+
+        if self.init_strategy == InitStrategy::Always || !self.firmware_is_expected()? {
+            self.boot_firmware(firmware)?;
+        }
+
 	self.write_to_register(0x7fff, 0x02)?;
 
 	/* Get offset NVM data and store them into the offset buffer */
+	self.init_stage(InitStage::NvmRead);
 	self.write_multi_to_register(0x2fd8, &VL53L5CX_GET_NVM_CMD)?;
-	self.poll_for_answer(4, 0,
-		VL53L5CX_UI_CMD_STATUS, 0xff, 2)?;
+	self.wait_for_command_status(CommandStatus::NvmReady)?;
 	self.read_from_register(VL53L5CX_UI_CMD_START, VL53L5CX_NVM_DATA_SIZE)?;
 	self.offset_data.copy_from_slice(&self.temp_buffer[..VL53L5CX_OFFSET_BUFFER_SIZE]);
 	self.send_offset_data(VL53L5CX_RESOLUTION_4X4)?;
 
 	/* Set default Xtalk shape. Send Xtalk to sensor */
-	self.xtalk_data.copy_from_slice(&VL53L5CX_DEFAULT_XTALK);
+	self.init_stage(InitStage::XtalkSent);
+	self.xtalk_data.copy_from_slice(self.sensor_variant.default_xtalk());
 	self.send_xtalk_data(VL53L5CX_RESOLUTION_4X4)?;
 
 	/* Send default configuration to VL53L5CX firmware */
-	self.write_multi_to_register(0x2c34, &VL53L5CX_DEFAULT_CONFIGURATION)?;
+	self.init_stage(InitStage::ConfigSent);
+	self.write_multi_to_register(0x2c34, self.sensor_variant.default_configuration())?;
 
-	self.poll_for_answer(4, 1,
-		VL53L5CX_UI_CMD_STATUS, 0xff, 0x03)?;
+	self.wait_for_command_status(CommandStatus::Done)?;
 
     self.temp_buffer[..4].copy_from_slice(&pipe_ctrl);
 	self.dci_write_data(VL53L5CX_DCI_PIPE_CONTROL, 4)?;
@@ -713,73 +1352,61 @@ if VL53L5CX_NB_TARGET_PER_ZONE != 1 {
 
 	self.dci_replace_data(VL53L5CX_GLARE_FILTER, 40, &[1], 1, 0x26)?;
 	self.dci_replace_data(VL53L5CX_GLARE_FILTER, 40, &[1], 1, 0x25)?;
-      
+
+        self.init_stage(InitStage::Done);
         Ok(())
     }
 
     /// This function starts a ranging session. 
-    /// When the sensor streams, host cannot change settings 'on-the-fly'.
+    /// When the sensor streams, host cannot change settings 'on-the-fly'. 
+    /// An InvalidState error is returned if the sensor is already ranging, and a SizeMismatch 
+    /// error if the frame size computed by the driver is not the one of the firmware. 
+    /// With the auto-trim mode (see set_auto_trim()), a BusBudget error is returned if 
+    /// the frames do not fit in the bus budget, even without the optional outputs.
     pub fn start_ranging(&mut self) -> Result<(), Error<B::Error>> {
-        let resolution: u8 = self.get_resolution()?;
+        let zones: Resolution = self.resolution()?;
+        let resolution: u8 = zones.into();
         let mut tmp: [u16; 1] = [0];
         let mut header_config: [u32; 2] = [0, 0];
         let cmd: [u8; 4] = [0x00, 0x03, 0x00, 0x00];
 
+        if self.is_ranging {
+            return Err(Error::InvalidState);
+        }
+
         self.data_read_size = 0;
         self.streamcount = 255;
-        let mut bh: BlockHeader;
-
-        let mut output_bh_enable: [u32; 4] = [0x00000007, 0x00000000, 0x00000000, 0xC0000000];
-
-        let mut output: [u32; 12] = [
-            VL53L5CX_START_BH,
-            VL53L5CX_METADATA_BH,
-            VL53L5CX_COMMONDATA_BH,
-            VL53L5CX_AMBIENT_RATE_BH,
-            VL53L5CX_SPAD_COUNT_BH,
-            VL53L5CX_NB_TARGET_DETECTED_BH,
-            VL53L5CX_SIGNAL_RATE_BH,
-            VL53L5CX_RANGE_SIGMA_MM_BH,
-            VL53L5CX_DISTANCE_BH,
-            VL53L5CX_REFLECTANCE_BH,
-            VL53L5CX_TARGET_STATUS_BH,
-            VL53L5CX_MOTION_DETECT_BH
-        ];
-
-        if !cfg!(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD") { output_bh_enable[0] += 8; }
-        if !cfg!(feature = "VL53L5CX_DISABLE_NB_SPADS_ENABLED") { output_bh_enable[0] += 16; }
-        if !cfg!(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED") { output_bh_enable[0] += 32; }
-        if !cfg!(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD") { output_bh_enable[0] += 64; }
-        if !cfg!(feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM") { output_bh_enable[0] += 128; }
-        if !cfg!(feature = "VL53L5CX_DISABLE_DISTANCE_MM") { output_bh_enable[0] += 256; }
-        if !cfg!(feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT") { output_bh_enable[0] += 512; }
-        if !cfg!(feature = "VL53L5CX_DISABLE_TARGET_STATUS") { output_bh_enable[0] += 1024; }
-        if !cfg!(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR") { output_bh_enable[0] += 2048; }
-        
-        // Update data size 
-        for i in 0..12 {
-            if output[i] == 0 || output_bh_enable[i/32] & (1 << (i%32)) == 0 {
-                continue;
-            }
-            bh = BlockHeader(output[i]);
-            if bh.bh_type() >= 0x01 && bh.bh_type() < 0x0d {
-                if bh.bh_idx() >= 0x54d0 && bh.bh_idx() < 0x54d0 + 960 {
-                    bh.set_bh_size(resolution as u32);} 
-                else {
-                    bh.set_bh_size(resolution as u32 * VL53L5CX_NB_TARGET_PER_ZONE);}
-                self.data_read_size += bh.bh_type() * bh.bh_size();} 
-            else {
-                self.data_read_size += bh.bh_size();}
-            self.data_read_size += 4;
-            output[i] = bh.bh_bytes();
+        self.data_pending = false;
+        self.frames_to_discard = self.discarded_first_frames;
+        if let Some(watchdog) = self.frame_watchdog.as_mut() {
+            watchdog.last_frame = None;
+        }
+        let mut output_bh_enable: [u32; 4] = output_enables(self.motion_indicator_output);
+        let mut output: [u32; NB_STANDARD_OUTPUTS + VL53L5CX_MAX_DEBUG_OUTPUTS] = [0; NB_STANDARD_OUTPUTS + VL53L5CX_MAX_DEBUG_OUTPUTS];
+
+        // Drop optional outputs until the frames fit in the bus budget (see set_auto_trim())
+        self.trimmed_outputs = 0;
+        if let Some(bus_clock_hz) = self.auto_trim_bus_clock_hz {
+            let frequency_hz: u8 = self.get_frequency_hz()?;
+            let kept: u32 = trim_outputs(bus_clock_hz, zones, VL53L5CX_NB_TARGET_PER_ZONE as u8, frequency_hz, output_bh_enable[0])
+                .map_err(Error::BusBudget)?;
+            self.trimmed_outputs = output_bh_enable[0] & !kept;
+            output_bh_enable[0] = kept;
+        }
+
+        let (nb_outputs, data_read_size) = build_output_list(resolution, &mut output_bh_enable, &self.debug_outputs[..self.nb_debug_outputs], &mut output);
+        self.data_read_size = data_read_size;
+
+        // The whole frame must fit in the temporary buffer 
+        if self.data_read_size as usize > VL53L5CX_TEMPORARY_BUFFER_SIZE {
+            return Err(Error::BufferTooSmall);
         }
-        self.data_read_size += 24;
 
-        from_u32_to_u8(&output, &mut self.temp_buffer[..48]);
-        self.dci_write_data(VL53L5CX_DCI_OUTPUT_LIST, 48)?;
+        from_u32_to_u8(&output[..nb_outputs], &mut self.temp_buffer[..4 * nb_outputs]);
+        self.dci_write_data(VL53L5CX_DCI_OUTPUT_LIST, 4 * nb_outputs)?;
         
         header_config[0] = self.data_read_size;
-        header_config[1] = 12+1 as u32;
+        header_config[1] = nb_outputs as u32 + 1;
 
         from_u32_to_u8(&header_config, &mut self.temp_buffer[..8]);
         self.dci_write_data(VL53L5CX_DCI_OUTPUT_CONFIG, 8)?;
@@ -794,24 +1421,31 @@ if VL53L5CX_NB_TARGET_PER_ZONE != 1 {
 
         // Start ranging session 
         self.write_multi_to_register(VL53L5CX_UI_CMD_END - (4-1), &cmd)?;
-        self.poll_for_answer(4, 1, VL53L5CX_UI_CMD_STATUS, 0xff, 0x03)?;
+        self.wait_for_command_status(CommandStatus::Done)?;
+        self.is_ranging = true;
+        self.indicate(DriverState::Ranging);
 
         // Read ui range data content and compare if data size is the correct one 
         self.dci_read_data(0x5440, 12)?;
         from_u8_to_u16(&self.temp_buffer[0x8..0x8+2], &mut tmp);
         if tmp[0] != self.data_read_size as u16 {   
-            return Err(Error::Other);
+            return Err(Error::SizeMismatch { expected: self.data_read_size as usize, got: tmp[0] as usize });
         }
 
         Ok(())
     }
 
     /// This function stops the ranging session. 
-    /// It must be used when the sensor streams, after calling start_ranging().
+    /// It must be used when the sensor streams, after calling start_ranging(). 
+    /// An InvalidState error is returned if the sensor is not ranging.
     pub fn stop_ranging(&mut self) -> Result<(), Error<B::Error>> {
         let mut timeout: u16 = 0;
         let mut auto_flag_stop: [u32; 1] = [0];
 
+        if !self.is_ranging {
+            return Err(Error::InvalidState);
+        }
+
         self.read_from_register(0x2ffc, 4)?;
         from_u8_to_u32(&self.temp_buffer[..4], &mut auto_flag_stop);
 
@@ -836,6 +1470,8 @@ if VL53L5CX_NB_TARGET_PER_ZONE != 1 {
         if self.temp_buffer[0] & 0x80 != 0 {
             self.read_from_register(0x7, 1)?;
             if self.temp_buffer[0] != 0x84 && self.temp_buffer[0] != 0x85 {
+                self.is_ranging = false;
+                self.indicate(DriverState::Idle);
                 return Ok(());
             }
         }
@@ -848,6 +1484,8 @@ if VL53L5CX_NB_TARGET_PER_ZONE != 1 {
         // Stop xshut bypass 
         self.write_to_register(0x09, 0x04)?;
         self.write_to_register(0x7fff, 0x02)?;
+        self.is_ranging = false;
+        self.indicate(DriverState::Idle);
 
         Ok(())
     }
@@ -860,19 +1498,33 @@ if VL53L5CX_NB_TARGET_PER_ZONE != 1 {
     /// * `isReady` : Value is false if data is not ready, 
     /// or true if a new data is ready.
     pub fn check_data_ready(&mut self) -> Result<bool, Error<B::Error>> {
+        let is_ready: bool = self.with_bus_burst(BusBurst::DataReadyCheck, |s| s.poll_data_ready())?;
+        if is_ready && self.frames_to_discard > 0 {
+            // Warm-up frame, consumed without being read
+            self.frames_to_discard -= 1;
+            self.data_pending = false;
+            return Ok(false);
+        }
+
+        Ok(is_ready)
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function is the body of check_data_ready(), called once the bus is acquired.
+    fn poll_data_ready(&mut self) -> Result<bool, Error<B::Error>> {
+        if self.fast_data_ready {
+            return self.check_data_ready_fast();
+        }
+
         let is_ready: bool;
         self.read_from_register(0, 4)?;
-        if (self.temp_buffer[0] != self.streamcount) 
-            && (self.temp_buffer[0] != 0xff) 
-            && (self.temp_buffer[1] == 0x05) 
-            && (self.temp_buffer[2] & 0x05 == 0x05) 
-            && (self.temp_buffer[3] & 0x10 == 0x10) 
-        {
+        if self.temp_buffer[0] != self.streamcount && is_frame_complete(&self.temp_buffer[..4]) {
             is_ready = true;
             self.streamcount = self.temp_buffer[0];
+            self.data_pending = true;
         } else {
-            if self.temp_buffer[3] & 0x80 != 0 {
-                return Err(Error::Go2);
+            if is_go2_error(&self.temp_buffer[..4]) {
+                return Err(self.go2_error());
             }
             is_ready = false;
         }
@@ -880,160 +1532,197 @@ if VL53L5CX_NB_TARGET_PER_ZONE != 1 {
         Ok(is_ready)
     }
 
-    /// This function gets the ranging data, 
-    /// using the selected output and the resolution.
-    /// 
-    /// # Return
-    /// 
-    /// * `results` : VL53L5 results structure.
-    pub fn get_ranging_data(&mut self) -> Result<ResultsData, Error<B::Error>> {
-        let mut result: ResultsData = ResultsData::new();
-        let mut msize: usize;
-        let mut header_id: u16;
-        let mut footer_id: u16;
-        let mut bh: BlockHeader;
-
-        self.read_from_register(0, self.data_read_size as usize)?;
-        self.streamcount = self.temp_buffer[0];
-        swap_buffer(&mut self.temp_buffer, self.data_read_size as usize);
-
-        // Start conversion at position 16 to avoid headers 
-        let mut i: usize = 16;
-        while i < self.data_read_size as usize {
-
-            let mut buf: [u32; 1] = [0;1];
-            from_u8_to_u32(&self.temp_buffer[i..i+4], &mut buf);
-            bh = BlockHeader(buf[0]);
-
-            if bh.bh_type() > 0x1 && bh.bh_type() < 0xd {
-                msize = (bh.bh_type() * bh.bh_size()) as usize;
-            } else  {
-                msize = bh.bh_size() as usize;
-            }
-
-            i += 4;
+    /// Inner function, not available outside this file. 
+    /// This function records a GO2 error reported in the UI status bytes, which must 
+    /// still be in the temporary buffer, and gets the error to return.
+    fn go2_error(&mut self) -> Error<B::Error> {
+        let err: Error<B::Error> = self.stats.record(Error::Go2);
+        self.capture_go2_snapshot();
+        self.indicate(DriverState::Error);
+        err
+    }
 
-            if bh.bh_idx() == VL53L5CX_METADATA_IDX as u32 {
-                result.silicon_temp_degc = self.temp_buffer[i+8] as i8;
-                i += msize; 
-                continue;
-            } 
-            
-            let mut src: &[u8] = &[0];
-            if i+msize <= VL53L5CX_TEMPORARY_BUFFER_SIZE {
-                src = &self.temp_buffer[i..i+msize];
-            }
+    /// Inner function, not available outside this file. 
+    /// This function reports the start of an init() stage to the observer.
+    fn init_stage(&self, stage: InitStage) {
+        if let Some(observer) = self.init_observer {
+            observer(stage);
+        }
+    }
 
-            i += msize;
-            
-            #[cfg(not(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))] 
-            if bh.bh_idx() == VL53L5CX_AMBIENT_RATE_IDX as u32 {
-                from_u8_to_u32(src, &mut result.ambient_per_spad);
-                continue;
-            }
+    /// Inner function, not available outside this file. 
+    /// This function records a new driver state, and reports it to the status indicator.
+    fn indicate(&mut self, state: DriverState) {
+        if state == self.driver_state {
+            return;
+        }
+        self.driver_state = state;
+        if let Some(indicator) = self.status_indicator {
+            indicator(state);
+        }
+    }
 
-            #[cfg(not(feature = "VL53L5CX_DISABLE_NB_SPADS_ENABLED"))] 
-            if bh.bh_idx() == VL53L5CX_SPAD_COUNT_IDX as u32 {
-                from_u8_to_u32(src, &mut result.nb_spads_enabled);
-                continue;
-            }
+    /// Inner function, not available outside this file. 
+    /// This function checks if a new data is ready by reading only the stream count. 
+    /// The status bytes are checked when the frame is read: a GO2 error is returned 
+    /// by get_ranging_data() instead of check_data_ready().
+    fn check_data_ready_fast(&mut self) -> Result<bool, Error<B::Error>> {
+        self.read_from_register(0, 1)?;
+        if self.temp_buffer[0] != self.streamcount && self.temp_buffer[0] != 0xff {
+            self.streamcount = self.temp_buffer[0];
+            self.data_pending = true;
+            return Ok(true);
+        }
 
-            #[cfg(not(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED"))] 
-            if bh.bh_idx() == VL53L5CX_NB_TARGET_DETECTED_IDX as u32 {
-                result.nb_target_detected[..msize].copy_from_slice(src); 
-                continue;
-            }
+        Ok(false)
+    }
 
-            #[cfg(not(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
-            if bh.bh_idx() == VL53L5CX_SIGNAL_RATE_IDX as u32 {
-                from_u8_to_u32(src, &mut result.signal_per_spad);
-                continue;
-            }
+    /// Inner function, not available outside this file. 
+    /// This function saves the status registers after a GO2 error. 
+    /// The UI frame header must still be in the temporary buffer. 
+    /// Bus errors are ignored, so that the GO2 error is the one reported.
+    fn capture_go2_snapshot(&mut self) {
+        let mut ui_status: [u8; 4] = [0; 4];
+        ui_status.copy_from_slice(&self.temp_buffer[..4]);
+
+        let mcu_status: Option<[u8; 2]> = self.with_bank(0x00, |s| {
+            s.read_from_register(0x06, 2)?;
+            Ok([s.temp_buffer[0], s.temp_buffer[1]])
+        }).ok();
+
+        self.last_error = Some(LastErrorSnapshot {
+            ui_status,
+            go2_status: ui_status[2],
+            mcu_status,
+            streamcount: self.streamcount,
+            go2_errors: self.stats.go2_errors
+        });
+    }
 
-            #[cfg(not(feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
-            if bh.bh_idx() == VL53L5CX_RANGE_SIGMA_MM_IDX as u32 {
-                from_u8_to_u16(src, &mut result.range_sigma_mm);   
-                continue;
-            } 
+    /// Inner function, not available outside this file. 
+    /// This function reads a frame, and checks that it is a new and complete one: 
+    /// either reported by check_data_ready(), or with a new stream count.
+    fn read_new_frame(&mut self) -> Result<(), Error<B::Error>> {
+        self.with_bus_burst(BusBurst::FrameRead, |s| s.read_frame())
+    }
 
-            #[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))] 
-            if bh.bh_idx() == VL53L5CX_DISTANCE_IDX as u32 {
-                from_u8_to_i16(src, &mut result.distance_mm);
-                continue;
+    /// Inner function, not available outside this file. 
+    /// This function is the body of read_new_frame(), called once the bus is acquired.
+    fn read_frame(&mut self) -> Result<(), Error<B::Error>> {
+        // Without a frame reported by check_data_ready(), the UI status is checked 
+        // first, so that the full frame is only read when a new frame is pending 
+        if !self.data_pending {
+            self.read_from_register(0, 4)?;
+            if is_go2_error(&self.temp_buffer[..4]) {
+                return Err(self.go2_error());
             }
-
-            #[cfg(not(feature= "VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
-            if bh.bh_idx() == VL53L5CX_REFLECTANCE_EST_PC_IDX as u32 {
-                result.reflectance[..msize].copy_from_slice(src); 
-                continue;
+            if self.temp_buffer[0] == self.streamcount || !is_frame_complete(&self.temp_buffer[..4]) {
+                return Err(Error::NotReady);
             }
+        }
+        if self.overrun_check {
+            self.read_frame_checked(self.data_read_size as usize)?;
+        } else {
+            self.read_from_register(0, self.data_read_size as usize)?;
+        }
+        // The frame reported by check_data_ready() is consumed by the error
+        if is_go2_error(&self.temp_buffer[..4]) {
+            self.data_pending = false;
+            return Err(self.go2_error());
+        }
+        let is_new: bool = self.data_pending || self.temp_buffer[0] != self.streamcount;
+        if !is_new || !is_frame_complete(&self.temp_buffer[..4]) {
+            return Err(Error::NotReady);
+        }
+        self.data_pending = false;
+        self.streamcount = self.temp_buffer[0];
+        if self.frames_to_discard > 0 {
+            self.frames_to_discard -= 1;
+            return Err(Error::NotReady);
+        }
 
-            #[cfg(not(feature = "VL53L5CX_DISABLE_TARGET_STATUS"))]
-            if bh.bh_idx() == VL53L5CX_TARGET_STATUS_IDX as u32 {
-                result.target_status[..msize].copy_from_slice(src); 
-                continue;
-            }
+        Ok(())
+    }
 
-            #[cfg(not(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"))]
-            if bh.bh_idx() == VL53L5CX_MOTION_DETEC_IDX as u32 {
-                from_u8_to_motion_indicator(src, &mut result.motion_indicator);
-                continue;
-            }
+    /// Inner function, not available outside this file. 
+    /// This function reads a frame by chunks, as read_from_register() does, and reads 
+    /// the stream count again every VL53L5CX_OVERRUN_CHECK_INTERVAL bytes. If the sensor 
+    /// started writing the next frame during the read, the read is aborted.
+    /// 
+    /// # Return
+    /// 
+    /// * `Error::CorruptedFrame` if the stream count changed during the read.
+    fn read_frame_checked(&mut self, size: usize) -> Result<(), Error<B::Error>> {
+        let mut read_size: usize;
+        let mut next_check: usize = VL53L5CX_OVERRUN_CHECK_INTERVAL;
+        let mut streamcount: [u8; 1] = [0];
+        if size > VL53L5CX_TEMPORARY_BUFFER_SIZE {
+            return Err(Error::BufferTooSmall);
         }
-        if VL53L5CX_USE_RAW_FORMAT == 0 {
-            // Convert data into their real format 
-            #[cfg(not(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))] {
-                for i in 0..VL53L5CX_RESOLUTION_8X8 as usize {
-                    result.ambient_per_spad[i] /= 2048;
-                }
-            }
-            for i in 0..(VL53L5CX_RESOLUTION_8X8 as usize)*(VL53L5CX_NB_TARGET_PER_ZONE as usize) {
-                #[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))] {
-                    result.distance_mm[i] /= 4;
-                    if result.distance_mm[i] < 0 {
-                        result.distance_mm[i] = 0;
-                    }
-                }
-                #[cfg(not(feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))] {
-                    result.reflectance[i] /= 2;
-                }
-                #[cfg(not(feature = "VL53L5CX_RANGE_SIGMA_MM"))]{
-                    result.range_sigma_mm[i] /= 128;
-                }
-                #[cfg(not(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))] {
-                    result.signal_per_spad[i] /= 2048;   
-                }
-                // Set target status to 255 if no target is detected for this zone 
-                #[cfg(not(any(feature="VL53L5CX_DISABLE_DISTANCE_MM", feature="VL53L5CX_DISABLE_TARGET_STATUS")))] {
-                    for i in 0..VL53L5CX_RESOLUTION_8X8 as usize {
-                        if result.nb_target_detected[i] == 0 {
-                            for j in 0..VL53L5CX_NB_TARGET_PER_ZONE as usize {
-                                result.target_status[VL53L5CX_NB_TARGET_PER_ZONE as usize*i + j] = 255;
-                            }
-                        }
-                    }
-                }
-            }
-
-            #[cfg(not(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"))] {
-                for i in 0..32 {
-                    result.motion_indicator.motion[i] /= 65535;
+        for i in (0..size).step_by(self.chunk_size) {
+            read_size = if size - i > self.chunk_size { self.chunk_size } else { size - i };
+            let address: [u8; 2] = (i as u16).to_be_bytes();
+            self.bus.write_read(&address, &mut self.temp_buffer[i..i+read_size]).map_err(|e| self.stats.record(Error::Bus(e)))?;
+            if i + read_size >= next_check && i + read_size < size {
+                next_check += VL53L5CX_OVERRUN_CHECK_INTERVAL;
+                self.bus.write_read(&[0x00, 0x00], &mut streamcount).map_err(|e| self.stats.record(Error::Bus(e)))?;
+                if streamcount[0] != self.temp_buffer[0] {
+                    return Err(self.stats.record(Error::CorruptedFrame));
                 }
             }
         }
-        
-        // Check if footer id and header id are matching. This allows to detect corrupted frames 
-        header_id = (self.temp_buffer[8] as u16) << 8 & 0xff00;
-        header_id |= (self.temp_buffer[9] as u16) & 0x00ff;
 
-        footer_id = (self.temp_buffer[self.data_read_size as usize - 4] as u16) << 8 & 0xff00;
-        footer_id |= (self.temp_buffer[self.data_read_size as usize - 3] as u16) & 0x00ff;
+        Ok(())
+    }
 
-        if header_id != footer_id {
-            return Err(Error::CorruptedFrame);
+    /// This function gets the ranging data, 
+    /// using the selected output and the resolution. 
+    /// It must follow a data ready event (check_data_ready() or INT pin), 
+    /// Error::NotReady is returned if no new frame is pending, and Error::Go2 if the 
+    /// firmware reports a GO2 error (see last_error()).
+    /// 
+    /// # Return
+    /// 
+    /// * `results` : VL53L5 results structure.
+    pub fn get_ranging_data(&mut self) -> Result<ResultsData, Error<B::Error>> {
+        self.read_new_frame()?;
+        let mut result: ResultsData = parse_ranging_data_compensated(&mut self.temp_buffer, self.data_read_size as usize, &self.frame_layout, self.invalid_distance, self.temperature_compensation)
+            .map_err(|e| self.stats.record(e))?;
+        if let Some(array) = self.virtual_array {
+            result = array.crop(&result);
         }
 
         Ok(result)
     }    
 
+    /// This function gets the ranging data, and calls `visitor` for each block 
+    /// of the frame instead of filling a results structure. The block data is 
+    /// given in the firmware format (see parser::Block). The debug outputs 
+    /// (see set_debug_outputs()) are given as blocks whose output() is 0.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `visitor` : Function called for each block of the frame.
+    pub fn get_ranging_data_with(&mut self, visitor: impl FnMut(Block<'_>)) -> Result<(), Error<B::Error>> {
+        self.read_new_frame()?;
+        parse_frame_with(&mut self.temp_buffer, self.data_read_size as usize, &self.frame_layout, visitor)
+            .map_err(|e| self.stats.record(e))?;
+
+        Ok(())
+    }
+
+    /// This function checks if a new data is ready and, if so, gets the ranging data.
+    /// It can be used in a superloop instead of check_data_ready() followed by get_ranging_data().
+    /// 
+    /// # Return
+    /// 
+    /// * `results` : VL53L5 results structure, or None if no new data is ready.
+    pub fn try_get_ranging_data(&mut self) -> Result<Option<ResultsData>, Error<B::Error>> {
+        if !self.check_data_ready()? {
+            return Ok(None);
+        }
+        let results: ResultsData = self.get_ranging_data()?;
+
+        Ok(Some(results))
+    }
 }
\ No newline at end of file
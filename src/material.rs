@@ -0,0 +1,101 @@
+use consts::*;
+
+use crate::{consts, ResultsData, Resolution};
+
+/// Enum Material contains the classes of target surfaces returned by classify_material().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Material {
+    /// Retro-reflective surface (e.g. reflective tape), returning more than a white target.
+    RetroReflective,
+    /// Bright surface.
+    Bright,
+    /// Surface of medium reflectance.
+    Medium,
+    /// Dark surface.
+    Dark,
+    /// Very weak return, probably from a transparent surface (e.g. glass).
+    GlassLikely,
+    /// No valid target in the zone.
+    Unknown
+}
+
+/// Structure MaterialThresholds contains the thresholds used by classify_material(). 
+/// Reflectances are in percent (as in ResultsData), and signals in kcps/spad. 
+/// The default values are a starting point, to be tuned on the actual targets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MaterialThresholds {
+  // Minimum reflectance of retro-reflective targets
+    pub retro_reflective_percent: u8,
+  // Minimum signal of retro-reflective targets, whatever their reflectance
+    pub retro_reflective_signal_kcps: u32,
+  // Minimum reflectance of bright targets
+    pub bright_percent: u8,
+  // Maximum reflectance of dark targets
+    pub dark_percent: u8,
+  // Maximum reflectance of the targets which are probably glass
+    pub glass_percent: u8,
+  // Maximum signal of the targets which are probably glass
+    pub glass_signal_kcps: u32,
+}
+
+impl Default for MaterialThresholds {
+    fn default() -> Self {
+        MaterialThresholds {
+            retro_reflective_percent: 100,
+            retro_reflective_signal_kcps: 5000,
+            bright_percent: 50,
+            dark_percent: 15,
+            glass_percent: 3,
+            glass_signal_kcps: 10,
+        }
+    }
+}
+
+/// This function classifies a target from its reflectance and signal. 
+/// Targets whose status is not valid (5 or 9) are Material::Unknown.
+/// 
+/// # Arguments
+/// 
+/// * `reflectance_percent` : Reflectance of the target in percent.
+/// * `signal_kcps` : Signal of the target in kcps/spad.
+/// * `target_status` : Status of the target.
+/// * `thresholds` : Classification thresholds.
+pub fn classify_material(reflectance_percent: u8, signal_kcps: u32, target_status: u8, thresholds: &MaterialThresholds) -> Material {
+    if target_status != 5 && target_status != 9 {
+        return Material::Unknown;
+    }
+
+    if reflectance_percent >= thresholds.retro_reflective_percent || signal_kcps >= thresholds.retro_reflective_signal_kcps {
+        Material::RetroReflective
+    } else if reflectance_percent >= thresholds.bright_percent {
+        Material::Bright
+    } else if reflectance_percent <= thresholds.glass_percent && signal_kcps <= thresholds.glass_signal_kcps {
+        Material::GlassLikely
+    } else if reflectance_percent <= thresholds.dark_percent {
+        Material::Dark
+    } else {
+        Material::Medium
+    }
+}
+
+/// This function classifies the first target of each zone of a frame.
+/// 
+/// # Arguments
+/// 
+/// * `results` : Results of the frame.
+/// * `resolution` : Resolution used for the frame.
+/// * `thresholds` : Classification thresholds.
+/// 
+/// # Return
+/// 
+/// * `materials` : Class of each zone. Zones not present in the frame are Material::Unknown.
+pub fn classify_zones(results: &ResultsData, resolution: Resolution, thresholds: &MaterialThresholds) -> [Material; VL53L5CX_RESOLUTION_8X8 as usize] {
+    let mut materials: [Material; VL53L5CX_RESOLUTION_8X8 as usize] = [Material::Unknown; VL53L5CX_RESOLUTION_8X8 as usize];
+
+    for (zone, material) in materials.iter_mut().enumerate().take(resolution.nb_zones()) {
+        let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        *material = classify_material(results.reflectance[idx], results.signal_per_spad[idx], results.target_status[idx], thresholds);
+    }
+
+    materials
+}
@@ -55,6 +55,7 @@ impl MotionConfiguration {
 }
   
 #[repr(C)]
+#[derive(Copy, Clone, Debug)]
 pub struct MotionIndicator {
     pub global_indicator_1: u32,
     pub global_indicator_2: u32,
@@ -153,6 +154,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `resolution` : Wanted resolution, defined by macros VL53L8CX_RESOLUTION_4X4 or VL53L8CX_RESOLUTION_8X8.
     pub fn motion_indicator_init(&mut self, resolution: u8) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let mut motion_config = MotionConfiguration::new();
         self.motion_indicator_set_resolution(&mut motion_config, resolution)?;
         Ok(())
@@ -165,6 +169,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// * `distance_min_mm` : Minimum distance for indicator (min value 400mm, max 4000mm).
     /// * `distance_max_mm` : Maximum distance for indicator (min value 400mm, max 4000mm).
     pub fn motion_indicator_set_distance_motion(&mut self, motion_config: &mut MotionConfiguration, distance_min_mm: u16, distance_max_mm: u16) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let mut tmp: f64;
         if distance_max_mm - distance_min_mm > 1500 || distance_max_mm > 4000 || distance_min_mm < 400 {
             return Err(Error::InvalidParam);
@@ -189,11 +196,14 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// * `motion_config` : Structure containing the initialized motion configuration.
     /// * `resolution` : Wanted SCI resolution, defined by macros VL53L8CX_RESOLUTION_4X4 or VL53L8CX_RESOLUTION_8X8.
     pub fn motion_indicator_set_resolution(&mut self, motion_config: &mut MotionConfiguration, resolution: u8) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         if resolution == VL53L5CX_RESOLUTION_4X4 {
             for i in 0..VL53L5CX_RESOLUTION_4X4 as usize {
                 motion_config.map_id[i] = i as i8;
             }
-            for i in 16..VL53L5CX_RESOLUTION_4X4 as usize {
+            for i in 16..VL53L5CX_RESOLUTION_8X8 as usize {
                 motion_config.map_id[i] = -1;
             }
         } else if resolution == VL53L5CX_RESOLUTION_8X8 {
@@ -208,4 +218,19 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         self.dci_write_data(VL53L5CX_DCI_MOTION_DETECTOR_CFG, 156)?;
         Ok(())
     }
+
+    /// This function updates the motion indicator map of the configuration programmed 
+    /// on the sensor, keeping its other settings (e.g. the working distance). It is 
+    /// called by set_resolution(), as a map not matching the resolution corrupts the 
+    /// motion data.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `resolution` : New resolution, VL53L5CX_RESOLUTION_4X4 or VL53L5CX_RESOLUTION_8X8.
+    pub(crate) fn motion_indicator_update_resolution(&mut self, resolution: u8) -> Result<(), Error<B::Error>> {
+        let mut motion_config = MotionConfiguration::new();
+        self.dci_read_data(VL53L5CX_DCI_MOTION_DETECTOR_CFG, 156)?;
+        from_u8_to_motion_configuration(&self.temp_buffer[..156], &mut motion_config);
+        self.motion_indicator_set_resolution(&mut motion_config, resolution)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,91 @@
+use crate::{BusOperation, Error, PowerMode, Resolution, ResultsData, SensorVariant, Vl53l5cx, OutputPin, DelayNs};
+
+/// Trait MultizoneTof is the API shared by the multizone ToF sensors (VL53L5CX,
+/// VL53L7CX and VL53L8CX, see SensorVariant), so that the application code can be
+/// generic over the part. It covers the ranging session: init, resolution, frequency,
+/// power mode, start, stop and frames. The other functions are those of Vl53l5cx.
+/// The VL53L8CX is limited to the VL53L5CX init flow and power modes (see SensorVariant).
+pub trait MultizoneTof {
+    type Error;
+
+    /// This function gets the part driven by the driver.
+    fn variant(&self) -> SensorVariant;
+
+    /// This function initializes the sensor, uploading the firmware of the part.
+    fn init(&mut self) -> Result<(), Self::Error>;
+
+    /// This function gets the resolution of the frames.
+    fn resolution(&mut self) -> Result<Resolution, Self::Error>;
+
+    /// This function sets the resolution of the frames.
+    fn set_resolution(&mut self, resolution: Resolution) -> Result<(), Self::Error>;
+
+    /// This function gets the ranging frequency in Hz.
+    fn frequency_hz(&mut self) -> Result<u8, Self::Error>;
+
+    /// This function sets the ranging frequency in Hz.
+    fn set_frequency_hz(&mut self, frequency_hz: u8) -> Result<(), Self::Error>;
+
+    /// This function sets the power mode of the sensor, which must not be ranging.
+    fn set_power_mode(&mut self, power_mode: PowerMode) -> Result<(), Self::Error>;
+
+    /// This function starts a ranging session.
+    fn start_ranging(&mut self) -> Result<(), Self::Error>;
+
+    /// This function stops the ranging session.
+    fn stop_ranging(&mut self) -> Result<(), Self::Error>;
+
+    /// This function checks whether a new frame is ready.
+    fn check_data_ready(&mut self) -> Result<bool, Self::Error>;
+
+    /// This function reads and parses the new frame.
+    fn get_ranging_data(&mut self) -> Result<ResultsData, Self::Error>;
+}
+
+impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> MultizoneTof for Vl53l5cx<B, LPN, RST, T> {
+    type Error = Error<B::Error>;
+
+    fn variant(&self) -> SensorVariant {
+        self.get_sensor_variant()
+    }
+
+    fn init(&mut self) -> Result<(), Self::Error> {
+        Vl53l5cx::init(self)
+    }
+
+    fn resolution(&mut self) -> Result<Resolution, Self::Error> {
+        Vl53l5cx::resolution(self)
+    }
+
+    fn set_resolution(&mut self, resolution: Resolution) -> Result<(), Self::Error> {
+        Vl53l5cx::set_resolution(self, resolution)
+    }
+
+    fn frequency_hz(&mut self) -> Result<u8, Self::Error> {
+        self.get_frequency_hz()
+    }
+
+    fn set_frequency_hz(&mut self, frequency_hz: u8) -> Result<(), Self::Error> {
+        Vl53l5cx::set_frequency_hz(self, frequency_hz)
+    }
+
+    fn set_power_mode(&mut self, power_mode: PowerMode) -> Result<(), Self::Error> {
+        Vl53l5cx::set_power_mode(self, power_mode)
+    }
+
+    fn start_ranging(&mut self) -> Result<(), Self::Error> {
+        Vl53l5cx::start_ranging(self)
+    }
+
+    fn stop_ranging(&mut self) -> Result<(), Self::Error> {
+        Vl53l5cx::stop_ranging(self)
+    }
+
+    fn check_data_ready(&mut self) -> Result<bool, Self::Error> {
+        Vl53l5cx::check_data_ready(self)
+    }
+
+    fn get_ranging_data(&mut self) -> Result<ResultsData, Self::Error> {
+        Vl53l5cx::get_ranging_data(self)
+    }
+}
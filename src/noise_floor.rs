@@ -0,0 +1,149 @@
+use consts::*;
+
+use crate::{consts, ResultsData, Resolution};
+
+/// Structure NoiseFloorEstimator measures the noise of the distance of each zone
+/// over frames of a static scene, e.g. to choose the range sigma threshold above
+/// which a target is rejected, instead of guessing it. Only the first target of
+/// the zones with a valid status (5 or 9) is used.
+#[derive(Copy, Clone, Debug)]
+pub struct NoiseFloorEstimator {
+    counts: [u32; VL53L5CX_RESOLUTION_8X8 as usize],
+    sums: [i64; VL53L5CX_RESOLUTION_8X8 as usize],
+    sums_squares: [i64; VL53L5CX_RESOLUTION_8X8 as usize],
+    nb_frames: u32,
+}
+
+impl Default for NoiseFloorEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoiseFloorEstimator {
+    /// This function creates an estimator without samples.
+    pub fn new() -> Self {
+        NoiseFloorEstimator {
+            counts: [0; VL53L5CX_RESOLUTION_8X8 as usize],
+            sums: [0; VL53L5CX_RESOLUTION_8X8 as usize],
+            sums_squares: [0; VL53L5CX_RESOLUTION_8X8 as usize],
+            nb_frames: 0,
+        }
+    }
+
+    /// This function forgets the samples.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// This function adds the distances of a frame to the samples. The scene
+    /// must not change between the frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` : Results of the frame.
+    /// * `resolution` : Resolution used for the frame.
+    pub fn update(&mut self, results: &ResultsData, resolution: Resolution) {
+        for zone in 0..resolution.nb_zones() {
+            let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+            let status: u8 = results.target_status[idx];
+            if status != 5 && status != 9 {
+                continue;
+            }
+            let distance: i64 = results.distance_mm[idx] as i64;
+            self.counts[zone] += 1;
+            self.sums[zone] += distance;
+            self.sums_squares[zone] += distance * distance;
+        }
+        self.nb_frames += 1;
+    }
+
+    /// This function gets the number of frames added with update().
+    pub fn nb_frames(&self) -> u32 {
+        self.nb_frames
+    }
+
+    /// This function gets the number of valid samples of a zone.
+    pub fn nb_samples(&self, zone: usize) -> u32 {
+        self.counts.get(zone).copied().unwrap_or(0)
+    }
+
+    /// This function gets the mean distance of a zone.
+    ///
+    /// # Return
+    ///
+    /// * `mean_mm` : Mean distance in mm, None if the zone has no valid sample.
+    pub fn mean_mm(&self, zone: usize) -> Option<i16> {
+        let count: i64 = self.nb_samples(zone) as i64;
+        if count == 0 {
+            return None;
+        }
+        Some((self.sums[zone] / count) as i16)
+    }
+
+    /// This function gets the standard deviation of the distance of a zone.
+    ///
+    /// # Return
+    ///
+    /// * `std_dev_mm` : Sample standard deviation in mm, None if the zone has less than 2 valid samples.
+    pub fn std_dev_mm(&self, zone: usize) -> Option<u16> {
+        let count: i128 = self.nb_samples(zone) as i128;
+        if count < 2 {
+            return None;
+        }
+        let sum: i128 = self.sums[zone] as i128;
+        let variance: i128 = (count * self.sums_squares[zone] as i128 - sum * sum) / (count * (count - 1));
+
+        Some(isqrt(variance.max(0) as u64).min(u16::MAX as u64) as u16)
+    }
+
+    /// This function suggests a range sigma threshold for each zone : `factor` times
+    /// the standard deviation of the zone, e.g. 3 to keep 99.7% of the targets of a
+    /// static scene if the noise is gaussian.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` : Number of standard deviations.
+    /// * `min_samples` : Minimum number of valid samples of a zone (at least 2).
+    ///
+    /// # Return
+    ///
+    /// * `thresholds` : Threshold of each zone in mm, None for the zones with less than `min_samples` samples.
+    pub fn suggested_sigma_thresholds_mm(&self, factor: u16, min_samples: u32) -> [Option<u16>; VL53L5CX_RESOLUTION_8X8 as usize] {
+        core::array::from_fn(|zone| {
+            if self.counts[zone] < min_samples {
+                return None;
+            }
+            self.std_dev_mm(zone).map(|std_dev| std_dev.saturating_mul(factor))
+        })
+    }
+
+    /// This function suggests a single range sigma threshold for the frame, i.e. the
+    /// highest threshold of suggested_sigma_thresholds_mm().
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` : Number of standard deviations.
+    /// * `min_samples` : Minimum number of valid samples of a zone (at least 2).
+    ///
+    /// # Return
+    ///
+    /// * `threshold` : Threshold in mm, None if no zone has `min_samples` samples.
+    pub fn suggested_sigma_threshold_mm(&self, factor: u16, min_samples: u32) -> Option<u16> {
+        self.suggested_sigma_thresholds_mm(factor, min_samples).into_iter().flatten().max()
+    }
+}
+
+/// This function computes the integer square root of `value`, rounded down.
+fn isqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    let mut root: u64 = value;
+    let mut next: u64 = root.div_ceil(2);
+    while next < root {
+        root = next;
+        next = (root + value / root) / 2;
+    }
+    root
+}
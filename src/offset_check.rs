@@ -0,0 +1,98 @@
+use consts::*;
+
+use crate::{consts, BusOperation, CalibrationError, Error, ResultsData, Vl53l5cx, OutputPin, DelayNs};
+
+/// Structure OffsetReport contains the result of an offset drift check,
+/// done with the function verify_offset().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OffsetReport {
+  // Expected target distance in mm
+    pub expected_mm: u16,
+  // Average distance measured on the valid zones, in mm
+    pub measured_mm: i16,
+  // Measured distance minus expected distance, in mm
+    pub deviation_mm: i16,
+  // Number of valid measurements used for the average
+    pub nb_valid_measurements: u32,
+  // True if the deviation is out of the tolerance, and a new offset calibration is advised
+    pub recalibration_advised: bool,
+}
+
+impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
+    /// Inner function, not available outside this crate.
+    /// This function waits for a new frame and reads it.
+    pub(crate) fn wait_for_ranging_data(&mut self) -> Result<ResultsData, Error<B::Error>> {
+        self.wait_for_data_ready()?;
+        self.get_ranging_data()
+    }
+
+    /// This function checks the offset calibration drift, using a flat target placed
+    /// at a known distance and covering the full FoV. It starts a ranging session,
+    /// averages the first target distance of the valid zones over `nb_samples` frames,
+    /// and compares it to the expected distance. The sensor must not be ranging
+    /// when this function is called, and the current configuration is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `distance_mm` : Expected target distance in mm. Min value is 1mm, and max is 4000mm.
+    /// * `nb_samples` : Nb of frames used for the check. Minimum is 1 and maximum is 16.
+    /// * `tolerance_mm` : Maximum deviation in mm before a new offset calibration is advised.
+    ///
+    /// # Return
+    ///
+    /// * `report` : Measured distance and deviation from the expected distance.
+    ///   Error::NoValidTarget is returned if no zone has a valid target.
+    pub fn verify_offset(&mut self, distance_mm: u16, nb_samples: u8, tolerance_mm: u16) -> Result<OffsetReport, Error<B::Error>> {
+        let mut sum_mm: i32 = 0;
+        let mut nb_valid: u32 = 0;
+
+        // Check input arguments validity
+        if !(1..=4000).contains(&distance_mm) {
+            return Err(Error::Calibration(CalibrationError::InvalidDistance));
+        }
+        if !(1..=16).contains(&nb_samples) {
+            return Err(Error::Calibration(CalibrationError::InvalidNbSamples));
+        }
+        let nb_zones: usize = self.resolution()?.nb_zones();
+
+        self.start_ranging()?;
+        for _ in 0..nb_samples {
+            let results: ResultsData = match self.wait_for_ranging_data() {
+                Ok(results) => results,
+                Err(e) => {
+                    let _ = self.stop_ranging();
+                    return Err(e);
+                }
+            };
+            for zone in 0..nb_zones {
+                let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+                #[cfg(not(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+                if results.nb_target_detected[zone] == 0 {
+                    continue;
+                }
+                // Only keep 100% valid and 50% valid (large pulse) measurements
+                #[cfg(not(feature = "VL53L5CX_DISABLE_TARGET_STATUS"))]
+                if results.target_status[idx] != 5 && results.target_status[idx] != 9 {
+                    continue;
+                }
+                sum_mm += results.distance_mm[idx] as i32;
+                nb_valid += 1;
+            }
+        }
+        self.stop_ranging()?;
+
+        if nb_valid == 0 {
+            return Err(Error::NoValidTarget);
+        }
+        let measured_mm: i16 = (sum_mm / nb_valid as i32) as i16;
+        let deviation_mm: i16 = measured_mm - distance_mm as i16;
+
+        Ok(OffsetReport {
+            expected_mm: distance_mm,
+            measured_mm,
+            deviation_mm,
+            nb_valid_measurements: nb_valid,
+            recalibration_advised: deviation_mm.unsigned_abs() > tolerance_mm,
+        })
+    }
+}
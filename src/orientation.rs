@@ -0,0 +1,193 @@
+use consts::*;
+
+use crate::{consts, CalibrationError, ResultsData, Resolution};
+
+// Tangent of the angle between the sensor axis and the center of each zone column
+// (or row), for a 45° field of view.
+const ZONE_TAN_4X4: [f32; 4] = [-0.303347, -0.098491, 0.098491, 0.303347];
+const ZONE_TAN_8X8: [f32; 8] = [-0.357806, -0.250487, -0.148336, -0.049127, 0.049127, 0.148336, 0.250487, 0.357806];
+
+const RAD_TO_DEG: f32 = 180.0 / core::f32::consts::PI;
+
+/// Structure MountingCorrection is returned by estimate_mounting_correction().
+/// It contains the rotations to apply to the points of the sensor (see zone_point()),
+/// so that the reference wall becomes perpendicular to the sensor axis.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MountingCorrection {
+  // Rotation around the x axis in degrees, compensating the distance gradient along y
+    pub pitch_deg: f32,
+  // Rotation around the y axis in degrees, compensating the distance gradient along x
+    pub yaw_deg: f32,
+  // Distance of the wall along the sensor axis in mm
+    pub distance_mm: f32,
+  // RMS distance in mm between the zones and the fitted wall, high if the scene is not flat
+    pub residual_mm: f32,
+}
+
+/// Structure NearestObstacle is returned by nearest_obstacle(). It contains the
+/// closest valid target of a frame, and its direction in the sensor frame (see
+/// zone_point()), e.g. for a proximity warning pointing at the obstacle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NearestObstacle {
+  // Distance of the target in mm, along the direction of the center of its zone
+    pub distance_mm: i16,
+  // Zone index of the target, as in ResultsData
+    pub zone: usize,
+  // Unit vector x, y and z from the sensor to the center of the zone
+    pub direction: [f32; 3],
+}
+
+/// This function converts the distance of a zone to a point in the sensor frame :
+/// x along the rows of the grid (increasing with the column), y along the columns
+/// (increasing with the row), and z along the sensor axis. The distance is
+/// measured along the direction of the center of the zone.
+///
+/// # Arguments
+///
+/// * `resolution` : Resolution of the frame.
+/// * `zone` : Zone index, as in ResultsData.
+/// * `distance_mm` : Distance of the zone in mm.
+///
+/// # Return
+///
+/// * `point` : Coordinates x, y and z of the point in mm.
+pub fn zone_point(resolution: Resolution, zone: usize, distance_mm: i16) -> [f32; 3] {
+    let zone_tan: &[f32] = match resolution {
+        Resolution::Res4x4 => &ZONE_TAN_4X4,
+        Resolution::Res8x8 => &ZONE_TAN_8X8
+    };
+    let width: usize = zone_tan.len();
+    let tan_x: f32 = zone_tan[zone % width];
+    let tan_y: f32 = zone_tan[zone / width];
+    let z: f32 = distance_mm as f32 * inv_sqrt(1.0 + tan_x * tan_x + tan_y * tan_y);
+
+    [z * tan_x, z * tan_y, z]
+}
+
+/// This function estimates the mounting pitch and yaw of the sensor from a frame
+/// of a flat wall covering the field of view, e.g. to correct the point clouds of
+/// a sensor not mounted straight. A plane is fitted to the first target of the zones
+/// with a valid status (5 or 9), and the correction is the rotation bringing it
+/// perpendicular to the sensor axis. The accuracy is about 0.1°, averaging the
+/// corrections of a few frames reduces the noise of the distances.
+///
+/// # Arguments
+///
+/// * `results` : Results of the frame.
+/// * `resolution` : Resolution used for the frame.
+///
+/// # Return
+///
+/// * `correction` : Mounting correction, or CalibrationError::NotEnoughZones if less than half of the zones are valid.
+pub fn estimate_mounting_correction(results: &ResultsData, resolution: Resolution) -> Result<MountingCorrection, CalibrationError> {
+    let mut points: [[f32; 3]; VL53L5CX_RESOLUTION_8X8 as usize] = [[0.0; 3]; VL53L5CX_RESOLUTION_8X8 as usize];
+    let mut nb_points: usize = 0;
+
+    for zone in 0..resolution.nb_zones() {
+        let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        let status: u8 = results.target_status[idx];
+        if (status == 5 || status == 9) && results.distance_mm[idx] > 0 {
+            points[nb_points] = zone_point(resolution, zone, results.distance_mm[idx]);
+            nb_points += 1;
+        }
+    }
+    if nb_points < resolution.nb_zones() / 2 {
+        return Err(CalibrationError::NotEnoughZones);
+    }
+    let points: &[[f32; 3]] = &points[..nb_points];
+
+    // Least squares fit of z = a*x + b*y + c, on the centered coordinates
+    let n: f32 = nb_points as f32;
+    let mut mean: [f32; 3] = [0.0; 3];
+    for point in points {
+        for axis in 0..3 {
+            mean[axis] += point[axis] / n;
+        }
+    }
+    let (mut sxx, mut sxy, mut syy, mut sxz, mut syz) = (0.0f32, 0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for point in points {
+        let (x, y, z) = (point[0] - mean[0], point[1] - mean[1], point[2] - mean[2]);
+        sxx += x * x;
+        sxy += x * y;
+        syy += y * y;
+        sxz += x * z;
+        syz += y * z;
+    }
+    let det: f32 = sxx * syy - sxy * sxy;
+    if det <= f32::EPSILON * sxx * syy {
+        // Valid zones on a single line
+        return Err(CalibrationError::NotEnoughZones);
+    }
+    let a: f32 = (sxz * syy - syz * sxy) / det;
+    let b: f32 = (syz * sxx - sxz * sxy) / det;
+    let c: f32 = mean[2] - a * mean[0] - b * mean[1];
+
+    let mut sum_squares: f32 = 0.0;
+    for point in points {
+        // Distance to the plane, along its normal
+        let residual: f32 = (point[2] - a * point[0] - b * point[1] - c) * inv_sqrt(1.0 + a * a + b * b);
+        sum_squares += residual * residual;
+    }
+    let mean_square: f32 = sum_squares / n;
+
+    Ok(MountingCorrection {
+        pitch_deg: -atan(b) * RAD_TO_DEG,
+        yaw_deg: -atan(a) * RAD_TO_DEG,
+        distance_mm: c,
+        residual_mm: if mean_square > 0.0 { mean_square * inv_sqrt(mean_square) } else { 0.0 }
+    })
+}
+
+/// This function finds the nearest obstacle of a frame: the closest target with a
+/// valid status (5 or 9) among all the targets of all the zones, with the direction
+/// of its zone from the field of view geometry.
+///
+/// # Arguments
+///
+/// * `results` : Results of the frame.
+/// * `resolution` : Resolution used for the frame.
+///
+/// # Return
+///
+/// * `obstacle` : Nearest obstacle, or None if no zone has a valid target.
+pub fn nearest_obstacle(results: &ResultsData, resolution: Resolution) -> Option<NearestObstacle> {
+    let nb_targets: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+    let mut nearest: Option<(usize, i16)> = None;
+
+    for zone in 0..resolution.nb_zones() {
+        for idx in zone * nb_targets..(zone + 1) * nb_targets {
+            let status: u8 = results.target_status[idx];
+            let distance_mm: i16 = results.distance_mm[idx];
+            if (status == 5 || status == 9) && distance_mm > 0 && nearest.is_none_or(|(_, nearest_mm)| distance_mm < nearest_mm) {
+                nearest = Some((zone, distance_mm));
+            }
+        }
+    }
+
+    nearest.map(|(zone, distance_mm)| {
+        let point: [f32; 3] = zone_point(resolution, zone, distance_mm);
+        let inv_norm: f32 = inv_sqrt(point[0] * point[0] + point[1] * point[1] + point[2] * point[2]);
+        NearestObstacle { distance_mm, zone, direction: [point[0] * inv_norm, point[1] * inv_norm, point[2] * inv_norm] }
+    })
+}
+
+/// This function approximates 1/sqrt(x), for x > 0, without the std library.
+fn inv_sqrt(x: f32) -> f32 {
+    let mut y: f32 = f32::from_bits(0x5f37_59df - (x.to_bits() >> 1));
+    for _ in 0..3 {
+        y *= 1.5 - 0.5 * x * y * y;
+    }
+    y
+}
+
+/// This function approximates atan(x) in radians, within 0.0015 rad, without the std library.
+fn atan(x: f32) -> f32 {
+    let abs: f32 = if x < 0.0 { -x } else { x };
+    let angle: f32 = if abs <= 1.0 {
+        core::f32::consts::FRAC_PI_4 * abs - abs * (abs - 1.0) * (0.2447 + 0.0663 * abs)
+    } else {
+        let inv: f32 = 1.0 / abs;
+        core::f32::consts::FRAC_PI_2 - (core::f32::consts::FRAC_PI_4 * inv - inv * (inv - 1.0) * (0.2447 + 0.0663 * inv))
+    };
+    if x < 0.0 { -angle } else { angle }
+}
@@ -0,0 +1,442 @@
+use consts::*;
+#[cfg(feature = "float-results")]
+use float_results::*;
+use motion_indicator::*;
+use units::*;
+use utils::*;
+
+use crate::{consts, motion_indicator, units, utils, BlockHeader, Error, InvalidDistance, ResultsData, TemperatureCompensation};
+#[cfg(feature = "float-results")]
+use crate::float_results;
+
+/// Structure FrameLayout describes the position of the headers and metadata fields in a frame.
+/// The default layout matches the firmware embedded in this driver.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameLayout {
+  // Size of the frame header, blocks start right after it
+    pub header_size: usize,
+  // Position of the frame id in the header
+    pub header_id_pos: usize,
+  // Size of the frame footer, after the last block
+    pub footer_size: usize,
+  // Position of the frame id in the footer, counted from the end of the frame
+    pub footer_id_pos: usize,
+  // Position of the silicon temperature in the metadata block
+    pub silicon_temp_pos: usize
+}
+
+impl Default for FrameLayout {
+    fn default() -> Self {
+        FrameLayout::new()
+    }
+}
+
+impl FrameLayout {
+    pub const fn new() -> Self {
+        FrameLayout { 
+            header_size: 16, 
+            header_id_pos: 8, 
+            footer_size: 8, 
+            footer_id_pos: 4, 
+            silicon_temp_pos: 8 
+        }
+    }
+}
+
+/// Enum ParseError contains the causes of the frames rejected by the parser, 
+/// and of the unexpected values read from the sensor, given by Error::Parse.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The frame layout is not consistent (ids outside of the header or footer).
+    InvalidLayout,
+    /// The frame is smaller than its headers and footer, larger than the buffer, 
+    /// or ends in the middle of a block header.
+    Truncated,
+    /// A block overflows the frame.
+    BlockOverflow,
+    /// A block size does not match its output.
+    InvalidBlockSize,
+    /// The frame ids of the header and the footer differ, i.e. the frame 
+    /// was overwritten while it was read.
+    HeaderFooterMismatch,
+    /// A configuration value read from the sensor is not a known value.
+    UnexpectedValue(u8)
+}
+
+impl<E> From<ParseError> for Error<E> {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+/// Structure Block contains a block of a frame, as given by parse_frame_with(). 
+/// The data is in the firmware format: per zone or per target arrays of 
+/// u8, u16, i16 or u32 values, before the conversions done in ResultsData 
+/// (e.g. distances must be divided by 4 to get millimeters).
+#[derive(Copy, Clone, Debug)]
+pub struct Block<'a> {
+  // Block index, identifying the output
+    pub idx: u16,
+  // Block data, in host byte order
+    pub data: &'a [u8]
+}
+
+impl<'a> Block<'a> {
+    /// This function gets the output of the block.
+    /// 
+    /// # Return
+    /// 
+    /// `output` : Output of the block (macros VL53L5CX_OUTPUT_*), or 0 if the block is unknown.
+    pub fn output(&self) -> u32 {
+        match self.idx {
+            VL53L5CX_METADATA_IDX => VL53L5CX_OUTPUT_METADATA,
+            VL53L5CX_COMMONDATA_IDX => VL53L5CX_OUTPUT_COMMONDATA,
+            VL53L5CX_AMBIENT_RATE_IDX => VL53L5CX_OUTPUT_AMBIENT_PER_SPAD,
+            VL53L5CX_SPAD_COUNT_IDX => VL53L5CX_OUTPUT_NB_SPADS_ENABLED,
+            VL53L5CX_NB_TARGET_DETECTED_IDX => VL53L5CX_OUTPUT_NB_TARGET_DETECTED,
+            VL53L5CX_SIGNAL_RATE_IDX => VL53L5CX_OUTPUT_SIGNAL_PER_SPAD,
+            VL53L5CX_RANGE_SIGMA_MM_IDX => VL53L5CX_OUTPUT_RANGE_SIGMA_MM,
+            VL53L5CX_DISTANCE_IDX => VL53L5CX_OUTPUT_DISTANCE_MM,
+            VL53L5CX_REFLECTANCE_EST_PC_IDX => VL53L5CX_OUTPUT_REFLECTANCE_PERCENT,
+            VL53L5CX_TARGET_STATUS_IDX => VL53L5CX_OUTPUT_TARGET_STATUS,
+            VL53L5CX_MOTION_DETEC_IDX => VL53L5CX_OUTPUT_MOTION_INDICATOR,
+            _ => 0
+        }
+    }
+
+    /// This function iterates over the data as u16 values (range sigma).
+    pub fn iter_u16(&self) -> impl Iterator<Item = u16> + 'a {
+        self.data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]]))
+    }
+
+    /// This function iterates over the data as i16 values (distances).
+    pub fn iter_i16(&self) -> impl Iterator<Item = i16> + 'a {
+        self.data.chunks_exact(2).map(|c| i16::from_le_bytes([c[0], c[1]]))
+    }
+
+    /// This function iterates over the data as u32 values (ambient, signal, spads).
+    pub fn iter_u32(&self) -> impl Iterator<Item = u32> + 'a {
+        self.data.chunks_exact(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+    }
+}
+
+/// Inner function, not available outside this file. 
+/// This function checks that a block of `msize` bytes can be copied 
+/// into an array of `dst_len` elements of `elem_size` bytes.
+fn check_block_size<E>(msize: usize, elem_size: usize, dst_len: usize) -> Result<(), Error<E>> {
+    if !msize.is_multiple_of(elem_size) || msize / elem_size > dst_len {
+        return Err(Error::Parse(ParseError::InvalidBlockSize));
+    }
+    Ok(())
+}
+
+/// This function parses a frame read from the sensor into a results structure.
+/// The first `data_read_size` bytes of `buffer` must contain the frame,
+/// as read from register 0. Please note that the buffer is converted in place
+/// from the sensor format to the host format.
+/// 
+/// # Arguments
+/// 
+/// * `buffer` : Frame read from the sensor.
+/// * `data_read_size` : Size of the frame, depending on the resolution and the enabled outputs.
+/// 
+/// # Return
+/// 
+/// * `results` : VL53L5 results structure.
+pub fn parse_ranging_data<E>(buffer: &mut [u8], data_read_size: usize) -> Result<ResultsData, Error<E>> {
+    parse_ranging_data_with_layout(buffer, data_read_size, &FrameLayout::new())
+}
+
+/// This function parses a frame read from the sensor into a results structure, 
+/// using the given layout of the headers and metadata. It can be used with firmware 
+/// revisions whose frame layout differs from the default one.
+/// 
+/// # Arguments
+/// 
+/// * `buffer` : Frame read from the sensor.
+/// * `data_read_size` : Size of the frame, depending on the resolution and the enabled outputs.
+/// * `layout` : Position of the headers and metadata fields in the frame.
+/// 
+/// # Return
+/// 
+/// * `results` : VL53L5 results structure.
+pub fn parse_ranging_data_with_layout<E>(buffer: &mut [u8], data_read_size: usize, layout: &FrameLayout) -> Result<ResultsData, Error<E>> {
+    parse_ranging_data_with_options(buffer, data_read_size, layout, InvalidDistance::Zero)
+}
+
+/// This function parses a frame read from the sensor into a results structure, 
+/// using the given layout, and the given value for the invalid distances.
+/// 
+/// # Arguments
+/// 
+/// * `buffer` : Frame read from the sensor.
+/// * `data_read_size` : Size of the frame, depending on the resolution and the enabled outputs.
+/// * `layout` : Position of the headers and metadata fields in the frame.
+/// * `invalid_distance` : Value of the distances without a valid measurement.
+/// 
+/// # Return
+/// 
+/// * `results` : VL53L5 results structure.
+pub fn parse_ranging_data_with_options<E>(buffer: &mut [u8], data_read_size: usize, layout: &FrameLayout, invalid_distance: InvalidDistance) -> Result<ResultsData, Error<E>> {
+    parse_ranging_data_compensated(buffer, data_read_size, layout, invalid_distance, None)
+}
+
+/// Inner function, not available outside the crate. 
+/// This function parses a frame as parse_ranging_data_with_options() does, and corrects 
+/// the distances with `compensation` while they are still in the firmware format, 
+/// so the floating point view is filled from the compensated distances.
+#[cfg_attr(feature = "VL53L5CX_DISABLE_DISTANCE_MM", allow(unused_variables))]
+pub(crate) fn parse_ranging_data_compensated<E>(buffer: &mut [u8], data_read_size: usize, layout: &FrameLayout, invalid_distance: InvalidDistance, compensation: Option<TemperatureCompensation>) -> Result<ResultsData, Error<E>> {
+    let mut result: ResultsData = ResultsData::new();
+
+    for_each_block(buffer, data_read_size, layout, |idx: u16, src: &[u8]| -> Result<(), Error<E>> {
+        if idx == VL53L5CX_METADATA_IDX {
+            if src.len() <= layout.silicon_temp_pos {
+                return Err(Error::Parse(ParseError::InvalidBlockSize));
+            }
+            result.silicon_temp_degc = src[layout.silicon_temp_pos] as i8;
+            let metadata_size: usize = src.len().min(VL53L5CX_METADATA_SIZE);
+            result.frame_info.metadata[..metadata_size].copy_from_slice(&src[..metadata_size]);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_METADATA;
+            return Ok(());
+        } 
+
+        if idx == VL53L5CX_COMMONDATA_IDX {
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_COMMONDATA;
+            return Ok(());
+        } 
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))] 
+        if idx == VL53L5CX_AMBIENT_RATE_IDX {
+            check_block_size(src.len(), 4, result.ambient_per_spad.len())?;
+            from_u8_to_u32(src, &mut result.ambient_per_spad);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_AMBIENT_PER_SPAD;
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_NB_SPADS_ENABLED"))] 
+        if idx == VL53L5CX_SPAD_COUNT_IDX {
+            check_block_size(src.len(), 4, result.nb_spads_enabled.len())?;
+            from_u8_to_u32(src, &mut result.nb_spads_enabled);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_NB_SPADS_ENABLED;
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED"))] 
+        if idx == VL53L5CX_NB_TARGET_DETECTED_IDX {
+            check_block_size(src.len(), 1, result.nb_target_detected.len())?;
+            result.nb_target_detected[..src.len()].copy_from_slice(src);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_NB_TARGET_DETECTED;
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+        if idx == VL53L5CX_SIGNAL_RATE_IDX {
+            check_block_size(src.len(), 4, result.signal_per_spad.len())?;
+            from_u8_to_u32(src, &mut result.signal_per_spad);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_SIGNAL_PER_SPAD;
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+        if idx == VL53L5CX_RANGE_SIGMA_MM_IDX {
+            check_block_size(src.len(), 2, result.range_sigma_mm.len())?;
+            from_u8_to_u16(src, &mut result.range_sigma_mm);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_RANGE_SIGMA_MM;
+            return Ok(());
+        } 
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))] 
+        if idx == VL53L5CX_DISTANCE_IDX {
+            check_block_size(src.len(), 2, result.distance_mm.len())?;
+            from_u8_to_i16(src, &mut result.distance_mm);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_DISTANCE_MM;
+            return Ok(());
+        }
+
+        #[cfg(not(feature= "VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
+        if idx == VL53L5CX_REFLECTANCE_EST_PC_IDX {
+            check_block_size(src.len(), 1, result.reflectance.len())?;
+            result.reflectance[..src.len()].copy_from_slice(src);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_REFLECTANCE_PERCENT;
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_TARGET_STATUS"))]
+        if idx == VL53L5CX_TARGET_STATUS_IDX {
+            check_block_size(src.len(), 1, result.target_status.len())?;
+            result.target_status[..src.len()].copy_from_slice(src);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_TARGET_STATUS;
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+        if idx == VL53L5CX_MOTION_DETEC_IDX {
+            if src.len() < 140 {
+                return Err(Error::Parse(ParseError::InvalidBlockSize));
+            }
+            from_u8_to_motion_indicator(src, &mut result.motion_indicator);
+            result.frame_info.present_outputs |= VL53L5CX_OUTPUT_MOTION_INDICATOR;
+            return Ok(());
+        }
+
+        Ok(())
+    })?;
+
+    #[cfg(not(any(feature="VL53L5CX_DISABLE_DISTANCE_MM", feature="VL53L5CX_DISABLE_TARGET_STATUS")))]
+    if let Some(compensation) = compensation {
+        result.compensate_raw_temperature(compensation);
+    }
+    #[cfg(any(feature="VL53L5CX_DISABLE_DISTANCE_MM", feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
+    let _ = compensation;
+
+    #[cfg(feature = "float-results")]
+    fill_float_results(&mut result);
+
+    if VL53L5CX_USE_RAW_FORMAT == 0 {
+        // Convert data into their real format 
+        #[cfg(not(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))] {
+            for i in 0..VL53L5CX_RESOLUTION_8X8 as usize {
+                result.ambient_per_spad[i] = rate_kcps_per_spad(result.ambient_per_spad[i]);
+            }
+        }
+        for i in 0..(VL53L5CX_RESOLUTION_8X8 as usize)*(VL53L5CX_NB_TARGET_PER_ZONE as usize) {
+            #[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))] {
+                result.distance_mm[i] = match invalid_distance {
+                    InvalidDistance::Zero => distance_mm(result.distance_mm[i]),
+                    InvalidDistance::Min if !is_valid_distance(&result, i) => i16::MIN,
+                    InvalidDistance::Max if !is_valid_distance(&result, i) => i16::MAX,
+                    _ => result.distance_mm[i] / 4
+                };
+            }
+            #[cfg(not(feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))] {
+                result.reflectance[i] = reflectance_percent(result.reflectance[i]);
+            }
+            #[cfg(not(feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]{
+                result.range_sigma_mm[i] = range_sigma_mm(result.range_sigma_mm[i]);
+            }
+            #[cfg(not(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))] {
+                result.signal_per_spad[i] = rate_kcps_per_spad(result.signal_per_spad[i]);
+            }
+        }
+        // Set target status to 255 if no target is detected for this zone 
+        #[cfg(not(any(feature="VL53L5CX_DISABLE_NB_TARGET_DETECTED", feature="VL53L5CX_DISABLE_TARGET_STATUS")))] {
+            for i in 0..VL53L5CX_RESOLUTION_8X8 as usize {
+                if result.nb_target_detected[i] == 0 {
+                    for j in 0..VL53L5CX_NB_TARGET_PER_ZONE as usize {
+                        result.target_status[VL53L5CX_NB_TARGET_PER_ZONE as usize*i + j] = 255;
+                    }
+                }
+            }
+        }
+
+        #[cfg(not(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"))] {
+            for i in 0..32 {
+                result.motion_indicator.motion[i] = motion_indicator(result.motion_indicator.motion[i]);
+            }
+        }
+    }
+    
+    Ok(result)
+}
+
+/// Inner function, not available outside this file. 
+/// This function checks that the raw distance of the target `i` is positive, 
+/// and that the target was detected in its zone.
+#[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+fn is_valid_distance(result: &ResultsData, i: usize) -> bool {
+    #[cfg(not(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED"))] {
+        let zone: usize = i / VL53L5CX_NB_TARGET_PER_ZONE as usize;
+        let target: usize = i % VL53L5CX_NB_TARGET_PER_ZONE as usize;
+        if target >= result.nb_target_detected[zone] as usize {
+            return false;
+        }
+    }
+    result.distance_mm[i] >= 0
+}
+
+/// This function parses a frame read from the sensor, and calls `visitor` for each block 
+/// of the frame, without materializing the full results structure. It can be used 
+/// to save RAM when only a few outputs are needed. Please note that the block data 
+/// is given in the firmware format (see Block), and that the buffer is converted in place 
+/// from the sensor format to the host format.
+/// 
+/// # Arguments
+/// 
+/// * `buffer` : Frame read from the sensor.
+/// * `data_read_size` : Size of the frame, depending on the resolution and the enabled outputs.
+/// * `layout` : Position of the headers and metadata fields in the frame.
+/// * `visitor` : Function called for each block of the frame.
+pub fn parse_frame_with<E>(buffer: &mut [u8], data_read_size: usize, layout: &FrameLayout, mut visitor: impl FnMut(Block<'_>)) -> Result<(), Error<E>> {
+    for_each_block(buffer, data_read_size, layout, |idx: u16, data: &[u8]| -> Result<(), Error<E>> {
+        visitor(Block { idx, data });
+        Ok(())
+    })
+}
+
+/// Inner function, not available outside this file. 
+/// This function converts a frame to the host format, checks its headers, footer 
+/// and blocks, and then calls `f` with the index and the data of each block, 
+/// so that `f` never sees the blocks of a corrupted frame.
+fn for_each_block<E>(buffer: &mut [u8], data_read_size: usize, layout: &FrameLayout, f: impl FnMut(u16, &[u8]) -> Result<(), Error<E>>) -> Result<(), Error<E>> {
+    // Check that the frame fits in the buffer, and contains at least headers and footer 
+    if layout.header_id_pos + 2 > layout.header_size || layout.footer_id_pos < 2 || layout.footer_id_pos > layout.footer_size {
+        return Err(Error::Parse(ParseError::InvalidLayout));
+    }
+    if data_read_size < layout.header_size + layout.footer_size + 4 || data_read_size > buffer.len() {
+        return Err(Error::Parse(ParseError::Truncated));
+    }
+
+    swap_buffer(buffer, data_read_size);
+
+    // Check if footer id and header id are matching. This allows to detect corrupted frames 
+    let header_id: u16 = u16::from_be_bytes([buffer[layout.header_id_pos], buffer[layout.header_id_pos + 1]]);
+    let footer_id: u16 = u16::from_be_bytes([buffer[data_read_size - layout.footer_id_pos], buffer[data_read_size - layout.footer_id_pos + 1]]);
+
+    if header_id != footer_id {
+        return Err(Error::Parse(ParseError::HeaderFooterMismatch));
+    }
+
+    // Check the block headers before giving any block to `f` 
+    walk_blocks(buffer, data_read_size, layout, |_, _| Ok(()))?;
+    walk_blocks(buffer, data_read_size, layout, f)
+}
+
+/// Inner function, not available outside this file. 
+/// This function walks the blocks of a frame already converted to the host format, 
+/// and calls `f` with the index and the data of each block.
+fn walk_blocks<E>(buffer: &[u8], data_read_size: usize, layout: &FrameLayout, mut f: impl FnMut(u16, &[u8]) -> Result<(), Error<E>>) -> Result<(), Error<E>> {
+    let mut msize: usize;
+    let mut bh: BlockHeader;
+
+    // Blocks start after the header, and end before the footer, which is not a block 
+    let blocks_end: usize = data_read_size - layout.footer_size;
+    let mut i: usize = layout.header_size;
+    while i < blocks_end {
+        if i + 4 > blocks_end {
+            return Err(Error::Parse(ParseError::Truncated));
+        }
+
+        let mut buf: [u32; 1] = [0;1];
+        from_u8_to_u32(&buffer[i..i+4], &mut buf);
+        bh = BlockHeader(buf[0]);
+
+        if bh.bh_type() > 0x1 && bh.bh_type() < 0xd {
+            msize = (bh.bh_type() * bh.bh_size()) as usize;
+        } else  {
+            msize = bh.bh_size() as usize;
+        }
+
+        i += 4;
+
+        // Check that the block does not overflow the frame 
+        if i + msize > blocks_end {
+            return Err(Error::Parse(ParseError::BlockOverflow));
+        }
+
+        f(bh.bh_idx() as u16, &buffer[i..i+msize])?;
+        i += msize;
+    }
+
+    Ok(())
+}
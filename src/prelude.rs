@@ -0,0 +1,74 @@
+//! The prelude re-exports the driver, its error and result types, and the 
+//! public constants, so that applications only need a single import: 
+//! 
+//! ```ignore
+//! use vl53l5cx::prelude::*;
+//! ```
+
+pub use crate::consts::*;
+
+pub use crate::{Vl53l5cx, Error, Stats, BusArbiter, BusBurst, DriverState, LastErrorSnapshot, FirmwareMismatch, CommandStatus, InitStage, ResultsData, FrameInfo};
+pub use crate::app::write_results;
+pub use crate::bandwidth::{check_bus_budget, frame_size, trim_outputs, BusBudget};
+pub use crate::bus_operation::{BusOperation, Vl53l5cxI2C, Vl53l5cxSpi};
+pub use crate::config::{FwVersion, InitStrategy, InvalidDistance, PowerMode, PowerTimings, RangingMode, Resolution, SensorVariant, TargetOrder, VariantBlobs};
+pub use crate::config::VL53L5CX_EXPECTED_FW_VERSION;
+pub use crate::const_config::{ConstConfig, SensorConfig};
+pub use crate::delay::{BusyWait, SleepUs};
+pub use crate::detection_thresholds::{DetectionThresholds, ThresholdError, ThresholdEvents};
+pub use crate::erased::{DynOutputPin, DynPin, Vl53l5cxErased};
+pub use crate::events::{EventHandler, EventHandlers};
+pub use crate::firmware::{BuiltinFirmware, FirmwareReadError, FirmwareSource, SliceFirmware, VL53L5CX_FIRMWARE_SIZE};
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::glass::{detect_glass, GlassReport};
+pub use crate::grid::{to_grid, Grid, GridSize, DEPTH_IMAGE_INVALID};
+pub use crate::interrupt::DataReadyInterrupt;
+pub use crate::motion_indicator::{MotionConfiguration, MotionIndicator};
+pub use crate::multizone::MultizoneTof;
+pub use crate::parser::{Block, FrameLayout, ParseError};
+pub use crate::processor::{Chain, ChangedOnly, FrameProcessor, Throttle};
+pub use crate::ranging_session::RangingSession;
+pub use crate::st_layout::VL53L5CX_ST_RESULTS_SIZE;
+pub use crate::temperature::TemperatureCompensation;
+pub use crate::timing::{check_timing, TimingCheck};
+pub use crate::upsample::upsample_grid;
+pub use crate::virtual_array::{ArrayRotation, VirtualArray};
+pub use crate::watchdog::{Stall, StallRecovery, VL53L5CX_STALL_MISSED_FRAMES};
+pub use crate::xtalk::{CalibrationError, XtalkGrid};
+
+#[cfg(feature = "async")]
+pub use crate::asynch::Vl53l5cxAsync;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::background::BackgroundModel;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::ceiling_mount::{CeilingMount, FallEvent};
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::compact::{CompactFrame, COMPACT_FRAME_MAX_SIZE};
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::downsample::{downsample_8x8, DownsampleMode};
+#[cfg(feature = "alloc")]
+pub use crate::dyn_results::DynResultsData;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::feature_vector::{extract_features, NB_FEATURES};
+#[cfg(feature = "embedded-storage")]
+pub use crate::flash_recorder::{FlashRecorder, RecorderError};
+#[cfg(feature = "float-results")]
+pub use crate::float_results::FloatResults;
+#[cfg(feature = "heapless")]
+pub use crate::frame_pump::FramePump;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::material::{classify_material, classify_zones, Material, MaterialThresholds};
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::noise_floor::NoiseFloorEstimator;
+#[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+pub use crate::offset_check::OffsetReport;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+pub use crate::orientation::{estimate_mounting_correction, nearest_obstacle, zone_point, MountingCorrection, NearestObstacle};
+#[cfg(feature = "soft-i2c")]
+pub use crate::soft_i2c::{SoftI2c, SoftI2cError};
+#[cfg(not(feature = "VL53L5CX_DISABLE_TARGET_STATUS"))]
+pub use crate::status_histogram::StatusHistogram;
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", all(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))))]
+pub use crate::targets::ZoneTargets;
+#[cfg(not(feature = "VL53L5CX_DISABLE_DISTANCE_MM"))]
+pub use crate::vl53l1x_compat::Vl53l1xCompat;
@@ -0,0 +1,111 @@
+use crate::ResultsData;
+
+/// Trait FrameProcessor is implemented by the stages of a frame pipeline
+/// (filters, trackers, gesture recognizers...). A stage processes an input
+/// (by default the results of a frame) and may give an output, which can be
+/// processed by the next stage with chain(). The stages and the combinators
+/// are plain structures, so the pipelines do not need dynamic allocation.
+///
+/// The closures `FnMut(&I) -> Option<O>` are stages, so the existing helpers
+/// (e.g. CeilingMount::update()) are wrapped in a closure.
+pub trait FrameProcessor<I: ?Sized = ResultsData> {
+    type Output;
+
+    /// This function processes an input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` : Input of the stage, e.g. the results of a frame.
+    ///
+    /// # Return
+    ///
+    /// `output` : Output of the stage, or None if the input gives no output.
+    fn process(&mut self, input: &I) -> Option<Self::Output>;
+
+    /// This function chains a stage processing the outputs of this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` : Stage processing the outputs.
+    fn chain<P: FrameProcessor<Self::Output>>(self, next: P) -> Chain<Self, P> where Self: Sized {
+        Chain { first: self, next }
+    }
+
+    /// This function keeps one output of this stage out of `every` outputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `every` : Number of outputs for one output kept (1 keeps all the outputs).
+    fn throttle(self, every: u16) -> Throttle<Self> where Self: Sized {
+        Throttle { inner: self, every, count: 0 }
+    }
+
+    /// This function keeps the outputs of this stage which differ from the previous output.
+    fn changed_only(self) -> ChangedOnly<Self, Self::Output> where Self: Sized {
+        ChangedOnly { inner: self, last: None }
+    }
+}
+
+impl<I: ?Sized, O, F: FnMut(&I) -> Option<O>> FrameProcessor<I> for F {
+    type Output = O;
+
+    fn process(&mut self, input: &I) -> Option<O> {
+        self(input)
+    }
+}
+
+/// Structure Chain gives the outputs of a stage to the next stage, see FrameProcessor::chain().
+pub struct Chain<A, B> {
+    first: A,
+    next: B
+}
+
+impl<I: ?Sized, A: FrameProcessor<I>, B: FrameProcessor<A::Output>> FrameProcessor<I> for Chain<A, B> {
+    type Output = B::Output;
+
+    fn process(&mut self, input: &I) -> Option<B::Output> {
+        let output: A::Output = self.first.process(input)?;
+        self.next.process(&output)
+    }
+}
+
+/// Structure Throttle keeps one output out of `every`, see FrameProcessor::throttle().
+pub struct Throttle<P> {
+    inner: P,
+    every: u16,
+    count: u16
+}
+
+impl<I: ?Sized, P: FrameProcessor<I>> FrameProcessor<I> for Throttle<P> {
+    type Output = P::Output;
+
+    fn process(&mut self, input: &I) -> Option<P::Output> {
+        let output: P::Output = self.inner.process(input)?;
+        let keep: bool = self.count == 0;
+        self.count += 1;
+        if self.count >= self.every {
+            self.count = 0;
+        }
+        if keep { Some(output) } else { None }
+    }
+}
+
+/// Structure ChangedOnly keeps the outputs which differ from the previous one,
+/// see FrameProcessor::changed_only().
+pub struct ChangedOnly<P, O> {
+    inner: P,
+    last: Option<O>
+}
+
+impl<I: ?Sized, P: FrameProcessor<I>> FrameProcessor<I> for ChangedOnly<P, P::Output> where P::Output: Clone + PartialEq {
+    type Output = P::Output;
+
+    fn process(&mut self, input: &I) -> Option<P::Output> {
+        let output: P::Output = self.inner.process(input)?;
+        if self.last.as_ref() == Some(&output) {
+            return None;
+        }
+        self.last = Some(output.clone());
+        Some(output)
+    }
+}
@@ -0,0 +1,95 @@
+use consts::*;
+
+use crate::{consts, BusOperation, Error, Resolution, ResultsData, Vl53l5cx, OutputPin, DelayNs};
+
+/// Zones surrounding the center of the field of view, for each resolution.
+const CENTER_ZONES_4X4: [usize; 4] = [5, 6, 9, 10];
+const CENTER_ZONES_8X8: [usize; 4] = [27, 28, 35, 36];
+
+/// Inner function, not available outside this file.
+/// This function gets the distance of the first target of a zone, if it is valid.
+fn zone_distance_mm(results: &ResultsData, zone: usize) -> Option<u16> {
+    let idx: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+    #[cfg(not(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+    if results.nb_target_detected[zone] == 0 {
+        return None;
+    }
+    #[cfg(not(feature = "VL53L5CX_DISABLE_TARGET_STATUS"))]
+    if results.target_status[idx] != 5 && results.target_status[idx] != 9 {
+        return None;
+    }
+    if results.distance_mm[idx] < 0 {
+        return None;
+    }
+
+    Some(results.distance_mm[idx] as u16)
+}
+
+/// Inner function, not available outside this crate.
+/// This function gets the closest valid target of the zones at the center of the field of view.
+pub(crate) fn center_distance_mm(results: &ResultsData, resolution: Resolution) -> Option<u16> {
+    let zones: [usize; 4] = match resolution {
+        Resolution::Res4x4 => CENTER_ZONES_4X4,
+        Resolution::Res8x8 => CENTER_ZONES_8X8
+    };
+
+    zones.iter().filter_map(|&zone| zone_distance_mm(results, zone)).min()
+}
+
+impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
+    /// Inner function, not available outside this file.
+    /// This function gets a frame. If the sensor is not ranging, the ranging 
+    /// is started for this frame only.
+    fn single_frame(&mut self) -> Result<ResultsData, Error<B::Error>> {
+        if self.is_ranging {
+            return self.wait_for_ranging_data();
+        }
+        self.start_ranging()?;
+        let results: Result<ResultsData, Error<B::Error>> = self.wait_for_ranging_data();
+        let stopped: Result<(), Error<B::Error>> = self.stop_ranging();
+        let results: ResultsData = results?;
+        stopped?;
+
+        Ok(results)
+    }
+
+    /// This function measures the distance in front of the sensor as a single zone 
+    /// rangefinder (such as the VL53L0X or VL53L1X) would: it ranges one frame and 
+    /// returns the closest valid target of the 4 zones at the center of the field of view. 
+    /// The sensor must be initialized and not ranging: the ranging is started and 
+    /// stopped by this function, with the current configuration.
+    /// 
+    /// # Return
+    /// 
+    /// * `distance_mm` : Distance in mm. Error::NoValidTarget is returned if the 
+    ///   central zones have no valid target, and Error::InvalidState if the sensor is ranging.
+    pub fn get_distance_mm(&mut self) -> Result<u16, Error<B::Error>> {
+        if self.is_ranging {
+            return Err(Error::InvalidState);
+        }
+        let resolution: Resolution = self.resolution()?;
+        let results: ResultsData = self.single_frame()?;
+
+        center_distance_mm(&results, resolution).ok_or(Error::NoValidTarget)
+    }
+
+    /// This function measures the distance of the first target of a chosen zone. 
+    /// If the sensor is not ranging, the ranging is started and stopped by this 
+    /// function, otherwise the next frame is used.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `zone` : Zone number, below 16 in 4x4 and below 64 in 8x8.
+    /// 
+    /// # Return
+    /// 
+    /// * `distance_mm` : Distance in mm. Error::NoValidTarget is returned if the zone has no valid target.
+    pub fn get_zone_distance_mm(&mut self, zone: usize) -> Result<u16, Error<B::Error>> {
+        if zone >= VL53L5CX_RESOLUTION_8X8 as usize {
+            return Err(Error::InvalidParam);
+        }
+        let results: ResultsData = self.single_frame()?;
+
+        zone_distance_mm(&results, zone).ok_or(Error::NoValidTarget)
+    }
+}
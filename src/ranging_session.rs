@@ -0,0 +1,57 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{BusOperation, Error, Vl53l5cx, OutputPin, DelayNs};
+
+/// Structure RangingSession is a guard returned by start_ranging_session(). 
+/// It gives access to the driver while the sensor streams, and stops the 
+/// ranging (best effort, errors are ignored) when dropped, so that the sensor 
+/// does not stream forever after an early return or a panic in user code. 
+/// Use stop() to get the result of stop_ranging().
+pub struct RangingSession<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> {
+    sensor: &'a mut Vl53l5cx<B, LPN, RST, T>,
+    stopped: bool
+}
+
+impl<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> RangingSession<'a, B, LPN, RST, T> {
+    /// This function stops the ranging session.
+    pub fn stop(mut self) -> Result<(), Error<B::Error>> {
+        self.stopped = true;
+        self.sensor.stop_ranging()
+    }
+}
+
+impl<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Deref for RangingSession<'a, B, LPN, RST, T> {
+    type Target = Vl53l5cx<B, LPN, RST, T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.sensor
+    }
+}
+
+impl<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> DerefMut for RangingSession<'a, B, LPN, RST, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.sensor
+    }
+}
+
+impl<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Drop for RangingSession<'a, B, LPN, RST, T> {
+    fn drop(&mut self) {
+        if !self.stopped {
+            let _ = self.sensor.stop_ranging();
+        }
+    }
+}
+
+impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
+    /// This function starts a ranging session, and returns a guard which stops it 
+    /// when dropped. The driver is accessed through the guard while the sensor streams.
+    /// 
+    /// # Return
+    /// 
+    /// * `session` : Guard of the ranging session.
+    pub fn start_ranging_session(&mut self) -> Result<RangingSession<'_, B, LPN, RST, T>, Error<B::Error>> {
+        self.start_ranging()?;
+
+        Ok(RangingSession { sensor: self, stopped: false })
+    }
+}
@@ -0,0 +1,218 @@
+//! Parts of the driver which do not access the bus, shared by the blocking driver
+//! (Vl53l5cx) and the async driver (asynch::Vl53l5cxAsync) : the preparation of the
+//! offset and Xtalk buffers, the output list of the frames and the checks of the
+//! UI status bytes. The frames are parsed by the functions of the module parser.
+
+use consts::*;
+use utils::*;
+
+use crate::{consts, utils, BlockHeader};
+
+/// Number of standard outputs of the output list, before the debug outputs.
+pub(crate) const NB_STANDARD_OUTPUTS: usize = 12;
+
+/// This function checks the UI status bytes read at register 0: the stream count
+/// must be valid, and the firmware must report a complete frame.
+///
+/// # Arguments
+///
+/// * `ui_status` : First 4 bytes read at register 0.
+pub(crate) fn is_frame_complete(ui_status: &[u8]) -> bool {
+    ui_status[0] != 0xff
+        && ui_status[1] == 0x05
+        && ui_status[2] & 0x05 == 0x05
+        && ui_status[3] & 0x10 == 0x10
+}
+
+/// This function checks the GO2 error flag of the UI status bytes read at register 0.
+pub(crate) fn is_go2_error(ui_status: &[u8]) -> bool {
+    ui_status[3] & 0x80 != 0
+}
+
+/// This function copies the offset data into `buffer`, extrapolated to the 4x4
+/// resolution if needed, and framed as the command written at 0x2E18.
+///
+/// # Arguments
+///
+/// * `buffer` : Buffer of at least VL53L5CX_OFFSET_BUFFER_SIZE + 4 bytes.
+/// * `offset_data` : Offset data read from the NVM.
+/// * `resolution` : Resolution of the offset data to send.
+pub(crate) fn prepare_offset_data(buffer: &mut [u8], offset_data: &[u8; VL53L5CX_OFFSET_BUFFER_SIZE], resolution: u8) {
+    let mut signal_grid: [u32; 64] = [0; 64];
+    let mut range_grid: [i16; 64] = [0; 64];
+    let dss_4x4: [u8; 8] = [0x0F, 0x04, 0x04, 0x00, 0x08, 0x10, 0x10, 0x07];
+    let footer: [u8; 8] = [0x00, 0x00, 0x00, 0x0F, 0x03, 0x01, 0x01, 0xE4];
+
+    buffer[..VL53L5CX_OFFSET_BUFFER_SIZE].copy_from_slice(offset_data);
+
+    // Data extrapolation is required for 4X4 offset
+    if resolution == VL53L5CX_RESOLUTION_4X4 {
+        buffer[0x10..0x10+dss_4x4.len()].copy_from_slice(&dss_4x4);
+        swap_buffer(buffer, VL53L5CX_OFFSET_BUFFER_SIZE);
+        from_u8_to_u32(&buffer[0x3c..0x3c+256], &mut signal_grid);
+        from_u8_to_i16(&buffer[0x140..0x140+128], &mut range_grid);
+
+        for j in 0..4 {
+            for i in 0..4 {
+                signal_grid[i + (4 * j)] = ((
+                      signal_grid[(2 * i) + (16 * j)] as u64
+                    + signal_grid[(2 * i) + (16 * j) + 1] as u64
+                    + signal_grid[(2 * i) + (16 * j) + 8] as u64
+                    + signal_grid[(2 * i) + (16 * j) + 9] as u64
+                ) /4) as u32;
+                range_grid[i + (4 * j)] = ((
+                      range_grid[(2 * i) + (16 * j)] as i32
+                    + range_grid[(2 * i) + (16 * j) + 1] as i32
+                    + range_grid[(2 * i) + (16 * j) + 8] as i32
+                    + range_grid[(2 * i) + (16 * j) + 9] as i32
+                ) /4) as i16;
+            }
+        }
+        signal_grid[16..].copy_from_slice(&[0;48]);
+        range_grid[16..].copy_from_slice(&[0;48]);
+
+        from_u32_to_u8(&signal_grid, &mut buffer[0x3c..0x3c+256]);
+        from_i16_to_u8(&range_grid, &mut buffer[0x140..0x140+128]);
+
+        swap_buffer(buffer, VL53L5CX_OFFSET_BUFFER_SIZE);
+    }
+
+    buffer.copy_within(8..VL53L5CX_OFFSET_BUFFER_SIZE+4, 0);
+
+    buffer[0x1E0..0x1E0+footer.len()].copy_from_slice(&footer);
+}
+
+/// This function copies the Xtalk data into `buffer`, extrapolated to the 4x4
+/// resolution if needed, as the command written at 0x2CF8.
+///
+/// # Arguments
+///
+/// * `buffer` : Buffer of at least VL53L5CX_XTALK_BUFFER_SIZE bytes.
+/// * `xtalk_data` : Xtalk data, from the generic configuration or the user's calibration.
+/// * `resolution` : Resolution of the Xtalk data to send.
+pub(crate) fn prepare_xtalk_data(buffer: &mut [u8], xtalk_data: &[u8; VL53L5CX_XTALK_BUFFER_SIZE], resolution: u8) {
+    let res4x4: [u8; 8] = [0x0F, 0x04, 0x04, 0x17, 0x08, 0x10, 0x10, 0x07];
+    let dss_4x4: [u8; 8] = [0x00, 0x78, 0x00, 0x08, 0x00, 0x00, 0x00, 0x08];
+    let profile_4x4: [u8; 4] = [0xA0, 0xFC, 0x01, 0x00];
+    let mut signal_grid: [u32; 64] = [0; 64];
+
+    buffer[..VL53L5CX_XTALK_BUFFER_SIZE].copy_from_slice(xtalk_data);
+
+    // Data extrapolation is required for 4X4 Xtalk
+    if resolution == VL53L5CX_RESOLUTION_4X4 {
+        buffer[0x8..0x8 + res4x4.len()].copy_from_slice(&res4x4);
+        buffer[0x020..0x020 + dss_4x4.len()].copy_from_slice(&dss_4x4);
+
+        swap_buffer(buffer, VL53L5CX_XTALK_BUFFER_SIZE);
+        from_u8_to_u32(&buffer[0x34..0x34+256], &mut signal_grid);
+
+        for j in 0..4 {
+            for i in 0..4 {
+                signal_grid[i + (4 * j)] = ((
+                    signal_grid[(2 * i) + (16 * j)] as u64
+                  + signal_grid[(2 * i) + (16 * j) + 1] as u64
+                  + signal_grid[(2 * i) + (16 * j) + 8] as u64
+                  + signal_grid[(2 * i) + (16 * j) + 9] as u64
+              ) /4) as u32;
+            }
+        }
+        signal_grid[16..].copy_from_slice(&[0;48]);
+        from_u32_to_u8(&signal_grid, &mut buffer[0x34..0x34+256]);
+
+        swap_buffer(buffer, VL53L5CX_XTALK_BUFFER_SIZE);
+        buffer[0x134..0x134+profile_4x4.len()].copy_from_slice(&profile_4x4);
+        buffer[0x078..0x078+4].copy_from_slice(&[0; 4]);
+    }
+}
+
+/// This function gets the enable flags of the standard outputs, without the
+/// outputs removed by the VL53L5CX_DISABLE_* features.
+///
+/// # Arguments
+///
+/// * `motion_indicator` : True to enable the motion indicator output.
+pub(crate) fn output_enables(motion_indicator: bool) -> [u32; 4] {
+    let mut output_bh_enable: [u32; 4] = [0x00000007, 0x00000000, 0x00000000, 0xC0000000];
+
+    if !cfg!(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD") { output_bh_enable[0] += 8; }
+    if !cfg!(feature = "VL53L5CX_DISABLE_NB_SPADS_ENABLED") { output_bh_enable[0] += 16; }
+    if !cfg!(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED") { output_bh_enable[0] += 32; }
+    if !cfg!(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD") { output_bh_enable[0] += 64; }
+    if !cfg!(feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM") { output_bh_enable[0] += 128; }
+    if !cfg!(feature = "VL53L5CX_DISABLE_DISTANCE_MM") { output_bh_enable[0] += 256; }
+    if !cfg!(feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT") { output_bh_enable[0] += 512; }
+    if !cfg!(feature = "VL53L5CX_DISABLE_TARGET_STATUS") { output_bh_enable[0] += 1024; }
+    if motion_indicator { output_bh_enable[0] += 2048; }
+
+    output_bh_enable
+}
+
+/// This function builds the output list written at VL53L5CX_DCI_OUTPUT_LIST: the
+/// block headers of the enabled standard outputs, sized for the resolution, followed
+/// by the debug outputs, which are enabled in `output_bh_enable`.
+///
+/// # Arguments
+///
+/// * `resolution` : Resolution of the frames (16 or 64).
+/// * `output_bh_enable` : Enable flags of the outputs (see output_enables()).
+/// * `debug_outputs` : Block headers of the debug outputs.
+/// * `output` : Output list, filled with the block headers.
+///
+/// # Return
+///
+/// * `(nb_outputs, data_read_size)` : Number of outputs of the list, and size of the frames in bytes.
+pub(crate) fn build_output_list(resolution: u8, output_bh_enable: &mut [u32; 4], debug_outputs: &[u32], output: &mut [u32; NB_STANDARD_OUTPUTS + VL53L5CX_MAX_DEBUG_OUTPUTS]) -> (usize, u32) {
+    let mut data_read_size: u32 = 0;
+    let mut bh: BlockHeader;
+
+    output[..NB_STANDARD_OUTPUTS].copy_from_slice(&[
+        VL53L5CX_START_BH,
+        VL53L5CX_METADATA_BH,
+        VL53L5CX_COMMONDATA_BH,
+        VL53L5CX_AMBIENT_RATE_BH,
+        VL53L5CX_SPAD_COUNT_BH,
+        VL53L5CX_NB_TARGET_DETECTED_BH,
+        VL53L5CX_SIGNAL_RATE_BH,
+        VL53L5CX_RANGE_SIGMA_MM_BH,
+        VL53L5CX_DISTANCE_BH,
+        VL53L5CX_REFLECTANCE_BH,
+        VL53L5CX_TARGET_STATUS_BH,
+        VL53L5CX_MOTION_DETECT_BH
+    ]);
+    output[NB_STANDARD_OUTPUTS..].fill(0);
+
+    // Update data size
+    for i in 0..NB_STANDARD_OUTPUTS {
+        if output[i] == 0 || output_bh_enable[i/32] & (1 << (i%32)) == 0 {
+            continue;
+        }
+        bh = BlockHeader(output[i]);
+        if bh.bh_type() >= 0x01 && bh.bh_type() < 0x0d {
+            if bh.bh_idx() >= 0x54d0 && bh.bh_idx() < 0x54d0 + 960 {
+                bh.set_bh_size(resolution as u32);}
+            else {
+                bh.set_bh_size(resolution as u32 * VL53L5CX_NB_TARGET_PER_ZONE);}
+            data_read_size += bh.bh_type() * bh.bh_size();}
+        else {
+            data_read_size += bh.bh_size();}
+        data_read_size += 4;
+        output[i] = bh.bh_bytes();
+    }
+    // Debug outputs (see set_debug_outputs()) are added after the standard ones
+    let nb_outputs: usize = NB_STANDARD_OUTPUTS + debug_outputs.len();
+    for (i, header) in debug_outputs.iter().enumerate() {
+        let i: usize = NB_STANDARD_OUTPUTS + i;
+        bh = BlockHeader(*header);
+        if bh.bh_type() >= 0x01 && bh.bh_type() < 0x0d {
+            data_read_size += bh.bh_type() * bh.bh_size();
+        } else {
+            data_read_size += bh.bh_size();
+        }
+        data_read_size += 4;
+        output[i] = bh.bh_bytes();
+        output_bh_enable[0] |= 1 << i;
+    }
+    data_read_size += 24;
+
+    (nb_outputs, data_read_size)
+}
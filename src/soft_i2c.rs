@@ -0,0 +1,218 @@
+use embedded_hal::digital::{self, ErrorKind, InputPin};
+
+use consts::*;
+
+use crate::{consts, BusOperation, DelayNs, Error, OutputPin, SevenBitAddress, Vl53l5cx};
+
+/// Enum SoftI2cError contains the errors of the bit-bang I2C bus.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SoftI2cError {
+    /// The sensor did not acknowledge a byte.
+    Nack,
+    /// A pin returned an error.
+    Pin(ErrorKind)
+}
+
+/// Structure SoftI2c is a bit-bang I2C bus, for boards whose hardware I2C pins 
+/// are used by other peripherals. SDA must be an open-drain pin which can be read 
+/// back, and SCL an open-drain or push-pull pin. Clock stretching is not supported.
+/// 
+/// The default half period of 5us gives a 100kHz clock, which is enough for init(): 
+/// the firmware upload is done in chunks (see set_chunk_size()), and the MCU boot 
+/// and command polls only depend on the driver delays.
+pub struct SoftI2c<SCL, SDA, D> {
+    scl: SCL,
+    sda: SDA,
+    delay: D,
+    half_period_us: u32,
+    address: SevenBitAddress,
+}
+
+impl<SCL: OutputPin, SDA: OutputPin + InputPin, D: DelayNs> SoftI2c<SCL, SDA, D> {
+    /// This function creates a bit-bang I2C bus.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `scl` : Clock pin.
+    /// * `sda` : Open-drain data pin.
+    /// * `delay` : Delay provider used for the clock timing.
+    /// * `half_period_us` : Half period of the clock in us (5us for 100kHz). Min value is 1us.
+    pub fn new(scl: SCL, sda: SDA, delay: D, half_period_us: u32) -> Self {
+        SoftI2c { scl, sda, delay, half_period_us: half_period_us.max(1), address: VL53L5CX_DEFAULT_I2C_ADDRESS }
+    }
+
+    fn wait(&mut self) {
+        self.delay.delay_us(self.half_period_us);
+    }
+
+    fn scl(&mut self, high: bool) -> Result<(), SoftI2cError> {
+        let result = if high { self.scl.set_high() } else { self.scl.set_low() };
+        result.map_err(|e| SoftI2cError::Pin(digital::Error::kind(&e)))
+    }
+
+    fn sda(&mut self, high: bool) -> Result<(), SoftI2cError> {
+        let result = if high { self.sda.set_high() } else { self.sda.set_low() };
+        result.map_err(|e| SoftI2cError::Pin(digital::Error::kind(&e)))
+    }
+
+    fn sda_is_high(&mut self) -> Result<bool, SoftI2cError> {
+        self.sda.is_high().map_err(|e| SoftI2cError::Pin(digital::Error::kind(&e)))
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function sends a start, or a repeated start, condition.
+    fn start(&mut self) -> Result<(), SoftI2cError> {
+        self.sda(true)?;
+        self.scl(true)?;
+        self.wait();
+        self.sda(false)?;
+        self.wait();
+        self.scl(false)
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function sends a stop condition.
+    fn stop(&mut self) -> Result<(), SoftI2cError> {
+        self.sda(false)?;
+        self.wait();
+        self.scl(true)?;
+        self.wait();
+        self.sda(true)?;
+        self.wait();
+        Ok(())
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function sends a byte, MSB first, and checks the acknowledge.
+    fn write_byte(&mut self, byte: u8) -> Result<(), SoftI2cError> {
+        for bit in (0..8).rev() {
+            self.sda(byte & (1 << bit) != 0)?;
+            self.wait();
+            self.scl(true)?;
+            self.wait();
+            self.scl(false)?;
+        }
+
+        self.sda(true)?;
+        self.wait();
+        self.scl(true)?;
+        self.wait();
+        let nack: bool = self.sda_is_high()?;
+        self.scl(false)?;
+        if nack {
+            return Err(SoftI2cError::Nack);
+        }
+
+        Ok(())
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function reads a byte, MSB first, and acknowledges it if `ack` is true.
+    fn read_byte(&mut self, ack: bool) -> Result<u8, SoftI2cError> {
+        let mut byte: u8 = 0;
+
+        self.sda(true)?;
+        for _ in 0..8 {
+            self.wait();
+            self.scl(true)?;
+            self.wait();
+            byte = (byte << 1) | self.sda_is_high()? as u8;
+            self.scl(false)?;
+        }
+
+        self.sda(!ack)?;
+        self.wait();
+        self.scl(true)?;
+        self.wait();
+        self.scl(false)?;
+        self.sda(true)?;
+
+        Ok(byte)
+    }
+
+    /// Inner function, not available outside this file. 
+    /// This function runs a transfer, and always ends it with a stop condition.
+    fn transfer(&mut self, wbuf: &[u8], rbuf: &mut [u8]) -> Result<(), SoftI2cError> {
+        let result: Result<(), SoftI2cError> = self.transfer_inner(wbuf, rbuf);
+        self.stop()?;
+        result
+    }
+
+    fn transfer_inner(&mut self, wbuf: &[u8], rbuf: &mut [u8]) -> Result<(), SoftI2cError> {
+        if !wbuf.is_empty() {
+            self.start()?;
+            self.write_byte(self.address << 1)?;
+            for &byte in wbuf {
+                self.write_byte(byte)?;
+            }
+        }
+        if !rbuf.is_empty() {
+            self.start()?;
+            self.write_byte(self.address << 1 | 1)?;
+            let last: usize = rbuf.len() - 1;
+            for (i, byte) in rbuf.iter_mut().enumerate() {
+                *byte = self.read_byte(i != last)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<SCL: OutputPin, SDA: OutputPin + InputPin, D: DelayNs> BusOperation for SoftI2c<SCL, SDA, D> {
+    type Error = SoftI2cError;
+
+    fn read(&mut self, rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.transfer(&[], rbuf)
+    }
+
+    fn write(&mut self, wbuf: &[u8]) -> Result<(), Self::Error> {
+        self.transfer(wbuf, &mut [])
+    }
+
+    fn write_read(&mut self, wbuf: &[u8], rbuf: &mut [u8]) -> Result<(), Self::Error> {
+        self.transfer(wbuf, rbuf)
+    }
+}
+
+impl<SCL, SDA, D, LPN, RST, T> Vl53l5cx<SoftI2c<SCL, SDA, D>, LPN, RST, T>
+    where
+    SCL: OutputPin,
+    SDA: OutputPin + InputPin,
+    D: DelayNs,
+    LPN: OutputPin,
+    RST: OutputPin,
+    T: DelayNs
+{
+    /// This function creates a driver using a bit-bang I2C bus.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bus` : Bit-bang I2C bus.
+    /// * `lpn_pin` : LPn pin of the sensor.
+    /// * `i2c_rst_pin` : I2C reset pin of the sensor.
+    /// * `tim` : Delay provider.
+    pub fn new_soft_i2c(bus: SoftI2c<SCL, SDA, D>, lpn_pin: LPN, i2c_rst_pin: RST, tim: T) -> Result<Self, Error<SoftI2cError>> 
+    {
+        Ok(Vl53l5cx::with_bus(bus, lpn_pin, i2c_rst_pin, tim))
+    }
+
+    pub fn set_i2c_address(&mut self, i2c_address: SevenBitAddress) -> Result<(), Error<SoftI2cError>> {
+        self.with_bank(0x00, |s| {
+            s.write_to_register(0x4, i2c_address)?;
+            s.bus.address = i2c_address;
+            Ok(())
+        })
+    }
+
+    pub fn init_sensor(&mut self, address: u8) -> Result<(), Error<SoftI2cError>>{
+        self.off()?;
+        self.on()?;
+        if address != self.bus.address {
+            self.set_i2c_address(address)?;
+        }
+        self.probe_identity()?;
+        self.init()?;
+        Ok(())
+    }
+}
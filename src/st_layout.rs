@@ -0,0 +1,123 @@
+use consts::*;
+
+use crate::{consts, Error, ResultsData};
+
+/// Size in bytes of ST's VL53L5CX_ResultsData, written by as_st_c_layout(). It
+/// depends on the number of targets per zone and on the disabled outputs, as in C.
+pub const VL53L5CX_ST_RESULTS_SIZE: usize = st_results_size();
+
+const NB_ZONES: usize = VL53L5CX_RESOLUTION_8X8 as usize;
+const NB_TARGETS: usize = NB_ZONES * VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+const fn max(a: usize, b: usize) -> usize {
+    if a > b { a } else { b }
+}
+
+const fn align(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// This function computes the size of VL53L5CX_ResultsData, with the alignment
+/// and the padding of a 32 bits ARM compiler.
+#[cfg_attr(all(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD", feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", feature = "VL53L5CX_DISABLE_NB_SPADS_ENABLED", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM", feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_TARGET_STATUS", feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"), allow(unused_mut))]
+const fn st_results_size() -> usize {
+    // silicon_temp_degc, and alignment of the struct on its largest member
+    let mut size: usize = 1;
+    let mut alignment: usize = 1;
+    #[cfg(not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+    { size = align(size, 4) + 4 * NB_ZONES; alignment = max(alignment, 4); }
+    #[cfg(not(feature="VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+    { size += NB_ZONES; }
+    #[cfg(not(feature="VL53L5CX_DISABLE_NB_SPADS_ENABLED"))]
+    { size = align(size, 4) + 4 * NB_ZONES; alignment = max(alignment, 4); }
+    #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+    { size = align(size, 4) + 4 * NB_TARGETS; alignment = max(alignment, 4); }
+    #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+    { size = align(size, 2) + 2 * NB_TARGETS; alignment = max(alignment, 2); }
+    #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+    { size = align(size, 2) + 2 * NB_TARGETS; alignment = max(alignment, 2); }
+    #[cfg(not(feature="VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
+    { size += NB_TARGETS; }
+    #[cfg(not(feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
+    { size += NB_TARGETS; }
+    #[cfg(not(feature="VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+    { size = align(size, 4) + 4 + 4 + 4 + 4 * 32; alignment = max(alignment, 4); }
+
+    align(size, alignment)
+}
+
+// Writers of the little endian arrays, aligned as in C. They return the offset after the array.
+fn put_u32(out: &mut [u8], offset: usize, values: &[u32]) -> usize {
+    let mut offset: usize = align(offset, 4);
+    for value in values {
+        out[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        offset += 4;
+    }
+    offset
+}
+
+fn put_u16(out: &mut [u8], offset: usize, values: impl Iterator<Item = u16>) -> usize {
+    let mut offset: usize = align(offset, 2);
+    for value in values {
+        out[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+        offset += 2;
+    }
+    offset
+}
+
+fn put_u8(out: &mut [u8], offset: usize, values: &[u8]) -> usize {
+    out[offset..offset + values.len()].copy_from_slice(values);
+    offset + values.len()
+}
+
+impl ResultsData {
+    /// This function serializes the results in the byte layout of ST's VL53L5CX_ResultsData,
+    /// as compiled for a little endian 32 bits MCU with the same number of targets per zone
+    /// and disabled outputs. Host tools and visualizers written against the C ULD can
+    /// read the buffer as a VL53L5CX_ResultsData. The padding bytes are set to 0, and
+    /// frame_info and float_results, which are not in the C struct, are not written.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` : Output buffer, of at least VL53L5CX_ST_RESULTS_SIZE bytes.
+    ///
+    /// # Return
+    ///
+    /// * `size` : Number of bytes written (VL53L5CX_ST_RESULTS_SIZE), or Error::BufferTooSmall.
+    #[cfg_attr(all(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD", feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", feature = "VL53L5CX_DISABLE_NB_SPADS_ENABLED", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM", feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_TARGET_STATUS", feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"), allow(unused_mut))]
+    pub fn as_st_c_layout<E>(&self, out: &mut [u8]) -> Result<usize, Error<E>> {
+        if out.len() < VL53L5CX_ST_RESULTS_SIZE {
+            return Err(Error::BufferTooSmall);
+        }
+        let out: &mut [u8] = &mut out[..VL53L5CX_ST_RESULTS_SIZE];
+        out.fill(0);
+
+        let mut offset: usize = put_u8(out, 0, &[self.silicon_temp_degc as u8]);
+        #[cfg(not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+        { offset = put_u32(out, offset, &self.ambient_per_spad); }
+        #[cfg(not(feature="VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+        { offset = put_u8(out, offset, &self.nb_target_detected); }
+        #[cfg(not(feature="VL53L5CX_DISABLE_NB_SPADS_ENABLED"))]
+        { offset = put_u32(out, offset, &self.nb_spads_enabled); }
+        #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+        { offset = put_u32(out, offset, &self.signal_per_spad); }
+        #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+        { offset = put_u16(out, offset, self.range_sigma_mm.iter().copied()); }
+        #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+        { offset = put_u16(out, offset, self.distance_mm.iter().map(|&distance| distance as u16)); }
+        #[cfg(not(feature="VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
+        { offset = put_u8(out, offset, &self.reflectance); }
+        #[cfg(not(feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
+        { offset = put_u8(out, offset, &self.target_status); }
+        #[cfg(not(feature="VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+        {
+            let motion = &self.motion_indicator;
+            offset = put_u32(out, offset, &[motion.global_indicator_1, motion.global_indicator_2]);
+            offset = put_u8(out, offset, &[motion.status, motion.nb_of_detected_aggregates, motion.nb_of_aggregates, motion.spare]);
+            offset = put_u32(out, offset, &motion.motion);
+        }
+        debug_assert!(offset <= VL53L5CX_ST_RESULTS_SIZE);
+
+        Ok(VL53L5CX_ST_RESULTS_SIZE)
+    }
+}
@@ -0,0 +1,61 @@
+use consts::*;
+
+use crate::{consts, ResultsData, Resolution};
+
+/// Number of bins of the histogram. The last bin counts the statuses 
+/// from 15 to 255, i.e. mainly the not detected targets (255).
+pub const STATUS_HISTOGRAM_SIZE: usize = 16;
+
+/// Structure StatusHistogram contains the number of targets of a frame 
+/// for each target status, e.g. to tune the sharpener and integration time, 
+/// or to check a sensor on a production line.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StatusHistogram {
+  // Number of targets per status, the last bin counting statuses >= 15
+    pub counts: [u16; STATUS_HISTOGRAM_SIZE],
+  // Total number of targets counted
+    pub nb_targets: u16,
+}
+
+impl StatusHistogram {
+    /// This function counts the status of all the targets of a frame.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `results` : Results of the frame.
+    /// * `resolution` : Resolution used for the frame.
+    pub fn from_results(results: &ResultsData, resolution: Resolution) -> Self {
+        let mut histogram: StatusHistogram = StatusHistogram::default();
+        let nb_targets: usize = resolution.nb_zones() * VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+        for &status in &results.target_status[..nb_targets] {
+            histogram.counts[(status as usize).min(STATUS_HISTOGRAM_SIZE - 1)] += 1;
+        }
+        histogram.nb_targets = nb_targets as u16;
+
+        histogram
+    }
+
+    /// This function gets the number of targets with the given status.
+    /// Statuses >= 15 share the same bin.
+    pub fn count(&self, status: u8) -> u16 {
+        self.counts[(status as usize).min(STATUS_HISTOGRAM_SIZE - 1)]
+    }
+
+    /// This function gets the number of valid targets, i.e. with status 5 or 9.
+    pub fn nb_valid(&self) -> u16 {
+        self.counts[5] + self.counts[9]
+    }
+
+    /// This function gets the percentage of valid targets (status 5 or 9).
+    /// 
+    /// # Return
+    /// 
+    /// * `percent` : Valid targets in percent, 0 if no target was counted.
+    pub fn percent_valid(&self) -> u8 {
+        if self.nb_targets == 0 {
+            return 0;
+        }
+        (self.nb_valid() as u32 * 100 / self.nb_targets as u32) as u8
+    }
+}
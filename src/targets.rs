@@ -0,0 +1,92 @@
+use consts::*;
+
+use crate::{consts, ResultsData};
+
+/// Structure ZoneTargets is a sorted view of the targets detected in a zone, returned
+/// by ResultsData::targets_by_distance() and ResultsData::targets_by_signal(). It
+/// contains the indexes of the targets in the per target arrays of ResultsData
+/// (zone * VL53L5CX_NB_TARGET_PER_ZONE + target), so that the order does not depend
+/// on the target order of the sensor (see set_target_order()).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ZoneTargets {
+    zone: usize,
+    indexes: [usize; VL53L5CX_NB_TARGET_PER_ZONE as usize],
+    len: usize
+}
+
+impl ZoneTargets {
+    /// This function gets the zone of the targets.
+    pub fn zone(&self) -> usize {
+        self.zone
+    }
+
+    /// This function gets the number of targets detected in the zone.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// This function checks whether no target is detected in the zone.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// This function gets the indexes of the targets in the per target arrays of
+    /// ResultsData, e.g. `results.distance_mm[index]`, in the order of the view.
+    pub fn indexes(&self) -> &[usize] {
+        &self.indexes[..self.len]
+    }
+
+    /// Inner function, not available outside this file.
+    /// This function sorts the detected targets of a zone by increasing key.
+    fn sorted_by_key<K: Ord>(results: &ResultsData, zone: usize, key: impl Fn(usize) -> K) -> Self {
+        let nb_targets: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+        let mut targets: ZoneTargets = ZoneTargets { zone, indexes: [0; VL53L5CX_NB_TARGET_PER_ZONE as usize], len: 0 };
+        if zone >= VL53L5CX_RESOLUTION_8X8 as usize {
+            return targets;
+        }
+        targets.len = (results.nb_target_detected[zone] as usize).min(nb_targets);
+        for (target, index) in targets.indexes.iter_mut().enumerate() {
+            *index = zone * nb_targets + target;
+        }
+        // Insertion sort (stable), so the targets with the same key keep the order of the sensor
+        for i in 1..targets.len {
+            let mut j: usize = i;
+            while j > 0 && key(targets.indexes[j]) < key(targets.indexes[j - 1]) {
+                targets.indexes.swap(j, j - 1);
+                j -= 1;
+            }
+        }
+
+        targets
+    }
+}
+
+impl ResultsData {
+    /// This function gets the targets detected in a zone, closest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `zone` : Zone index, as in ResultsData.
+    ///
+    /// # Return
+    ///
+    /// * `targets` : Sorted view of the targets, empty if no target is detected or `zone` is not a zone.
+    #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+    pub fn targets_by_distance(&self, zone: usize) -> ZoneTargets {
+        ZoneTargets::sorted_by_key(self, zone, |index| self.distance_mm[index])
+    }
+
+    /// This function gets the targets detected in a zone, strongest signal first.
+    ///
+    /// # Arguments
+    ///
+    /// * `zone` : Zone index, as in ResultsData.
+    ///
+    /// # Return
+    ///
+    /// * `targets` : Sorted view of the targets, empty if no target is detected or `zone` is not a zone.
+    #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+    pub fn targets_by_signal(&self, zone: usize) -> ZoneTargets {
+        ZoneTargets::sorted_by_key(self, zone, |index| core::cmp::Reverse(self.signal_per_spad[index]))
+    }
+}
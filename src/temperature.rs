@@ -0,0 +1,65 @@
+//! Temperature compensation of the distances.
+//! 
+//! The offset calibration of the sensor is done at a single temperature, so the 
+//! distances drift slightly with the temperature of the silicon, which is reported 
+//! in each frame. The drift depends on the module and on the cover glass, and is 
+//! typically a few millimeters over -20..60 °C. For precision measurements, it can 
+//! be characterized by ranging a fixed target at several temperatures, and 
+//! corrected with a model given to set_temperature_compensation().
+
+use consts::*;
+
+use crate::{consts, ResultsData};
+
+/// Type TemperatureCompensation is a function adjusting a distance with a user model. 
+/// It receives the silicon temperature of the frame in degrees Celsius, the zone 
+/// of the target, and the measured distance in mm, and returns the corrected distance.
+pub type TemperatureCompensation = fn(silicon_temp_degc: i8, zone: usize, distance_mm: i16) -> i16;
+
+impl ResultsData {
+    /// This function corrects the distances of the valid targets (status 5 or 9) 
+    /// with a temperature compensation model, using the silicon temperature of the frame. 
+    /// The targets without valid measurement keep their distance, e.g. the sentinels 
+    /// of set_invalid_distance().
+    /// 
+    /// # Arguments
+    /// 
+    /// * `compensation` : Model giving the corrected distance of a target.
+    #[cfg(not(any(feature="VL53L5CX_DISABLE_DISTANCE_MM", feature="VL53L5CX_DISABLE_TARGET_STATUS")))]
+    pub fn compensate_temperature(&mut self, compensation: TemperatureCompensation) {
+        let nb_targets: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+        for t in 0..self.distance_mm.len() {
+            if self.target_status[t] != 5 && self.target_status[t] != 9 {
+                continue;
+            }
+            let distance_mm: i16 = compensation(self.silicon_temp_degc, t / nb_targets, self.distance_mm[t]);
+            #[cfg(feature = "float-results")]
+            {
+                self.float_results.distance_mm[t] += (distance_mm - self.distance_mm[t]) as f32;
+            }
+            self.distance_mm[t] = distance_mm;
+        }
+    }
+
+    /// Inner function, not available outside the crate. 
+    /// This function corrects the distances as compensate_temperature() does, while they 
+    /// are still in the firmware format (1/4 mm, negative distances read as 0). The targets 
+    /// of the zones without detected target are skipped, as their status is set to 255 later.
+    #[cfg(not(any(feature="VL53L5CX_DISABLE_DISTANCE_MM", feature="VL53L5CX_DISABLE_TARGET_STATUS")))]
+    pub(crate) fn compensate_raw_temperature(&mut self, compensation: TemperatureCompensation) {
+        let nb_targets: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+        for t in 0..self.distance_mm.len() {
+            if self.target_status[t] != 5 && self.target_status[t] != 9 {
+                continue;
+            }
+            #[cfg(not(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+            if self.nb_target_detected[t / nb_targets] == 0 {
+                continue;
+            }
+            let raw: i16 = self.distance_mm[t].max(0);
+            let distance_mm: i16 = raw / 4;
+            let delta_mm: i16 = compensation(self.silicon_temp_degc, t / nb_targets, distance_mm).saturating_sub(distance_mm);
+            self.distance_mm[t] = raw.saturating_add(delta_mm.saturating_mul(4));
+        }
+    }
+}
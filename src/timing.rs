@@ -0,0 +1,61 @@
+use crate::{RangingMode, Resolution};
+
+/// Maximum ranging frequency in Hz for the 4x4 resolution.
+pub const VL53L5CX_MAX_FREQUENCY_HZ_4X4: u8 = 60;
+
+/// Maximum ranging frequency in Hz for the 8x8 resolution.
+pub const VL53L5CX_MAX_FREQUENCY_HZ_8X8: u8 = 15;
+
+/// Minimum and maximum integration times in ms.
+pub const VL53L5CX_MIN_INTEGRATION_TIME_MS: u32 = 2;
+pub const VL53L5CX_MAX_INTEGRATION_TIME_MS: u32 = 1000;
+
+/// Structure TimingCheck contains the result of check_timing().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimingCheck {
+  // True if the requested frequency can be reached with this configuration
+    pub feasible: bool,
+  // Maximum ranging frequency in Hz for the resolution, ranging mode and integration time, 0 if none
+    pub max_frequency_hz: u8,
+}
+
+/// This function checks that a ranging frequency can be reached with the given 
+/// resolution, ranging mode and integration time, instead of letting the firmware 
+/// silently clamp it. The timing model is the one of the datasheet : 
+/// - the frequency is limited to 60Hz in 4x4, and to 15Hz in 8x8.
+/// - in autonomous mode, the integration time (2ms to 1000ms) is repeated 4 times 
+///   per frame in 8x8, and the integrations of a frame must fit in the ranging period.
+/// 
+/// # Arguments
+/// 
+/// * `resolution` : Resolution of the frames.
+/// * `ranging_mode` : Ranging mode. The integration time is only used in autonomous mode.
+/// * `frequency_hz` : Requested ranging frequency in Hz.
+/// * `integration_time_ms` : Requested integration time in ms.
+/// 
+/// # Return
+/// 
+/// * `check` : Feasibility, and maximum achievable frequency.
+pub const fn check_timing(resolution: Resolution, ranging_mode: RangingMode, frequency_hz: u8, integration_time_ms: u32) -> TimingCheck {
+    let mut max_frequency_hz: u32 = match resolution {
+        Resolution::Res4x4 => VL53L5CX_MAX_FREQUENCY_HZ_4X4 as u32,
+        Resolution::Res8x8 => VL53L5CX_MAX_FREQUENCY_HZ_8X8 as u32
+    };
+
+    // Written without PartialEq and ranges, so that it can be evaluated at compile time 
+    if matches!(ranging_mode, RangingMode::Autonomous) {
+        if integration_time_ms < VL53L5CX_MIN_INTEGRATION_TIME_MS || integration_time_ms > VL53L5CX_MAX_INTEGRATION_TIME_MS {
+            return TimingCheck { feasible: false, max_frequency_hz: 0 };
+        }
+        let nb_integrations: u32 = if matches!(resolution, Resolution::Res8x8) { 4 } else { 1 };
+        let max_autonomous_hz: u32 = 1000 / (nb_integrations * integration_time_ms);
+        if max_autonomous_hz < max_frequency_hz {
+            max_frequency_hz = max_autonomous_hz;
+        }
+    }
+
+    TimingCheck {
+        feasible: frequency_hz >= 1 && frequency_hz as u32 <= max_frequency_hz,
+        max_frequency_hz: max_frequency_hz as u8
+    }
+}
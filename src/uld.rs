@@ -0,0 +1,248 @@
+//! Functions named after ST's C ULD (vl53l5cx_api.h), thinly wrapping the driver, so that
+//! ST examples and application notes can be ported line by line. As in C, the device is
+//! given as first argument and the values are read through output arguments, while the
+//! status is the Result of the function:
+//!
+//! ```ignore
+//! // C: status = vl53l5cx_check_data_ready(&Dev, &isReady);
+//! vl53l5cx_check_data_ready(&mut dev, &mut is_ready)?;
+//! // C: status = vl53l5cx_get_ranging_data(&Dev, &Results);
+//! vl53l5cx_get_ranging_data(&mut dev, &mut results)?;
+//! ```
+//!
+//! The functions are enabled with the feature `uld-api`. New code should use the methods
+//! of Vl53l5cx, which return the values and take the typed configurations (Resolution, ...).
+
+#![allow(non_snake_case, non_camel_case_types)]
+
+use consts::*;
+
+use crate::{consts, BusOperation, DetectionThresholds, Error, MotionConfiguration, ResultsData, Vl53l5cx, Vl53l5cxI2C, I2c, OutputPin, DelayNs};
+
+/// Driver of the device, named after the C VL53L5CX_Configuration.
+pub type VL53L5CX_Configuration<B, LPN, RST, T> = Vl53l5cx<B, LPN, RST, T>;
+/// Results of a frame, named after the C VL53L5CX_ResultsData.
+pub type VL53L5CX_ResultsData = ResultsData;
+/// Motion indicator configuration, named after the C VL53L5CX_Motion_Configuration.
+pub type VL53L5CX_Motion_Configuration = MotionConfiguration;
+/// Detection threshold, named after the C VL53L5CX_DetectionThresholds.
+pub type VL53L5CX_DetectionThresholds = DetectionThresholds;
+
+/// This function checks the sensor identity, as vl53l5cx_is_alive().
+/// `p_is_alive` is set to 1 if the device and revision ids match, 0 otherwise.
+pub fn vl53l5cx_is_alive<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_is_alive: &mut u8) -> Result<(), Error<B::Error>> {
+    match p_dev.probe_identity() {
+        Ok(()) => *p_is_alive = 1,
+        Err(Error::IdMismatch { .. }) => *p_is_alive = 0,
+        Err(err) => return Err(err)
+    }
+    Ok(())
+}
+
+/// This function initializes the sensor, as vl53l5cx_init().
+pub fn vl53l5cx_init<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>) -> Result<(), Error<B::Error>> {
+    p_dev.init()
+}
+
+/// This function changes the I2C address of the sensor, as vl53l5cx_set_i2c_address().
+/// As in C, `i2c_address` is the 8 bits address (e.g. 0x52 for the default address).
+pub fn vl53l5cx_set_i2c_address<P: I2c, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<Vl53l5cxI2C<P>, LPN, RST, T>, i2c_address: u16) -> Result<(), Error<P::Error>> {
+    p_dev.set_i2c_address((i2c_address >> 1) as u8)
+}
+
+/// This function gets the power mode, as vl53l5cx_get_power_mode().
+pub fn vl53l5cx_get_power_mode<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_power_mode: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_power_mode = p_dev.power_mode()?.into();
+    Ok(())
+}
+
+/// This function sets the power mode, as vl53l5cx_set_power_mode().
+pub fn vl53l5cx_set_power_mode<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, power_mode: u8) -> Result<(), Error<B::Error>> {
+    p_dev.set_power_mode(power_mode)
+}
+
+/// This function starts a ranging session, as vl53l5cx_start_ranging().
+pub fn vl53l5cx_start_ranging<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>) -> Result<(), Error<B::Error>> {
+    p_dev.start_ranging()
+}
+
+/// This function stops the ranging session, as vl53l5cx_stop_ranging().
+pub fn vl53l5cx_stop_ranging<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>) -> Result<(), Error<B::Error>> {
+    p_dev.stop_ranging()
+}
+
+/// This function checks if a new frame is ready, as vl53l5cx_check_data_ready().
+/// `p_is_ready` is set to 1 if a new frame is ready, 0 otherwise.
+pub fn vl53l5cx_check_data_ready<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_is_ready: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_is_ready = p_dev.check_data_ready()? as u8;
+    Ok(())
+}
+
+/// This function reads the new frame, as vl53l5cx_get_ranging_data().
+pub fn vl53l5cx_get_ranging_data<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_results: &mut ResultsData) -> Result<(), Error<B::Error>> {
+    *p_results = p_dev.get_ranging_data()?;
+    Ok(())
+}
+
+/// This function gets the resolution, as vl53l5cx_get_resolution().
+pub fn vl53l5cx_get_resolution<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_resolution: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_resolution = p_dev.resolution()?.into();
+    Ok(())
+}
+
+/// This function sets the resolution, as vl53l5cx_set_resolution().
+pub fn vl53l5cx_set_resolution<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, resolution: u8) -> Result<(), Error<B::Error>> {
+    p_dev.set_resolution(resolution)
+}
+
+/// This function gets the ranging frequency, as vl53l5cx_get_ranging_frequency_hz().
+pub fn vl53l5cx_get_ranging_frequency_hz<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_frequency_hz: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_frequency_hz = p_dev.get_frequency_hz()?;
+    Ok(())
+}
+
+/// This function sets the ranging frequency, as vl53l5cx_set_ranging_frequency_hz().
+pub fn vl53l5cx_set_ranging_frequency_hz<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, frequency_hz: u8) -> Result<(), Error<B::Error>> {
+    p_dev.set_frequency_hz(frequency_hz)
+}
+
+/// This function gets the integration time, as vl53l5cx_get_integration_time_ms().
+pub fn vl53l5cx_get_integration_time_ms<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_time_ms: &mut u32) -> Result<(), Error<B::Error>> {
+    *p_time_ms = p_dev.get_integration_time()?;
+    Ok(())
+}
+
+/// This function sets the integration time, as vl53l5cx_set_integration_time_ms().
+pub fn vl53l5cx_set_integration_time_ms<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, integration_time_ms: u32) -> Result<(), Error<B::Error>> {
+    p_dev.set_integration_time(integration_time_ms)
+}
+
+/// This function gets the sharpener, as vl53l5cx_get_sharpener_percent().
+pub fn vl53l5cx_get_sharpener_percent<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_sharpener_percent: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_sharpener_percent = p_dev.get_sharpener_percent()? as u8;
+    Ok(())
+}
+
+/// This function sets the sharpener, as vl53l5cx_set_sharpener_percent().
+pub fn vl53l5cx_set_sharpener_percent<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, sharpener_percent: u8) -> Result<(), Error<B::Error>> {
+    p_dev.set_sharpener_percent(sharpener_percent as u32)
+}
+
+/// This function gets the target order, as vl53l5cx_get_target_order().
+pub fn vl53l5cx_get_target_order<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_target_order: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_target_order = p_dev.target_order()?.into();
+    Ok(())
+}
+
+/// This function sets the target order, as vl53l5cx_set_target_order().
+pub fn vl53l5cx_set_target_order<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, target_order: u8) -> Result<(), Error<B::Error>> {
+    p_dev.set_target_order(target_order)
+}
+
+/// This function gets the ranging mode, as vl53l5cx_get_ranging_mode().
+pub fn vl53l5cx_get_ranging_mode<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_ranging_mode: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_ranging_mode = p_dev.ranging_mode()?.into();
+    Ok(())
+}
+
+/// This function sets the ranging mode, as vl53l5cx_set_ranging_mode().
+pub fn vl53l5cx_set_ranging_mode<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, ranging_mode: u8) -> Result<(), Error<B::Error>> {
+    p_dev.set_ranging_mode(ranging_mode)
+}
+
+/// This function enables the internal charge pump, as vl53l5cx_enable_internal_cp().
+pub fn vl53l5cx_enable_internal_cp<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>) -> Result<(), Error<B::Error>> {
+    p_dev.enable_internal_cp()
+}
+
+/// This function disables the internal charge pump, as vl53l5cx_disable_internal_cp().
+pub fn vl53l5cx_disable_internal_cp<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>) -> Result<(), Error<B::Error>> {
+    p_dev.disable_internal_cp()
+}
+
+/// This function gets the VHV repeat count, as vl53l5cx_get_VHV_repeat_count().
+pub fn vl53l5cx_get_VHV_repeat_count<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_repeat_count: &mut u32) -> Result<(), Error<B::Error>> {
+    *p_repeat_count = p_dev.get_vhv_repeat_count()?;
+    Ok(())
+}
+
+/// This function sets the VHV repeat count, as vl53l5cx_set_VHV_repeat_count().
+pub fn vl53l5cx_set_VHV_repeat_count<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, repeat_count: u32) -> Result<(), Error<B::Error>> {
+    p_dev.set_vhv_repeat_count(repeat_count)
+}
+
+/// This function gets the Xtalk calibration data, as vl53l5cx_get_caldata_xtalk().
+pub fn vl53l5cx_get_caldata_xtalk<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_xtalk_data: &mut [u8; VL53L5CX_XTALK_BUFFER_SIZE]) -> Result<(), Error<B::Error>> {
+    *p_xtalk_data = p_dev.get_caldata_xtalk()?;
+    Ok(())
+}
+
+/// This function sets the Xtalk calibration data, as vl53l5cx_set_caldata_xtalk().
+pub fn vl53l5cx_set_caldata_xtalk<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_xtalk_data: &[u8; VL53L5CX_XTALK_BUFFER_SIZE]) -> Result<(), Error<B::Error>> {
+    p_dev.set_caldata_xtalk(*p_xtalk_data)
+}
+
+/// This function gets the Xtalk margin, as vl53l5cx_get_xtalk_margin().
+pub fn vl53l5cx_get_xtalk_margin<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_xtalk_margin: &mut u32) -> Result<(), Error<B::Error>> {
+    *p_xtalk_margin = p_dev.get_xtalk_margin()?;
+    Ok(())
+}
+
+/// This function sets the Xtalk margin, as vl53l5cx_set_xtalk_margin().
+pub fn vl53l5cx_set_xtalk_margin<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, xtalk_margin: u32) -> Result<(), Error<B::Error>> {
+    p_dev.set_xtalk_margin(xtalk_margin)
+}
+
+/// This function calibrates the Xtalk, as vl53l5cx_calibrate_xtalk().
+pub fn vl53l5cx_calibrate_xtalk<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, reflectance_percent: u16, nb_samples: u8, distance_mm: u16) -> Result<(), Error<B::Error>> {
+    p_dev.calibrate_xtalk(reflectance_percent, nb_samples, distance_mm)
+}
+
+/// This function gets the detection thresholds state, as vl53l5cx_get_detection_thresholds_enable().
+pub fn vl53l5cx_get_detection_thresholds_enable<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_enabled: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_enabled = p_dev.get_detection_thresholds_enable()?;
+    Ok(())
+}
+
+/// This function enables or disables the detection thresholds, as vl53l5cx_set_detection_thresholds_enable().
+pub fn vl53l5cx_set_detection_thresholds_enable<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, enabled: u8) -> Result<(), Error<B::Error>> {
+    p_dev.set_detection_thresholds_enable(enabled)
+}
+
+/// This function gets the detection thresholds, as vl53l5cx_get_detection_thresholds().
+pub fn vl53l5cx_get_detection_thresholds<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_thresholds: &mut [DetectionThresholds; VL53L5CX_NB_THRESHOLDS]) -> Result<(), Error<B::Error>> {
+    *p_thresholds = p_dev.get_detection_thresholds()?;
+    Ok(())
+}
+
+/// This function sets the detection thresholds, as vl53l5cx_set_detection_thresholds().
+pub fn vl53l5cx_set_detection_thresholds<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_thresholds: &mut [DetectionThresholds; VL53L5CX_NB_THRESHOLDS]) -> Result<(), Error<B::Error>> {
+    p_dev.set_detection_thresholds(p_thresholds)
+}
+
+/// This function gets the detection thresholds auto stop, as vl53l5cx_get_detection_thresholds_auto_stop().
+pub fn vl53l5cx_get_detection_thresholds_auto_stop<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_auto_stop: &mut u8) -> Result<(), Error<B::Error>> {
+    *p_auto_stop = p_dev.get_detection_thresholds_auto_stop()? as u8;
+    Ok(())
+}
+
+/// This function sets the detection thresholds auto stop, as vl53l5cx_set_detection_thresholds_auto_stop().
+pub fn vl53l5cx_set_detection_thresholds_auto_stop<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, auto_stop: u8) -> Result<(), Error<B::Error>> {
+    p_dev.set_detection_thresholds_auto_stop(auto_stop != 0)
+}
+
+/// This function initializes the motion indicator, as vl53l5cx_motion_indicator_init().
+pub fn vl53l5cx_motion_indicator_init<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_motion_config: &mut MotionConfiguration, resolution: u8) -> Result<(), Error<B::Error>> {
+    *p_motion_config = MotionConfiguration::new();
+    p_dev.motion_indicator_set_resolution(p_motion_config, resolution)
+}
+
+/// This function sets the distances of the motion indicator, as vl53l5cx_motion_indicator_set_distance_motion().
+pub fn vl53l5cx_motion_indicator_set_distance_motion<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_motion_config: &mut MotionConfiguration, distance_min_mm: u16, distance_max_mm: u16) -> Result<(), Error<B::Error>> {
+    p_dev.motion_indicator_set_distance_motion(p_motion_config, distance_min_mm, distance_max_mm)
+}
+
+/// This function sets the resolution of the motion indicator, as vl53l5cx_motion_indicator_set_resolution().
+pub fn vl53l5cx_motion_indicator_set_resolution<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs>(p_dev: &mut Vl53l5cx<B, LPN, RST, T>, p_motion_config: &mut MotionConfiguration, resolution: u8) -> Result<(), Error<B::Error>> {
+    p_dev.motion_indicator_set_resolution(p_motion_config, resolution)
+}
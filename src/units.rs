@@ -0,0 +1,61 @@
+//! Conversions from the firmware fixed point formats to physical units. 
+//! They are the ones applied by the ST ULD driver when the raw format is not used: 
+//! - Ambient and signal rates are unsigned fixed point numbers with 11 fractional bits 
+//!   (format 21.11), in kcps/SPAD : rate = raw / 2048.
+//! - Distances are signed fixed point numbers with 2 fractional bits (format 14.2), 
+//!   in mm : distance = raw / 4, negative distances being clamped to 0.
+//! - Range sigma is an unsigned fixed point number with 7 fractional bits (format 9.7), 
+//!   in mm : sigma = raw / 128.
+//! - Reflectance is an unsigned fixed point number with 1 fractional bit (format 7.1), 
+//!   in percent : reflectance = raw / 2.
+//! - Motion indicator values are scaled by 65535 : motion = raw / 65535.
+//! 
+//! Integer conversions truncate the fractional part, as the ST ULD driver does.
+
+/// This function converts a raw rate (ambient or signal) in kcps/SPAD.
+pub const fn rate_kcps_per_spad(raw: u32) -> u32 {
+    raw / 2048
+}
+
+/// This function converts a raw rate (ambient or signal) in cps/SPAD, 
+/// keeping 3 decimal digits of the kcps/SPAD value.
+pub const fn rate_cps_per_spad(raw: u32) -> u32 {
+    ((raw as u64 * 1000) / 2048) as u32
+}
+
+/// This function converts a raw rate (ambient or signal) in kcps/SPAD.
+pub fn rate_kcps_per_spad_f32(raw: u32) -> f32 {
+    raw as f32 / 2048.0
+}
+
+/// This function converts a raw distance in mm. Negative distances are clamped to 0.
+pub const fn distance_mm(raw: i16) -> i16 {
+    let distance_mm: i16 = raw / 4;
+    if distance_mm < 0 { 0 } else { distance_mm }
+}
+
+/// This function converts a raw distance in mm. Negative distances are clamped to 0.
+pub fn distance_mm_f32(raw: i16) -> f32 {
+    let distance_mm: f32 = raw as f32 / 4.0;
+    if distance_mm < 0.0 { 0.0 } else { distance_mm }
+}
+
+/// This function converts a raw range sigma in mm.
+pub const fn range_sigma_mm(raw: u16) -> u16 {
+    raw / 128
+}
+
+/// This function converts a raw range sigma in mm.
+pub fn range_sigma_mm_f32(raw: u16) -> f32 {
+    raw as f32 / 128.0
+}
+
+/// This function converts a raw reflectance in percent.
+pub const fn reflectance_percent(raw: u8) -> u8 {
+    raw / 2
+}
+
+/// This function converts a raw motion indicator value.
+pub const fn motion_indicator(raw: u32) -> u32 {
+    raw / 65535
+}
@@ -0,0 +1,42 @@
+use crate::{Grid, GridSize};
+
+/// This function interpolates a N x N grid of zones into a M x M grid, e.g. the 8x8 
+/// distance map into 32x32 pixels for a small LCD. The interpolation is bilinear, 
+/// between the centers of the zones, with integer math only (8 fractional bits). 
+/// The pixels beyond the centers of the border zones take the value of the border.
+/// 
+/// # Arguments
+/// 
+/// * `grid` : Grid of zones, e.g. given by distance_grid().
+/// 
+/// # Return
+/// 
+/// * `pixels` : Interpolated grid, indexed by (row, col).
+pub fn upsample_grid<const N: usize, const M: usize>(grid: &[[i16; N]; N]) -> [[i16; M]; M]
+    where Grid<N>: GridSize
+{
+    let mut pixels: [[i16; M]; M] = [[0; M]; M];
+    let mut coords: [(usize, i32); M] = [(0, 0); M];
+    let max: i32 = (N as i32 - 1) * 256;
+
+    // Position of the pixel centers in the grid, with 8 fractional bits
+    for (o, coord) in coords.iter_mut().enumerate() {
+        let pos: i32 = (((2 * o as i32 + 1) * N as i32 - M as i32) * 256 / (2 * M as i32)).clamp(0, max);
+        let index: usize = ((pos >> 8) as usize).min(N.saturating_sub(2));
+        *coord = (index, pos - ((index as i32) << 8));
+    }
+
+    for (row, line) in pixels.iter_mut().enumerate() {
+        let (r, fr) = coords[row];
+        let r1: usize = (r + 1).min(N - 1);
+        for (col, pixel) in line.iter_mut().enumerate() {
+            let (c, fc) = coords[col];
+            let c1: usize = (c + 1).min(N - 1);
+            let top: i32 = grid[r][c] as i32 * (256 - fc) + grid[r][c1] as i32 * fc;
+            let bottom: i32 = grid[r1][c] as i32 * (256 - fc) + grid[r1][c1] as i32 * fc;
+            *pixel = ((top * (256 - fr) + bottom * fr + (1 << 15)) >> 16) as i16;
+        }
+    }
+
+    pixels
+}
@@ -1,53 +1,74 @@
-pub(crate) fn from_u8_to_i16(src: &[u8], dst: &mut[i16]) {
-    for (i, chunk) in src.chunks(2).enumerate() {
-        dst[i] = ((chunk[0] as u16) | (chunk[1] as u16) << 8) as i16;
+//! Conversion functions between the little endian byte buffers exchanged with 
+//! the sensor and the host values. They can be used by host tools parsing saved frames.
+
+/// This function converts little endian bytes into i16 values. 
+/// `dst` must contain at least `src.len() / 2` values, trailing bytes are ignored.
+pub fn from_u8_to_i16(src: &[u8], dst: &mut[i16]) {
+    for (i, chunk) in src.chunks_exact(2).enumerate() {
+        dst[i] = i16::from_le_bytes([chunk[0], chunk[1]]);
     }
 }
 
-pub(crate) fn from_u8_to_i32(src: &[u8], dst: &mut[i32]) {
-    for (i, chunk) in src.chunks(4).enumerate() {
-        dst[i] = ((chunk[0] as u32) | (chunk[1] as u32) << 8 | (chunk[2] as u32) << 16 | (chunk[3] as u32) << 24) as i32;
+/// This function converts little endian bytes into i32 values. 
+/// `dst` must contain at least `src.len() / 4` values, trailing bytes are ignored.
+pub fn from_u8_to_i32(src: &[u8], dst: &mut[i32]) {
+    for (i, chunk) in src.chunks_exact(4).enumerate() {
+        dst[i] = i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
     }
 }
 
-pub(crate) fn from_u8_to_u16(src: &[u8], dst: &mut[u16]) {
-    for (i, chunk) in src.chunks(2).enumerate() {
-        dst[i] = (chunk[0] as u16) | (chunk[1] as u16) << 8;
+/// This function converts little endian bytes into u16 values. 
+/// `dst` must contain at least `src.len() / 2` values, trailing bytes are ignored.
+pub fn from_u8_to_u16(src: &[u8], dst: &mut[u16]) {
+    for (i, chunk) in src.chunks_exact(2).enumerate() {
+        dst[i] = u16::from_le_bytes([chunk[0], chunk[1]]);
     }
 }
 
-pub(crate) fn from_u8_to_u32(src: &[u8], dst: &mut[u32]) {
-    for (i, chunk) in src.chunks(4).enumerate() {
-        dst[i] = (chunk[0] as u32) | (chunk[1] as u32) << 8 | (chunk[2] as u32) << 16 | (chunk[3] as u32) << 24;
+/// This function converts little endian bytes into u32 values. 
+/// `dst` must contain at least `src.len() / 4` values, trailing bytes are ignored.
+pub fn from_u8_to_u32(src: &[u8], dst: &mut[u32]) {
+    for (i, chunk) in src.chunks_exact(4).enumerate() {
+        dst[i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
     }
 }
 
-pub(crate) fn from_i16_to_u8(src: &[i16], dst: &mut[u8]) {
+/// This function converts i16 values into little endian bytes. 
+/// `dst` must contain at least `2 * src.len()` bytes.
+pub fn from_i16_to_u8(src: &[i16], dst: &mut[u8]) {
     for (i, &num) in src.iter().enumerate() {
         dst[i*2..(i+1)*2].copy_from_slice(&num.to_le_bytes()); 
     }
 }
 
-pub(crate) fn from_i32_to_u8(src: &[i32], dst: &mut[u8]) {
+/// This function converts i32 values into little endian bytes. 
+/// `dst` must contain at least `4 * src.len()` bytes.
+pub fn from_i32_to_u8(src: &[i32], dst: &mut[u8]) {
     for (i, &num) in src.iter().enumerate() {
         dst[i*4..(i+1)*4].copy_from_slice(&num.to_le_bytes()); 
     }
 }
 
-#[allow(dead_code)]
-pub(crate) fn from_u16_to_u8(src: &[u16], dst: &mut[u8]) {
+/// This function converts u16 values into little endian bytes. 
+/// `dst` must contain at least `2 * src.len()` bytes.
+pub fn from_u16_to_u8(src: &[u16], dst: &mut[u8]) {
     for (i, &num) in src.iter().enumerate() {
         dst[i*2..(i+1)*2].copy_from_slice(&num.to_le_bytes()); 
     }
 }
 
-pub(crate) fn from_u32_to_u8(src: &[u32], dst: &mut[u8]) {
+/// This function converts u32 values into little endian bytes. 
+/// `dst` must contain at least `4 * src.len()` bytes.
+pub fn from_u32_to_u8(src: &[u32], dst: &mut[u8]) {
     for (i, &num) in src.iter().enumerate() {
         dst[i*4..(i+1)*4].copy_from_slice(&num.to_le_bytes()); 
     }
 }
 
-pub(crate) fn swap_buffer(buffer: &mut [u8], size: usize) {
+/// This function reverses the byte order of each 32 bits word in the first `size` bytes 
+/// of `buffer`. It converts the frames read from the sensor (big endian words) to the 
+/// host format, and conversely. Trailing bytes which do not fill a word are left unchanged.
+pub fn swap_buffer(buffer: &mut [u8], size: usize) {
     for chunk in buffer[..size].chunks_exact_mut(4) {
         let tmp: u32 = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
         chunk.copy_from_slice(&tmp.to_le_bytes());
@@ -0,0 +1,203 @@
+use consts::*;
+
+use crate::{consts, ResultsData, Resolution};
+
+/// Enum ArrayRotation contains the rotations of the zones of a VirtualArray,
+/// e.g. to read a vertical strip as rows when the sensor is mounted sideways.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ArrayRotation {
+    /// The zones keep the order of the sensor grid.
+    #[default]
+    None,
+    /// The sub-grid is turned by 90° clockwise.
+    Clockwise90,
+    /// The sub-grid is turned by 180°.
+    Rotate180,
+    /// The sub-grid is turned by 90° counterclockwise.
+    CounterClockwise90
+}
+
+/// Structure VirtualArray selects a contiguous sub-grid of the zones, e.g. the two
+/// top rows of a 8x8 frame for a line scanner, while the sensor keeps ranging the
+/// whole grid. crop() packs the zones of the sub-grid at the start of the results,
+/// in row-major order of the (rotated) sub-grid, so that the processing and the
+/// transmission only handle nb_zones() zones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VirtualArray {
+    width: u8,
+    first_row: u8,
+    first_col: u8,
+    nb_rows: u8,
+    nb_cols: u8,
+    rotation: ArrayRotation
+}
+
+impl VirtualArray {
+    /// This function creates a virtual array on the sensor grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolution` : Resolution of the frames.
+    /// * `first_row` : First row of the sub-grid.
+    /// * `first_col` : First column of the sub-grid.
+    /// * `nb_rows` : Number of rows of the sub-grid, at least 1.
+    /// * `nb_cols` : Number of columns of the sub-grid, at least 1.
+    ///
+    /// # Return
+    ///
+    /// * `array` : Virtual array, or None if the sub-grid is empty or exceeds the grid.
+    pub fn new(resolution: Resolution, first_row: u8, first_col: u8, nb_rows: u8, nb_cols: u8) -> Option<Self> {
+        let width: u8 = match resolution {
+            Resolution::Res4x4 => 4,
+            Resolution::Res8x8 => 8
+        };
+        if nb_rows == 0 || nb_cols == 0 || first_row as u16 + nb_rows as u16 > width as u16 || first_col as u16 + nb_cols as u16 > width as u16 {
+            return None;
+        }
+
+        Some(VirtualArray { width, first_row, first_col, nb_rows, nb_cols, rotation: ArrayRotation::None })
+    }
+
+    /// This function sets the rotation of the zones of the sub-grid.
+    pub fn with_rotation(mut self, rotation: ArrayRotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// This function gets the number of rows of the virtual array, after the rotation.
+    pub fn rows(&self) -> usize {
+        match self.rotation {
+            ArrayRotation::None | ArrayRotation::Rotate180 => self.nb_rows as usize,
+            ArrayRotation::Clockwise90 | ArrayRotation::CounterClockwise90 => self.nb_cols as usize
+        }
+    }
+
+    /// This function gets the number of columns of the virtual array, after the rotation.
+    pub fn cols(&self) -> usize {
+        match self.rotation {
+            ArrayRotation::None | ArrayRotation::Rotate180 => self.nb_cols as usize,
+            ArrayRotation::Clockwise90 | ArrayRotation::CounterClockwise90 => self.nb_rows as usize
+        }
+    }
+
+    /// This function gets the number of zones of the virtual array.
+    pub fn nb_zones(&self) -> usize {
+        self.nb_rows as usize * self.nb_cols as usize
+    }
+
+    /// This function gets the zone of the sensor grid of a zone of the virtual array.
+    ///
+    /// # Arguments
+    ///
+    /// * `zone` : Zone of the virtual array, row * cols() + col.
+    ///
+    /// # Return
+    ///
+    /// * `sensor_zone` : Zone of the frame, None if `zone` is not in the virtual array.
+    pub fn sensor_zone(&self, zone: usize) -> Option<usize> {
+        if zone >= self.nb_zones() {
+            return None;
+        }
+        let (row, col) = (zone / self.cols(), zone % self.cols());
+        let (nb_rows, nb_cols) = (self.nb_rows as usize, self.nb_cols as usize);
+        let (sub_row, sub_col) = match self.rotation {
+            ArrayRotation::None => (row, col),
+            ArrayRotation::Clockwise90 => (nb_rows - 1 - col, row),
+            ArrayRotation::Rotate180 => (nb_rows - 1 - row, nb_cols - 1 - col),
+            ArrayRotation::CounterClockwise90 => (col, nb_cols - 1 - row)
+        };
+
+        Some((self.first_row as usize + sub_row) * self.width as usize + self.first_col as usize + sub_col)
+    }
+
+    /// This function keeps the zones of the virtual array. The first nb_zones() zones
+    /// of the returned results are the zones of the virtual array, the other zones
+    /// have no target (status 255). The temperature, the motion indicator and the
+    /// frame information are copied.
+    ///
+    /// # Arguments
+    ///
+    /// * `results` : Results of a frame at the resolution of the virtual array.
+    ///
+    /// # Return
+    ///
+    /// * `cropped` : Results of the virtual array.
+    #[cfg_attr(all(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD", feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM", feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT", feature = "VL53L5CX_DISABLE_TARGET_STATUS"), allow(unused_variables))]
+    pub fn crop(&self, results: &ResultsData) -> ResultsData {
+        let nb_targets: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+        let mut cropped: ResultsData = ResultsData::new();
+        cropped.silicon_temp_degc = results.silicon_temp_degc;
+        cropped.frame_info = results.frame_info;
+        #[cfg(not(feature="VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+        {
+            cropped.motion_indicator = results.motion_indicator;
+        }
+        #[cfg(not(feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
+        {
+            cropped.target_status = [255; (VL53L5CX_RESOLUTION_8X8 as usize) * (VL53L5CX_NB_TARGET_PER_ZONE as usize)];
+        }
+
+        for zone in 0..self.nb_zones() {
+            let Some(src) = self.sensor_zone(zone) else {
+                continue;
+            };
+            #[cfg(not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+            {
+                cropped.ambient_per_spad[zone] = results.ambient_per_spad[src];
+            }
+            #[cfg(not(feature="VL53L5CX_DISABLE_NB_TARGET_DETECTED"))]
+            {
+                cropped.nb_target_detected[zone] = results.nb_target_detected[src];
+            }
+            #[cfg(not(feature="VL53L5CX_DISABLE_NB_SPADS_ENABLED"))]
+            {
+                cropped.nb_spads_enabled[zone] = results.nb_spads_enabled[src];
+            }
+            #[cfg(all(feature = "float-results", not(feature="VL53L5CX_DISABLE_AMBIENT_PER_SPAD")))]
+            {
+                cropped.float_results.ambient_per_spad[zone] = results.float_results.ambient_per_spad[src];
+            }
+
+            for target in 0..nb_targets {
+                let (dst, src): (usize, usize) = (zone * nb_targets + target, src * nb_targets + target);
+                #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+                {
+                    cropped.signal_per_spad[dst] = results.signal_per_spad[src];
+                }
+                #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+                {
+                    cropped.range_sigma_mm[dst] = results.range_sigma_mm[src];
+                }
+                #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+                {
+                    cropped.distance_mm[dst] = results.distance_mm[src];
+                }
+                #[cfg(not(feature="VL53L5CX_DISABLE_REFLECTANCE_PERCENT"))]
+                {
+                    cropped.reflectance[dst] = results.reflectance[src];
+                }
+                #[cfg(not(feature="VL53L5CX_DISABLE_TARGET_STATUS"))]
+                {
+                    cropped.target_status[dst] = results.target_status[src];
+                }
+                #[cfg(feature = "float-results")]
+                {
+                    #[cfg(not(feature="VL53L5CX_DISABLE_SIGNAL_PER_SPAD"))]
+                    {
+                        cropped.float_results.signal_per_spad[dst] = results.float_results.signal_per_spad[src];
+                    }
+                    #[cfg(not(feature="VL53L5CX_DISABLE_RANGE_SIGMA_MM"))]
+                    {
+                        cropped.float_results.range_sigma_mm[dst] = results.float_results.range_sigma_mm[src];
+                    }
+                    #[cfg(not(feature="VL53L5CX_DISABLE_DISTANCE_MM"))]
+                    {
+                        cropped.float_results.distance_mm[dst] = results.float_results.distance_mm[src];
+                    }
+                }
+            }
+        }
+
+        cropped
+    }
+}
@@ -0,0 +1,95 @@
+//! Adapter with the method names of the VL53L1X drivers, so that a board upgraded 
+//! from a VL53L1X to a VL53L5CX keeps its application code. The adapter ranges 
+//! continuously and reports the center of the field of view, as get_distance_mm() does.
+//! 
+//! | VL53L1X driver        | Vl53l1xCompat         | VL53L5CX driver                        |
+//! |-----------------------|-----------------------|----------------------------------------|
+//! | `start_ranging()`     | `start_ranging()`     | `start_ranging()`                      |
+//! | `stop_ranging()`      | `stop_ranging()`      | `stop_ranging()`                       |
+//! | `check_for_data_ready()` | `check_for_data_ready()` | `check_data_ready()`              |
+//! | `get_distance()`      | `get_distance()`      | `get_ranging_data()`, center zones     |
+//! | `clear_interrupt()`   | `clear_interrupt()`   | not needed, cleared by the frame read  |
+//! | `set_timing_budget_ms()` | `set_timing_budget_ms()` | `set_integration_time()`          |
+//! | `set_inter_measurement_period_ms()` | `set_inter_measurement_period_ms()` | `set_frequency_hz()` |
+
+use crate::rangefinder::center_distance_mm;
+use crate::{BusOperation, Error, RangingMode, Resolution, ResultsData, Vl53l5cx, OutputPin, DelayNs};
+
+/// Structure Vl53l1xCompat wraps the driver with the method names of the VL53L1X drivers.
+pub struct Vl53l1xCompat<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> {
+    sensor: &'a mut Vl53l5cx<B, LPN, RST, T>,
+    resolution: Resolution,
+    distance_mm: u16
+}
+
+impl<'a, B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l1xCompat<'a, B, LPN, RST, T> {
+    /// This function creates the adapter. The sensor must be initialized and not ranging.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sensor` : Initialized driver.
+    pub fn new(sensor: &'a mut Vl53l5cx<B, LPN, RST, T>) -> Result<Self, Error<B::Error>> {
+        let resolution: Resolution = sensor.resolution()?;
+
+        Ok(Vl53l1xCompat { sensor, resolution, distance_mm: 0 })
+    }
+
+    /// This function starts the ranging.
+    pub fn start_ranging(&mut self) -> Result<(), Error<B::Error>> {
+        self.sensor.start_ranging()
+    }
+
+    /// This function stops the ranging.
+    pub fn stop_ranging(&mut self) -> Result<(), Error<B::Error>> {
+        self.sensor.stop_ranging()
+    }
+
+    /// This function checks if a new distance is ready, and reads it if so.
+    pub fn check_for_data_ready(&mut self) -> Result<bool, Error<B::Error>> {
+        if !self.sensor.check_data_ready()? {
+            return Ok(false);
+        }
+        let results: ResultsData = self.sensor.get_ranging_data()?;
+        // As the VL53L1X, the last distance is kept when no target is found
+        if let Some(distance_mm) = center_distance_mm(&results, self.resolution) {
+            self.distance_mm = distance_mm;
+        }
+
+        Ok(true)
+    }
+
+    /// This function gets the last distance in mm, read by check_for_data_ready().
+    pub fn get_distance(&mut self) -> Result<u16, Error<B::Error>> {
+        Ok(self.distance_mm)
+    }
+
+    /// This function does nothing, as the interrupt is cleared by the frame read.
+    pub fn clear_interrupt(&mut self) -> Result<(), Error<B::Error>> {
+        Ok(())
+    }
+
+    /// This function sets the timing budget, i.e. the integration time. 
+    /// The autonomous ranging mode is selected, as the integration time is only used in this mode.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `timing_budget_ms` : Integration time in ms, from 2 to 1000.
+    pub fn set_timing_budget_ms(&mut self, timing_budget_ms: u16) -> Result<(), Error<B::Error>> {
+        self.sensor.set_ranging_mode(RangingMode::Autonomous)?;
+        self.sensor.set_integration_time(timing_budget_ms as u32)
+    }
+
+    /// This function sets the period between two measurements, i.e. the ranging frequency.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `period_ms` : Period in ms, rounded to the closest supported frequency.
+    pub fn set_inter_measurement_period_ms(&mut self, period_ms: u16) -> Result<(), Error<B::Error>> {
+        if period_ms == 0 {
+            return Err(Error::InvalidParam);
+        }
+        let frequency_hz: u32 = ((1000 + period_ms as u32 / 2) / period_ms as u32).max(1);
+
+        self.sensor.set_frequency_hz(frequency_hz.min(u8::MAX as u32) as u8)
+    }
+}
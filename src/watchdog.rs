@@ -0,0 +1,114 @@
+use crate::{is_frame_complete, is_go2_error, BusBurst, BusOperation, DelayNs, DriverState, Error, OutputPin, Vl53l5cx};
+
+/// Number of frame periods without a new frame after which check_stalled() reports a stall.
+pub const VL53L5CX_STALL_MISSED_FRAMES: u32 = 3;
+
+/// Enum StallRecovery contains the recoveries suggested by check_stalled(),
+/// performed by recover_from_stall().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StallRecovery {
+    /// The firmware answers but stopped streaming: the ranging session is restarted.
+    Restart,
+    /// The firmware reported a GO2 error: the sensor is powered off and initialized again.
+    Reinit
+}
+
+/// Structure Stall describes a stall reported by check_stalled().
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Stall {
+  // Time of the last new frame, in ms (clock of check_stalled())
+    pub last_frame_ms: u32,
+  // Time since the last new frame, in ms
+    pub elapsed_ms: u32,
+  // Stream count frozen since the last new frame
+    pub streamcount: u8,
+  // Suggested recovery
+    pub recovery: StallRecovery
+}
+
+/// Structure FrameWatchdog contains the state of the frame cadence watchdog
+/// (see expect_frame_every()).
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct FrameWatchdog {
+    pub(crate) period_ms: u32,
+  // Stream count and time of the last new frame, None until the first check of a session
+    pub(crate) last_frame: Option<(u8, u32)>
+}
+
+impl FrameWatchdog {
+    pub(crate) fn new(period_ms: u32) -> Self {
+        FrameWatchdog { period_ms, last_frame: None }
+    }
+}
+
+impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
+    /// This function checks the frame cadence set with expect_frame_every(): the sensor
+    /// is stalled when the stream count did not change for VL53L5CX_STALL_MISSED_FRAMES
+    /// frame periods. It only reads the UI status bytes, so it does not consume a pending
+    /// frame, and must be called at least once per frame period, e.g. from a timer task.
+    /// The first check of a ranging session starts the watchdog, and None is returned
+    /// while the sensor is not ranging or the watchdog is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `now_ms` : Current time in ms, from any clock of the application (wrapping around is handled).
+    ///
+    /// # Return
+    ///
+    /// * `stall` : Stall and suggested recovery (see recover_from_stall()), or None if the frames are coming.
+    pub fn check_stalled(&mut self, now_ms: u32) -> Result<Option<Stall>, Error<B::Error>> {
+        let Some(watchdog) = self.frame_watchdog else {
+            return Ok(None);
+        };
+        if !self.is_ranging {
+            return Ok(None);
+        }
+        let mut ui_status: [u8; 4] = [0; 4];
+        self.with_bus_burst(BusBurst::DataReadyCheck, |s| s.read_register(0, &mut ui_status))?;
+
+        let (streamcount, last_frame_ms) = match watchdog.last_frame {
+            Some((streamcount, last_frame_ms)) if streamcount == ui_status[0] || !is_frame_complete(&ui_status) => (streamcount, last_frame_ms),
+            _ => {
+                self.frame_watchdog = Some(FrameWatchdog { last_frame: Some((ui_status[0], now_ms)), ..watchdog });
+                return Ok(None);
+            }
+        };
+        let elapsed_ms: u32 = now_ms.wrapping_sub(last_frame_ms);
+        if elapsed_ms <= watchdog.period_ms.saturating_mul(VL53L5CX_STALL_MISSED_FRAMES) {
+            return Ok(None);
+        }
+        let recovery: StallRecovery = if is_go2_error(&ui_status) { StallRecovery::Reinit } else { StallRecovery::Restart };
+
+        Ok(Some(Stall { last_frame_ms, elapsed_ms, streamcount, recovery }))
+    }
+
+    /// This function recovers from a stall reported by check_stalled().
+    /// With StallRecovery::Restart, the ranging session is stopped (errors are ignored)
+    /// and started again. With StallRecovery::Reinit, the sensor is powered off and on,
+    /// and initialized with init(): the settings of the sensor (resolution, frequency...)
+    /// are back to their defaults, and must be set again before start_ranging(). 
+    /// Error::ConfigLocked is returned before the power cycle if the configuration is 
+    /// locked (see lock_configuration()).
+    ///
+    /// # Arguments
+    ///
+    /// * `recovery` : Recovery to perform, usually the one suggested by check_stalled().
+    pub fn recover_from_stall(&mut self, recovery: StallRecovery) -> Result<(), Error<B::Error>> {
+        match recovery {
+            StallRecovery::Restart => {
+                let _ = self.stop_ranging();
+                self.is_ranging = false;
+                self.start_ranging()
+            }
+            StallRecovery::Reinit => {
+                if self.config_locked {
+                    return Err(Error::ConfigLocked);
+                }
+                self.indicate(DriverState::Error);
+                self.off()?;
+                self.on()?;
+                self.init()
+            }
+        }
+    }
+}
@@ -3,7 +3,79 @@ use consts::*;
 use buffers::*;
 use utils::*;
 
-use crate::{buffers, consts, utils, BlockHeader, BusOperation, Error, Vl53l5cx, OutputPin, DelayNs};
+use units::*;
+
+use crate::{buffers, consts, units, utils, BlockHeader, BusOperation, Error, Vl53l5cx, OutputPin, DelayNs};
+
+/// Enum CalibrationError contains the invalid arguments of the calibrations 
+/// (Xtalk calibration, offset check, orientation), given by Error::Calibration.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CalibrationError {
+    /// The target reflectance is outside of 1% to 99%.
+    InvalidReflectance,
+    /// The target distance is outside of the allowed range.
+    InvalidDistance,
+    /// The number of samples is outside of 1 to 16.
+    InvalidNbSamples,
+    /// The Xtalk margin is higher than 10000 kcps/spads.
+    InvalidMargin,
+    /// Too few zones have a valid target on the reference wall of the orientation calibration.
+    NotEnoughZones
+}
+
+impl<E> From<CalibrationError> for Error<E> {
+    fn from(err: CalibrationError) -> Self {
+        Error::Calibration(err)
+    }
+}
+
+/// Offset of the per zone xtalk signal grid in the Xtalk buffer.
+const XTALK_GRID_OFFSET: usize = 0x34;
+
+/// Structure XtalkGrid contains the per zone crosstalk of an Xtalk buffer 
+/// (see get_caldata_xtalk()), e.g. to visualize the coverglass crosstalk. 
+/// The grid is always 8x8, zones being ordered as in ResultsData. Values are 
+/// in the firmware rate format (kcps/spad with 11 fractional bits).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct XtalkGrid {
+  // Crosstalk of each zone, in firmware format
+    pub signal: [u32; VL53L5CX_RESOLUTION_8X8 as usize]
+}
+
+impl XtalkGrid {
+    /// This function decodes the xtalk grid of an Xtalk buffer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `xtalk_data` : Xtalk buffer, as returned by get_caldata_xtalk().
+    pub fn from_caldata(xtalk_data: &[u8; VL53L5CX_XTALK_BUFFER_SIZE]) -> Self {
+        let mut grid: XtalkGrid = XtalkGrid { signal: [0; VL53L5CX_RESOLUTION_8X8 as usize] };
+        let src: &[u8] = &xtalk_data[XTALK_GRID_OFFSET..XTALK_GRID_OFFSET + 4 * VL53L5CX_RESOLUTION_8X8 as usize];
+        for (value, chunk) in grid.signal.iter_mut().zip(src.chunks_exact(4)) {
+            *value = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        grid
+    }
+
+    /// This function encodes the xtalk grid into an Xtalk buffer. 
+    /// The other fields of the buffer are left unchanged.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `xtalk_data` : Xtalk buffer, then given to set_caldata_xtalk().
+    pub fn write_caldata(&self, xtalk_data: &mut [u8; VL53L5CX_XTALK_BUFFER_SIZE]) {
+        let dst: &mut [u8] = &mut xtalk_data[XTALK_GRID_OFFSET..XTALK_GRID_OFFSET + 4 * VL53L5CX_RESOLUTION_8X8 as usize];
+        for (&value, chunk) in self.signal.iter().zip(dst.chunks_exact_mut(4)) {
+            chunk.copy_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    /// This function gets the crosstalk of a zone in kcps/spad.
+    pub fn kcps_per_spad(&self, zone: usize) -> u32 {
+        rate_kcps_per_spad(self.signal[zone])
+    }
+}
 
 impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LPN, RST, T> {
     fn poll_for_answer_xtalk(&mut self, address: u16, expected_val: u8) -> Result<(), Error<B::Error>> {
@@ -16,16 +88,17 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
             self.delay(10); 
 
             if self.temp_buffer[2] >= 0x7f {
-                return Err(Error::Mcu);
+                return Err(self.stats.record(Error::Mcu));
             } 
+            self.stats.retries = self.stats.retries.wrapping_add(1);
             timeout += 1; 
         }
-        Err(Error::Timeout)
+        Err(self.stats.record(Error::Timeout))
     }
 
     fn program_output_config(&mut self) -> Result<(), Error<B::Error>> {
         let mut header_config: [u32; 2] = [0, 0];
-        let resolution = self.get_resolution()?;
+        let resolution: u8 = self.resolution()?.into();
         let mut bh: BlockHeader;
         self.data_read_size = 0;
         // Enable mandatory output (meta and common data) 
@@ -112,8 +185,11 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// * `xtalk_margin` : New Xtalk margin in kcps/spads. Min value is 0 kcps/spads, and max is 10.000 kcps/spads
     pub fn set_xtalk_margin(&mut self, xtalk_margin: u32) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         if xtalk_margin > 10000 {
-            return Err(Error::InvalidParam);
+            return Err(Error::Calibration(CalibrationError::InvalidMargin));
         }
         let mut margin_kcps: [u8; 4] = [0; 4];
         from_u32_to_u8(&[xtalk_margin*2048], &mut margin_kcps);
@@ -131,6 +207,9 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// * `nb_samples` : Nb of samples used for calibration. A higher number of samples means a higher accuracy, but it increases the calibration time. Minimum is 1 and maximum is 16.
     /// * `distance_mm` : Target distance in mm. The minimum allowed distance is 600mm, and maximum is 3000mm. The target must stay in Full FOV, so short distance are easier for calibration.
     pub fn calibrate_xtalk(&mut self, reflectance_percent: u16, nb_samples: u8, distance_mm: u16) -> Result<(), Error<B::Error>> {
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
         let mut timeout: u16 = 0;
         let cmd: [u8; 4] = [0x00, 0x03, 0x00, 0x00];
         let footer: [u8; 8] = [0x00, 0x00, 0x00, 0x0F, 0x00, 0x01, 0x03, 0x04];
@@ -139,19 +218,23 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         let samples: [u8;1] = [nb_samples];
         
         // Get initial configuration 
-        let resolution = self.get_resolution()?;
+        let resolution: u8 = self.resolution()?.into();
         let frequency = self.get_frequency_hz()?;
         let sharpener_percent = self.get_sharpener_percent()?;
         let integration_time_ms = self.get_integration_time()?;
-        let target_order = self.get_target_order()?;
+        let target_order = self.target_order()?;
         let xtalk_margin = self.get_xtalk_margin()?;
-        let ranging_mode = self.get_ranging_mode()?;
+        let ranging_mode = self.ranging_mode()?;
 
         // Check input arguments validity 
-        if reflectance_percent < 1 || reflectance_percent > 99
-            || distance_mm < 600 || distance_mm > 3000
-            || nb_samples < 1 || nb_samples > 16 {
-            return Err(Error::InvalidParam);
+        if !(1..=99).contains(&reflectance_percent) {
+            return Err(Error::Calibration(CalibrationError::InvalidReflectance));
+        }
+        if !(600..=3000).contains(&distance_mm) {
+            return Err(Error::Calibration(CalibrationError::InvalidDistance));
+        }
+        if !(1..=16).contains(&nb_samples) {
+            return Err(Error::Calibration(CalibrationError::InvalidNbSamples));
         }
         self.set_resolution(VL53L5CX_RESOLUTION_8X8)?;
 
@@ -182,7 +265,7 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
             if self.temp_buffer[0] != VL53L5CX_STATUS_ERROR {
                 // Coverglass too good for Xtalk calibration 
                 if self.temp_buffer[2] >= 0x7f && self.temp_buffer[3] & 0x80 >> 7 == 1 {
-                    self.xtalk_data.copy_from_slice(&VL53L5CX_DEFAULT_XTALK);
+                    self.xtalk_data.copy_from_slice(self.sensor_variant.default_xtalk());
                 }
                 break;
             } else {
@@ -200,7 +283,7 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
         self.xtalk_data[VL53L5CX_XTALK_BUFFER_SIZE-8..].copy_from_slice(&footer);
 
         // Reset default buffer 
-        self.write_multi_to_register(0x2c34, &VL53L5CX_DEFAULT_CONFIGURATION)?;
+        self.write_multi_to_register(0x2c34, self.sensor_variant.default_configuration())?;
         self.poll_for_answer_xtalk(VL53L5CX_UI_CMD_STATUS, 3)?;
 
         // Reset initial configuration 
@@ -223,7 +306,7 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     pub fn get_caldata_xtalk(&mut self) -> Result<[u8; VL53L5CX_XTALK_BUFFER_SIZE], Error<B::Error>> {
         let footer: [u8; 8] = [0x00, 0x00, 0x00, 0x0F, 0x00, 0x01, 0x03, 0x04];
         let mut xtalk_data: [u8; VL53L5CX_XTALK_BUFFER_SIZE] = [0; VL53L5CX_XTALK_BUFFER_SIZE];
-        let resolution = self.get_resolution()?;
+        let resolution: u8 = self.resolution()?.into();
         self.set_resolution(VL53L5CX_RESOLUTION_8X8)?;
 
         self.temp_buffer[..72].copy_from_slice(&VL53L5CX_GET_XTALK_CMD);
@@ -245,10 +328,24 @@ impl<B: BusOperation, LPN: OutputPin, RST: OutputPin, T: DelayNs> Vl53l5cx<B, LP
     /// 
     /// `xtalk_data` : Buffer with a size defined by macro VL53L5CX_XTALK_SIZE.
     pub fn set_caldata_xtalk(&mut self, xtalk_data: [u8; VL53L5CX_XTALK_BUFFER_SIZE]) -> Result<(), Error<B::Error>> {
-        let resolution = self.get_resolution()?;
+        if self.config_locked {
+            return Err(Error::ConfigLocked);
+        }
+        let resolution: u8 = self.resolution()?.into();
         self.xtalk_data.copy_from_slice(&xtalk_data);
         self.set_resolution(resolution)?;
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// This function reads the Xtalk buffer, and decodes its per zone crosstalk.
+    /// 
+    /// # Returns
+    /// 
+    /// `xtalk_grid` : Crosstalk of each zone.
+    pub fn get_xtalk_grid(&mut self) -> Result<XtalkGrid, Error<B::Error>> {
+        let xtalk_data: [u8; VL53L5CX_XTALK_BUFFER_SIZE] = self.get_caldata_xtalk()?;
+
+        Ok(XtalkGrid::from_caldata(&xtalk_data))
+    }
+}
@@ -0,0 +1,199 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use core::cell::RefCell;
+use core::future::{poll_fn, Future};
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+use embedded_hal::i2c::{ErrorType, I2c as _, Operation};
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::asynch::{collect_all, Vl53l5cxAsync};
+use vl53l5cx::config::Resolution;
+use vl53l5cx::{Error, Vl53l5cx};
+
+/// Runs a future to completion, the simulated bus being always ready.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+/// Async bus on the simulated sensor.
+struct AsyncSim<'a>(&'a mut SimSensor);
+
+impl ErrorType for AsyncSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for AsyncSim<'_> {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.0.transaction(address, operations)
+    }
+}
+
+/// Async bus shared between the driver and the test.
+struct SharedAsyncSim<'a>(&'a RefCell<SimSensor>);
+
+impl ErrorType for SharedAsyncSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for SharedAsyncSim<'_> {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+struct AsyncNoDelay;
+
+impl DelayNs for AsyncNoDelay {
+    async fn delay_ns(&mut self, _ns: u32) {}
+}
+
+#[test]
+fn frames_are_read_by_the_async_driver() {
+    let mut sim = SimSensor::new(2, 1);
+    let mut sensor = Vl53l5cxAsync::new(AsyncSim(&mut sim), AsyncNoDelay);
+
+    block_on(async {
+        sensor.start_ranging().await.unwrap();
+        assert!(matches!(sensor.get_ranging_data().await, Err(Error::NotReady)));
+        for n in 1..=3 {
+            sensor.wait_for_data(1000).await.unwrap();
+            let results = sensor.get_ranging_data().await.unwrap();
+            assert_eq!(SimSensor::frame_number(results.distance_mm[0]), n);
+        }
+        sensor.stop_ranging().await.unwrap();
+        assert!(matches!(sensor.stop_ranging().await, Err(Error::InvalidState)));
+    });
+}
+
+#[test]
+fn async_results_match_the_blocking_driver() {
+    let mut sim = SimSensor::new(1, 1);
+    let expected = {
+        let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+        sensor.start_ranging().unwrap();
+        while !sensor.check_data_ready().unwrap() {}
+        sensor.get_ranging_data().unwrap()
+    };
+
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cxAsync::new(AsyncSim(&mut sim), AsyncNoDelay);
+    let results = block_on(async {
+        sensor.start_ranging().await.unwrap();
+        while !sensor.check_data_ready().await.unwrap() {}
+        sensor.get_ranging_data().await.unwrap()
+    });
+
+    assert_eq!(results.distance_mm, expected.distance_mm);
+    assert_eq!(results.target_status, expected.target_status);
+    assert_eq!(results.ambient_per_spad, expected.ambient_per_spad);
+    assert_eq!(results.frame_info.present_outputs, expected.frame_info.present_outputs);
+}
+
+#[test]
+fn resolution_is_set_through_the_dci() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cxAsync::new(AsyncSim(&mut sim), AsyncNoDelay);
+
+    block_on(async {
+        assert_eq!(sensor.resolution().await.unwrap(), Resolution::Res4x4);
+        sensor.set_resolution(Resolution::Res8x8).await.unwrap();
+        assert_eq!(sensor.resolution().await.unwrap(), Resolution::Res8x8);
+        sensor.set_frequency_hz(10).await.unwrap();
+        assert_eq!(sensor.get_frequency_hz().await.unwrap(), 10);
+    });
+}
+
+#[test]
+fn init_times_out_when_the_sensor_does_not_boot() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cxAsync::new(AsyncSim(&mut sim), AsyncNoDelay);
+
+    assert!(matches!(block_on(sensor.init()), Err(Error::Timeout)));
+}
+
+#[test]
+fn async_frame_is_not_read_when_not_ready() {
+    let sim = RefCell::new(SimSensor::new(3, 1));
+    let mut sensor = Vl53l5cxAsync::new(SharedAsyncSim(&sim), AsyncNoDelay);
+
+    block_on(async {
+        sensor.start_ranging().await.unwrap();
+        // Only the UI status is read
+        let read_bytes = sim.borrow().read_bytes;
+        assert!(matches!(sensor.get_ranging_data().await, Err(Error::NotReady)));
+        assert_eq!(sim.borrow().read_bytes - read_bytes, 4);
+    });
+}
+
+/// Async bus on a simulated sensor, logging the sensor of each transaction.
+struct LoggedSim<'a> {
+    sim: SimSensor,
+    id: usize,
+    log: &'a RefCell<Vec<usize>>
+}
+
+impl ErrorType for LoggedSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for LoggedSim<'_> {
+    async fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.log.borrow_mut().push(self.id);
+        self.sim.transaction(address, operations)
+    }
+}
+
+/// Delay yielding once to the executor, as a timer does.
+struct YieldDelay;
+
+impl DelayNs for YieldDelay {
+    async fn delay_ns(&mut self, _ns: u32) {
+        let mut yielded: bool = false;
+        poll_fn(|cx| {
+            if yielded {
+                return Poll::Ready(());
+            }
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }).await
+    }
+}
+
+#[test]
+fn frames_are_collected_concurrently() {
+    let log = RefCell::new(Vec::new());
+    let mut sensors: [Vl53l5cxAsync<LoggedSim, YieldDelay>; 3] = core::array::from_fn(|id| {
+        Vl53l5cxAsync::new(LoggedSim { sim: SimSensor::new(3 + 2 * id as u32, 1), id, log: &log }, YieldDelay)
+    });
+
+    block_on(async {
+        for sensor in sensors.iter_mut() {
+            sensor.start_ranging().await.unwrap();
+        }
+        log.borrow_mut().clear();
+        for n in 1..=2 {
+            for results in collect_all(&mut sensors, 1000).await {
+                assert_eq!(SimSensor::frame_number(results.unwrap().distance_mm[0]), n);
+            }
+        }
+    });
+
+    // The last sensor is polled before the first one got its frame
+    let log = log.borrow();
+    let first_poll_of_last = log.iter().position(|&id| id == 2).unwrap();
+    let last_read_of_first = log.iter().rposition(|&id| id == 0).unwrap();
+    assert!(first_poll_of_last < last_read_of_first);
+}
@@ -0,0 +1,51 @@
+use vl53l5cx::background::BackgroundModel;
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::ResultsData;
+
+fn frame(distance_mm: i16, status: u8) -> ResultsData {
+    let mut results = ResultsData::new();
+    for zone in 0..16 {
+        let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        results.distance_mm[idx] = distance_mm;
+        results.target_status[idx] = status;
+    }
+    results
+}
+
+#[test]
+fn first_frame_is_learnt_as_background() {
+    let mut model = BackgroundModel::new(4, 100);
+    assert_eq!(model.background_mm(0), None);
+    assert_eq!(model.update(&frame(1500, 5), Resolution::Res4x4), 0);
+    assert_eq!(model.background_mm(3), Some(1500));
+    assert_eq!(model.background_mm(16), None);
+}
+
+#[test]
+fn closer_target_is_in_foreground() {
+    let mut model = BackgroundModel::new(4, 100);
+    model.update(&frame(1500, 5), Resolution::Res4x4);
+
+    let mut results = frame(1500, 5);
+    results.distance_mm[VL53L5CX_NB_TARGET_PER_ZONE as usize * 5] = 800;
+    results.distance_mm[VL53L5CX_NB_TARGET_PER_ZONE as usize * 6] = 1450;
+    assert_eq!(model.update(&results, Resolution::Res4x4), 1 << 5);
+
+    // Invalid zones are ignored
+    assert_eq!(model.update(&frame(200, 255), Resolution::Res4x4), 0);
+}
+
+#[test]
+fn static_object_becomes_background() {
+    let mut model = BackgroundModel::new(2, 100);
+    model.update(&frame(1500, 5), Resolution::Res4x4);
+
+    let mut frames = 0;
+    while model.update(&frame(800, 9), Resolution::Res4x4) != 0 {
+        frames += 1;
+        assert!(frames < 1000);
+    }
+    assert!(frames > 16);
+    assert!(model.background_mm(0).unwrap() < 900);
+}
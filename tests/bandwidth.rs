@@ -0,0 +1,59 @@
+mod common;
+
+use common::*;
+use vl53l5cx::bandwidth::*;
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::Vl53l5cx;
+
+const ALL_OUTPUTS: u32 = VL53L5CX_OUTPUT_AMBIENT_PER_SPAD | VL53L5CX_OUTPUT_NB_SPADS_ENABLED
+    | VL53L5CX_OUTPUT_NB_TARGET_DETECTED | VL53L5CX_OUTPUT_SIGNAL_PER_SPAD | VL53L5CX_OUTPUT_RANGE_SIGMA_MM
+    | VL53L5CX_OUTPUT_DISTANCE_MM | VL53L5CX_OUTPUT_REFLECTANCE_PERCENT | VL53L5CX_OUTPUT_TARGET_STATUS;
+
+#[test]
+fn frame_size_matches_built_frames() {
+    let (_, size) = FrameSpec::pattern(16, true).build();
+    assert_eq!(frame_size(Resolution::Res4x4, NB_TARGET as u8, ALL_OUTPUTS | VL53L5CX_OUTPUT_MOTION_INDICATOR), size);
+
+    let (_, size) = FrameSpec::pattern(64, false).build();
+    assert_eq!(frame_size(Resolution::Res8x8, NB_TARGET as u8, ALL_OUTPUTS), size);
+}
+
+#[test]
+fn slow_bus_can_not_sustain_8x8() {
+    let outputs: u32 = ALL_OUTPUTS | VL53L5CX_OUTPUT_MOTION_INDICATOR;
+    let budget = check_bus_budget(100_000, Resolution::Res8x8, 1, 15, outputs).unwrap_err();
+    assert_eq!(budget.frame_size, 1444);
+    assert!(budget.load_percent() > 100);
+
+    let budget = check_bus_budget(400_000, Resolution::Res8x8, 1, 15, outputs).unwrap();
+    assert!(budget.load_percent() < 100);
+}
+
+#[test]
+fn outputs_are_trimmed_in_order() {
+    let outputs: u32 = ALL_OUTPUTS | VL53L5CX_OUTPUT_MOTION_INDICATOR;
+    assert_eq!(trim_outputs(400_000, Resolution::Res4x4, 1, 60, outputs), Ok(outputs));
+    assert_eq!(trim_outputs(320_000, Resolution::Res4x4, 1, 60, outputs), Ok(outputs & !VL53L5CX_OUTPUT_REFLECTANCE_PERCENT));
+    assert!(trim_outputs(100_000, Resolution::Res4x4, 1, 60, outputs).is_err());
+}
+
+#[test]
+fn start_ranging_trims_outputs() {
+    let outputs: u32 = ALL_OUTPUTS | VL53L5CX_OUTPUT_MOTION_INDICATOR;
+    let mut dropped: u32 = 0;
+    for output in VL53L5CX_TRIM_ORDER {
+        dropped |= output;
+        // Bus clock just fitting the frames once the outputs up to this one are dropped
+        let budget = check_bus_budget(u32::MAX, Resolution::Res4x4, NB_TARGET as u8, 60, outputs & !dropped).unwrap();
+
+        let mut sim = sim::SimSensor::new(3, 1);
+        let mut sensor = Vl53l5cx::new_i2c(&mut sim, mock::NoPin, mock::NoPin, mock::NoDelay).unwrap();
+        sensor.set_frequency_hz(60).unwrap();
+        sensor.set_auto_trim(Some(budget.required_hz)).unwrap();
+        sensor.start_ranging().unwrap();
+
+        assert_eq!(sensor.trimmed_outputs(), dropped);
+        assert_eq!(sim.data_read_size, budget.frame_size);
+    }
+}
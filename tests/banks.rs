@@ -0,0 +1,41 @@
+mod common;
+
+use common::mock::*;
+use vl53l5cx::consts::*;
+use vl53l5cx::{Error, Vl53l5cx};
+
+fn bank_writes(writes: &[Vec<u8>]) -> Vec<u8> {
+    let select = VL53L5CX_BANK_SELECT.to_be_bytes();
+    writes.iter().filter(|w| w.len() == 3 && w[..2] == select).map(|w| w[2]).collect()
+}
+
+#[test]
+fn with_bank_selects_and_restores_bank() {
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+    sensor.with_bank(0x00, |s| s.write_register(0x0004, 0x10)).unwrap();
+
+    assert_eq!(i2c.writes, vec![vec![0x7f, 0xff, 0x00], vec![0x00, 0x04, 0x10], vec![0x7f, 0xff, VL53L5CX_DEFAULT_BANK]]);
+}
+
+#[test]
+fn with_bank_restores_bank_on_error() {
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+    let result: Result<(), _> = sensor.with_bank(0x01, |_| Err(Error::InvalidParam));
+    assert!(matches!(result, Err(Error::InvalidParam)));
+
+    assert_eq!(bank_writes(&i2c.writes), vec![0x01, VL53L5CX_DEFAULT_BANK]);
+}
+
+#[test]
+fn failed_power_mode_read_restores_bank() {
+    let mut i2c = MockI2c { read_value: 0x55, ..Default::default() };
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+    assert!(sensor.power_mode().is_err());
+    let mut rbuf = [0u8; 2];
+    sensor.with_bank(0x00, |s| s.read_register(0x0000, &mut rbuf)).unwrap();
+
+    assert_eq!(rbuf, [0x55, 0x55]);
+    assert_eq!(bank_writes(&i2c.writes), vec![0x00, VL53L5CX_DEFAULT_BANK, 0x00, VL53L5CX_DEFAULT_BANK]);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use common::mock::*;
+use vl53l5cx::{BusArbiter, BusBurst, Error, Vl53l5cx};
+
+static GRANTED: AtomicBool = AtomicBool::new(true);
+static ACQUIRED: AtomicU32 = AtomicU32::new(0);
+static RELEASED: AtomicU32 = AtomicU32::new(0);
+
+fn acquire(burst: BusBurst) -> bool {
+    assert_eq!(burst, BusBurst::DataReadyCheck);
+    if !GRANTED.load(Ordering::SeqCst) {
+        return false;
+    }
+    ACQUIRED.fetch_add(1, Ordering::SeqCst);
+    true
+}
+
+fn release(_burst: BusBurst) {
+    RELEASED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn bursts_are_arbitrated() {
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_bus_arbiter(Some(BusArbiter { acquire, release }));
+
+    GRANTED.store(false, Ordering::SeqCst);
+    assert!(matches!(sensor.check_data_ready(), Err(Error::BusDeferred)));
+    GRANTED.store(true, Ordering::SeqCst);
+    assert!(!sensor.check_data_ready().unwrap());
+    sensor.set_bus_arbiter(None);
+    assert!(!sensor.check_data_ready().unwrap());
+
+    assert_eq!(ACQUIRED.load(Ordering::SeqCst), 1);
+    assert_eq!(RELEASED.load(Ordering::SeqCst), 1);
+    // Only the two checks done with the bus granted reached the bus
+    assert_eq!(i2c.writes.len(), 2);
+}
@@ -0,0 +1,56 @@
+use vl53l5cx::ceiling_mount::{CeilingMount, FallEvent};
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::ResultsData;
+
+/// Empty room seen from 2500mm, with a person of `height_mm` in zone 5.
+fn frame(height_mm: i16) -> ResultsData {
+    let mut results = ResultsData::new();
+    for zone in 0..16 {
+        let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        results.distance_mm[idx] = 2500;
+        results.target_status[idx] = 5;
+    }
+    results.distance_mm[VL53L5CX_NB_TARGET_PER_ZONE as usize * 5] = 2500 - height_mm;
+    results
+}
+
+#[test]
+fn height_map_is_relative_to_floor() {
+    let mount = CeilingMount::new(2500, 800, 500, 3);
+    let mut results = frame(1700);
+    results.target_status[0] = 255;
+    let height_map = mount.height_map(&results, Resolution::Res4x4);
+    assert_eq!(height_map[0], 0);
+    assert_eq!(height_map[1], 0);
+    assert_eq!(height_map[5], 1700);
+}
+
+#[test]
+fn fall_is_reported_once_after_dwell() {
+    let mut mount = CeilingMount::new(2500, 800, 500, 3);
+    for _ in 0..10 {
+        assert_eq!(mount.update(&frame(1700), Resolution::Res4x4), None);
+    }
+    assert_eq!(mount.update(&frame(300), Resolution::Res4x4), None);
+    assert_eq!(mount.update(&frame(300), Resolution::Res4x4), None);
+    assert_eq!(mount.update(&frame(250), Resolution::Res4x4), Some(FallEvent { height_before_mm: 1700, height_after_mm: 250 }));
+    for _ in 0..10 {
+        assert_eq!(mount.update(&frame(300), Resolution::Res4x4), None);
+    }
+}
+
+#[test]
+fn sitting_down_is_not_a_fall() {
+    let mut mount = CeilingMount::new(2500, 800, 500, 3);
+    for _ in 0..10 {
+        mount.update(&frame(1700), Resolution::Res4x4);
+    }
+    // Crouching briefly, then getting up
+    mount.update(&frame(400), Resolution::Res4x4);
+    assert_eq!(mount.update(&frame(1200), Resolution::Res4x4), None);
+    // Slow sit down to a chair height
+    for height in [1500, 1300, 1100, 900, 700, 600, 600, 600, 600, 600] {
+        assert_eq!(mount.update(&frame(height), Resolution::Res4x4), None);
+    }
+}
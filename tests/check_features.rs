@@ -0,0 +1,59 @@
+//! Feature matrix of the VL53L5CX_DISABLE_* features: the crate is built and the 
+//! parser tests of tests/disabled_blocks.rs are run with each feature alone, with 
+//! all the features but one, and with all of them. Each combination is a separate 
+//! cargo invocation, so these tests are ignored by default, and run in CI with:
+//! 
+//! ```sh
+//! cargo test --test check_features -- --ignored
+//! ```
+
+use std::process::Command;
+
+const DISABLE_FEATURES: [&str; 9] = [
+    "VL53L5CX_DISABLE_AMBIENT_PER_SPAD",
+    "VL53L5CX_DISABLE_NB_SPADS_ENABLED",
+    "VL53L5CX_DISABLE_NB_TARGET_DETECTED",
+    "VL53L5CX_DISABLE_SIGNAL_PER_SPAD",
+    "VL53L5CX_DISABLE_RANGE_SIGMA_MM",
+    "VL53L5CX_DISABLE_DISTANCE_MM",
+    "VL53L5CX_DISABLE_REFLECTANCE_PERCENT",
+    "VL53L5CX_DISABLE_TARGET_STATUS",
+    "VL53L5CX_DISABLE_MOTION_INDICATOR",
+];
+
+fn check(features: &[&str]) {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    // A separate target directory, as the one of the running tests is locked
+    let status = Command::new(env!("CARGO"))
+        .current_dir(manifest_dir)
+        .args(["test", "--test", "disabled_blocks", "--target-dir"])
+        .arg(format!("{}/target/check-features", manifest_dir))
+        .arg("--features")
+        .arg(features.join(","))
+        .status()
+        .expect("cargo could not be run");
+    assert!(status.success(), "features {:?}", features);
+}
+
+#[test]
+#[ignore]
+fn each_disabled_feature() {
+    for feature in DISABLE_FEATURES {
+        check(&[feature]);
+    }
+}
+
+#[test]
+#[ignore]
+fn all_disabled_features_but_one() {
+    for i in 0..DISABLE_FEATURES.len() {
+        let features: Vec<&str> = DISABLE_FEATURES.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, f)| *f).collect();
+        check(&features);
+    }
+}
+
+#[test]
+#[ignore]
+fn all_disabled_features() {
+    check(&DISABLE_FEATURES);
+}
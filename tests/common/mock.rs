@@ -0,0 +1,51 @@
+//! Mock bus, pins and delay, to run the driver on host.
+
+use std::convert::Infallible;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorType as PinErrorType, OutputPin};
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+
+/// I2C bus recording the writes, and answering `read_value` to the reads.
+#[derive(Default)]
+pub struct MockI2c {
+    pub writes: Vec<Vec<u8>>,
+    pub read_value: u8,
+}
+
+impl ErrorType for MockI2c {
+    type Error = Infallible;
+}
+
+impl I2c for MockI2c {
+    fn transaction(&mut self, _address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Infallible> {
+        for operation in operations {
+            match operation {
+                Operation::Write(wbuf) => self.writes.push(wbuf.to_vec()),
+                Operation::Read(rbuf) => rbuf.fill(self.read_value),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct NoPin;
+pub struct NoDelay;
+
+impl PinErrorType for NoPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for NoPin {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
@@ -0,0 +1,154 @@
+//! Frame builder shared by the host-side tests.
+//!
+//! Frames are synthesized following the block layout programmed by
+//! start_ranging(): a 16 bytes header, one block per enabled output (starting
+//! with the empty start block) and a 8 bytes footer carrying the same id as
+//! the header. Values are written in the firmware format, then each 32 bits
+//! word is byte-swapped to obtain the frame as it is read through I2C.
+//!
+//! The builder is meant for unit tests of the driver logic: the parser is
+//! checked against raw frame dumps in tests/golden_dumps.rs.
+
+#![allow(dead_code)]
+
+pub mod mock;
+pub mod sim;
+
+use vl53l5cx::consts::VL53L5CX_NB_TARGET_PER_ZONE;
+
+pub const NB_TARGET: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+// Block headers (idx, type) of the outputs, depending on the number of targets per zone.
+pub const METADATA: (u16, u8) = (0x54B4, 0x0);
+pub const COMMONDATA: (u16, u8) = (0x54C0, 0x0);
+pub const AMBIENT_RATE: (u16, u8) = (0x54D0, 0x4);
+pub const SPAD_COUNT: (u16, u8) = (0x55D0, 0x4);
+pub const NB_TARGET_DETECTED: (u16, u8) = if NB_TARGET == 1 { (0xDB84, 0x1) } else { (0x57D0, 0x1) };
+pub const SIGNAL_RATE: (u16, u8) = if NB_TARGET == 1 { (0xDBC4, 0x4) } else { (0x5890, 0x4) };
+pub const RANGE_SIGMA_MM: (u16, u8) = if NB_TARGET == 1 { (0xDEC4, 0x2) } else { (0x6490, 0x2) };
+pub const DISTANCE: (u16, u8) = if NB_TARGET == 1 { (0xDF44, 0x2) } else { (0x6690, 0x2) };
+pub const REFLECTANCE: (u16, u8) = if NB_TARGET == 1 { (0xE044, 0x1) } else { (0x6A90, 0x1) };
+pub const TARGET_STATUS: (u16, u8) = if NB_TARGET == 1 { (0xE084, 0x1) } else { (0x6B90, 0x1) };
+pub const START: (u16, u8) = (0x0000, 0xD);
+pub const MOTION_DETECT: (u16, u8) = if NB_TARGET == 1 { (0xD858, 0x0) } else { (0xCC50, 0x0) };
+
+/// Values of a frame, in firmware format.
+pub struct FrameSpec {
+    pub resolution: usize,
+    pub streamcount: u8,
+    pub frame_id: u16,
+    pub silicon_temp_degc: i8,
+    pub ambient_per_spad: Vec<u32>,
+    pub nb_spads_enabled: Vec<u32>,
+    pub nb_target_detected: Vec<u8>,
+    pub signal_per_spad: Vec<u32>,
+    pub range_sigma_mm: Vec<u16>,
+    pub distance_mm: Vec<i16>,
+    pub reflectance: Vec<u8>,
+    pub target_status: Vec<u8>,
+    pub motion: Option<Vec<u32>>,
+}
+
+impl FrameSpec {
+    /// Deterministic frame where every zone and target holds a distinct value.
+    pub fn pattern(resolution: usize, with_motion: bool) -> Self {
+        let zones = resolution;
+        let targets = resolution * NB_TARGET;
+        FrameSpec {
+            resolution,
+            streamcount: 7,
+            frame_id: 0x1234,
+            silicon_temp_degc: 31,
+            ambient_per_spad: (0..zones).map(|z| (z as u32 + 1) * 2048).collect(),
+            nb_spads_enabled: (0..zones).map(|z| 256 * (z as u32 + 10)).collect(),
+            nb_target_detected: (0..zones).map(|z| if z % 5 == 4 { 0 } else { NB_TARGET as u8 }).collect(),
+            signal_per_spad: (0..targets).map(|t| (t as u32 + 100) * 2048).collect(),
+            range_sigma_mm: (0..targets).map(|t| (t as u16 % 16 + 1) * 128).collect(),
+            distance_mm: (0..targets).map(|t| if t % 7 == 6 { -8 } else { (t as i16 * 10 + 50) * 4 }).collect(),
+            reflectance: (0..targets).map(|t| (t as u8 % 50) * 2).collect(),
+            target_status: (0..targets).map(|t| if t % 3 == 0 { 5 } else { 9 }).collect(),
+            motion: if with_motion { Some((0..32).map(|m| m as u32 * 65535).collect()) } else { None },
+        }
+    }
+
+    /// Builds the frame as read from register 0, and returns it with its size.
+    /// The size of each block is given by the length of the matching values, 
+    /// and the blocks without values are omitted, as the disabled outputs.
+    pub fn build(&self) -> (Vec<u8>, usize) {
+        let mut frame: Vec<u8> = vec![0; 16];
+        frame[0] = self.streamcount;
+        frame[8..10].copy_from_slice(&self.frame_id.to_be_bytes());
+
+        frame.extend(((START.0 as u32) << 16 | START.1 as u32).to_le_bytes());
+        let mut metadata = vec![0u8; 12];
+        metadata[8] = self.silicon_temp_degc as u8;
+        push_block(&mut frame, METADATA, 12, &metadata);
+        push_block(&mut frame, COMMONDATA, 4, &[0; 4]);
+        push_block(&mut frame, AMBIENT_RATE, self.ambient_per_spad.len(), &le_u32(&self.ambient_per_spad));
+        push_block(&mut frame, SPAD_COUNT, self.nb_spads_enabled.len(), &le_u32(&self.nb_spads_enabled));
+        push_block(&mut frame, NB_TARGET_DETECTED, self.nb_target_detected.len(), &self.nb_target_detected);
+        push_block(&mut frame, SIGNAL_RATE, self.signal_per_spad.len(), &le_u32(&self.signal_per_spad));
+        push_block(&mut frame, RANGE_SIGMA_MM, self.range_sigma_mm.len(), &le_u16(&self.range_sigma_mm));
+        let distance: Vec<u16> = self.distance_mm.iter().map(|&d| d as u16).collect();
+        push_block(&mut frame, DISTANCE, distance.len(), &le_u16(&distance));
+        push_block(&mut frame, REFLECTANCE, self.reflectance.len(), &self.reflectance);
+        push_block(&mut frame, TARGET_STATUS, self.target_status.len(), &self.target_status);
+        if let Some(motion) = &self.motion {
+            let mut data = vec![0u8; 12];
+            data.extend(le_u32(motion));
+            push_block(&mut frame, MOTION_DETECT, 140, &data);
+        }
+
+        // Footer, ending with the frame id
+        let mut footer = vec![0u8; 8];
+        footer[4..6].copy_from_slice(&self.frame_id.to_be_bytes());
+        frame.extend(footer);
+
+        let size = frame.len();
+        for word in frame.chunks_exact_mut(4) {
+            word.reverse();
+        }
+        (frame, size)
+    }
+}
+
+/// Copies a frame into a buffer as large as the driver temporary buffer.
+pub fn into_buffer(frame: &[u8]) -> Vec<u8> {
+    let mut buffer = vec![0u8; frame.len().max(1024)];
+    buffer[..frame.len()].copy_from_slice(frame);
+    buffer
+}
+
+/// Returns the offset of the first block with the given index in a built frame.
+pub fn block_offset(frame: &[u8], idx: u16) -> usize {
+    let mut i = 16;
+    loop {
+        let bh = u32::from_be_bytes([frame[i], frame[i + 1], frame[i + 2], frame[i + 3]]);
+        if (bh >> 16) as u16 == idx {
+            return i;
+        }
+        let bh_type = bh & 0xF;
+        let bh_size = ((bh >> 4) & 0xFFF) as usize;
+        i += 4 + if bh_type > 1 && bh_type < 0xD { bh_type as usize * bh_size } else { bh_size };
+    }
+}
+
+fn push_block(frame: &mut Vec<u8>, header: (u16, u8), size: usize, data: &[u8]) {
+    if data.is_empty() {
+        return;
+    }
+    let bh: u32 = (header.0 as u32) << 16 | (size as u32) << 4 | header.1 as u32;
+    frame.extend(bh.to_le_bytes());
+    frame.extend(data);
+    while !frame.len().is_multiple_of(4) {
+        frame.push(0);
+    }
+}
+
+fn le_u32(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn le_u16(values: &[u16]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
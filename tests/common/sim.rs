@@ -0,0 +1,249 @@
+//! Simulated sensor, answering the DCI commands of the driver and streaming
+//! frames once ranging, with optional fault injection (NACKs, corrupted
+//! frames and late frames) driven by a deterministic pseudo random generator.
+
+use std::collections::HashMap;
+
+use embedded_hal::i2c::{self, ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation, SevenBitAddress};
+
+use super::FrameSpec;
+
+const UI_CMD_STATUS: usize = 0x2C00;
+const UI_CMD_START: usize = 0x2C04;
+const UI_CMD_END: usize = 0x2FFF;
+const DCI_ZONE_CONFIG: u16 = 0x5450;
+const DCI_OUTPUT_CONFIG: u16 = 0xD968;
+const DCI_UI_RANGE_DATA: u16 = 0x5440;
+
+/// Transaction not acknowledged by the simulated sensor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Nack;
+
+impl i2c::Error for Nack {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)
+    }
+}
+
+/// Faults injected once the sensor is ranging, as probabilities in per mille.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Faults {
+    pub nack_permille: u32,
+    pub corrupted_permille: u32,
+    pub late_permille: u32,
+    // Frames replaced by a GO2 error in the UI status bytes
+    pub go2_permille: u32,
+}
+
+pub struct SimSensor {
+    mem: Vec<u8>,
+    dci: HashMap<u16, Vec<u8>>,
+    seed: u32,
+    pub faults: Faults,
+    pub ranging: bool,
+    // Number of data ready polls between two frames, and polls left before the next one
+    pub frame_period: u32,
+    countdown: u32,
+    // Frames published, corrupted frames published, and NACKs injected
+    pub published: u32,
+    pub corrupted: u32,
+    pub nacks: u32,
+    // Bytes read by the host
+    pub read_bytes: usize,
+    // Size of the frames, as programmed by start_ranging()
+    pub data_read_size: usize,
+    // Selected register bank, and bytes written in the firmware banks
+    bank: u8,
+    firmware_bytes: usize,
+}
+
+impl SimSensor {
+    /// Sensor in 4x4, publishing a frame every `frame_period` data ready polls.
+    pub fn new(frame_period: u32, seed: u32) -> Self {
+        let mut dci: HashMap<u16, Vec<u8>> = HashMap::new();
+        dci.insert(DCI_ZONE_CONFIG, vec![4, 4, 0, 0, 8, 8, 0, 0]);
+        SimSensor {
+            mem: vec![0; 0x10000],
+            dci,
+            seed,
+            faults: Faults::default(),
+            ranging: false,
+            frame_period,
+            countdown: frame_period,
+            published: 0,
+            corrupted: 0,
+            nacks: 0,
+            read_bytes: 0,
+            data_read_size: 0,
+            bank: 0,
+            firmware_bytes: 0,
+        }
+    }
+
+    /// Frame number `n`, with the frame number encoded in the first distance.
+    pub fn frame_spec(n: u32) -> FrameSpec {
+        let mut spec = FrameSpec::pattern(16, true);
+        // Stream count 255 is ignored by the driver
+        spec.streamcount = (n % 255) as u8;
+        spec.frame_id = n as u16;
+        spec.distance_mm[0] = ((n % 4000) as i16 + 1) * 4;
+        spec
+    }
+
+    /// Frame number encoded in the first distance of a frame (modulo 4000).
+    pub fn frame_number(distance_mm: i16) -> u32 {
+        (distance_mm - 1) as u32
+    }
+
+    fn random(&mut self, permille: u32) -> bool {
+        // xorshift32
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed % 1000 < permille
+    }
+
+    fn publish(&mut self) {
+        let n: u32 = self.published + 1;
+        let (mut frame, size) = Self::frame_spec(n).build();
+        assert_eq!(size, self.data_read_size, "frame size programmed by start_ranging");
+        // Stream count and UI status bytes, as checked by check_data_ready()
+        frame[..4].copy_from_slice(&[(n % 255) as u8, 0x05, 0x05, 0x10]);
+        if self.random(self.faults.corrupted_permille) {
+            // Footer id, which no longer matches the header id
+            frame[size - 1] ^= 0xFF;
+            self.corrupted += 1;
+        }
+        if self.faults.go2_permille > 0 && self.random(self.faults.go2_permille) {
+            frame[..4].copy_from_slice(&[(n % 255) as u8, 0x05, 0x05, 0x80]);
+        }
+        self.mem[..size].copy_from_slice(&frame);
+        self.published = n;
+    }
+
+    fn read(&mut self, reg: usize, rbuf: &mut [u8]) {
+        // Data ready poll, of the UI status or of the stream count only: 
+        // time passes, and a new frame may be published
+        if self.ranging && reg == 0 && (rbuf.len() == 4 || rbuf.len() == 1) {
+            self.countdown -= 1;
+            if self.countdown == 0 {
+                self.countdown = self.frame_period;
+                if self.random(self.faults.late_permille) {
+                    self.countdown += self.frame_period;
+                }
+                self.publish();
+            }
+        }
+        rbuf.copy_from_slice(&self.mem[reg..reg + rbuf.len()]);
+        self.read_bytes += rbuf.len();
+    }
+
+    fn write(&mut self, wbuf: &[u8]) {
+        let reg: usize = u16::from_be_bytes([wbuf[0], wbuf[1]]) as usize;
+        let data: &[u8] = &wbuf[2..];
+        if reg == 0x7FFF {
+            self.bank = data[0];
+        }
+        if (0x09..=0x0B).contains(&self.bank) && reg != 0x7FFF {
+            self.firmware_bytes += data.len();
+            return;
+        }
+        self.mem[reg..reg + data.len()].copy_from_slice(data);
+        if reg + data.len() == UI_CMD_END + 1 {
+            self.command();
+        }
+    }
+
+    /// Runs the command ending at UI_CMD_END: DCI read, DCI write or start.
+    fn command(&mut self) {
+        let end: [u8; 4] = [self.mem[UI_CMD_END - 3], self.mem[UI_CMD_END - 2], self.mem[UI_CMD_END - 1], self.mem[UI_CMD_END]];
+        match end {
+            [0x00, 0x02, 0x00, 0x08] => {
+                let cmd: Vec<u8> = self.mem[UI_CMD_END - 11..].to_vec();
+                let index: u16 = u16::from_be_bytes([cmd[0], cmd[1]]);
+                let size: usize = ((cmd[2] as usize) << 4) | (cmd[3] as usize >> 4);
+                let mut answer: Vec<u8> = vec![0; 4];
+                let mut value: Vec<u8> = self.dci.get(&index).cloned().unwrap_or_default();
+                value.resize(size, 0);
+                answer.extend(value);
+                answer.extend([0; 8]);
+                for word in answer.chunks_exact_mut(4) {
+                    word.reverse();
+                }
+                self.mem[UI_CMD_START..UI_CMD_START + answer.len()].copy_from_slice(&answer);
+            }
+            [0x05, 0x01, _, _] => {
+                let size: usize = u16::from_be_bytes([end[2], end[3]]) as usize - 8;
+                let address: usize = UI_CMD_END - (size + 12) + 1;
+                let index: u16 = u16::from_be_bytes([self.mem[address], self.mem[address + 1]]);
+                let mut value: Vec<u8> = self.mem[address + 4..address + 4 + size].to_vec();
+                for word in value.chunks_exact_mut(4) {
+                    word.reverse();
+                }
+                if index == DCI_OUTPUT_CONFIG {
+                    self.data_read_size = u32::from_le_bytes([value[0], value[1], value[2], value[3]]) as usize;
+                    let mut range_data: Vec<u8> = vec![0; 12];
+                    range_data[8..10].copy_from_slice(&(self.data_read_size as u16).to_le_bytes());
+                    self.dci.insert(DCI_UI_RANGE_DATA, range_data);
+                }
+                self.dci.insert(index, value);
+            }
+            [0x00, 0x03, 0x00, 0x00] => {
+                // No frame yet: stream count 255
+                self.mem[0] = 0xff;
+                self.ranging = true;
+            }
+            [0x02, 0x02, 0x00, 0x24] => {
+                // NVM read: offset data left to 0, and NVM ready status
+                self.mem[UI_CMD_START..UI_CMD_START + 492].fill(0);
+                self.mem[UI_CMD_STATUS..UI_CMD_STATUS + 4].copy_from_slice(&[0x02, 0x00, 0x00, 0x00]);
+                return;
+            }
+            _ => {}
+        }
+        // Command done
+        self.mem[UI_CMD_STATUS..UI_CMD_STATUS + 4].copy_from_slice(&[0x00, 0x03, 0x00, 0x00]);
+    }
+
+    /// Sets the device and revision ids, read by probe_identity() before the ranging starts.
+    pub fn set_identity(&mut self, device_id: u8, revision_id: u8) {
+        self.mem[..2].copy_from_slice(&[device_id, revision_id]);
+    }
+
+    /// Sets the value of a DCI index, e.g. the firmware version.
+    pub fn set_dci(&mut self, index: u16, value: &[u8]) {
+        self.dci.insert(index, value.to_vec());
+    }
+
+    /// Value of a DCI index, as last written by the driver.
+    pub fn dci(&self, index: u16) -> Option<&[u8]> {
+        self.dci.get(&index).map(|value| value.as_slice())
+    }
+
+    /// Number of bytes written in the firmware pages (banks 9 to 11).
+    pub fn firmware_bytes(&self) -> usize {
+        self.firmware_bytes
+    }
+}
+
+impl ErrorType for SimSensor {
+    type Error = Nack;
+}
+
+impl I2c for SimSensor {
+    fn transaction(&mut self, _address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        if self.ranging && self.random(self.faults.nack_permille) {
+            self.nacks += 1;
+            return Err(Nack);
+        }
+        let mut reg: usize = 0;
+        for operation in operations {
+            match operation {
+                Operation::Write(wbuf) if wbuf.len() == 2 => reg = u16::from_be_bytes([wbuf[0], wbuf[1]]) as usize,
+                Operation::Write(wbuf) => self.write(wbuf),
+                Operation::Read(rbuf) => self.read(reg, rbuf),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use vl53l5cx::compact::*;
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::{Error, ResultsData};
+
+fn frame(nb_zones: usize) -> ResultsData {
+    let mut results = ResultsData::new();
+    results.silicon_temp_degc = -5;
+    for zone in 0..nb_zones {
+        let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        results.distance_mm[idx] = (zone * 60) as i16;
+        results.target_status[idx] = if zone % 5 == 0 { 255 } else { 5 };
+    }
+    results
+}
+
+#[test]
+fn round_trip_8x8_fits_radio_frame() {
+    let results = frame(64);
+    let mut out = [0u8; COMPACT_FRAME_MAX_SIZE];
+    let size = encode_frame::<()>(&results, Resolution::Res8x8, &mut out).unwrap();
+    assert_eq!(size, 130);
+    assert!(size < 200);
+
+    let decoded = decode_frame::<()>(&out[..size]).unwrap();
+    assert_eq!(decoded.resolution, Resolution::Res8x8);
+    assert_eq!(decoded.silicon_temp_degc, -5);
+    for zone in 0..64 {
+        let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        assert_eq!(decoded.distance_mm[zone], results.distance_mm[idx].min(4095) as u16);
+        assert_eq!(decoded.target_status[zone], results.target_status[idx].min(15));
+    }
+}
+
+#[test]
+fn round_trip_4x4() {
+    let results = frame(16);
+    let mut out = [0u8; 34];
+    let size = encode_frame::<()>(&results, Resolution::Res4x4, &mut out).unwrap();
+    let decoded = decode_frame::<()>(&out[..size]).unwrap();
+    assert_eq!(decoded.resolution, Resolution::Res4x4);
+    assert_eq!(decoded.distance_mm[15], 900);
+    assert_eq!(decoded.distance_mm[16], 0);
+}
+
+#[test]
+fn invalid_inputs_are_rejected() {
+    let results = frame(64);
+    let mut out = [0u8; COMPACT_FRAME_MAX_SIZE];
+    assert!(matches!(encode_frame::<()>(&results, Resolution::Res8x8, &mut out[..100]), Err(Error::BufferTooSmall)));
+
+    let size = encode_frame::<()>(&results, Resolution::Res8x8, &mut out).unwrap();
+    assert!(matches!(decode_frame::<()>(&out[..size - 1]), Err(Error::CorruptedFrame)));
+    out[0] = 0x21;
+    assert!(matches!(decode_frame::<()>(&out[..size]), Err(Error::CorruptedFrame)));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::config::Resolution;
+use vl53l5cx::{Error, Vl53l5cx};
+
+#[test]
+fn locked_configuration_is_not_written() {
+    let mut sim = SimSensor::new(2, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_frequency_hz(10).unwrap();
+    sensor.lock_configuration();
+    assert!(sensor.is_configuration_locked());
+
+    assert!(matches!(sensor.set_resolution(Resolution::Res8x8), Err(Error::ConfigLocked)));
+    assert!(matches!(sensor.set_frequency_hz(15), Err(Error::ConfigLocked)));
+    assert!(matches!(sensor.set_caldata_xtalk([0; 776]), Err(Error::ConfigLocked)));
+    assert!(matches!(sensor.init(), Err(Error::ConfigLocked)));
+    assert_eq!(sensor.resolution().unwrap(), Resolution::Res4x4);
+    assert_eq!(sensor.get_frequency_hz().unwrap(), 10);
+
+    sensor.unlock_configuration();
+    sensor.set_resolution(Resolution::Res8x8).unwrap();
+    assert_eq!(sensor.resolution().unwrap(), Resolution::Res8x8);
+}
+
+#[test]
+fn ranging_is_not_locked() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.lock_configuration();
+
+    sensor.start_ranging().unwrap();
+    while !sensor.check_data_ready().unwrap() {}
+    let results = sensor.get_ranging_data().unwrap();
+    assert_eq!(SimSensor::frame_number(results.distance_mm[0]), 1);
+    sensor.stop_ranging().unwrap();
+}
+
+#[test]
+fn locked_setters_do_not_access_the_sensor() {
+    let mut i2c = MockI2c::default();
+    {
+        let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+        sensor.lock_configuration();
+
+        assert!(matches!(sensor.set_resolution(Resolution::Res8x8), Err(Error::ConfigLocked)));
+        assert!(matches!(sensor.set_frequency_hz(15), Err(Error::ConfigLocked)));
+        assert!(matches!(sensor.set_integration_time(20), Err(Error::ConfigLocked)));
+        // Settings applied by the next start_ranging()
+        assert!(matches!(sensor.set_motion_indicator_output(false), Err(Error::ConfigLocked)));
+        assert!(matches!(sensor.set_debug_outputs(&[]), Err(Error::ConfigLocked)));
+        assert!(matches!(sensor.set_auto_trim(Some(400_000)), Err(Error::ConfigLocked)));
+    }
+    assert!(i2c.writes.is_empty());
+}
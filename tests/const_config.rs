@@ -0,0 +1,27 @@
+use vl53l5cx::const_config::*;
+use vl53l5cx::config::{RangingMode, Resolution, TargetOrder};
+
+const TRACKING: SensorConfig = SensorConfig {
+    resolution: Resolution::Res8x8,
+    ranging_mode: RangingMode::Autonomous,
+    frequency_hz: 15,
+    integration_time_ms: 10,
+    sharpener_percent: 5,
+    target_order: TargetOrder::Closest,
+};
+
+#[test]
+fn valid_config_is_accepted() {
+    // Evaluated at compile time
+    const { assert!(TRACKING.is_valid()) };
+    assert!(SensorConfig { resolution: Resolution::Res4x4, frequency_hz: 60, integration_time_ms: 5, ..TRACKING }.is_valid());
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    // 4 integrations of 30ms per frame do not fit in 66ms
+    assert!(!SensorConfig { integration_time_ms: 30, ..TRACKING }.is_valid());
+    assert!(!SensorConfig { frequency_hz: 16, ranging_mode: RangingMode::Continuous, ..TRACKING }.is_valid());
+    assert!(!SensorConfig { integration_time_ms: 0, ranging_mode: RangingMode::Continuous, ..TRACKING }.is_valid());
+    assert!(!SensorConfig { sharpener_percent: 100, ..TRACKING }.is_valid());
+}
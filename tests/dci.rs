@@ -0,0 +1,62 @@
+use vl53l5cx::dci::*;
+use vl53l5cx::Error;
+
+#[test]
+fn read_command_encodes_index_and_size() {
+    assert_eq!(read_command(0xAD30, 4), [0xAD, 0x30, 0x00, 0x40, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x02, 0x00, 0x08]);
+    assert_eq!(read_command(0x9FE4, 768)[..4], [0x9F, 0xE4, 0x30, 0x00]);
+}
+
+#[test]
+fn read_answer_is_unframed() {
+    // Header, two big endian words, footer
+    let mut buffer: [u8; 20] = [
+        0xAD, 0x30, 0x00, 0x80,
+        0x04, 0x03, 0x02, 0x01,
+        0x08, 0x07, 0x06, 0x05,
+        0x00, 0x00, 0x00, 0x0f, 0x00, 0x02, 0x00, 0x08
+    ];
+    unframe_read_answer::<()>(&mut buffer, 8).unwrap();
+
+    assert_eq!(buffer[..8], [1, 2, 3, 4, 5, 6, 7, 8]);
+}
+
+#[test]
+fn write_frame_wraps_the_data() {
+    let mut buffer: [u8; 24] = [0xEE; 24];
+    buffer[..8].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+    let frame_size: usize = frame_write_command::<()>(&mut buffer, 0xAD38, 8).unwrap();
+
+    assert_eq!(frame_size, 20);
+    assert_eq!(buffer[..20], [
+        0xAD, 0x38, 0x00, 0x80,
+        0x04, 0x03, 0x02, 0x01,
+        0x08, 0x07, 0x06, 0x05,
+        0x00, 0x00, 0x00, 0x0f, 0x05, 0x01, 0x00, 0x10
+    ]);
+    // Bytes after the frame are untouched
+    assert_eq!(buffer[20..], [0xEE; 4]);
+}
+
+#[test]
+fn write_frame_is_reverted() {
+    let data: Vec<u8> = (0..=255).cycle().take(768).collect();
+    let mut buffer: Vec<u8> = vec![0; 1024];
+    buffer[..768].copy_from_slice(&data);
+
+    frame_write_command::<()>(&mut buffer, 0x9FE4, 768).unwrap();
+    assert_eq!(buffer[..4], [0x9F, 0xE4, 0x30, 0x00]);
+    assert_eq!(buffer[772..780], [0x00, 0x00, 0x00, 0x0f, 0x05, 0x01, 0x03, 0x08]);
+
+    unframe_write_command(&mut buffer, 768);
+    assert_eq!(buffer[..768], data[..]);
+}
+
+#[test]
+fn short_buffers_are_rejected() {
+    let mut buffer: [u8; 19] = [0; 19];
+
+    assert!(matches!(frame_write_command::<()>(&mut buffer, 0xAD38, 8), Err(Error::BufferTooSmall)));
+    assert!(matches!(unframe_read_answer::<()>(&mut buffer, 8), Err(Error::BufferTooSmall)));
+    assert_eq!(buffer, [0; 19]);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use std::convert::Infallible;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+
+use common::mock::*;
+use vl53l5cx::delay::SleepUs;
+use vl53l5cx::{CommandStatus, Error, Vl53l5cx};
+
+#[test]
+fn sleep_function_is_given_microseconds() {
+    let mut sleeps: Vec<u32> = Vec::new();
+    {
+        let mut delay = SleepUs::new(|us| sleeps.push(us));
+        delay.delay_ns(1);
+        delay.delay_ns(2500);
+        delay.delay_us(10);
+        delay.delay_ms(2500);
+    }
+
+    assert_eq!(sleeps, vec![1, 3, 10, 1_000_000, 1_000_000, 500_000]);
+}
+
+#[test]
+fn function_pointers_are_sleep_functions() {
+    fn sleep_us(_us: u32) {}
+    let mut delay = SleepUs::new(sleep_us as fn(u32));
+    delay.delay_ms(1);
+}
+
+#[test]
+fn status_polls_wait_the_poll_interval() {
+    let mut i2c = MockI2c::default();
+    let mut sleeps: Vec<u32> = Vec::new();
+    {
+        let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, SleepUs::new(|us| sleeps.push(us))).unwrap();
+        sensor.set_poll_interval_us(250).unwrap();
+        assert!(matches!(sensor.wait_for_command_status(CommandStatus::Done), Err(Error::Timeout)));
+    }
+
+    // Polled every 250us during 2s
+    assert_eq!(sleeps.len(), 8001);
+    assert!(sleeps.iter().all(|&us| us == 250));
+}
+
+/// I2C bus of a sensor whose MCU is booted, answering the status polls 
+/// of the reboot sequence, and 0 to the other reads.
+#[derive(Default)]
+struct BootingI2c {
+    reg: u16,
+}
+
+impl ErrorType for BootingI2c {
+    type Error = Infallible;
+}
+
+impl I2c for BootingI2c {
+    fn transaction(&mut self, _address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Infallible> {
+        for operation in operations {
+            match operation {
+                Operation::Write(wbuf) => self.reg = u16::from_be_bytes([wbuf[0], wbuf[1]]),
+                Operation::Read(rbuf) => {
+                    rbuf.fill(0);
+                    rbuf[0] = match self.reg {
+                        0x06 => 0x01,
+                        0x21 => 0x10,
+                        _ => 0x00
+                    };
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn boot_polls_wait_the_boot_poll_interval() {
+    let mut sleeps: Vec<u32> = Vec::new();
+    {
+        let mut sensor = Vl53l5cx::new_i2c(BootingI2c::default(), NoPin, NoPin, SleepUs::new(|us| sleeps.push(us))).unwrap();
+        sensor.set_boot_poll_interval_us(7).unwrap();
+        let _ = sensor.init();
+    }
+
+    // The MCU boot status is read once, and then the MCU is booted
+    assert_eq!(sleeps.iter().filter(|&&us| us == 7).count(), 1);
+}
+
+#[test]
+fn poll_intervals_are_checked() {
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+
+    assert!(matches!(sensor.set_poll_interval_us(0), Err(Error::InvalidParam)));
+    assert!(matches!(sensor.set_boot_poll_interval_us(100_001), Err(Error::InvalidParam)));
+    assert_eq!(sensor.get_poll_interval_us(), 1000);
+    assert_eq!(sensor.get_boot_poll_interval_us(), 100);
+}
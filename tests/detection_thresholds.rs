@@ -0,0 +1,58 @@
+use vl53l5cx::consts::*;
+use vl53l5cx::detection_thresholds::{evaluate_thresholds, DetectionThresholds};
+use vl53l5cx::ResultsData;
+
+fn threshold(zone_num: u8, measurement: u8, th_type: u8, low: i32, high: i32, math_op: u8) -> DetectionThresholds {
+    DetectionThresholds {
+        param_low_thresh: low,
+        param_high_thresh: high,
+        measurement,
+        th_type,
+        zone_num,
+        math_op
+    }
+}
+
+#[test]
+fn in_window_distance_sets_zone_bit() {
+    let mut results = ResultsData::new();
+    results.distance_mm[3 * VL53L5CX_NB_TARGET_PER_ZONE as usize] = 150;
+    results.distance_mm[5 * VL53L5CX_NB_TARGET_PER_ZONE as usize] = 800;
+
+    let thresholds = [
+        threshold(3, VL53L5CX_DISTANCE_MM, VL53L5CX_IN_WINDOW, 100, 200, VL53L5CX_OPERATION_NONE),
+        threshold(5 | VL53L5CX_LAST_THRESHOLD, VL53L5CX_DISTANCE_MM, VL53L5CX_IN_WINDOW, 100, 200, VL53L5CX_OPERATION_NONE),
+    ];
+    let events = evaluate_thresholds(&results, &thresholds);
+
+    assert_eq!(events.zones_mask, 1 << 3);
+    assert!(events.is_zone_triggered(3));
+    assert_eq!(events.measurements[3], 150);
+    assert_eq!(events.measurements[5], 800);
+}
+
+#[test]
+fn and_operation_combines_checkers_of_a_zone() {
+    let mut results = ResultsData::new();
+    results.distance_mm[0] = 150;
+    results.signal_per_spad[0] = 10;
+
+    let mut thresholds = [
+        threshold(0, VL53L5CX_DISTANCE_MM, VL53L5CX_LESS_THAN_EQUAL_MIN_CHECKER, 200, 0, VL53L5CX_OPERATION_NONE),
+        threshold(VL53L5CX_LAST_THRESHOLD, VL53L5CX_SIGNAL_PER_SPAD_KCPS, VL53L5CX_GREATER_THAN_MAX_CHECKER, 0, 50, VL53L5CX_OPERATION_AND),
+    ];
+    assert_eq!(evaluate_thresholds(&results, &thresholds).zones_mask, 0);
+
+    thresholds[1].math_op = VL53L5CX_OPERATION_OR;
+    assert_eq!(evaluate_thresholds(&results, &thresholds).zones_mask, 1);
+}
+
+#[test]
+fn thresholds_after_last_are_ignored() {
+    let results = ResultsData::new();
+    let thresholds = [
+        threshold(VL53L5CX_LAST_THRESHOLD, VL53L5CX_DISTANCE_MM, VL53L5CX_GREATER_THAN_MAX_CHECKER, 0, 100, VL53L5CX_OPERATION_NONE),
+        threshold(1, VL53L5CX_DISTANCE_MM, VL53L5CX_LESS_THAN_EQUAL_MIN_CHECKER, 100, 0, VL53L5CX_OPERATION_NONE),
+    ];
+    assert_eq!(evaluate_thresholds(&results, &thresholds).zones_mask, 0);
+}
@@ -0,0 +1,58 @@
+//! Parser tests which build with any combination of the VL53L5CX_DISABLE_* features, 
+//! run for each combination by tests/check_features.rs.
+
+mod common;
+
+use common::*;
+use vl53l5cx::consts::*;
+use vl53l5cx::parser::parse_ranging_data;
+
+/// Optional outputs, with true if the matching VL53L5CX_DISABLE_* feature is enabled.
+const OUTPUTS: [(u32, bool); 9] = [
+    (VL53L5CX_OUTPUT_AMBIENT_PER_SPAD, cfg!(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD")),
+    (VL53L5CX_OUTPUT_NB_SPADS_ENABLED, cfg!(feature = "VL53L5CX_DISABLE_NB_SPADS_ENABLED")),
+    (VL53L5CX_OUTPUT_NB_TARGET_DETECTED, cfg!(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED")),
+    (VL53L5CX_OUTPUT_SIGNAL_PER_SPAD, cfg!(feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD")),
+    (VL53L5CX_OUTPUT_RANGE_SIGMA_MM, cfg!(feature = "VL53L5CX_DISABLE_RANGE_SIGMA_MM")),
+    (VL53L5CX_OUTPUT_DISTANCE_MM, cfg!(feature = "VL53L5CX_DISABLE_DISTANCE_MM")),
+    (VL53L5CX_OUTPUT_REFLECTANCE_PERCENT, cfg!(feature = "VL53L5CX_DISABLE_REFLECTANCE_PERCENT")),
+    (VL53L5CX_OUTPUT_TARGET_STATUS, cfg!(feature = "VL53L5CX_DISABLE_TARGET_STATUS")),
+    (VL53L5CX_OUTPUT_MOTION_INDICATOR, cfg!(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR")),
+];
+
+#[test]
+fn disabled_outputs_are_skipped() {
+    for resolution in [VL53L5CX_RESOLUTION_4X4 as usize, VL53L5CX_RESOLUTION_8X8 as usize] {
+        let spec = FrameSpec::pattern(resolution, true);
+        let (frame, size) = spec.build();
+        let mut buffer = into_buffer(&frame);
+        let results = parse_ranging_data::<()>(&mut buffer, size).unwrap();
+        assert_eq!(results.silicon_temp_degc, spec.silicon_temp_degc);
+        for (output, disabled) in OUTPUTS {
+            assert_eq!(results.frame_info.is_present(output), !disabled, "output {:#x}", output);
+        }
+    }
+}
+
+#[test]
+fn missing_blocks_are_accepted() {
+    for (i, (output, _)) in OUTPUTS.iter().enumerate() {
+        let mut spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_8X8 as usize, true);
+        match i {
+            0 => spec.ambient_per_spad.clear(),
+            1 => spec.nb_spads_enabled.clear(),
+            2 => spec.nb_target_detected.clear(),
+            3 => spec.signal_per_spad.clear(),
+            4 => spec.range_sigma_mm.clear(),
+            5 => spec.distance_mm.clear(),
+            6 => spec.reflectance.clear(),
+            7 => spec.target_status.clear(),
+            _ => spec.motion = None,
+        }
+        let (frame, size) = spec.build();
+        let mut buffer = into_buffer(&frame);
+        let results = parse_ranging_data::<()>(&mut buffer, size).unwrap();
+        assert_eq!(results.silicon_temp_degc, spec.silicon_temp_degc);
+        assert!(!results.frame_info.is_present(*output), "output {:#x}", output);
+    }
+}
@@ -0,0 +1,50 @@
+use vl53l5cx::consts::*;
+use vl53l5cx::downsample::{downsample_8x8, DownsampleMode};
+use vl53l5cx::ResultsData;
+
+const NB_TARGET: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+fn frame() -> ResultsData {
+    let mut results = ResultsData::new();
+    results.silicon_temp_degc = 31;
+    for zone in 0..64 {
+        results.distance_mm[zone * NB_TARGET] = 100 * (zone / 8) as i16 + (zone % 8) as i16;
+        results.target_status[zone * NB_TARGET] = 5;
+        results.ambient_per_spad[zone] = zone as u32;
+    }
+    results
+}
+
+#[test]
+fn min_keeps_closest_target_of_block() {
+    let results = downsample_8x8(&frame(), DownsampleMode::Min);
+    assert_eq!(results.silicon_temp_degc, 31);
+    // Block of zones 18, 19, 26, 27
+    assert_eq!(results.distance_mm[5 * NB_TARGET], 202);
+    assert_eq!(results.target_status[5 * NB_TARGET], 5);
+    assert_eq!(results.nb_target_detected[5], 1);
+    assert_eq!(results.ambient_per_spad[5], (18 + 19 + 26 + 27) / 4);
+    // Outside of the 4x4 frame
+    assert_eq!(results.target_status[16 * NB_TARGET], 255);
+}
+
+#[test]
+fn mean_ignores_invalid_targets() {
+    let mut frame = frame();
+    frame.target_status[27 * NB_TARGET] = 255;
+    let results = downsample_8x8(&frame, DownsampleMode::Mean);
+    assert_eq!(results.distance_mm[5 * NB_TARGET], (202 + 203 + 302) / 3);
+    assert_eq!(results.distance_mm[0], (1 + 100 + 101) / 4);
+}
+
+#[test]
+fn block_without_valid_target_is_empty() {
+    let mut frame = frame();
+    for zone in [0, 1, 8, 9] {
+        frame.target_status[zone * NB_TARGET] = 4;
+    }
+    let results = downsample_8x8(&frame, DownsampleMode::Min);
+    assert_eq!(results.distance_mm[0], 0);
+    assert_eq!(results.target_status[0], 255);
+    assert_eq!(results.nb_target_detected[0], 0);
+}
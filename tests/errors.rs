@@ -0,0 +1,12 @@
+mod common;
+
+use common::mock::*;
+use vl53l5cx::{Error, Vl53l5cx};
+
+#[test]
+fn wrong_ids_are_reported() {
+    let mut i2c = MockI2c { read_value: 0x55, ..Default::default() };
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+
+    assert!(matches!(sensor.probe_identity(), Err(Error::IdMismatch { device_id: 0x55, revision_id: 0x55 })));
+}
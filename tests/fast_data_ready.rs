@@ -0,0 +1,59 @@
+mod common;
+
+use std::cell::RefCell;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::{DriverState, Error, Vl53l5cx};
+
+/// Bus shared between the driver and the test, which reads the counters of the simulated sensor.
+struct SharedSim<'a>(&'a RefCell<SimSensor>);
+
+impl ErrorType for SharedSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for SharedSim<'_> {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+#[test]
+fn fast_check_reads_the_stream_count_only() {
+    let sim = RefCell::new(SimSensor::new(2, 1));
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim), NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_fast_data_ready(true);
+    sensor.start_ranging().unwrap();
+
+    for n in 1..=3 {
+        let read_bytes = sim.borrow().read_bytes;
+        let mut nb_checks: usize = 1;
+        while !sensor.check_data_ready().unwrap() {
+            nb_checks += 1;
+        }
+        // One byte read per check
+        assert_eq!(sim.borrow().read_bytes - read_bytes, nb_checks);
+        assert_eq!(SimSensor::frame_number(sensor.get_ranging_data().unwrap().distance_mm[0]), n);
+    }
+}
+
+#[test]
+fn fast_check_reports_go2_errors_when_the_frame_is_read() {
+    let mut sim = SimSensor::new(2, 1);
+    sim.faults.go2_permille = 1000;
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_fast_data_ready(true);
+    sensor.start_ranging().unwrap();
+
+    // Only the stream count is checked
+    while !sensor.check_data_ready().unwrap() {}
+    assert!(matches!(sensor.get_ranging_data(), Err(Error::Go2)));
+    assert_eq!(sensor.driver_state(), DriverState::Error);
+    assert!(sensor.last_error().is_some());
+    // The pending frame is consumed, the UI status still reports the error
+    assert!(matches!(sensor.get_ranging_data(), Err(Error::Go2)));
+    assert_eq!(sensor.stats().go2_errors, 2);
+}
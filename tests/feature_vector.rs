@@ -0,0 +1,53 @@
+#![cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::feature_vector::*;
+use vl53l5cx::ResultsData;
+
+fn set_zone(results: &mut ResultsData, zone: usize, distance_mm: i16, status: u8) {
+    let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+    results.distance_mm[idx] = distance_mm;
+    results.target_status[idx] = status;
+}
+
+#[test]
+fn quadrants_follow_the_grid() {
+    let mut results = ResultsData::new();
+    for zone in 0..64 {
+        // 100mm steps per quadrant, top left to bottom right
+        let quadrant = 2 * (zone / 8 >= 4) as i16 + (zone % 8 >= 4) as i16;
+        set_zone(&mut results, zone, 1000 + 100 * quadrant, 5);
+    }
+    set_zone(&mut results, 63, 400, 9);
+    set_zone(&mut results, 0, 10, 255);
+
+    let features = extract_features(&results, Resolution::Res8x8);
+    assert_eq!(features[FEATURE_MEAN_TOP_LEFT..=FEATURE_MEAN_BOTTOM_RIGHT], [1000, 1100, 1200, (15 * 1300 + 400) / 16]);
+    assert_eq!(features[FEATURE_MIN_DISTANCE], 400);
+    assert_eq!(features[FEATURE_VALID_COUNT], 63);
+}
+
+#[test]
+fn empty_frame_has_zero_features() {
+    let results = ResultsData::new();
+
+    assert_eq!(extract_features(&results, Resolution::Res4x4), [0; NB_FEATURES]);
+}
+
+#[cfg(not(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+#[test]
+fn motion_energy_is_the_mean_of_the_aggregates() {
+    let mut results = ResultsData::new();
+    set_zone(&mut results, 2, 800, 5);
+    results.motion_indicator.nb_of_aggregates = 4;
+    results.motion_indicator.motion[..5].copy_from_slice(&[100, 200, 300, 400, 5000]);
+
+    let features = extract_features(&results, Resolution::Res4x4);
+    assert_eq!(features[FEATURE_MOTION_ENERGY], 250);
+    assert_eq!(features[FEATURE_MEAN_TOP_RIGHT], 800);
+    assert_eq!(features[FEATURE_VALID_COUNT], 1);
+
+    results.motion_indicator.motion[0] = u32::MAX;
+    assert_eq!(extract_features(&results, Resolution::Res4x4)[FEATURE_MOTION_ENERGY], i16::MAX);
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "ffi")]
+
+mod common;
+
+use core::ffi::c_void;
+use core::mem::offset_of;
+
+use common::sim::*;
+use embedded_hal::i2c::I2c;
+use vl53l5cx::ffi::*;
+use vl53l5cx::ResultsData;
+
+const ADDRESS: u8 = 0x29;
+
+fn sim<'a>(user: *mut c_void) -> &'a mut SimSensor {
+    unsafe { &mut *(user as *mut SimSensor) }
+}
+
+extern "C" fn write(user: *mut c_void, data: *const u8, len: u32) -> u8 {
+    let data = unsafe { core::slice::from_raw_parts(data, len as usize) };
+    sim(user).write(ADDRESS, data).map_or(1, |_| 0)
+}
+
+extern "C" fn read(user: *mut c_void, data: *mut u8, len: u32) -> u8 {
+    let data = unsafe { core::slice::from_raw_parts_mut(data, len as usize) };
+    sim(user).read(ADDRESS, data).map_or(1, |_| 0)
+}
+
+extern "C" fn write_read(user: *mut c_void, wdata: *const u8, wlen: u32, rdata: *mut u8, rlen: u32) -> u8 {
+    let wdata = unsafe { core::slice::from_raw_parts(wdata, wlen as usize) };
+    let rdata = unsafe { core::slice::from_raw_parts_mut(rdata, rlen as usize) };
+    sim(user).write_read(ADDRESS, wdata, rdata).map_or(1, |_| 0)
+}
+
+extern "C" fn delay_us(_user: *mut c_void, _us: u32) {}
+
+fn platform(sim: &mut SimSensor) -> Vl53l5cxPlatform {
+    Vl53l5cxPlatform {
+        user: sim as *mut SimSensor as *mut c_void,
+        write: Some(write),
+        read: Some(read),
+        write_read: Some(write_read),
+        delay_us: Some(delay_us)
+    }
+}
+
+#[test]
+fn ranging_through_the_c_abi() {
+    let mut sim = SimSensor::new(2, 1);
+    let platform = platform(&mut sim);
+    let mut storage = vec![0u64; vl53l5cx_ffi_handle_size().div_ceil(8)];
+    let mut results = vec![0u8; vl53l5cx_ffi_results_size()];
+    let mut is_ready: u8 = 0;
+    let mut frequency_hz: u8 = 0;
+
+    unsafe {
+        let dev = vl53l5cx_ffi_create(storage.as_mut_ptr().cast(), storage.len() * 8, &platform);
+        assert!(!dev.is_null());
+
+        assert_eq!(vl53l5cx_ffi_set_ranging_frequency_hz(dev, 15), 0);
+        assert_eq!(vl53l5cx_ffi_get_ranging_frequency_hz(dev, &mut frequency_hz), 0);
+        assert_eq!(frequency_hz, 15);
+
+        assert_eq!(vl53l5cx_ffi_start_ranging(dev), 0);
+        while is_ready == 0 {
+            assert_eq!(vl53l5cx_ffi_check_data_ready(dev, &mut is_ready), 0);
+        }
+        assert_eq!(vl53l5cx_ffi_get_ranging_data(dev, results.as_mut_ptr(), results.len() as u32), 0);
+        vl53l5cx_ffi_destroy(dev);
+    }
+
+    // Results in the layout of VL53L5CX_ResultsData
+    let offset = offset_of!(ResultsData, distance_mm);
+    let distance_mm = i16::from_le_bytes([results[offset], results[offset + 1]]);
+    assert_eq!(SimSensor::frame_number(distance_mm), 1);
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let mut sim = SimSensor::new(2, 1);
+    let platform = platform(&mut sim);
+    let mut storage = [0u64; 4];
+    let mut is_ready: u8 = 0;
+
+    unsafe {
+        assert!(vl53l5cx_ffi_create(storage.as_mut_ptr().cast(), 32, &platform).is_null());
+        assert_eq!(vl53l5cx_ffi_check_data_ready(core::ptr::null_mut(), &mut is_ready), 127);
+    }
+}
+
+#[test]
+fn null_callbacks_are_rejected() {
+    let mut sim = SimSensor::new(2, 1);
+    let mut storage = vec![0u64; vl53l5cx_ffi_handle_size().div_ceil(8)];
+
+    let mut platform = platform(&mut sim);
+    platform.delay_us = None;
+    unsafe {
+        assert!(vl53l5cx_ffi_create(storage.as_mut_ptr().cast(), storage.len() * 8, &platform).is_null());
+    }
+}
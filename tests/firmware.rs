@@ -0,0 +1,18 @@
+use vl53l5cx::firmware::{BuiltinFirmware, FirmwareReadError, FirmwareSource, VL53L5CX_FIRMWARE_SIZE};
+
+#[test]
+fn builtin_firmware_reads_chunks() {
+    let mut firmware = BuiltinFirmware;
+    let mut whole = vec![0u8; VL53L5CX_FIRMWARE_SIZE];
+    firmware.read(0, &mut whole).unwrap();
+
+    let mut chunk = [0u8; 64];
+    firmware.read(0x8000 - 32, &mut chunk).unwrap();
+    assert_eq!(&chunk[..], &whole[0x8000 - 32..0x8000 + 32]);
+}
+
+#[test]
+fn builtin_firmware_rejects_out_of_range() {
+    let mut chunk = [0u8; 16];
+    assert_eq!(BuiltinFirmware.read(VL53L5CX_FIRMWARE_SIZE - 8, &mut chunk), Err(FirmwareReadError));
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "embedded-storage")]
+
+use embedded_storage::nor_flash::{ErrorType, NorFlash, NorFlashErrorKind, ReadNorFlash};
+use vl53l5cx::flash_recorder::*;
+
+const SECTOR: usize = 256;
+
+/// NOR flash in RAM : writes can only clear bits, and are checked for alignment.
+struct RamFlash {
+    mem: Vec<u8>,
+    erases: usize
+}
+
+impl RamFlash {
+    fn new(nb_sectors: usize) -> Self {
+        RamFlash { mem: vec![0xFF; nb_sectors * SECTOR], erases: 0 }
+    }
+}
+
+impl ErrorType for RamFlash {
+    type Error = NorFlashErrorKind;
+}
+
+impl ReadNorFlash for RamFlash {
+    const READ_SIZE: usize = 1;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        let offset = offset as usize;
+        bytes.copy_from_slice(self.mem.get(offset..offset + bytes.len()).ok_or(NorFlashErrorKind::OutOfBounds)?);
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.mem.len()
+    }
+}
+
+impl NorFlash for RamFlash {
+    const WRITE_SIZE: usize = 4;
+    const ERASE_SIZE: usize = SECTOR;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        if !(from as usize).is_multiple_of(SECTOR) || !(to as usize).is_multiple_of(SECTOR) {
+            return Err(NorFlashErrorKind::NotAligned);
+        }
+        self.mem[from as usize..to as usize].fill(0xFF);
+        self.erases += 1;
+        Ok(())
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        let offset = offset as usize;
+        if !offset.is_multiple_of(Self::WRITE_SIZE) || !bytes.len().is_multiple_of(Self::WRITE_SIZE) {
+            return Err(NorFlashErrorKind::NotAligned);
+        }
+        for (cell, byte) in self.mem[offset..offset + bytes.len()].iter_mut().zip(bytes) {
+            assert_eq!(*cell & byte, *byte, "write on a cell not erased");
+            *cell &= byte;
+        }
+        Ok(())
+    }
+}
+
+fn record(n: u8) -> Vec<u8> {
+    (0..70).map(|i| n.wrapping_add(i)).collect()
+}
+
+#[test]
+fn records_are_read_newest_first() {
+    // 2 sectors of 2 slots of 128 bytes
+    let mut recorder = FlashRecorder::new(RamFlash::new(3), SECTOR as u32, 2 * SECTOR as u32, 128).unwrap();
+    assert_eq!(recorder.nb_slots(), 4);
+    assert_eq!(recorder.max_record_size(), 112);
+    let mut out = [0u8; 112];
+    assert_eq!(recorder.read(0, &mut out), Ok(None));
+
+    for n in 0..3 {
+        assert_eq!(recorder.record(&record(n)), Ok(n as u32));
+    }
+
+    assert_eq!(recorder.read(0, &mut out), Ok(Some((2, 70))));
+    assert_eq!(out[..70], record(2)[..]);
+    assert_eq!(recorder.read(2, &mut out), Ok(Some((0, 70))));
+    assert_eq!(out[..70], record(0)[..]);
+    assert_eq!(recorder.read(3, &mut out), Ok(None));
+    // The first sector of the flash is outside the region
+    assert!(recorder.release().mem[..SECTOR].iter().all(|&b| b == 0xFF));
+}
+
+#[test]
+fn ring_drops_the_oldest_sector() {
+    let mut recorder = FlashRecorder::new(RamFlash::new(2), 0, 2 * SECTOR as u32, 128).unwrap();
+    for n in 0..5 {
+        recorder.record(&record(n)).unwrap();
+    }
+
+    // Record 4 erased the sector of records 0 and 1
+    let mut out = [0u8; 112];
+    let ages: Vec<u32> = (0..4).map_while(|age| recorder.read(age, &mut out).unwrap().map(|(sequence, _)| sequence)).collect();
+    assert_eq!(ages, vec![4, 3, 2]);
+    assert_eq!(recorder.release().erases, 3);
+}
+
+#[test]
+fn recorder_resumes_after_a_reset() {
+    let mut recorder = FlashRecorder::new(RamFlash::new(4), 0, 4 * SECTOR as u32, 128).unwrap();
+    for n in 0..3 {
+        recorder.record(&record(n)).unwrap();
+    }
+    let flash = recorder.release();
+
+    let mut recorder = FlashRecorder::new(flash, 0, 4 * SECTOR as u32, 128).unwrap();
+    assert_eq!(recorder.record(&record(3)), Ok(3));
+
+    let mut out = [0u8; 112];
+    assert_eq!(recorder.read(0, &mut out), Ok(Some((3, 70))));
+    assert_eq!(recorder.read(1, &mut out), Ok(Some((2, 70))));
+    assert_eq!(out[..70], record(2)[..]);
+    assert_eq!(recorder.read(3, &mut out), Ok(Some((0, 70))));
+}
+
+#[test]
+fn interrupted_record_is_ignored() {
+    let mut recorder = FlashRecorder::new(RamFlash::new(2), 0, 2 * SECTOR as u32, 128).unwrap();
+    recorder.record(&record(0)).unwrap();
+    recorder.record(&record(1)).unwrap();
+    let mut flash = recorder.release();
+    // Reset before the header of record 1 was written
+    flash.mem[128..144].fill(0xFF);
+
+    let mut recorder = FlashRecorder::new(flash, 0, 2 * SECTOR as u32, 128).unwrap();
+    let mut out = [0u8; 112];
+    assert_eq!(recorder.read(0, &mut out), Ok(Some((0, 70))));
+    assert_eq!(recorder.read(1, &mut out), Ok(None));
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let geometry = |base: u32, size: u32, slot_size: u32| FlashRecorder::new(RamFlash::new(4), base, size, slot_size).err();
+
+    assert_eq!(geometry(0, SECTOR as u32, 128), Some(RecorderError::InvalidGeometry));
+    assert_eq!(geometry(16, 2 * SECTOR as u32, 128), Some(RecorderError::InvalidGeometry));
+    assert_eq!(geometry(0, 2 * SECTOR as u32, 130), Some(RecorderError::InvalidGeometry));
+    assert_eq!(geometry(0, 2 * SECTOR as u32, 512), Some(RecorderError::InvalidGeometry));
+    assert_eq!(geometry(0, 8 * SECTOR as u32, 128), Some(RecorderError::InvalidGeometry));
+
+    let mut recorder = FlashRecorder::new(RamFlash::new(2), 0, 2 * SECTOR as u32, 128).unwrap();
+    assert_eq!(recorder.record(&[0; 113]), Err(RecorderError::RecordTooLarge));
+    recorder.record(&record(0)).unwrap();
+    assert_eq!(recorder.read(0, &mut [0; 69]), Err(RecorderError::BufferTooSmall));
+}
@@ -0,0 +1,38 @@
+#![cfg(all(feature = "float-results", not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS", feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED"))))]
+
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::consts::*;
+use vl53l5cx::{ResultsData, Vl53l5cx};
+
+const NB_TARGET: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+// 1mm closer per zone
+fn model(_silicon_temp_degc: i8, zone: usize, distance_mm: i16) -> i16 {
+    distance_mm - zone as i16
+}
+
+#[test]
+fn float_distances_are_compensated() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_temperature_compensation(Some(model));
+
+    sensor.start_ranging().unwrap();
+    while !sensor.check_data_ready().unwrap() {}
+    let results: ResultsData = sensor.get_ranging_data().unwrap();
+
+    let spec = SimSensor::frame_spec(SimSensor::frame_number(results.distance_mm[0]));
+    for t in 0..16 * NB_TARGET {
+        let zone: usize = t / NB_TARGET;
+        if spec.nb_target_detected[zone] == 0 || (spec.target_status[t] != 5 && spec.target_status[t] != 9) {
+            continue;
+        }
+        // Negative distances are clamped to 0, before and after the compensation
+        let expected: i16 = model(0, zone, spec.distance_mm[t].max(0) / 4).max(0);
+        assert_eq!(results.distance_mm[t], expected, "target {}", t);
+        assert_eq!(results.float_results.distance_mm[t], expected as f32, "target {}", t);
+    }
+}
@@ -0,0 +1,36 @@
+#![cfg(any(feature = "nb_targets_2", feature = "nb_targets_3", feature = "nb_targets_4"))]
+
+use vl53l5cx::consts::*;
+use vl53l5cx::glass::detect_glass;
+use vl53l5cx::ResultsData;
+
+const NB_TARGET: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+fn set_target(results: &mut ResultsData, zone: usize, target: usize, distance_mm: i16, reflectance: u8) {
+    results.distance_mm[NB_TARGET * zone + target] = distance_mm;
+    results.reflectance[NB_TARGET * zone + target] = reflectance;
+    results.target_status[NB_TARGET * zone + target] = 5;
+}
+
+#[test]
+fn weak_first_target_with_target_behind_is_glass() {
+    let mut results = ResultsData::new();
+    // Window at 300mm, wall at 1200mm
+    results.nb_target_detected[2] = 2;
+    set_target(&mut results, 2, 0, 300, 4);
+    set_target(&mut results, 2, 1, 1200, 40);
+    // Two objects close to each other
+    results.nb_target_detected[3] = 2;
+    set_target(&mut results, 3, 0, 300, 50);
+    set_target(&mut results, 3, 1, 1200, 40);
+    // Single target
+    results.nb_target_detected[4] = 1;
+    set_target(&mut results, 4, 0, 300, 4);
+
+    let report = detect_glass(&results);
+    assert_eq!(report.zones_mask, 1 << 2);
+    assert_eq!(report.nb_zones, 1);
+    assert_eq!(report.distance_mm[2], 300);
+    assert!(report.is_glass(2));
+    assert!(!report.is_glass(64));
+}
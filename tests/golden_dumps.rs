@@ -0,0 +1,133 @@
+//! Parser tests on raw frames, as read from register 0 before the byte swap.
+//!
+//! The dumps in tests/golden/ are encoded from the output list of the ST ULD
+//! (VL53L5CX_*_BH block headers, 16 bytes header, 8 bytes footer ending with
+//! the frame id, 32 bits words byte-swapped on the bus), without the frame
+//! builder of the other tests, so that the parser is not checked against
+//! itself. The expected values are the ones of the scenes they encode.
+
+mod common;
+
+use common::into_buffer;
+use vl53l5cx::parser::parse_ranging_data;
+use vl53l5cx::ResultsData;
+
+fn parse(dump: &[u8]) -> ResultsData {
+    let mut buffer = into_buffer(dump);
+    parse_ranging_data::<()>(&mut buffer, dump.len()).unwrap()
+}
+
+// 4x4 scene: a wall at 1.2 m, a box at 0.4 m in the 4 central zones, no target in zone 12
+#[cfg(not(any(feature = "nb_targets_2", feature = "nb_targets_3", feature = "nb_targets_4")))]
+mod single_target {
+    use super::*;
+
+    const DISTANCE_4X4: [i16; 16] = [1203, 1198, 1195, 1201, 1190, 412, 408, 1188, 1185, 405, 401, 1180, 0, 1172, 1169, 1165];
+    const STATUS_4X4: [u8; 16] = [5, 5, 5, 9, 5, 5, 5, 5, 5, 5, 5, 5, 255, 5, 5, 5];
+    const BOX_4X4: [usize; 4] = [5, 6, 9, 10];
+
+    fn check_4x4(results: &ResultsData) {
+        assert_eq!(results.silicon_temp_degc, 27);
+        assert_eq!(results.distance_mm[..16], DISTANCE_4X4);
+        assert_eq!(results.target_status[..16], STATUS_4X4);
+        assert_eq!(results.ambient_per_spad[..6], [2, 3, 4, 2, 3, 4]);
+        for zone in 0..16 {
+            let (signal, sigma, reflectance, spads) = match zone {
+                12 => (0, 0, 0, 12288),
+                _ if BOX_4X4.contains(&zone) => (210, 2, 38, 2048),
+                _ => (35, 4, 12, 12288),
+            };
+            assert_eq!(results.nb_target_detected[zone], if zone == 12 { 0 } else { 1 }, "zone {}", zone);
+            assert_eq!(results.signal_per_spad[zone], signal, "zone {}", zone);
+            assert_eq!(results.range_sigma_mm[zone], sigma, "zone {}", zone);
+            assert_eq!(results.reflectance[zone], reflectance, "zone {}", zone);
+            assert_eq!(results.nb_spads_enabled[zone], spads, "zone {}", zone);
+        }
+        // Zones outside of the 4x4 resolution are left empty
+        assert_eq!(results.distance_mm[16..64], [0; 48]);
+    }
+
+    #[test]
+    fn dump_4x4() {
+        let results = parse(include_bytes!("golden/4x4.bin"));
+        check_4x4(&results);
+        assert_eq!(results.motion_indicator.nb_of_aggregates, 0);
+    }
+
+    #[test]
+    fn dump_4x4_with_motion() {
+        let results = parse(include_bytes!("golden/4x4_motion.bin"));
+        check_4x4(&results);
+        let motion = &results.motion_indicator;
+        assert_eq!(motion.global_indicator_1, 0x1F40);
+        assert_eq!(motion.global_indicator_2, 0x100);
+        assert_eq!(motion.nb_of_detected_aggregates, 4);
+        assert_eq!(motion.nb_of_aggregates, 16);
+        assert_eq!(motion.motion[..8], [1, 1, 1, 1, 1, 40, 40, 1]);
+        assert_eq!(motion.motion[8..16], [1, 40, 40, 1, 1, 1, 1, 1]);
+        assert_eq!(motion.motion[16..], [0; 16]);
+    }
+
+    // 8x8 scene: a tilted wall from 1.5 m, a box at 0.65 m in rows 2 to 4 and
+    // columns 3 to 5, no target in zone 56
+    fn check_8x8(results: &ResultsData) {
+        assert_eq!(results.silicon_temp_degc, -3);
+        for (zone, distance, status, signal, sigma, reflectance, spads) in [
+            (0, 1500, 5, 22, 7, 10, 15360),
+            (7, 1514, 6, 22, 7, 10, 15360),
+            (19, 655, 5, 150, 3, 41, 4096),
+            (36, 658, 5, 150, 3, 41, 4096),
+            (45, 1460, 5, 22, 7, 10, 15360),
+            (56, 0, 255, 0, 0, 0, 15360),
+            (63, 1444, 5, 22, 7, 10, 15360),
+        ] {
+            assert_eq!(results.distance_mm[zone], distance, "zone {}", zone);
+            assert_eq!(results.target_status[zone], status, "zone {}", zone);
+            assert_eq!(results.signal_per_spad[zone], signal, "zone {}", zone);
+            assert_eq!(results.range_sigma_mm[zone], sigma, "zone {}", zone);
+            assert_eq!(results.reflectance[zone], reflectance, "zone {}", zone);
+            assert_eq!(results.nb_spads_enabled[zone], spads, "zone {}", zone);
+        }
+        assert_eq!(results.nb_target_detected[56], 0);
+        assert_eq!(results.nb_target_detected.iter().filter(|&&nb| nb == 1).count(), 63);
+        assert_eq!(results.ambient_per_spad[60..64], [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn dump_8x8() {
+        let results = parse(include_bytes!("golden/8x8.bin"));
+        check_8x8(&results);
+        assert_eq!(results.motion_indicator.nb_of_aggregates, 0);
+    }
+
+    #[test]
+    fn dump_8x8_with_motion() {
+        let results = parse(include_bytes!("golden/8x8_motion.bin"));
+        check_8x8(&results);
+        let motion = &results.motion_indicator;
+        assert_eq!(motion.nb_of_aggregates, 16);
+        assert_eq!(motion.motion[..8], [0, 0, 0, 0, 0, 25, 25, 0]);
+        assert_eq!(motion.motion[8..16], [0, 25, 25, 0, 0, 0, 0, 0]);
+    }
+}
+
+// 4x4 scene of the single target dumps, with the wall detected behind the box as a second target
+#[cfg(feature = "nb_targets_2")]
+#[test]
+fn dump_4x4_2_targets_with_motion() {
+    let results = parse(include_bytes!("golden/4x4_2_targets_motion.bin"));
+    assert_eq!(results.silicon_temp_degc, 27);
+    assert_eq!(results.nb_target_detected[..8], [1, 1, 1, 1, 1, 2, 2, 1]);
+    assert_eq!(results.nb_target_detected[12], 0);
+    // Box and wall behind it
+    assert_eq!(results.distance_mm[10..12], [412, 1192]);
+    assert_eq!(results.target_status[10..12], [5, 9]);
+    assert_eq!(results.signal_per_spad[10..12], [210, 18]);
+    assert_eq!(results.range_sigma_mm[10..12], [2, 6]);
+    assert_eq!(results.reflectance[10..12], [38, 12]);
+    // Single target, the second one is empty
+    assert_eq!(results.distance_mm[..2], [1203, 0]);
+    assert_eq!(results.target_status[6..8], [9, 0]);
+    assert_eq!(results.target_status[24..26], [255, 255]);
+    assert_eq!(results.motion_indicator.motion[5], 40);
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use common::*;
+use vl53l5cx::consts::*;
+use vl53l5cx::parser::{parse_frame_with, ParseError, parse_ranging_data, parse_ranging_data_with_layout, parse_ranging_data_with_options, FrameLayout};
+use vl53l5cx::config::InvalidDistance;
+use vl53l5cx::{Error, ResultsData};
+
+fn parse(spec: &FrameSpec) -> Result<ResultsData, Error<()>> {
+    let (frame, size) = spec.build();
+    let mut buffer = into_buffer(&frame);
+    parse_ranging_data::<()>(&mut buffer, size)
+}
+
+fn check_golden(spec: &FrameSpec) {
+    let results = parse(spec).unwrap();
+    let zones = spec.resolution;
+
+    assert_eq!(results.silicon_temp_degc, spec.silicon_temp_degc);
+    assert_eq!(results.frame_info.metadata[8], spec.silicon_temp_degc as u8);
+    for z in 0..VL53L5CX_RESOLUTION_8X8 as usize {
+        if z < zones {
+            assert_eq!(results.ambient_per_spad[z], spec.ambient_per_spad[z] / 2048, "ambient zone {}", z);
+            assert_eq!(results.nb_spads_enabled[z], spec.nb_spads_enabled[z], "spads zone {}", z);
+            assert_eq!(results.nb_target_detected[z], spec.nb_target_detected[z], "nb target zone {}", z);
+        } else {
+            assert_eq!(results.ambient_per_spad[z], 0);
+            assert_eq!(results.nb_target_detected[z], 0);
+        }
+    }
+    for t in 0..zones * NB_TARGET {
+        let zone = t / NB_TARGET;
+        assert_eq!(results.signal_per_spad[t], spec.signal_per_spad[t] / 2048, "signal target {}", t);
+        assert_eq!(results.range_sigma_mm[t], spec.range_sigma_mm[t] / 128, "sigma target {}", t);
+        assert_eq!(results.distance_mm[t], (spec.distance_mm[t] / 4).max(0), "distance target {}", t);
+        assert_eq!(results.reflectance[t], spec.reflectance[t] / 2, "reflectance target {}", t);
+        let status = if spec.nb_target_detected[zone] == 0 { 255 } else { spec.target_status[t] };
+        assert_eq!(results.target_status[t], status, "status target {}", t);
+    }
+    let outputs = VL53L5CX_OUTPUT_METADATA | VL53L5CX_OUTPUT_COMMONDATA
+        | VL53L5CX_OUTPUT_AMBIENT_PER_SPAD | VL53L5CX_OUTPUT_NB_SPADS_ENABLED
+        | VL53L5CX_OUTPUT_NB_TARGET_DETECTED | VL53L5CX_OUTPUT_SIGNAL_PER_SPAD
+        | VL53L5CX_OUTPUT_RANGE_SIGMA_MM | VL53L5CX_OUTPUT_DISTANCE_MM
+        | VL53L5CX_OUTPUT_REFLECTANCE_PERCENT | VL53L5CX_OUTPUT_TARGET_STATUS;
+    assert!(results.frame_info.is_present(outputs));
+    assert_eq!(results.frame_info.is_present(VL53L5CX_OUTPUT_MOTION_INDICATOR), spec.motion.is_some());
+    for m in 0..32 {
+        let expected = spec.motion.as_ref().map_or(0, |motion| motion[m] / 65535);
+        assert_eq!(results.motion_indicator.motion[m], expected, "motion {}", m);
+    }
+}
+
+#[test]
+fn golden_4x4_without_motion() {
+    check_golden(&FrameSpec::pattern(VL53L5CX_RESOLUTION_4X4 as usize, false));
+}
+
+#[test]
+fn golden_4x4_with_motion() {
+    check_golden(&FrameSpec::pattern(VL53L5CX_RESOLUTION_4X4 as usize, true));
+}
+
+#[test]
+fn golden_8x8_without_motion() {
+    check_golden(&FrameSpec::pattern(VL53L5CX_RESOLUTION_8X8 as usize, false));
+}
+
+#[test]
+fn golden_8x8_with_motion() {
+    check_golden(&FrameSpec::pattern(VL53L5CX_RESOLUTION_8X8 as usize, true));
+}
+
+#[test]
+fn negative_distances_are_clamped() {
+    let spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_8X8 as usize, false);
+    let results = parse(&spec).unwrap();
+    assert_eq!(results.distance_mm[6], 0);
+}
+
+#[test]
+fn invalid_distances_use_sentinel() {
+    let spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_8X8 as usize, false);
+    for (invalid_distance, sentinel) in [(InvalidDistance::Min, i16::MIN), (InvalidDistance::Max, i16::MAX)] {
+        let (frame, size) = spec.build();
+        let mut buffer = into_buffer(&frame);
+        let results = parse_ranging_data_with_options::<()>(&mut buffer, size, &FrameLayout::new(), invalid_distance).unwrap();
+        // Negative distance, and no target detected in zone 4
+        assert_eq!(results.distance_mm[6], sentinel);
+        assert_eq!(results.distance_mm[4 * NB_TARGET], sentinel);
+        assert_eq!(results.distance_mm[0], 50);
+    }
+}
+
+#[test]
+fn mismatching_footer_is_corrupted() {
+    let spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_4X4 as usize, true);
+    let (mut frame, size) = spec.build();
+    // Footer id is the last word, byte-swapped
+    frame[size - 1] ^= 0xFF;
+    let mut buffer = into_buffer(&frame);
+    assert!(matches!(parse_ranging_data::<()>(&mut buffer, size), Err(Error::Parse(ParseError::HeaderFooterMismatch))));
+}
+
+#[test]
+fn block_overflowing_frame_is_corrupted() {
+    let spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_4X4 as usize, true);
+    let (mut frame, size) = spec.build();
+    // Block headers are read as big-endian words from the bus
+    let offset = block_offset(&frame, DISTANCE.0);
+    frame[offset + 2] = 0xFF;
+    let mut buffer = into_buffer(&frame);
+    assert!(matches!(parse_ranging_data::<()>(&mut buffer, size), Err(Error::Parse(ParseError::BlockOverflow))));
+}
+
+#[test]
+fn block_larger_than_results_is_corrupted() {
+    let mut spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_8X8 as usize, false);
+    spec.nb_target_detected = vec![1; 2 * VL53L5CX_RESOLUTION_8X8 as usize];
+    let (frame, size) = spec.build();
+    let mut buffer = into_buffer(&frame);
+    assert!(matches!(parse_ranging_data::<()>(&mut buffer, size), Err(Error::Parse(ParseError::InvalidBlockSize))));
+}
+
+#[test]
+fn frame_larger_than_buffer_is_corrupted() {
+    let spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_4X4 as usize, false);
+    let (mut frame, size) = spec.build();
+    assert!(matches!(parse_ranging_data::<()>(&mut frame[..size - 4], size), Err(Error::Parse(ParseError::Truncated))));
+}
+
+#[test]
+fn custom_layout_with_larger_header() {
+    let spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_4X4 as usize, false);
+    let (frame, size) = spec.build();
+    // Insert an extra word at the end of the header
+    let mut extended = frame[..16].to_vec();
+    extended.extend([0xFF; 4]);
+    extended.extend(&frame[16..]);
+    let layout = FrameLayout { header_size: 20, ..FrameLayout::new() };
+
+    let mut buffer = into_buffer(&extended);
+    let results = parse_ranging_data_with_layout::<()>(&mut buffer, size + 4, &layout).unwrap();
+    assert_eq!(results.silicon_temp_degc, spec.silicon_temp_degc);
+    assert_eq!(results.distance_mm[1], spec.distance_mm[1] / 4);
+
+    let mut buffer = into_buffer(&extended);
+    assert!(parse_ranging_data::<()>(&mut buffer, size + 4).is_err());
+}
+
+#[test]
+fn visitor_yields_raw_blocks() {
+    let spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_4X4 as usize, true);
+    let (frame, size) = spec.build();
+    let mut buffer = into_buffer(&frame);
+    let mut outputs: u32 = 0;
+    let mut distances: Vec<i16> = Vec::new();
+    parse_frame_with::<()>(&mut buffer, size, &FrameLayout::new(), |block| {
+        outputs |= block.output();
+        if block.output() == VL53L5CX_OUTPUT_DISTANCE_MM {
+            distances.extend(block.iter_i16());
+        }
+    }).unwrap();
+
+    assert_ne!(outputs & VL53L5CX_OUTPUT_MOTION_INDICATOR, 0);
+    // Distances are given in the firmware format
+    assert_eq!(distances, spec.distance_mm);
+}
+
+#[test]
+fn visitor_does_not_see_corrupted_frames() {
+    let spec = FrameSpec::pattern(VL53L5CX_RESOLUTION_4X4 as usize, true);
+    let (mut frame, size) = spec.build();
+    frame[size - 1] ^= 0xFF;
+    let mut buffer = into_buffer(&frame);
+    let mut nb_blocks: usize = 0;
+    let result = parse_frame_with::<()>(&mut buffer, size, &FrameLayout::new(), |_| nb_blocks += 1);
+    assert!(matches!(result, Err(Error::Parse(ParseError::HeaderFooterMismatch))));
+    assert_eq!(nb_blocks, 0);
+
+    // The distance block overflows the frame, after valid blocks
+    let (mut frame, size) = spec.build();
+    let offset = block_offset(&frame, DISTANCE.0);
+    frame[offset + 2] = 0xFF;
+    let mut buffer = into_buffer(&frame);
+    let result = parse_frame_with::<()>(&mut buffer, size, &FrameLayout::new(), |_| nb_blocks += 1);
+    assert!(matches!(result, Err(Error::Parse(ParseError::BlockOverflow))));
+    assert_eq!(nb_blocks, 0);
+}
@@ -0,0 +1,42 @@
+use vl53l5cx::consts::*;
+use vl53l5cx::grid::{to_grid, DEPTH_IMAGE_INVALID};
+use vl53l5cx::ResultsData;
+
+#[test]
+fn distance_grid_is_row_major() {
+    let mut results = ResultsData::new();
+    for zone in 0..64 {
+        results.distance_mm[VL53L5CX_NB_TARGET_PER_ZONE as usize * zone] = zone as i16;
+        results.target_status[VL53L5CX_NB_TARGET_PER_ZONE as usize * zone] = 5;
+    }
+
+    let grid = results.distance_grid::<8>();
+    assert_eq!(grid[0][0], 0);
+    assert_eq!(grid[1][0], 8);
+    assert_eq!(grid[7][3], 59);
+
+    let grid = results.distance_grid::<4>();
+    assert_eq!(grid[3][3], 15);
+    assert_eq!(results.target_status_grid::<4>(), [[5; 4]; 4]);
+}
+
+#[test]
+fn per_zone_values_use_stride_one() {
+    let values: [u32; 16] = core::array::from_fn(|i| i as u32 * 10);
+    let grid: [[u32; 4]; 4] = to_grid(&values, 1);
+    assert_eq!(grid[2][1], 90);
+}
+
+#[test]
+fn depth_image_is_scaled_and_clamped() {
+    let mut results = ResultsData::new();
+    for (zone, distance) in [(0, 50), (1, 100), (2, 1050), (3, 2000), (4, 3000), (5, 1000)] {
+        results.distance_mm[VL53L5CX_NB_TARGET_PER_ZONE as usize * zone] = distance;
+        results.target_status[VL53L5CX_NB_TARGET_PER_ZONE as usize * zone] = if zone == 5 { 255 } else { 5 };
+    }
+
+    let image = results.to_depth_image_u8::<8>(100, 2000);
+    assert_eq!(image[0][..6], [1, 1, 128, 255, 255, DEPTH_IMAGE_INVALID]);
+    // Zones without target
+    assert_eq!(image[7], [DEPTH_IMAGE_INVALID; 8]);
+}
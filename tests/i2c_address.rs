@@ -0,0 +1,91 @@
+mod common;
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+
+use embedded_hal::digital::{ErrorType as PinErrorType, OutputPin, StatefulOutputPin};
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+
+use common::mock::*;
+use vl53l5cx::{Error, Vl53l5cx};
+
+/// I2C bus logging the writes of the I2C address register.
+struct LogI2c<'a>(&'a RefCell<Vec<String>>);
+
+impl ErrorType for LogI2c<'_> {
+    type Error = Infallible;
+}
+
+impl I2c for LogI2c<'_> {
+    fn transaction(&mut self, _address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Infallible> {
+        for operation in operations {
+            match operation {
+                Operation::Write(wbuf) if wbuf.len() == 3 && wbuf[..2] == [0x00, 0x04] => {
+                    self.0.borrow_mut().push(format!("address {:#x}", wbuf[2]));
+                }
+                Operation::Write(_) => {}
+                Operation::Read(rbuf) => rbuf.fill(0),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// LPn pin of another sensor, logging its changes.
+struct LogPin<'a> {
+    name: &'static str,
+    high: bool,
+    log: &'a RefCell<Vec<String>>,
+}
+
+impl PinErrorType for LogPin<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for LogPin<'_> {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.high = false;
+        self.log.borrow_mut().push(format!("{} low", self.name));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.high = true;
+        self.log.borrow_mut().push(format!("{} high", self.name));
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for LogPin<'_> {
+    fn is_set_high(&mut self) -> Result<bool, Infallible> {
+        Ok(self.high)
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Infallible> {
+        Ok(!self.high)
+    }
+}
+
+#[test]
+fn powered_down_sensors_stay_powered_down() {
+    let log: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    let mut left = LogPin { name: "left", high: true, log: &log };
+    let mut right = LogPin { name: "right", high: false, log: &log };
+    let mut sensor = Vl53l5cx::new_i2c(LogI2c(&log), NoPin, NoPin, NoDelay).unwrap();
+
+    sensor.set_i2c_address_exclusive(0x30, &mut [&mut left, &mut right]).unwrap();
+
+    assert_eq!(*log.borrow(), vec!["left low", "address 0x30", "left high"]);
+    assert!(left.high);
+    assert!(!right.high);
+}
+
+#[test]
+fn too_many_pins_are_rejected() {
+    let log: RefCell<Vec<String>> = RefCell::new(Vec::new());
+    let mut pins: Vec<LogPin<'_>> = (0..129).map(|_| LogPin { name: "other", high: true, log: &log }).collect();
+    let mut sensor = Vl53l5cx::new_i2c(LogI2c(&log), NoPin, NoPin, NoDelay).unwrap();
+
+    assert!(matches!(sensor.set_i2c_address_exclusive(0x30, &mut pins), Err(Error::InvalidParam)));
+    assert!(log.borrow().is_empty());
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use std::cell::RefCell;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::config::InitStrategy;
+use vl53l5cx::{Error, InitStage, Vl53l5cx};
+
+const DCI_FW_VERSION: u16 = 0x5440;
+
+thread_local! {
+    static STAGES: RefCell<Vec<InitStage>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(stage: InitStage) {
+    STAGES.with(|stages| stages.borrow_mut().push(stage));
+}
+
+fn stages() -> Vec<InitStage> {
+    STAGES.with(|stages| stages.take())
+}
+
+/// Sensor already running the firmware `major.minor.build`.
+fn warm_sensor(major: u8, minor: u8, build: u16) -> SimSensor {
+    let mut sim = SimSensor::new(2, 1);
+    let raw: u32 = (major as u32) << 24 | (minor as u32) << 16 | build as u32;
+    sim.set_dci(DCI_FW_VERSION, &raw.to_le_bytes());
+    sim
+}
+
+#[test]
+fn upload_is_skipped_when_firmware_is_running() {
+    let mut sim = warm_sensor(1, 3, 11);
+    {
+        let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+        sensor.set_init_observer(Some(record));
+        sensor.set_init_strategy(InitStrategy::IfNeeded);
+
+        sensor.init().unwrap();
+    }
+
+    assert_eq!(stages(), vec![InitStage::FwCheck, InitStage::NvmRead, InitStage::XtalkSent,
+        InitStage::ConfigSent, InitStage::Done]);
+    assert_eq!(sim.firmware_bytes(), 0);
+}
+
+#[test]
+fn other_firmware_is_uploaded_again() {
+    let mut sim = warm_sensor(1, 2, 0);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_init_observer(Some(record));
+    sensor.set_init_strategy(InitStrategy::IfNeeded);
+
+    // The simulated sensor does not boot after the reboot
+    assert!(matches!(sensor.init(), Err(Error::Timeout)));
+    assert_eq!(stages(), vec![InitStage::FwCheck, InitStage::Reboot]);
+}
+
+#[test]
+fn firmware_is_always_uploaded_by_default() {
+    let mut sim = warm_sensor(1, 3, 11);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_init_observer(Some(record));
+
+    assert!(sensor.init().is_err());
+    assert_eq!(stages(), vec![InitStage::Reboot]);
+}
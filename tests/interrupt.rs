@@ -0,0 +1,156 @@
+mod common;
+
+use std::cell::{Cell, RefCell};
+use std::convert::Infallible;
+use std::ops::Range;
+
+use embedded_hal::digital::{ErrorKind, ErrorType, InputPin};
+use embedded_hal::i2c::{ErrorType as I2cErrorType, I2c, Operation, SevenBitAddress};
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::delay::SleepUs;
+use vl53l5cx::interrupt::DataReadyInterrupt;
+use vl53l5cx::{Error, Vl53l5cx};
+
+/// Bus on the simulated sensor shared with the test, counting the transactions.
+struct SharedSim<'a>(&'a RefCell<SimSensor>, &'a Cell<u32>);
+
+impl I2cErrorType for SharedSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for SharedSim<'_> {
+    fn transaction(&mut self, address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.1.set(self.1.get() + 1);
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+/// INT pin low for the reads listed in `low`, counted from 0.
+struct IntPin {
+    reads: u32,
+    low: Vec<u32>,
+}
+
+impl ErrorType for IntPin {
+    type Error = Infallible;
+}
+
+impl InputPin for IntPin {
+    fn is_high(&mut self) -> Result<bool, Infallible> {
+        self.is_low().map(|low| !low)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Infallible> {
+        self.reads += 1;
+        Ok(self.low.contains(&(self.reads - 1)))
+    }
+}
+
+/// INT pin low during `low`, in us of the clock advanced by the delays of the driver.
+struct TimedPin<'a> {
+    clock: &'a Cell<u64>,
+    low: Range<u64>,
+}
+
+impl ErrorType for TimedPin<'_> {
+    type Error = Infallible;
+}
+
+impl InputPin for TimedPin<'_> {
+    fn is_high(&mut self) -> Result<bool, Infallible> {
+        self.is_low().map(|low| !low)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Infallible> {
+        Ok(self.low.contains(&self.clock.get()))
+    }
+}
+
+/// INT pin which can not be read.
+struct BrokenPin;
+
+impl ErrorType for BrokenPin {
+    type Error = ErrorKind;
+}
+
+impl InputPin for BrokenPin {
+    fn is_high(&mut self) -> Result<bool, ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+
+    fn is_low(&mut self) -> Result<bool, ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+}
+
+#[test]
+fn bus_is_only_read_on_the_interrupt() {
+    let sim = RefCell::new(SimSensor::new(1, 1));
+    let transactions = Cell::new(0);
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim, &transactions), NoPin, NoPin, NoDelay).unwrap();
+    sensor.start_ranging().unwrap();
+    let started: u32 = transactions.get();
+
+    // A pulse of 3 reads at the read 50: one data ready poll, then the frame
+    let mut irq = DataReadyInterrupt::new(&mut sensor, IntPin { reads: 0, low: vec![50, 51, 52, 80] });
+    irq.wait_for_data_ready(1000).unwrap();
+    assert_eq!(transactions.get() - started, 1);
+    let results = irq.get_ranging_data().unwrap();
+    assert_eq!(SimSensor::frame_number(results.distance_mm[0]), 1);
+
+    // The end of the pulse does not poll the bus again
+    let before: u32 = transactions.get();
+    irq.wait_for_data_ready(1000).unwrap();
+    assert_eq!(transactions.get() - before, 1);
+    assert_eq!(irq.release().reads, 81);
+}
+
+#[test]
+fn wait_times_out_without_interrupt() {
+    let sim = RefCell::new(SimSensor::new(1, 1));
+    let transactions = Cell::new(0);
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim, &transactions), NoPin, NoPin, NoDelay).unwrap();
+    sensor.start_ranging().unwrap();
+    let started: u32 = transactions.get();
+
+    let mut irq = DataReadyInterrupt::new(&mut sensor, IntPin { reads: 0, low: vec![] });
+    irq.set_poll_interval_us(100);
+    assert!(matches!(irq.wait_for_data_ready(5), Err(Error::Timeout)));
+    assert_eq!(transactions.get(), started);
+    assert_eq!(irq.release().reads, 51);
+}
+
+#[test]
+fn pulse_shorter_than_the_poll_interval_is_missed() {
+    let sim = RefCell::new(SimSensor::new(1, 1));
+    let transactions = Cell::new(0);
+    let clock = Cell::new(0u64);
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim, &transactions), NoPin, NoPin, SleepUs::new(|us| clock.set(clock.get() + us as u64))).unwrap();
+    sensor.start_ranging().unwrap();
+
+    // 100us pulse between two reads of the pin, 1ms apart
+    let start: u64 = clock.get();
+    let mut irq = DataReadyInterrupt::new(&mut sensor, TimedPin { clock: &clock, low: start + 2500..start + 2600 });
+    irq.set_poll_interval_us(1000);
+    assert!(matches!(irq.wait_for_data_ready(5), Err(Error::Timeout)));
+    irq.release();
+
+    // Same pulse, read every 50us
+    let start: u64 = clock.get();
+    let mut irq = DataReadyInterrupt::new(&mut sensor, TimedPin { clock: &clock, low: start + 2500..start + 2600 });
+    irq.set_poll_interval_us(50);
+    irq.wait_for_data_ready(5).unwrap();
+}
+
+#[test]
+fn pin_error_is_reported() {
+    let sim = RefCell::new(SimSensor::new(1, 1));
+    let transactions = Cell::new(0);
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim, &transactions), NoPin, NoPin, NoDelay).unwrap();
+    sensor.start_ranging().unwrap();
+
+    let mut irq = DataReadyInterrupt::new(&mut sensor, BrokenPin);
+    assert!(matches!(irq.wait_for_data_ready(5), Err(Error::Pin)));
+}
@@ -0,0 +1,29 @@
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::material::*;
+use vl53l5cx::ResultsData;
+
+#[test]
+fn targets_are_bucketed() {
+    let thresholds = MaterialThresholds::default();
+    assert_eq!(classify_material(120, 100, 5, &thresholds), Material::RetroReflective);
+    assert_eq!(classify_material(40, 8000, 5, &thresholds), Material::RetroReflective);
+    assert_eq!(classify_material(80, 100, 9, &thresholds), Material::Bright);
+    assert_eq!(classify_material(30, 100, 5, &thresholds), Material::Medium);
+    assert_eq!(classify_material(10, 100, 5, &thresholds), Material::Dark);
+    assert_eq!(classify_material(2, 5, 5, &thresholds), Material::GlassLikely);
+    assert_eq!(classify_material(80, 100, 255, &thresholds), Material::Unknown);
+}
+
+#[test]
+fn zones_use_first_target() {
+    let mut results = ResultsData::new();
+    let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * 3;
+    results.reflectance[idx] = 60;
+    results.signal_per_spad[idx] = 200;
+    results.target_status[idx] = 5;
+
+    let materials = classify_zones(&results, Resolution::Res4x4, &MaterialThresholds::default());
+    assert_eq!(materials[3], Material::Bright);
+    assert_eq!(materials[0], Material::Unknown);
+}
@@ -0,0 +1,60 @@
+#![cfg(not(feature = "VL53L5CX_DISABLE_MOTION_INDICATOR"))]
+
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::consts::*;
+use vl53l5cx::{Error, Vl53l5cx};
+
+const DCI_MOTION_DETECTOR_CFG: u16 = 0xBFAC;
+
+/// Offset of map_id in the motion indicator configuration.
+const MAP_ID: usize = 28;
+
+#[test]
+fn set_resolution_updates_the_motion_indicator_map() {
+    let mut sim = SimSensor::new(1, 1);
+    // 4x4 map, with a working distance which is not the default one
+    let mut config: Vec<u8> = vec![0; 156];
+    config[..4].copy_from_slice(&1234i32.to_le_bytes());
+    for (i, id) in config[MAP_ID..MAP_ID + 64].iter_mut().enumerate() {
+        *id = if i < 16 { i as u8 } else { 0xff };
+    }
+    sim.set_dci(DCI_MOTION_DETECTOR_CFG, &config);
+
+    {
+        let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+        sensor.set_resolution(VL53L5CX_RESOLUTION_8X8).unwrap();
+    }
+
+    let config: &[u8] = sim.dci(DCI_MOTION_DETECTOR_CFG).unwrap();
+    // 8x8 map: 2x2 zones aggregated
+    for (i, &id) in config[MAP_ID..MAP_ID + 64].iter().enumerate() {
+        assert_eq!(id as i8, (i as i8 % 8) / 2 + 4 * (i as i8 / 16), "zone {}", i);
+    }
+    // Other settings are kept
+    assert_eq!(config[..4], 1234i32.to_le_bytes());
+}
+
+#[test]
+fn motion_indicator_output_is_dropped_from_the_frames() {
+    let mut sim = SimSensor::new(1000, 1);
+    {
+        let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+        sensor.start_ranging().unwrap();
+        assert!(matches!(sensor.set_motion_indicator_output(false), Err(Error::InvalidState)));
+        sensor.stop_ranging().unwrap();
+        sensor.set_motion_indicator_output(false).unwrap();
+        sensor.start_ranging().unwrap();
+        sensor.stop_ranging().unwrap();
+    }
+    let disabled: usize = sim.data_read_size;
+
+    {
+        let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+        sensor.start_ranging().unwrap();
+    }
+    // Motion indicator block: header and 140 bytes
+    assert_eq!(sim.data_read_size - disabled, 144);
+}
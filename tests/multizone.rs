@@ -0,0 +1,64 @@
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::config::{FwVersion, InitStrategy, Resolution, SensorVariant, VariantBlobs};
+use vl53l5cx::consts::{VL53L5CX_CONFIGURATION_SIZE, VL53L5CX_XTALK_BUFFER_SIZE};
+use vl53l5cx::firmware::VL53L5CX_FIRMWARE_SIZE;
+use vl53l5cx::multizone::MultizoneTof;
+use vl53l5cx::{Error, Vl53l5cx};
+
+const DCI_FW_VERSION: u16 = 0x5440;
+
+// Placeholders for the buffers of the VL53L8CX ULD
+static FIRMWARE: [u8; VL53L5CX_FIRMWARE_SIZE] = [0; VL53L5CX_FIRMWARE_SIZE];
+static CONFIGURATION: [u8; VL53L5CX_CONFIGURATION_SIZE] = [0; VL53L5CX_CONFIGURATION_SIZE];
+static XTALK: [u8; VL53L5CX_XTALK_BUFFER_SIZE] = [0; VL53L5CX_XTALK_BUFFER_SIZE];
+
+const VL53L8CX: VariantBlobs = VariantBlobs {
+    firmware: &FIRMWARE,
+    default_configuration: &CONFIGURATION,
+    default_xtalk: &XTALK,
+    fw_version: FwVersion { major: 3, minor: 1, build: 7 }
+};
+
+/// Application code generic over the part: the distance of the first zone of `n` frames.
+fn first_zone<S: MultizoneTof>(sensor: &mut S, n: usize) -> Result<Vec<i16>, S::Error> {
+    sensor.init()?;
+    sensor.set_resolution(Resolution::Res4x4)?;
+    sensor.start_ranging()?;
+    let mut distances: Vec<i16> = Vec::new();
+    while distances.len() < n {
+        if sensor.check_data_ready()? {
+            distances.push(sensor.get_ranging_data()?.distance_mm[0]);
+        }
+    }
+    sensor.stop_ranging()?;
+
+    Ok(distances)
+}
+
+#[test]
+fn generic_code_drives_the_vl53l8cx() {
+    let mut sim = SimSensor::new(2, 1);
+    let raw: u32 = 3 << 24 | 1 << 16 | 7;
+    sim.set_dci(DCI_FW_VERSION, &raw.to_le_bytes());
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_sensor_variant(SensorVariant::Vl53l8cx(VL53L8CX));
+    sensor.set_init_strategy(InitStrategy::IfNeeded);
+
+    assert!(matches!(sensor.variant(), SensorVariant::Vl53l8cx(_)));
+    let distances: Vec<i16> = first_zone(&mut sensor, 3).unwrap();
+    let frames: Vec<u32> = distances.into_iter().map(SimSensor::frame_number).collect();
+    assert_eq!(frames, vec![1, 2, 3]);
+}
+
+#[test]
+fn vl53l8cx_revision_id_is_expected() {
+    let mut sim = SimSensor::new(2, 1);
+    sim.set_identity(0xF0, 0x0C);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    assert!(matches!(sensor.probe_identity(), Err(Error::IdMismatch { device_id: 0xF0, revision_id: 0x0C })));
+    sensor.set_sensor_variant(SensorVariant::Vl53l8cx(VL53L8CX));
+    sensor.probe_identity().unwrap();
+}
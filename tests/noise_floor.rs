@@ -0,0 +1,56 @@
+#![cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::noise_floor::NoiseFloorEstimator;
+use vl53l5cx::ResultsData;
+
+/// 4x4 frame at 1000mm, zone 1 alternating by +/- `swing_mm`, zone 2 without target.
+fn frame(n: u32, swing_mm: i16) -> ResultsData {
+    let mut results = ResultsData::new();
+    for zone in 0..16 {
+        let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        results.distance_mm[idx] = match (zone, n % 2) {
+            (1, 0) => 1000 + swing_mm,
+            (1, _) => 1000 - swing_mm,
+            _ => 1000
+        };
+        results.target_status[idx] = if zone == 2 { 255 } else { 5 };
+    }
+    results
+}
+
+#[test]
+fn std_dev_is_measured_per_zone() {
+    let mut noise = NoiseFloorEstimator::new();
+    for n in 0..100 {
+        noise.update(&frame(n, 10), Resolution::Res4x4);
+    }
+
+    assert_eq!(noise.nb_frames(), 100);
+    assert_eq!(noise.mean_mm(1), Some(1000));
+    // Sample standard deviation of +/-10 over 100 frames
+    assert_eq!(noise.std_dev_mm(1), Some(10));
+    assert_eq!(noise.std_dev_mm(0), Some(0));
+    assert_eq!(noise.nb_samples(2), 0);
+    assert_eq!(noise.std_dev_mm(2), None);
+    assert_eq!(noise.std_dev_mm(20), None);
+}
+
+#[test]
+fn thresholds_are_suggested_from_the_noisiest_zone() {
+    let mut noise = NoiseFloorEstimator::new();
+    for n in 0..10 {
+        noise.update(&frame(n, 20), Resolution::Res4x4);
+    }
+
+    let thresholds = noise.suggested_sigma_thresholds_mm(3, 10);
+    assert_eq!(thresholds[0], Some(0));
+    assert_eq!(thresholds[1], Some(63));
+    assert_eq!(thresholds[2], None);
+    assert_eq!(noise.suggested_sigma_threshold_mm(3, 10), Some(63));
+    assert_eq!(noise.suggested_sigma_threshold_mm(3, 11), None);
+
+    noise.reset();
+    assert_eq!(noise.suggested_sigma_threshold_mm(3, 2), None);
+}
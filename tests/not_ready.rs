@@ -0,0 +1,40 @@
+mod common;
+
+use std::cell::RefCell;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::{Error, Vl53l5cx};
+
+/// Bus shared between the driver and the test, which reads the counters of the simulated sensor.
+struct SharedSim<'a>(&'a RefCell<SimSensor>);
+
+impl ErrorType for SharedSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for SharedSim<'_> {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+#[test]
+fn frame_is_not_read_when_not_ready() {
+    let sim = RefCell::new(SimSensor::new(3, 1));
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim), NoPin, NoPin, NoDelay).unwrap();
+    sensor.start_ranging().unwrap();
+
+    // Only the UI status is read
+    let read_bytes = sim.borrow().read_bytes;
+    assert!(matches!(sensor.get_ranging_data(), Err(Error::NotReady)));
+    assert_eq!(sim.borrow().read_bytes - read_bytes, 4);
+
+    // The status read by check_data_ready() is not read again
+    while !sensor.check_data_ready().unwrap() {}
+    let read_bytes = sim.borrow().read_bytes;
+    assert_eq!(SimSensor::frame_number(sensor.get_ranging_data().unwrap().distance_mm[0]), 1);
+    assert_eq!(sim.borrow().read_bytes - read_bytes, sim.borrow().data_read_size);
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use common::NB_TARGET;
+use vl53l5cx::xtalk::CalibrationError;
+use vl53l5cx::{Error, Vl53l5cx};
+
+/// Distance in mm of the first target of a zone in the frame `n` of the simulated sensor, 
+/// after the negative distances are clamped to 0.
+fn first_target_mm(n: u32, zone: usize) -> i32 {
+    let t: usize = NB_TARGET * zone;
+    if t == 0 {
+        n as i32 + 1
+    } else if t % 7 == 6 {
+        0
+    } else {
+        t as i32 * 10 + 50
+    }
+}
+
+#[test]
+fn offset_is_averaged_over_the_valid_zones() {
+    let mut sim = SimSensor::new(3, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+
+    let report = sensor.verify_offset(300, 2, 10).unwrap();
+
+    // Zones 4, 9 and 14 have no target
+    let zones: Vec<usize> = (0..16).filter(|z| z % 5 != 4).collect();
+    let sum: i32 = (1..=2).flat_map(|n| zones.iter().map(move |&z| first_target_mm(n, z))).sum();
+    let measured_mm: i16 = (sum / (2 * zones.len() as i32)) as i16;
+    assert_eq!(report.nb_valid_measurements, 2 * zones.len() as u32);
+    assert_eq!(report.measured_mm, measured_mm);
+    assert_eq!(report.deviation_mm, measured_mm - 300);
+    assert_eq!(report.recalibration_advised, (measured_mm - 300).abs() > 10);
+}
+
+#[test]
+fn offset_check_arguments_are_checked() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+
+    assert!(matches!(sensor.verify_offset(0, 4, 10), Err(Error::Calibration(CalibrationError::InvalidDistance))));
+    assert!(matches!(sensor.verify_offset(4001, 4, 10), Err(Error::Calibration(CalibrationError::InvalidDistance))));
+    assert!(matches!(sensor.verify_offset(600, 0, 10), Err(Error::Calibration(CalibrationError::InvalidNbSamples))));
+    assert!(matches!(sensor.verify_offset(600, 17, 10), Err(Error::Calibration(CalibrationError::InvalidNbSamples))));
+}
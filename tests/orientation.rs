@@ -0,0 +1,112 @@
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::orientation::*;
+use vl53l5cx::xtalk::CalibrationError;
+use vl53l5cx::ResultsData;
+
+/// 8x8 frame of a wall at `distance_mm` along the sensor axis, the sensor
+/// being turned by `yaw_deg` and `pitch_deg`.
+fn wall(distance_mm: f64, yaw_deg: f64, pitch_deg: f64) -> ResultsData {
+    let a: f64 = yaw_deg.to_radians().tan();
+    let b: f64 = pitch_deg.to_radians().tan();
+    let mut results = ResultsData::new();
+    for zone in 0..64 {
+        let tan_x: f64 = (((zone % 8) as f64 - 3.5) * 45.0 / 8.0).to_radians().tan();
+        let tan_y: f64 = (((zone / 8) as f64 - 3.5) * 45.0 / 8.0).to_radians().tan();
+        // Intersection of the zone direction with the plane z = a*x + b*y + distance
+        let norm: f64 = (1.0 + tan_x * tan_x + tan_y * tan_y).sqrt();
+        let range: f64 = distance_mm * norm / (1.0 - a * tan_x - b * tan_y);
+        let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        results.distance_mm[idx] = range.round() as i16;
+        results.target_status[idx] = 5;
+    }
+    results
+}
+
+#[test]
+fn straight_mounting_needs_no_correction() {
+    let correction = estimate_mounting_correction(&wall(1000.0, 0.0, 0.0), Resolution::Res8x8).unwrap();
+
+    assert!(correction.yaw_deg.abs() < 0.1 && correction.pitch_deg.abs() < 0.1);
+    assert!((correction.distance_mm - 1000.0).abs() < 2.0);
+    assert!(correction.residual_mm < 1.0);
+}
+
+#[test]
+fn correction_compensates_the_mounting_angles() {
+    let correction = estimate_mounting_correction(&wall(1000.0, 10.0, -5.0), Resolution::Res8x8).unwrap();
+
+    assert!((correction.yaw_deg + 10.0).abs() < 0.2, "yaw {}", correction.yaw_deg);
+    assert!((correction.pitch_deg - 5.0).abs() < 0.2, "pitch {}", correction.pitch_deg);
+    assert!(correction.residual_mm < 1.0);
+}
+
+#[test]
+fn zones_without_target_are_ignored() {
+    let mut results = wall(800.0, -15.0, 0.0);
+    for zone in 0..64 {
+        if zone % 3 == 0 {
+            let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+            results.distance_mm[idx] = 50;
+            results.target_status[idx] = 255;
+        }
+    }
+    let correction = estimate_mounting_correction(&results, Resolution::Res8x8).unwrap();
+
+    assert!((correction.yaw_deg - 15.0).abs() < 0.2, "yaw {}", correction.yaw_deg);
+}
+
+#[test]
+fn too_few_valid_zones_are_rejected() {
+    let mut results = wall(1000.0, 0.0, 0.0);
+    for zone in 0..40 {
+        results.target_status[VL53L5CX_NB_TARGET_PER_ZONE as usize * zone] = 255;
+    }
+
+    assert_eq!(estimate_mounting_correction(&results, Resolution::Res8x8), Err(CalibrationError::NotEnoughZones));
+}
+
+#[test]
+fn scene_not_flat_has_residual() {
+    let mut results = wall(1000.0, 0.0, 0.0);
+    for zone in 0..32 {
+        results.distance_mm[VL53L5CX_NB_TARGET_PER_ZONE as usize * zone] -= 300;
+    }
+    let correction = estimate_mounting_correction(&results, Resolution::Res8x8).unwrap();
+
+    assert!(correction.residual_mm > 50.0);
+}
+
+#[test]
+fn zone_point_follows_the_zone_direction() {
+    let [x, y, z] = zone_point(Resolution::Res4x4, 3, 1000);
+
+    assert!(x > 0.0 && y < 0.0 && z < 1000.0);
+    assert!((x * x + y * y + z * z - 1000.0 * 1000.0).abs() < 1000.0);
+}
+
+#[test]
+fn nearest_obstacle_points_at_the_closest_valid_zone() {
+    let mut results = wall(1000.0, 0.0, 0.0);
+    let idx = |zone: usize| VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+    // Invalid closer target, then the obstacle at the bottom left
+    results.distance_mm[idx(10)] = 200;
+    results.target_status[idx(10)] = 255;
+    results.distance_mm[idx(56)] = 350;
+
+    let obstacle = nearest_obstacle(&results, Resolution::Res8x8).unwrap();
+    assert_eq!((obstacle.zone, obstacle.distance_mm), (56, 350));
+    let [x, y, z] = obstacle.direction;
+    assert!(x < 0.0 && y > 0.0 && z > 0.0);
+    assert!((x * x + y * y + z * z - 1.0).abs() < 1e-3);
+    let [px, py, pz] = zone_point(Resolution::Res8x8, 56, 350);
+    assert!((px - 350.0 * x).abs() < 0.5 && (py - 350.0 * y).abs() < 0.5 && (pz - 350.0 * z).abs() < 0.5);
+}
+
+#[test]
+fn no_obstacle_without_valid_target() {
+    let mut results = wall(1000.0, 0.0, 0.0);
+    results.target_status.fill(255);
+
+    assert_eq!(nearest_obstacle(&results, Resolution::Res8x8), None);
+}
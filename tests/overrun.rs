@@ -0,0 +1,53 @@
+mod common;
+
+use std::cell::RefCell;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::{Error, Vl53l5cx};
+
+/// Bus shared between the driver and the test, which reads the counters of the simulated sensor.
+struct SharedSim<'a>(&'a RefCell<SimSensor>);
+
+impl ErrorType for SharedSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for SharedSim<'_> {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+#[test]
+fn overwritten_frame_read_is_aborted() {
+    // A frame is published at each stream count read, i.e. while the frame is read
+    let sim = RefCell::new(SimSensor::new(1, 1));
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim), NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_overrun_check(true);
+    sensor.start_ranging().unwrap();
+
+    while !sensor.check_data_ready().unwrap() {}
+    let read_bytes = sim.borrow().read_bytes;
+    assert!(matches!(sensor.get_ranging_data(), Err(Error::CorruptedFrame)));
+    // First 256 bytes of the frame, and the stream count
+    assert_eq!(sim.borrow().read_bytes - read_bytes, 256 + 1);
+    assert_eq!(sensor.stats().corrupted_frames, 1);
+}
+
+#[test]
+fn frame_read_is_checked_without_overrun() {
+    // Longer than the stream count reads of the largest frame (4 targets per zone)
+    let sim = RefCell::new(SimSensor::new(16, 1));
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim), NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_overrun_check(true);
+    sensor.start_ranging().unwrap();
+
+    for n in 1..=3 {
+        while !sensor.check_data_ready().unwrap() {}
+        assert_eq!(SimSensor::frame_number(sensor.get_ranging_data().unwrap().distance_mm[0]), n);
+    }
+    assert_eq!(sensor.stats().corrupted_frames, 0);
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::sync::Mutex;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorKind, ErrorType, OutputPin};
+
+use common::mock::*;
+use vl53l5cx::config::PowerTimings;
+use vl53l5cx::{Error, Vl53l5cx};
+
+static RAIL: Mutex<Vec<bool>> = Mutex::new(Vec::new());
+
+fn power_rail(on: bool) {
+    RAIL.lock().unwrap().push(on);
+}
+
+#[test]
+fn power_rail_follows_on_and_off() {
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+    sensor.on().unwrap();
+    sensor.set_power_rail(Some(power_rail));
+    sensor.off().unwrap();
+    sensor.on().unwrap();
+    sensor.set_power_rail(None);
+    sensor.off().unwrap();
+
+    assert_eq!(*RAIL.lock().unwrap(), vec![false, true]);
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Event {
+    Rail(bool),
+    Lpn(bool),
+    Rst(bool),
+    WaitUs(u32),
+}
+
+thread_local! {
+    static EVENTS: RefCell<Vec<Event>> = const { RefCell::new(Vec::new()) };
+}
+
+fn log(event: Event) {
+    EVENTS.with(|events| events.borrow_mut().push(event));
+}
+
+fn events() -> Vec<Event> {
+    EVENTS.with(|events| events.take())
+}
+
+fn logged_rail(on: bool) {
+    log(Event::Rail(on));
+}
+
+/// Pin logging its level, LPn or I2C_RST.
+struct LogPin(fn(bool) -> Event);
+
+impl ErrorType for LogPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for LogPin {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        log((self.0)(false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        log((self.0)(true));
+        Ok(())
+    }
+}
+
+struct LogDelay;
+
+impl DelayNs for LogDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        log(Event::WaitUs(ns / 1000));
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        log(Event::WaitUs(us));
+    }
+}
+
+#[test]
+fn power_sequence_follows_the_timings() {
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, LogPin(Event::Lpn), LogPin(Event::Rst), LogDelay).unwrap();
+    sensor.set_power_rail(Some(logged_rail));
+
+    sensor.off().unwrap();
+    sensor.on().unwrap();
+    assert_eq!(events(), vec![
+        Event::Lpn(false), Event::WaitUs(10_000), Event::Rail(false), Event::WaitUs(100_000),
+        Event::Rst(false), Event::Rail(true), Event::WaitUs(1_000), Event::Lpn(true), Event::WaitUs(100_000),
+    ]);
+
+    sensor.set_power_rail(None);
+    sensor.set_power_timings(PowerTimings { boot_us: 20_000, lpn_low_us: 500, ..PowerTimings::default() });
+    sensor.off().unwrap();
+    sensor.on().unwrap();
+    assert_eq!(events(), vec![
+        Event::Lpn(false), Event::WaitUs(500),
+        Event::Rst(false), Event::Lpn(true), Event::WaitUs(20_000),
+    ]);
+}
+
+/// Pin which can not be set.
+struct BrokenPin;
+
+impl ErrorType for BrokenPin {
+    type Error = ErrorKind;
+}
+
+impl OutputPin for BrokenPin {
+    fn set_low(&mut self) -> Result<(), ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+
+    fn set_high(&mut self) -> Result<(), ErrorKind> {
+        Err(ErrorKind::Other)
+    }
+}
+
+#[test]
+fn pin_errors_are_returned() {
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, BrokenPin, NoPin, NoDelay).unwrap();
+    assert!(matches!(sensor.on(), Err(Error::Pin)));
+    assert!(matches!(sensor.off(), Err(Error::Pin)));
+
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, BrokenPin, NoDelay).unwrap();
+    assert!(matches!(sensor.on(), Err(Error::Pin)));
+    sensor.off().unwrap();
+}
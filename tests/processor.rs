@@ -0,0 +1,30 @@
+use vl53l5cx::processor::*;
+
+fn pass() -> impl FnMut(&u32) -> Option<u32> {
+    |value: &u32| Some(*value)
+}
+
+#[test]
+fn chain_gives_the_outputs_to_the_next_stage() {
+    let even = |value: &u32| if value & 1 == 0 { Some(*value) } else { None };
+    let mut pipeline = even.chain(|value: &u32| Some(value * 10));
+
+    let outputs: Vec<Option<u32>> = (0..4).map(|i| pipeline.process(&i)).collect();
+    assert_eq!(outputs, vec![Some(0), None, Some(20), None]);
+}
+
+#[test]
+fn throttle_keeps_one_output_out_of_every() {
+    let mut pipeline = pass().throttle(3);
+
+    let outputs: Vec<Option<u32>> = (0..7).map(|i| pipeline.process(&i)).collect();
+    assert_eq!(outputs, vec![Some(0), None, None, Some(3), None, None, Some(6)]);
+}
+
+#[test]
+fn changed_only_drops_repeated_outputs() {
+    let mut pipeline = pass().changed_only();
+
+    let outputs: Vec<Option<u32>> = [1, 1, 2, 2, 1].iter().map(|i| pipeline.process(i)).collect();
+    assert_eq!(outputs, vec![Some(1), None, Some(2), None, Some(1)]);
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::bus_operation::Vl53l5cxI2C;
+use vl53l5cx::{Error, Vl53l5cx};
+
+type SimDriver<'a> = Vl53l5cx<Vl53l5cxI2C<&'a mut SimSensor>, NoPin, NoPin, NoDelay>;
+
+/// Reads frames until the frame `last` is read, and fails with a user error if it is reached.
+fn read_until(sensor: &mut SimDriver<'_>, last: u32) -> Result<(), &'static str> {
+    let mut session = sensor.start_ranging_session().map_err(|_| "start")?;
+    loop {
+        if session.check_data_ready().map_err(|_| "check")? {
+            let results = session.get_ranging_data().map_err(|_| "read")?;
+            if SimSensor::frame_number(results.distance_mm[0]) == last {
+                return Err("last frame");
+            }
+        }
+    }
+}
+
+#[test]
+fn session_is_stopped_when_dropped() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+
+    // Early return from the user code while the sensor streams
+    assert_eq!(read_until(&mut sensor, 3), Err("last frame"));
+    assert!(!sensor.is_ranging());
+}
+
+#[test]
+fn session_is_stopped_once() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+
+    let session = sensor.start_ranging_session().unwrap();
+    assert!(session.is_ranging());
+    session.stop().unwrap();
+    assert!(!sensor.is_ranging());
+    // The guard does not stop the next session
+    sensor.start_ranging().unwrap();
+    assert!(sensor.is_ranging());
+}
+
+#[test]
+fn start_and_stop_out_of_order_are_rejected() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+
+    assert!(matches!(sensor.stop_ranging(), Err(Error::InvalidState)));
+    sensor.start_ranging().unwrap();
+    assert!(matches!(sensor.start_ranging(), Err(Error::InvalidState)));
+    assert!(matches!(sensor.start_ranging_session(), Err(Error::InvalidState)));
+    assert!(sensor.is_ranging());
+    sensor.stop_ranging().unwrap();
+    assert!(matches!(sensor.stop_ranging(), Err(Error::InvalidState)));
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::{Error, Vl53l5cx};
+
+/// Result of a soak run.
+struct Soak {
+    // Frame numbers received, in order
+    received: Vec<u32>,
+    // Frames rejected by the parser
+    rejected: u32,
+    sim: SimSensor,
+    bus_errors: u32,
+    corrupted_frames: u32,
+}
+
+/// Runs a host loop polling the sensor `nb_polls` times, retrying the frame reads
+/// failing on the bus and dropping the frames rejected by the parser.
+fn soak(faults: Faults, nb_polls: u32, seed: u32) -> Soak {
+    let mut sim = SimSensor::new(3, seed);
+    // Faults are only injected once ranging
+    sim.faults = faults;
+    let mut received: Vec<u32> = Vec::new();
+    let mut rejected: u32 = 0;
+    let mut pending: bool = false;
+
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.start_ranging().unwrap();
+
+    for _ in 0..nb_polls {
+        match sensor.check_data_ready() {
+            Ok(ready) => pending |= ready,
+            Err(Error::Bus(Nack)) => continue,
+            Err(e) => panic!("unexpected error {:?}", e),
+        }
+        if !pending {
+            continue;
+        }
+        match sensor.get_ranging_data() {
+            Ok(results) => {
+                received.push(SimSensor::frame_number(results.distance_mm[0]));
+                pending = false;
+            }
+            // The frame is still pending, and read again at the next poll
+            Err(Error::Bus(Nack)) => {}
+            Err(Error::Parse(_)) => {
+                rejected += 1;
+                pending = false;
+            }
+            Err(e) => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    let stats = sensor.stats();
+    Soak { received, rejected, sim, bus_errors: stats.bus_errors, corrupted_frames: stats.corrupted_frames }
+}
+
+#[test]
+fn clean_stream_delivers_every_frame() {
+    let run = soak(Faults::default(), 3000, 1);
+
+    assert_eq!(run.sim.published, 1000);
+    assert_eq!(run.received, (1..=1000).collect::<Vec<u32>>());
+    assert_eq!(run.bus_errors, 0);
+}
+
+#[test]
+fn stream_survives_injected_faults() {
+    let faults = Faults { nack_permille: 20, corrupted_permille: 10, late_permille: 50, go2_permille: 0 };
+    // About 3500 frames, the frame numbers being checked modulo 4000
+    let run = soak(faults, 11000, 0x1234_5678);
+
+    assert!(run.sim.nacks > 100 && run.sim.corrupted > 10);
+    assert_eq!(run.bus_errors, run.sim.nacks);
+    assert_eq!(run.corrupted_frames, run.rejected);
+    assert!(run.rejected <= run.sim.corrupted);
+
+    // Frames are delivered in order, without duplicates or stale frames
+    assert!(run.received.windows(2).all(|w| w[0] < w[1]));
+    // Frames are only lost to corruption, or when overwritten while the bus fails
+    let lost: u32 = run.sim.published - run.received.len() as u32;
+    assert!(lost < run.sim.corrupted + run.sim.nacks);
+    // Most bus errors are recovered by reading the frame again. The bound is on the bus 
+    // errors, as the larger frames of several targets per zone take more transactions.
+    assert!(lost < run.sim.corrupted + run.sim.nacks / 4);
+}
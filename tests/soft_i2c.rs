@@ -0,0 +1,147 @@
+#![cfg(feature = "soft-i2c")]
+
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use vl53l5cx::bus_operation::BusOperation;
+use vl53l5cx::consts::VL53L5CX_DEFAULT_I2C_ADDRESS;
+use vl53l5cx::soft_i2c::{SoftI2c, SoftI2cError};
+
+/// Simulated bus with a device acknowledging the written bytes, 
+/// and answering 0xA5 to the reads.
+#[derive(Default)]
+struct Line {
+    scl: bool,
+    sda: bool,
+    edges: u32,
+    starts: u32,
+    read_mode: bool,
+    ack: bool,
+    bits: Vec<bool>,
+}
+
+impl Line {
+    fn device_level(&self) -> bool {
+        let pos = self.edges % 9;
+        if pos == 0 {
+            // Acknowledge slot, driven by the device for the address and the written bytes
+            return !(self.ack && (!self.read_mode || self.edges == 9));
+        }
+        if self.read_mode && self.edges > 9 {
+            return (0xA5u8 >> (8 - pos)) & 1 != 0;
+        }
+        true
+    }
+
+    fn level(&self) -> bool {
+        self.sda && self.device_level()
+    }
+}
+
+struct Scl(Rc<RefCell<Line>>);
+struct Sda(Rc<RefCell<Line>>);
+struct NoDelay;
+
+impl ErrorType for Scl {
+    type Error = Infallible;
+}
+
+impl OutputPin for Scl {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().scl = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        let mut line = self.0.borrow_mut();
+        if !line.scl {
+            line.scl = true;
+            line.edges += 1;
+            let bit = line.level();
+            line.bits.push(bit);
+            if line.edges == 8 {
+                line.read_mode = bit;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ErrorType for Sda {
+    type Error = Infallible;
+}
+
+impl OutputPin for Sda {
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        let mut line = self.0.borrow_mut();
+        if line.scl && line.sda {
+            // Start condition
+            line.starts += 1;
+            line.edges = 0;
+            line.read_mode = false;
+        }
+        line.sda = false;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.borrow_mut().sda = true;
+        Ok(())
+    }
+}
+
+impl InputPin for Sda {
+    fn is_high(&mut self) -> Result<bool, Infallible> {
+        Ok(self.0.borrow().level())
+    }
+
+    fn is_low(&mut self) -> Result<bool, Infallible> {
+        Ok(!self.0.borrow().level())
+    }
+}
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+fn bus(ack: bool) -> (SoftI2c<Scl, Sda, NoDelay>, Rc<RefCell<Line>>) {
+    let line = Rc::new(RefCell::new(Line { scl: true, sda: true, ack, ..Default::default() }));
+    (SoftI2c::new(Scl(line.clone()), Sda(line.clone()), NoDelay, 5), line)
+}
+
+/// Bytes seen on the bus, without the acknowledge bits and the stop condition.
+fn bytes(bits: &[bool]) -> Vec<u8> {
+    bits.chunks_exact(9).map(|c| c[..8].iter().fold(0u8, |b, &bit| (b << 1) | bit as u8)).collect()
+}
+
+#[test]
+fn write_sends_address_and_bytes() {
+    let (mut bus, line) = bus(true);
+    bus.write(&[0x12, 0x34]).unwrap();
+
+    let line = line.borrow();
+    assert_eq!(line.starts, 1);
+    assert_eq!(bytes(&line.bits), vec![VL53L5CX_DEFAULT_I2C_ADDRESS << 1, 0x12, 0x34]);
+    // Bus released by the stop condition
+    assert!(line.scl && line.sda);
+}
+
+#[test]
+fn write_read_uses_repeated_start() {
+    let (mut bus, line) = bus(true);
+    let mut rbuf = [0u8; 2];
+    bus.write_read(&[0x00, 0x05], &mut rbuf).unwrap();
+
+    assert_eq!(rbuf, [0xA5, 0xA5]);
+    assert_eq!(line.borrow().starts, 2);
+}
+
+#[test]
+fn missing_device_is_nack() {
+    let (mut bus, line) = bus(false);
+    assert_eq!(bus.write(&[0x12]), Err(SoftI2cError::Nack));
+    assert!(line.borrow().sda);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use std::convert::Infallible;
+
+use embedded_hal::spi::{ErrorType, Operation, SpiDevice};
+
+use common::mock::*;
+use vl53l5cx::{Error, Vl53l5cx};
+
+/// SPI device recording the transactions, one Vec per CS assertion,
+/// and answering `read_value` to the reads.
+#[derive(Default)]
+struct MockSpi {
+    transactions: Vec<Vec<u8>>,
+    read_value: u8,
+}
+
+impl ErrorType for MockSpi {
+    type Error = Infallible;
+}
+
+impl SpiDevice for MockSpi {
+    fn transaction(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Infallible> {
+        let mut written: Vec<u8> = Vec::new();
+        for operation in operations {
+            match operation {
+                Operation::Write(wbuf) => written.extend_from_slice(wbuf),
+                Operation::Read(rbuf) => rbuf.fill(self.read_value),
+                _ => unimplemented!(),
+            }
+        }
+        self.transactions.push(written);
+        Ok(())
+    }
+}
+
+#[test]
+fn register_address_carries_the_write_bit() {
+    let mut spi = MockSpi { read_value: 0xf0, ..MockSpi::default() };
+    let result = {
+        let mut sensor = Vl53l5cx::new_spi(&mut spi, NoPin, NoPin, NoDelay).unwrap();
+        sensor.probe_identity()
+    };
+
+    assert!(matches!(result, Err(Error::IdMismatch { device_id: 0xf0, revision_id: 0xf0 })));
+    assert_eq!(spi.transactions, vec![
+        vec![0xff, 0xff, 0x00],
+        vec![0x00, 0x00],
+        vec![0xff, 0xff, 0x02],
+    ]);
+}
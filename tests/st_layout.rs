@@ -0,0 +1,70 @@
+use core::mem::offset_of;
+
+use vl53l5cx::consts::*;
+use vl53l5cx::st_layout::VL53L5CX_ST_RESULTS_SIZE;
+use vl53l5cx::{Error, ResultsData};
+
+// ResultsData starts with the fields of VL53L5CX_ResultsData, in the same order
+// and with repr(C), so its offsets are the ones of the C struct.
+
+const NB_TARGETS: usize = 64 * VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+fn results() -> ResultsData {
+    let mut results = ResultsData::new();
+    results.silicon_temp_degc = -12;
+    for zone in 0..64 {
+        results.ambient_per_spad[zone] = 0x1000 + zone as u32;
+        results.nb_spads_enabled[zone] = 0x2000 + zone as u32;
+        results.nb_target_detected[zone] = zone as u8;
+    }
+    for target in 0..NB_TARGETS {
+        results.signal_per_spad[target] = 0x30000 + target as u32;
+        results.range_sigma_mm[target] = 0x400 + target as u16;
+        results.distance_mm[target] = -(target as i16);
+        results.reflectance[target] = target as u8;
+        results.target_status[target] = 5;
+    }
+    results.motion_indicator.global_indicator_1 = 0xAABBCCDD;
+    results.motion_indicator.nb_of_aggregates = 16;
+    results.motion_indicator.motion[31] = 0x01020304;
+    results
+}
+
+fn u32_at(buffer: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buffer[offset..offset + 4].try_into().unwrap())
+}
+
+fn u16_at(buffer: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(buffer[offset..offset + 2].try_into().unwrap())
+}
+
+#[test]
+fn layout_matches_the_c_struct() {
+    let mut buffer = [0xFFu8; VL53L5CX_ST_RESULTS_SIZE];
+    let size = results().as_st_c_layout::<()>(&mut buffer).unwrap();
+    assert_eq!(size, VL53L5CX_ST_RESULTS_SIZE);
+
+    assert_eq!(buffer[0] as i8, -12);
+    // Padding after the temperature
+    assert_eq!(&buffer[1..4], &[0, 0, 0]);
+    assert_eq!(u32_at(&buffer, offset_of!(ResultsData, ambient_per_spad) + 4 * 63), 0x1000 + 63);
+    assert_eq!(buffer[offset_of!(ResultsData, nb_target_detected) + 7], 7);
+    assert_eq!(u32_at(&buffer, offset_of!(ResultsData, nb_spads_enabled) + 4 * 2), 0x2002);
+    assert_eq!(u32_at(&buffer, offset_of!(ResultsData, signal_per_spad) + 4 * (NB_TARGETS - 1)), 0x30000 + NB_TARGETS as u32 - 1);
+    assert_eq!(u16_at(&buffer, offset_of!(ResultsData, range_sigma_mm) + 2 * 5), 0x405);
+    assert_eq!(u16_at(&buffer, offset_of!(ResultsData, distance_mm) + 2 * 9) as i16, -9);
+    assert_eq!(buffer[offset_of!(ResultsData, reflectance) + 11], 11);
+    assert_eq!(buffer[offset_of!(ResultsData, target_status) + NB_TARGETS - 1], 5);
+
+    let motion = offset_of!(ResultsData, motion_indicator);
+    assert_eq!(u32_at(&buffer, motion), 0xAABBCCDD);
+    assert_eq!(buffer[motion + 10], 16);
+    assert_eq!(u32_at(&buffer, motion + 12 + 4 * 31), 0x01020304);
+    assert_eq!(VL53L5CX_ST_RESULTS_SIZE, motion + 140);
+}
+
+#[test]
+fn small_buffer_is_rejected() {
+    let mut buffer = [0u8; 64];
+    assert!(matches!(results().as_st_c_layout::<()>(&mut buffer), Err(Error::BufferTooSmall)));
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::parser::ParseError;
+use vl53l5cx::{CommandStatus, Error, Stats, Vl53l5cx};
+
+#[test]
+fn timeouts_and_retries_are_counted() {
+    let mut i2c = MockI2c::default();
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+
+    assert!(matches!(sensor.wait_for_command_status(CommandStatus::Done), Err(Error::Timeout)));
+    // Polled every 1ms during 2s
+    assert_eq!(sensor.stats(), Stats { timeouts: 1, retries: 2001, ..Stats::default() });
+
+    sensor.reset_stats();
+    assert_eq!(sensor.stats(), Stats::default());
+}
+
+#[test]
+fn mcu_errors_are_counted() {
+    let mut i2c = MockI2c { read_value: 0xff, ..Default::default() };
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+
+    assert!(matches!(sensor.wait_for_command_status(CommandStatus::Done), Err(Error::Mcu)));
+    assert_eq!(sensor.stats(), Stats { mcu_errors: 1, ..Stats::default() });
+}
+
+#[test]
+fn bus_errors_are_counted() {
+    let mut sim = SimSensor::new(1, 1);
+    sim.faults.nack_permille = 1000;
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+
+    // The first transaction after the start command is not acknowledged
+    assert!(matches!(sensor.start_ranging(), Err(Error::Bus(Nack))));
+    assert_eq!(sensor.stats(), Stats { bus_errors: 1, ..Stats::default() });
+}
+
+#[test]
+fn corrupted_frames_are_counted() {
+    let mut sim = SimSensor::new(1, 1);
+    sim.faults.corrupted_permille = 1000;
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.start_ranging().unwrap();
+
+    while !sensor.check_data_ready().unwrap() {}
+    assert!(matches!(sensor.get_ranging_data(), Err(Error::Parse(ParseError::HeaderFooterMismatch))));
+    assert_eq!(sensor.stats().corrupted_frames, 1);
+    assert_eq!(sensor.stats().bus_errors, 0);
+}
@@ -0,0 +1,27 @@
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::status_histogram::StatusHistogram;
+use vl53l5cx::ResultsData;
+
+#[test]
+fn statuses_are_counted() {
+    let mut results = ResultsData::new();
+    let nb_targets = 16 * VL53L5CX_NB_TARGET_PER_ZONE as usize;
+    for t in 0..nb_targets {
+        results.target_status[t] = [5, 9, 255, 4][t % 4];
+    }
+    // Outside of the 4x4 frame
+    results.target_status[nb_targets] = 5;
+
+    let histogram = StatusHistogram::from_results(&results, Resolution::Res4x4);
+    assert_eq!(histogram.nb_targets as usize, nb_targets);
+    assert_eq!(histogram.count(5) as usize, nb_targets / 4);
+    assert_eq!(histogram.count(255), histogram.count(15));
+    assert_eq!(histogram.nb_valid() as usize, nb_targets / 2);
+    assert_eq!(histogram.percent_valid(), 50);
+}
+
+#[test]
+fn empty_histogram_has_no_valid_target() {
+    assert_eq!(StatusHistogram::default().percent_valid(), 0);
+}
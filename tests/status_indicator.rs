@@ -0,0 +1,31 @@
+mod common;
+
+use std::sync::Mutex;
+
+use common::mock::*;
+use vl53l5cx::{DriverState, Error, Vl53l5cx};
+
+static STATES: Mutex<Vec<DriverState>> = Mutex::new(Vec::new());
+
+fn indicator(state: DriverState) {
+    STATES.lock().unwrap().push(state);
+}
+
+#[test]
+fn go2_error_is_indicated() {
+    // UI status bytes with the GO2 error bit set
+    let mut i2c = MockI2c { read_value: 0x80, ..Default::default() };
+    let mut sensor = Vl53l5cx::new_i2c(&mut i2c, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_status_indicator(Some(indicator));
+    assert_eq!(sensor.driver_state(), DriverState::Off);
+
+    assert!(matches!(sensor.check_data_ready(), Err(Error::Go2)));
+    assert!(matches!(sensor.check_data_ready(), Err(Error::Go2)));
+    assert_eq!(sensor.driver_state(), DriverState::Error);
+    // The error is kept until the next initialization
+    sensor.off().unwrap();
+    assert_eq!(sensor.driver_state(), DriverState::Error);
+
+    // Only the changes of state are reported
+    assert_eq!(*STATES.lock().unwrap(), vec![DriverState::Error]);
+}
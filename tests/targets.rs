@@ -0,0 +1,46 @@
+#![cfg(not(any(feature = "VL53L5CX_DISABLE_NB_TARGET_DETECTED", feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_SIGNAL_PER_SPAD")))]
+
+use vl53l5cx::consts::VL53L5CX_NB_TARGET_PER_ZONE;
+use vl53l5cx::ResultsData;
+
+const NB_TARGETS: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+/// Zone 5 with all its targets detected, the farther the stronger.
+fn frame() -> ResultsData {
+    let mut results = ResultsData::new();
+    results.nb_target_detected[5] = NB_TARGETS as u8;
+    for target in 0..NB_TARGETS {
+        // Targets in the order of the sensor: 1000, 400, 1600, 700...
+        let distance_mm: i16 = [1000, 400, 1600, 700][target];
+        results.distance_mm[5 * NB_TARGETS + target] = distance_mm;
+        results.signal_per_spad[5 * NB_TARGETS + target] = distance_mm as u32;
+    }
+    results
+}
+
+#[test]
+fn targets_are_sorted_host_side() {
+    let results = frame();
+
+    let by_distance = results.targets_by_distance(5);
+    assert_eq!(by_distance.zone(), 5);
+    assert_eq!(by_distance.len(), NB_TARGETS);
+    let distances: Vec<i16> = by_distance.indexes().iter().map(|&i| results.distance_mm[i]).collect();
+    let mut expected: Vec<i16> = distances.clone();
+    expected.sort();
+    assert_eq!(distances, expected);
+
+    let signals: Vec<u32> = results.targets_by_signal(5).indexes().iter().map(|&i| results.signal_per_spad[i]).collect();
+    expected.reverse();
+    assert_eq!(signals, expected.iter().map(|&d| d as u32).collect::<Vec<u32>>());
+}
+
+#[test]
+fn undetected_targets_are_left_out() {
+    let mut results = frame();
+    results.nb_target_detected[5] = 1;
+
+    assert_eq!(results.targets_by_distance(5).indexes(), &[5 * NB_TARGETS]);
+    assert!(results.targets_by_distance(6).is_empty());
+    assert!(results.targets_by_signal(64).is_empty());
+}
@@ -0,0 +1,34 @@
+use vl53l5cx::consts::*;
+use vl53l5cx::ResultsData;
+
+const NB_TARGET: usize = VL53L5CX_NB_TARGET_PER_ZONE as usize;
+
+// 1mm per 10 degrees above 25 degrees, plus 2mm on zone 3
+fn model(silicon_temp_degc: i8, zone: usize, distance_mm: i16) -> i16 {
+    let offset = (silicon_temp_degc as i16 - 25) / 10 + if zone == 3 { 2 } else { 0 };
+    distance_mm - offset
+}
+
+#[test]
+fn valid_targets_are_compensated() {
+    let mut results = ResultsData::new();
+    results.silicon_temp_degc = 55;
+    for zone in 0..4 {
+        results.distance_mm[zone * NB_TARGET] = 500;
+        results.target_status[zone * NB_TARGET] = [5, 9, 255, 5][zone];
+    }
+    results.compensate_temperature(model);
+    assert_eq!(results.distance_mm[0], 497);
+    assert_eq!(results.distance_mm[NB_TARGET], 497);
+    assert_eq!(results.distance_mm[3 * NB_TARGET], 495);
+}
+
+#[test]
+fn invalid_targets_keep_their_distance() {
+    let mut results = ResultsData::new();
+    results.silicon_temp_degc = -20;
+    results.distance_mm[2 * NB_TARGET] = i16::MAX;
+    results.target_status[2 * NB_TARGET] = 255;
+    results.compensate_temperature(model);
+    assert_eq!(results.distance_mm[2 * NB_TARGET], i16::MAX);
+}
@@ -0,0 +1,17 @@
+use vl53l5cx::config::{RangingMode, Resolution};
+use vl53l5cx::timing::{check_timing, TimingCheck};
+
+#[test]
+fn resolution_limits_frequency() {
+    assert_eq!(check_timing(Resolution::Res4x4, RangingMode::Continuous, 60, 0), TimingCheck { feasible: true, max_frequency_hz: 60 });
+    assert_eq!(check_timing(Resolution::Res8x8, RangingMode::Continuous, 30, 0), TimingCheck { feasible: false, max_frequency_hz: 15 });
+    assert!(!check_timing(Resolution::Res4x4, RangingMode::Continuous, 0, 0).feasible);
+}
+
+#[test]
+fn integration_time_limits_autonomous_frequency() {
+    // 4 integrations of 20ms per 8x8 frame
+    assert_eq!(check_timing(Resolution::Res8x8, RangingMode::Autonomous, 15, 20), TimingCheck { feasible: false, max_frequency_hz: 12 });
+    assert_eq!(check_timing(Resolution::Res4x4, RangingMode::Autonomous, 30, 20), TimingCheck { feasible: true, max_frequency_hz: 50 });
+    assert_eq!(check_timing(Resolution::Res4x4, RangingMode::Autonomous, 1, 1001).max_frequency_hz, 0);
+}
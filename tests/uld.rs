@@ -0,0 +1,43 @@
+#![cfg(feature = "uld-api")]
+
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::uld::*;
+use vl53l5cx::Vl53l5cx;
+
+#[test]
+fn configuration_is_read_through_output_arguments() {
+    let mut sim = SimSensor::new(2, 1);
+    let mut dev = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+
+    let mut resolution: u8 = 0;
+    vl53l5cx_get_resolution(&mut dev, &mut resolution).unwrap();
+    assert_eq!(resolution, 16);
+
+    let mut frequency_hz: u8 = 0;
+    vl53l5cx_set_ranging_frequency_hz(&mut dev, 15).unwrap();
+    vl53l5cx_get_ranging_frequency_hz(&mut dev, &mut frequency_hz).unwrap();
+    assert_eq!(frequency_hz, 15);
+}
+
+#[test]
+fn ranging_loop_ported_from_c() {
+    let mut sim = SimSensor::new(2, 1);
+    let mut dev = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    let mut is_ready: u8 = 0;
+    let mut results = VL53L5CX_ResultsData::new();
+    let mut frames: Vec<u32> = Vec::new();
+
+    vl53l5cx_start_ranging(&mut dev).unwrap();
+    while frames.len() < 3 {
+        vl53l5cx_check_data_ready(&mut dev, &mut is_ready).unwrap();
+        if is_ready != 0 {
+            vl53l5cx_get_ranging_data(&mut dev, &mut results).unwrap();
+            frames.push(SimSensor::frame_number(results.distance_mm[0]));
+        }
+    }
+
+    assert_eq!(frames, vec![1, 2, 3]);
+}
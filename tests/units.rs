@@ -0,0 +1,42 @@
+use vl53l5cx::units::*;
+
+// Reference values computed with the conversions of the ST ULD driver (vl53l5cx_api.c)
+
+#[test]
+fn rates_match_uld() {
+    assert_eq!(rate_kcps_per_spad(0), 0);
+    assert_eq!(rate_kcps_per_spad(2047), 0);
+    assert_eq!(rate_kcps_per_spad(76800), 37);
+    assert_eq!(rate_kcps_per_spad(u32::MAX), 2097151);
+
+    assert_eq!(rate_cps_per_spad(76800), 37500);
+    assert_eq!(rate_cps_per_spad(1), 0);
+    assert_eq!(rate_cps_per_spad(u32::MAX), 2097151999);
+
+    assert_eq!(rate_kcps_per_spad_f32(76800), 37.5);
+    assert_eq!(rate_kcps_per_spad_f32(1), 1.0 / 2048.0);
+}
+
+#[test]
+fn distances_match_uld() {
+    assert_eq!(distance_mm(0), 0);
+    assert_eq!(distance_mm(4003), 1000);
+    assert_eq!(distance_mm(-3), 0);
+    assert_eq!(distance_mm(-8), 0);
+    assert_eq!(distance_mm(i16::MAX), 8191);
+
+    assert_eq!(distance_mm_f32(4003), 1000.75);
+    assert_eq!(distance_mm_f32(-8), 0.0);
+}
+
+#[test]
+fn sigma_reflectance_and_motion_match_uld() {
+    assert_eq!(range_sigma_mm(640), 5);
+    assert_eq!(range_sigma_mm(127), 0);
+    assert_eq!(range_sigma_mm_f32(704), 5.5);
+
+    assert_eq!(reflectance_percent(121), 60);
+
+    assert_eq!(motion_indicator(65534), 0);
+    assert_eq!(motion_indicator(3 * 65535 + 1), 3);
+}
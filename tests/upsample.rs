@@ -0,0 +1,28 @@
+use vl53l5cx::upsample::upsample_grid;
+
+#[test]
+fn constant_grid_stays_constant() {
+    let pixels: [[i16; 32]; 32] = upsample_grid(&[[750; 8]; 8]);
+    assert!(pixels.iter().flatten().all(|&p| p == 750));
+}
+
+#[test]
+fn ramp_is_interpolated_between_zone_centers() {
+    let mut grid = [[0i16; 4]; 4];
+    for line in grid.iter_mut() {
+        *line = [0, 100, 200, 300];
+    }
+    let pixels: [[i16; 8]; 8] = upsample_grid(&grid);
+    // Pixel centers at 1/4 and 3/4 of each zone, borders clamped
+    assert_eq!(pixels[3], [0, 25, 75, 125, 175, 225, 275, 300]);
+}
+
+#[test]
+fn same_size_is_identity() {
+    let mut grid = [[0i16; 8]; 8];
+    for (i, value) in grid.iter_mut().flatten().enumerate() {
+        *value = i as i16 * 37 - 500;
+    }
+    let pixels: [[i16; 8]; 8] = upsample_grid(&grid);
+    assert_eq!(pixels, grid);
+}
@@ -0,0 +1,100 @@
+use vl53l5cx::utils::*;
+
+#[test]
+fn u8_to_values_are_little_endian() {
+    let src: [u8; 8] = [0x01, 0x02, 0x03, 0x04, 0xFE, 0xFF, 0xFF, 0xFF];
+
+    let mut u16s: [u16; 4] = [0; 4];
+    from_u8_to_u16(&src, &mut u16s);
+    assert_eq!(u16s, [0x0201, 0x0403, 0xFFFE, 0xFFFF]);
+
+    let mut i16s: [i16; 4] = [0; 4];
+    from_u8_to_i16(&src, &mut i16s);
+    assert_eq!(i16s, [0x0201, 0x0403, -2, -1]);
+
+    let mut u32s: [u32; 2] = [0; 2];
+    from_u8_to_u32(&src, &mut u32s);
+    assert_eq!(u32s, [0x04030201, 0xFFFFFFFE]);
+
+    let mut i32s: [i32; 2] = [0; 2];
+    from_u8_to_i32(&src, &mut i32s);
+    assert_eq!(i32s, [0x04030201, -2]);
+}
+
+#[test]
+fn values_to_u8_round_trip() {
+    let mut buf: [u8; 8] = [0; 8];
+
+    from_u16_to_u8(&[0x0201, 0xFFFE], &mut buf);
+    assert_eq!(buf[..4], [0x01, 0x02, 0xFE, 0xFF]);
+
+    let mut i16s: [i16; 3] = [0; 3];
+    from_i16_to_u8(&[-2, 300, i16::MIN], &mut buf);
+    from_u8_to_i16(&buf[..6], &mut i16s);
+    assert_eq!(i16s, [-2, 300, i16::MIN]);
+
+    let mut u32s: [u32; 2] = [0; 2];
+    from_u32_to_u8(&[0x04030201, u32::MAX], &mut buf);
+    assert_eq!(buf[..4], [0x01, 0x02, 0x03, 0x04]);
+    from_u8_to_u32(&buf, &mut u32s);
+    assert_eq!(u32s, [0x04030201, u32::MAX]);
+
+    let mut i32s: [i32; 2] = [0; 2];
+    from_i32_to_u8(&[-100000, i32::MAX], &mut buf);
+    from_u8_to_i32(&buf, &mut i32s);
+    assert_eq!(i32s, [-100000, i32::MAX]);
+}
+
+#[test]
+fn trailing_bytes_are_ignored() {
+    let mut u16s: [u16; 1] = [0; 1];
+    from_u8_to_u16(&[0x01, 0x02, 0x03], &mut u16s);
+    assert_eq!(u16s, [0x0201]);
+
+    let mut u32s: [u32; 1] = [0; 1];
+    from_u8_to_u32(&[0x01, 0x02, 0x03, 0x04, 0x05], &mut u32s);
+    assert_eq!(u32s, [0x04030201]);
+}
+
+#[test]
+fn swap_buffer_reverses_words() {
+    let mut buf: [u8; 10] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+    swap_buffer(&mut buf, 10);
+    assert_eq!(buf, [3, 2, 1, 0, 7, 6, 5, 4, 8, 9]);
+
+    // Only the first `size` bytes are converted
+    swap_buffer(&mut buf, 4);
+    assert_eq!(buf, [0, 1, 2, 3, 7, 6, 5, 4, 8, 9]);
+}
+
+#[test]
+fn wire_words_decode_on_any_host() {
+    // Words are sent big endian by the sensor, whatever the host endianness
+    let mut wire: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0xFF, 0xFF, 0xFF, 0xF8];
+    swap_buffer(&mut wire, 8);
+
+    let mut u32s: [u32; 2] = [0; 2];
+    from_u8_to_u32(&wire, &mut u32s);
+    assert_eq!(u32s, [0x12345678, 0xFFFFFFF8]);
+
+    let mut i16s: [i16; 4] = [0; 4];
+    from_u8_to_i16(&wire, &mut i16s);
+    assert_eq!(i16s, [0x5678, 0x1234, -8, -1]);
+}
+
+#[test]
+fn misaligned_slices_decode() {
+    let buf: [u8; 9] = [0xAA, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+
+    let mut u32s: [u32; 2] = [0; 2];
+    from_u8_to_u32(&buf[1..], &mut u32s);
+    assert_eq!(u32s, [0x04030201, 0x08070605]);
+}
+
+#[test]
+fn fw_version_decodes_fields() {
+    let version = vl53l5cx::config::FwVersion::from_raw(0x0103_000B);
+    assert_eq!((version.major, version.minor, version.build), (1, 3, 11));
+    assert!(version.is_expected());
+    assert!(!vl53l5cx::config::FwVersion::from_raw(0x0102_000B).is_expected());
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::config::{FwVersion, InitStrategy, SensorVariant, VariantBlobs};
+use vl53l5cx::consts::{VL53L5CX_CONFIGURATION_SIZE, VL53L5CX_XTALK_BUFFER_SIZE};
+use vl53l5cx::firmware::VL53L5CX_FIRMWARE_SIZE;
+use vl53l5cx::{Error, Vl53l5cx};
+
+const DCI_FW_VERSION: u16 = 0x5440;
+
+// Placeholders for the buffers of the VL53L7CX ULD
+static FIRMWARE: [u8; VL53L5CX_FIRMWARE_SIZE] = [0; VL53L5CX_FIRMWARE_SIZE];
+static CONFIGURATION: [u8; VL53L5CX_CONFIGURATION_SIZE] = [0; VL53L5CX_CONFIGURATION_SIZE];
+static XTALK: [u8; VL53L5CX_XTALK_BUFFER_SIZE] = [0; VL53L5CX_XTALK_BUFFER_SIZE];
+
+fn vl53l7cx(firmware: &'static [u8]) -> SensorVariant {
+    SensorVariant::Vl53l7cx(VariantBlobs {
+        firmware,
+        default_configuration: &CONFIGURATION,
+        default_xtalk: &XTALK,
+        fw_version: FwVersion { major: 2, minor: 0, build: 5 }
+    })
+}
+
+/// Sensor already running the firmware `major.minor.build`.
+fn warm_sensor(major: u8, minor: u8, build: u16) -> SimSensor {
+    let mut sim = SimSensor::new(2, 1);
+    let raw: u32 = (major as u32) << 24 | (minor as u32) << 16 | build as u32;
+    sim.set_dci(DCI_FW_VERSION, &raw.to_le_bytes());
+    sim
+}
+
+#[test]
+fn variant_firmware_version_is_expected() {
+    let mut sim = warm_sensor(2, 0, 5);
+    {
+        let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+        sensor.set_sensor_variant(vl53l7cx(&FIRMWARE));
+        sensor.set_init_strategy(InitStrategy::IfNeeded);
+        sensor.init().unwrap();
+    }
+    assert_eq!(sim.firmware_bytes(), 0);
+
+    // The VL53L5CX firmware is not the one of the variant: the sensor is rebooted,
+    // and the simulated sensor does not boot
+    let mut sim = warm_sensor(1, 3, 11);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_sensor_variant(vl53l7cx(&FIRMWARE));
+    sensor.set_init_strategy(InitStrategy::IfNeeded);
+
+    assert!(matches!(sensor.init(), Err(Error::Timeout)));
+}
+
+#[test]
+fn variant_firmware_size_is_checked() {
+    let mut sim = SimSensor::new(2, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_sensor_variant(vl53l7cx(&FIRMWARE[..0x8000]));
+
+    assert!(matches!(sensor.init(), Err(Error::SizeMismatch { expected: VL53L5CX_FIRMWARE_SIZE, got: 0x8000 })));
+}
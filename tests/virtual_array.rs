@@ -0,0 +1,71 @@
+mod common;
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::config::Resolution;
+use vl53l5cx::consts::*;
+use vl53l5cx::virtual_array::*;
+use vl53l5cx::{ResultsData, Vl53l5cx};
+
+#[test]
+fn sub_grid_must_fit_in_the_grid() {
+    assert!(VirtualArray::new(Resolution::Res8x8, 0, 0, 2, 8).is_some());
+    assert!(VirtualArray::new(Resolution::Res8x8, 6, 4, 2, 4).is_some());
+    assert!(VirtualArray::new(Resolution::Res8x8, 7, 0, 2, 8).is_none());
+    assert!(VirtualArray::new(Resolution::Res4x4, 0, 0, 2, 8).is_none());
+    assert!(VirtualArray::new(Resolution::Res4x4, 0, 0, 0, 4).is_none());
+}
+
+#[test]
+fn zones_follow_the_rotation() {
+    // Rows 2 and 3, columns 1 to 3 of a 8x8 grid
+    let array = VirtualArray::new(Resolution::Res8x8, 2, 1, 2, 3).unwrap();
+    let zones = |array: VirtualArray| -> Vec<usize> { (0..array.nb_zones()).filter_map(|zone| array.sensor_zone(zone)).collect() };
+
+    assert_eq!((array.rows(), array.cols()), (2, 3));
+    assert_eq!(zones(array), vec![17, 18, 19, 25, 26, 27]);
+
+    let array = array.with_rotation(ArrayRotation::Clockwise90);
+    assert_eq!((array.rows(), array.cols()), (3, 2));
+    assert_eq!(zones(array), vec![25, 17, 26, 18, 27, 19]);
+    assert_eq!(zones(array.with_rotation(ArrayRotation::Rotate180)), vec![27, 26, 25, 19, 18, 17]);
+    assert_eq!(zones(array.with_rotation(ArrayRotation::CounterClockwise90)), vec![19, 27, 18, 26, 17, 25]);
+    assert_eq!(array.sensor_zone(6), None);
+}
+
+#[cfg(not(any(feature = "VL53L5CX_DISABLE_DISTANCE_MM", feature = "VL53L5CX_DISABLE_TARGET_STATUS")))]
+#[test]
+fn crop_packs_the_zones_of_the_strip() {
+    let mut results = ResultsData::new();
+    for zone in 0..64 {
+        let idx = VL53L5CX_NB_TARGET_PER_ZONE as usize * zone;
+        results.distance_mm[idx] = 1000 + zone as i16;
+        results.target_status[idx] = 5;
+    }
+    results.silicon_temp_degc = 42;
+
+    // Top 8x2 rows for a line scanner
+    let cropped = VirtualArray::new(Resolution::Res8x8, 0, 0, 2, 8).unwrap().crop(&results);
+
+    assert_eq!(cropped.silicon_temp_degc, 42);
+    for zone in 0..16 {
+        assert_eq!(cropped.distance_mm[VL53L5CX_NB_TARGET_PER_ZONE as usize * zone], 1000 + zone as i16);
+    }
+    assert_eq!(cropped.target_status[VL53L5CX_NB_TARGET_PER_ZONE as usize * 16], 255);
+    assert_eq!(cropped.distance_mm[VL53L5CX_NB_TARGET_PER_ZONE as usize * 16], 0);
+}
+
+#[cfg(not(feature = "VL53L5CX_DISABLE_AMBIENT_PER_SPAD"))]
+#[test]
+fn ranging_data_is_cropped_by_the_driver() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.set_virtual_array(VirtualArray::new(Resolution::Res4x4, 1, 0, 2, 4));
+    sensor.start_ranging().unwrap();
+    while !sensor.check_data_ready().unwrap() {}
+
+    let results = sensor.get_ranging_data().unwrap();
+    // The simulated ambient of zone z is z + 1
+    assert_eq!(results.ambient_per_spad[..8], [5, 6, 7, 8, 9, 10, 11, 12]);
+    assert_eq!(results.ambient_per_spad[8], 0);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use std::cell::RefCell;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::bus_operation::Vl53l5cxI2C;
+use vl53l5cx::{Error, Vl53l5cx};
+
+fn next_frame(sensor: &mut Vl53l5cx<Vl53l5cxI2C<&mut SimSensor>, NoPin, NoPin, NoDelay>) -> u32 {
+    while !sensor.check_data_ready().unwrap() {}
+    SimSensor::frame_number(sensor.get_ranging_data().unwrap().distance_mm[0])
+}
+
+#[test]
+fn first_frames_are_discarded_after_each_start() {
+    let mut sim = SimSensor::new(1, 1);
+    let mut sensor = Vl53l5cx::new_i2c(&mut sim, NoPin, NoPin, NoDelay).unwrap();
+    sensor.discard_first_frames(2);
+
+    sensor.start_ranging().unwrap();
+    assert_eq!(next_frame(&mut sensor), 3);
+    assert_eq!(next_frame(&mut sensor), 4);
+    sensor.stop_ranging().unwrap();
+
+    sensor.start_ranging().unwrap();
+    let first = next_frame(&mut sensor);
+    assert_eq!(next_frame(&mut sensor), first + 1);
+    // Frames 5 and 6 discarded
+    assert_eq!(first, 7);
+}
+
+/// Bus shared between the driver and the test, which publishes the frames.
+struct SharedSim<'a>(&'a RefCell<SimSensor>);
+
+impl ErrorType for SharedSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for SharedSim<'_> {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+#[test]
+fn discarded_frame_is_not_ready_on_interrupt() {
+    let sim = RefCell::new(SimSensor::new(2, 1));
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim), NoPin, NoPin, NoDelay).unwrap();
+    sensor.discard_first_frames(1);
+    sensor.start_ranging().unwrap();
+    // Status read letting time pass before the INT pin interrupt, the next frame 
+    // being published by the status read of get_ranging_data()
+    let tick = || sim.borrow_mut().write_read(0x29, &[0, 0], &mut [0; 4]).unwrap();
+
+    tick();
+    assert!(matches!(sensor.get_ranging_data(), Err(Error::NotReady)));
+    tick();
+    assert_eq!(SimSensor::frame_number(sensor.get_ranging_data().unwrap().distance_mm[0]), 2);
+}
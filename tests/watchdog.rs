@@ -0,0 +1,86 @@
+mod common;
+
+use std::cell::RefCell;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use common::mock::*;
+use common::sim::*;
+use vl53l5cx::watchdog::{Stall, StallRecovery};
+use vl53l5cx::Vl53l5cx;
+
+/// Bus shared between the driver and the test, which freezes the sensor.
+struct SharedSim<'a>(&'a RefCell<SimSensor>);
+
+impl ErrorType for SharedSim<'_> {
+    type Error = Nack;
+}
+
+impl I2c for SharedSim<'_> {
+    fn transaction(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Nack> {
+        self.0.borrow_mut().transaction(address, operations)
+    }
+}
+
+/// Writes the UI status bytes, as a frozen firmware would leave them.
+fn freeze(sim: &RefCell<SimSensor>, go2_error: bool) {
+    let mut sim = sim.borrow_mut();
+    sim.ranging = false;
+    let mut ui_status: [u8; 4] = [0; 4];
+    sim.write_read(0x29, &[0, 0], &mut ui_status).unwrap();
+    if go2_error {
+        ui_status[3] |= 0x80;
+    }
+    sim.write(0x29, &[0, 0, ui_status[0], ui_status[1], ui_status[2], ui_status[3]]).unwrap();
+}
+
+#[test]
+fn frozen_stream_count_is_reported_and_restarted() {
+    let sim = RefCell::new(SimSensor::new(1, 1));
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim), NoPin, NoPin, NoDelay).unwrap();
+    sensor.expect_frame_every(100);
+    assert_eq!(sensor.check_stalled(0).unwrap(), None);
+    sensor.start_ranging().unwrap();
+
+    // Each check reads the status bytes, which publishes a new frame
+    for now_ms in (0..1000).step_by(50) {
+        assert_eq!(sensor.check_stalled(now_ms).unwrap(), None);
+    }
+    freeze(&sim, false);
+    let streamcount: u8 = (sim.borrow().published % 255) as u8;
+    assert_eq!(sensor.check_stalled(1000).unwrap(), None);
+    assert_eq!(sensor.check_stalled(1250).unwrap(), None);
+    let stall: Stall = sensor.check_stalled(1300).unwrap().unwrap();
+    assert_eq!(stall, Stall { last_frame_ms: 950, elapsed_ms: 350, streamcount, recovery: StallRecovery::Restart });
+
+    sensor.recover_from_stall(stall.recovery).unwrap();
+    assert!(sensor.is_ranging());
+    for now_ms in (1300..2000).step_by(50) {
+        assert_eq!(sensor.check_stalled(now_ms).unwrap(), None);
+    }
+}
+
+#[test]
+fn go2_error_suggests_a_reinit() {
+    let sim = RefCell::new(SimSensor::new(1, 1));
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim), NoPin, NoPin, NoDelay).unwrap();
+    sensor.expect_frame_every(50);
+    sensor.start_ranging().unwrap();
+
+    assert_eq!(sensor.check_stalled(u32::MAX - 10).unwrap(), None);
+    freeze(&sim, true);
+    // Time wrapped around
+    assert_eq!(sensor.check_stalled(100).unwrap(), None);
+    assert_eq!(sensor.check_stalled(150).unwrap().map(|stall| stall.recovery), Some(StallRecovery::Reinit));
+}
+
+#[test]
+fn watchdog_is_disabled_by_default() {
+    let sim = RefCell::new(SimSensor::new(1, 1));
+    let mut sensor = Vl53l5cx::new_i2c(SharedSim(&sim), NoPin, NoPin, NoDelay).unwrap();
+    sensor.start_ranging().unwrap();
+    freeze(&sim, false);
+
+    assert_eq!(sensor.check_stalled(0).unwrap(), None);
+    assert_eq!(sensor.check_stalled(1_000_000).unwrap(), None);
+}
@@ -0,0 +1,28 @@
+use vl53l5cx::xtalk::XtalkGrid;
+
+const XTALK_BUFFER_SIZE: usize = 776;
+
+#[test]
+fn grid_is_decoded_from_sensor_words() {
+    let mut xtalk_data = [0xAAu8; XTALK_BUFFER_SIZE];
+    // Zone 1 holds 3 kcps/spad, as a big endian word
+    xtalk_data[0x38..0x3C].copy_from_slice(&(3u32 * 2048).to_be_bytes());
+
+    let grid = XtalkGrid::from_caldata(&xtalk_data);
+    assert_eq!(grid.signal[1], 3 * 2048);
+    assert_eq!(grid.kcps_per_spad(1), 3);
+    assert_eq!(grid.signal[0], 0xAAAA_AAAA);
+}
+
+#[test]
+fn grid_is_re_encoded_in_place() {
+    let mut xtalk_data = [0x55u8; XTALK_BUFFER_SIZE];
+    let mut grid = XtalkGrid::from_caldata(&xtalk_data);
+    grid.signal[63] = 0x0102_0304;
+    grid.write_caldata(&mut xtalk_data);
+
+    assert_eq!(&xtalk_data[0x34 + 4 * 63..0x34 + 4 * 64], &[1, 2, 3, 4]);
+    assert_eq!(xtalk_data[0x33], 0x55);
+    assert_eq!(xtalk_data[0x134], 0x55);
+    assert_eq!(XtalkGrid::from_caldata(&xtalk_data), grid);
+}